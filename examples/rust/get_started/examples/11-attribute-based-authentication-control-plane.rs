@@ -84,6 +84,7 @@ async fn start_node(ctx: Context, project_information_path: &str, token: OneTime
             vec![project.authority_public_identity()],
             "credential_exchange",
             true,
+            true,
             attr_storage.async_try_clone().await?,
         )
         .await?;
@@ -80,6 +80,7 @@ async fn start_node(ctx: Context, project_information_path: &str, token: OneTime
             vec![project.authority_public_identity()],
             "credential_exchange",
             true,
+            true,
             attr_storage.async_try_clone().await?,
         )
         .await?;
@@ -125,6 +126,7 @@ async fn start_node(ctx: Context, project_information_path: &str, token: OneTime
         .present_credential_mutual(
             route![secure_channel_to_control.clone(), "credential_exchange"],
             vec![&project.authority_public_identity()],
+            true,
             &attr_storage,
         )
         .await?;
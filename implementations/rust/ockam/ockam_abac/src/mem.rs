@@ -67,6 +67,16 @@ impl Inner {
             Vec::new()
         }
     }
+
+    fn all_policies(&self) -> Vec<(Resource, Action, Expr)> {
+        self.policies
+            .iter()
+            .flat_map(|(r, p)| {
+                p.iter()
+                    .map(move |(a, e)| (r.clone(), a.clone(), e.clone()))
+            })
+            .collect()
+    }
 }
 
 #[async_trait]
@@ -88,6 +98,10 @@ impl PolicyStorage for Memory {
     async fn policies(&self, r: &Resource) -> Result<Vec<(Action, Expr)>> {
         Ok(self.inner.write().unwrap().policies(r))
     }
+
+    async fn all_policies(&self) -> Result<Vec<(Resource, Action, Expr)>> {
+        Ok(self.inner.read().unwrap().all_policies())
+    }
 }
 
 #[cfg(test)]
@@ -1,11 +1,11 @@
 use core::{fmt, str};
 use ockam_core::compat::boxed::Box;
 use ockam_core::compat::format;
-use ockam_core::compat::string::ToString;
 use ockam_core::{async_trait, RelayMessage};
 use ockam_core::{IncomingAccessControl, Result};
 use ockam_identity::{
-    authenticated_storage::IdentityAttributeStorage, IdentitySecureChannelLocalInfo,
+    authenticated_storage::{AttributeType, IdentityAttributeStorage},
+    IdentitySecureChannelLocalInfo,
 };
 use tracing as log;
 
@@ -130,7 +130,10 @@ where
                         }
                         continue;
                     }
-                    e.put(format!("subject.{k}"), str(s.to_string()));
+                    e.put(
+                        format!("subject.{k}"),
+                        attribute_expr(s, attrs.attr_type(k)),
+                    );
                 }
                 Err(e) => {
                     log::warn! {
@@ -180,3 +183,13 @@ where
         }
     }
 }
+
+/// Interpret an attribute value according to its type, falling back to
+/// a string if the value does not parse.
+fn attribute_expr(v: &str, t: AttributeType) -> Expr {
+    match t {
+        AttributeType::Str => str(v),
+        AttributeType::Int => v.parse().map(Expr::Int).unwrap_or_else(|_| str(v)),
+        AttributeType::Bool => v.parse().map(Expr::Bool).unwrap_or_else(|_| str(v)),
+    }
+}
@@ -11,4 +11,5 @@ pub trait PolicyStorage: Send + Sync + 'static {
     async fn set_policy(&self, r: &Resource, a: &Action, c: &Expr) -> Result<()>;
     async fn del_policy(&self, r: &Resource, a: &Action) -> Result<()>;
     async fn policies(&self, r: &Resource) -> Result<Vec<(Action, Expr)>>;
+    async fn all_policies(&self) -> Result<Vec<(Resource, Action, Expr)>>;
 }
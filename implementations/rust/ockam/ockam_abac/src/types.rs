@@ -2,11 +2,31 @@ use either::Either;
 use minicbor::decode::{self, Decoder};
 use minicbor::encode::{self, Encoder, Write};
 use minicbor::{Decode, Encode};
+use ockam_core::compat::format;
 use ockam_core::compat::string::{String, ToString};
 use str_buf::StrBuf;
 
+use crate::ParseError;
+
+/// Check that `s` is a non-empty name made of ASCII letters, digits, `-`, `_` or `.`.
+fn validate_name(kind: &str, s: &str) -> Result<(), ParseError> {
+    if s.is_empty() {
+        return Err(ParseError::message(format!(
+            "{kind} name must not be empty"
+        )));
+    }
+    let valid = |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.');
+    if let Some(c) = s.chars().find(|c| !valid(*c)) {
+        return Err(ParseError::message(format!(
+            "invalid character {c:?} in {kind} name {s:?}: \
+             only ASCII letters, digits, '-', '_' and '.' are allowed"
+        )));
+    }
+    Ok(())
+}
+
 macro_rules! define {
-    ($t:ident) => {
+    ($t:ident, $kind:literal) => {
         #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
         pub struct $t(Either<String, StrBuf<{ Self::MAX_INLINE_SIZE }>>);
 
@@ -32,11 +52,27 @@ macro_rules! define {
                 Self(Either::Left(s.to_string()))
             }
 
+            /// Like `new`, but checks that `s` is a valid name first.
+            ///
+            /// Names are non-empty and made of ASCII letters, digits, `-`, `_` or `.`.
+            pub fn try_new(s: &str) -> Result<Self, ParseError> {
+                validate_name($kind, s)?;
+                Ok(Self::new(s))
+            }
+
             pub fn as_str(&self) -> &str {
                 &self.0
             }
         }
 
+        impl core::str::FromStr for $t {
+            type Err = ParseError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::try_new(s)
+            }
+        }
+
         impl From<&str> for $t {
             fn from(s: &str) -> Self {
                 Self::new(s)
@@ -80,6 +116,27 @@ macro_rules! define {
     };
 }
 
-define!(Subject);
-define!(Resource);
-define!(Action);
+define!(Subject, "subject");
+define!(Resource, "resource");
+define!(Action, "action");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validated_names() {
+        assert_eq!(
+            Resource::try_new("tcp-inlet").unwrap(),
+            Resource::new("tcp-inlet")
+        );
+        assert!(Resource::try_new("my_app.db").is_ok());
+        let long = "a-resource-name-longer-than-the-inline-size";
+        assert_eq!(Resource::try_new(long).unwrap().as_str(), long);
+        assert!(Resource::try_new("").is_err());
+        assert!(Resource::try_new("foo/bar").is_err());
+        assert!(Resource::try_new("foo:bar").is_err());
+        assert!(Action::try_new("read write").is_err());
+        assert!("handle_message".parse::<Action>().is_ok());
+    }
+}
@@ -5,12 +5,14 @@ use core::fmt;
 use minicbor::Decoder;
 use ockam_core::api::{decode_option, Method, Request, Response};
 use ockam_core::{self, Address, DenyAll, Result, Route, Routed, Worker};
-use ockam_identity::authenticated_storage::{AttributesEntry, IdentityAttributeStorageReader};
+use ockam_identity::authenticated_storage::{AttributesEntry, IdentityAttributeStorage};
 use ockam_identity::IdentityIdentifier;
 use ockam_node::api::request;
 use ockam_node::Context;
 use tracing::trace;
 
+use self::types::{AttributesPage, ListRequest};
+
 /// Auth API server.
 #[derive(Debug)]
 pub struct Server<S> {
@@ -18,7 +20,7 @@ pub struct Server<S> {
 }
 
 #[ockam_core::worker]
-impl<S: IdentityAttributeStorageReader> Worker for Server<S> {
+impl<S: IdentityAttributeStorage> Worker for Server<S> {
     type Context = Context;
     type Message = Vec<u8>;
 
@@ -32,7 +34,7 @@ impl<S: IdentityAttributeStorageReader> Worker for Server<S> {
     }
 }
 
-impl<S: IdentityAttributeStorageReader> Server<S> {
+impl<S: IdentityAttributeStorage> Server<S> {
     pub fn new(s: S) -> Self {
         Server { store: s }
     }
@@ -52,9 +54,23 @@ impl<S: IdentityAttributeStorageReader> Server<S> {
 
         let res = match req.method() {
             Some(Method::Get) => match req.path_segments::<2>().as_slice() {
-                [""] => Response::ok(req.id())
-                    .body(self.store.list().await?)
-                    .to_vec()?,
+                // Clients paging through or filtering the entries send a `ListRequest`
+                [""] if req.has_body() => {
+                    let list: ListRequest = dec.decode()?;
+                    let filter = list.filter();
+                    let entries = if filter.is_empty() {
+                        self.store.list().await?
+                    } else {
+                        self.store.filter(&filter).await?
+                    };
+                    let page = AttributesPage::new(entries, &list);
+                    Response::ok(req.id()).body(page).to_vec()?
+                }
+                [""] => {
+                    let mut entries = self.store.list().await?;
+                    entries.sort_by(|a, b| a.0.cmp(&b.0));
+                    Response::ok(req.id()).body(entries).to_vec()?
+                }
                 [id] => {
                     let identifier = IdentityIdentifier::try_from(id.to_string())?;
                     if let Some(a) = self.store.get_attributes(&identifier).await? {
@@ -109,6 +125,7 @@ impl Client {
         self.buf = request(&self.ctx, label, None, self.route.clone(), req).await?;
         decode_option(label, "attribute", &self.buf)
     }
+
     pub async fn list(&mut self) -> ockam_core::Result<Vec<(IdentityIdentifier, AttributesEntry)>> {
         let label = "list known identities";
         let req = Request::get("/");
@@ -117,4 +134,22 @@ impl Client {
             decode_option(label, "attribute", &self.buf)?;
         Ok(a.unwrap())
     }
+
+    /// List at most `limit` entries, starting at `offset`.
+    pub async fn list_page(
+        &mut self,
+        offset: u32,
+        limit: Option<u32>,
+    ) -> ockam_core::Result<AttributesPage> {
+        self.query(ListRequest::new(offset, limit)).await
+    }
+
+    /// List the entries selected by the given request, see [`ListRequest`].
+    pub async fn query(&mut self, list: ListRequest) -> ockam_core::Result<AttributesPage> {
+        let label = "list known identities";
+        let req = Request::get("/").body(list);
+        self.buf = request(&self.ctx, label, None, self.route.clone(), req).await?;
+        let a: Option<AttributesPage> = decode_option(label, None, &self.buf)?;
+        Ok(a.unwrap())
+    }
 }
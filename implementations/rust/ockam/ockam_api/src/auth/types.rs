@@ -1,6 +1,9 @@
 use minicbor::{Decode, Encode};
+use ockam_core::compat::collections::BTreeMap;
 #[cfg(feature = "tag")]
 use ockam_core::TypeTag;
+use ockam_identity::authenticated_storage::AttributesEntry;
+use ockam_identity::IdentityIdentifier;
 
 #[derive(Debug, Clone, Encode, Decode)]
 #[rustfmt::skip]
@@ -26,3 +29,110 @@ impl<'a> Attribute<'a> {
         self.val
     }
 }
+
+/// Request a page of the stored attribute entries.
+#[derive(Debug, Clone, Default, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ListRequest {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3520411>,
+    #[n(1)] offset: u32,
+    #[n(2)] limit: Option<u32>,
+    /// Only list the entries having all these attribute values
+    #[n(3)] filter: Option<BTreeMap<String, String>>,
+}
+
+impl ListRequest {
+    pub fn new(offset: u32, limit: impl Into<Option<u32>>) -> Self {
+        ListRequest {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            offset,
+            limit: limit.into(),
+            filter: None,
+        }
+    }
+
+    /// Only list the entries whose attribute `key` has the given value.
+    pub fn with_filter(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.filter
+            .get_or_insert_with(BTreeMap::new)
+            .insert(key.into(), value.into());
+        self
+    }
+
+    /// Number of entries to skip.
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Maximum number of entries to return, all the remaining ones if not set.
+    pub fn limit(&self) -> Option<u32> {
+        self.limit
+    }
+
+    /// Attribute values the listed entries must all have, as stored.
+    pub fn filter(&self) -> BTreeMap<String, Vec<u8>> {
+        self.filter
+            .iter()
+            .flatten()
+            .map(|(k, v)| (k.clone(), v.as_bytes().to_vec()))
+            .collect()
+    }
+}
+
+/// A page of the stored attribute entries, ordered by identity identifier.
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct AttributesPage {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<8164937>,
+    #[n(1)] entries: Vec<(IdentityIdentifier, AttributesEntry)>,
+    #[n(2)] total: u32,
+    #[n(3)] next_offset: Option<u32>,
+}
+
+impl AttributesPage {
+    /// Select the page described by `req` from all the entries.
+    pub fn new(mut entries: Vec<(IdentityIdentifier, AttributesEntry)>, req: &ListRequest) -> Self {
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        let total = entries.len() as u32;
+        let start = req.offset.min(total);
+        let end = match req.limit {
+            Some(limit) => start.saturating_add(limit).min(total),
+            None => total,
+        };
+        let entries = entries
+            .into_iter()
+            .skip(start as usize)
+            .take((end - start) as usize)
+            .collect();
+        AttributesPage {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            entries,
+            total,
+            next_offset: if end < total { Some(end) } else { None },
+        }
+    }
+
+    pub fn entries(&self) -> &[(IdentityIdentifier, AttributesEntry)] {
+        &self.entries
+    }
+
+    pub fn into_entries(self) -> Vec<(IdentityIdentifier, AttributesEntry)> {
+        self.entries
+    }
+
+    /// Total number of stored entries.
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+
+    /// Offset of the next page, if there are more entries.
+    pub fn next_offset(&self) -> Option<u32> {
+        self.next_offset
+    }
+}
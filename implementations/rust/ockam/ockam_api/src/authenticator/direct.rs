@@ -1,19 +1,20 @@
 pub mod types;
 
 use core::{fmt, str};
-use std::collections::HashMap;
-use std::num::NonZeroUsize;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 use std::time::{Duration, Instant};
 
-use lru::LruCache;
 use minicbor::{Decoder, Encode};
 use ockam::identity::authenticated_storage::{
+    AttributeType,
     AttributesEntry,
     AuthenticatedStorage,
     IdentityAttributeStorage,
 };
-use ockam::identity::credential::{Credential, OneTimeCode, SchemaId, Timestamp};
+use ockam::identity::credential::{
+    Credential, CredentialBuilder, OneTimeCode, SchemaId, Timestamp, MAX_CREDENTIAL_VALIDITY,
+};
 use ockam::identity::{
     Identity,
     IdentityIdentifier,
@@ -29,21 +30,25 @@ use ockam_core::api::{
     Request,
     RequestBuilder,
     Response,
-    ResponseBuilder,
     Status,
 };
 use ockam_core::errcode::{Kind, Origin};
-use ockam_core::{self, Address, DenyAll, Result, Route, Routed, Worker};
+use ockam_core::{self, Address, CowStr, DenyAll, Result, Route, Routed, Worker};
 use ockam_node::Context;
 use serde_json as json;
 use tracing::{trace, warn};
 use types::AddMember;
 
-use self::types::Enroller;
-use crate::authenticator::direct::types::CreateToken;
+use self::types::{AttributeValue, AuthDenyReason, Enroller};
+use crate::authenticator::direct::types::{CreateToken, CreateTokens};
 
 const LEGACY_MEMBER: &str = "member";
-const MAX_TOKEN_DURATION: Duration = Duration::from_secs(600);
+/// Default validity of an enrollment token.
+pub const DEFAULT_TOKEN_DURATION: Duration = Duration::from_secs(600);
+/// Default validity of an issued membership credential.
+pub const DEFAULT_CREDENTIAL_TTL: Duration = Duration::from_secs(24 * 3600);
+/// Maximum number of enrollment tokens created by a single request.
+pub const MAX_TOKENS_PER_REQUEST: u32 = 100;
 
 /// Schema identifier for a project membership credential.
 ///
@@ -61,13 +66,18 @@ pub struct Server<S: AuthenticatedStorage, IS: IdentityAttributeStorage, V: Iden
     filename: Option<String>,
     enrollers: HashMap<IdentityIdentifier, Enroller>,
     reload_enrollers: bool,
-    tokens: LruCache<[u8; 32], Token>,
+    tokens: HashMap<[u8; 32], Token>,
+    token_duration: Duration,
+    credential_ttl: Duration,
+    inherited_attributes: Vec<String>,
 }
 
 struct Token {
-    attrs: HashMap<String, String>,
+    attrs: HashMap<String, AttributeValue<'static>>,
     generated_by: IdentityIdentifier,
     time: Instant,
+    remaining_uses: u32,
+    requestable_attrs: Vec<String>,
 }
 
 #[ockam_core::worker]
@@ -87,7 +97,7 @@ where
         } else {
             let mut dec = Decoder::new(m.as_body());
             let req: Request = dec.decode()?;
-            let res = api::forbidden(&req, "secure channel required").to_vec()?;
+            let res = forbidden(&req, AuthDenyReason::SecureChannelRequired)?;
             c.send(m.return_route(), res).await
         }
     }
@@ -133,22 +143,74 @@ where
             filename,
             enrollers: enrollers_data,
             reload_enrollers,
-            tokens: LruCache::new(NonZeroUsize::new(128).expect("0 < 128")),
+            tokens: HashMap::new(),
+            token_duration: DEFAULT_TOKEN_DURATION,
+            credential_ttl: DEFAULT_CREDENTIAL_TTL,
+            inherited_attributes: Vec::new(),
         })
     }
 
+    /// Set how long an enrollment token stays valid after its creation.
+    pub fn with_token_duration(mut self, duration: Duration) -> Self {
+        self.token_duration = duration;
+        self
+    }
+
+    /// Set how long issued credentials stay valid, at most [`MAX_CREDENTIAL_VALIDITY`].
+    pub fn with_credential_ttl(mut self, ttl: Duration) -> Self {
+        self.credential_ttl = ttl.min(MAX_CREDENTIAL_VALIDITY);
+        self
+    }
+
+    /// Copy these attributes of an enroller to the members it adds or creates tokens for.
+    ///
+    /// An inherited attribute replaces the value given by the enroller for it, so that
+    /// an enroller can't enroll members with another value than its own.
+    pub fn with_inherited_attributes(mut self, attributes: Vec<String>) -> Self {
+        self.inherited_attributes = attributes;
+        self
+    }
+
+    /// The inherited attributes of an enroller, which doesn't need to have all of them.
+    async fn enroller_attributes(
+        &self,
+        enroller: &IdentityIdentifier,
+    ) -> Result<HashMap<String, AttributeValue<'static>>> {
+        if self.inherited_attributes.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let entry = match self.store.get_attributes(enroller).await? {
+            Some(entry) => entry,
+            None => return Ok(HashMap::new()),
+        };
+        Ok(self
+            .inherited_attributes
+            .iter()
+            .filter_map(|name| {
+                let value = entry.attrs().get(name)?;
+                Some((name.clone(), attribute_value(value, entry.attr_type(name))))
+            })
+            .collect())
+    }
+
+    /// Builder of a membership credential valid from now on for the configured ttl.
+    fn credential_builder(&self, subject: &IdentityIdentifier) -> Result<CredentialBuilder> {
+        let now = Timestamp::now().ok_or_else(|| {
+            ockam_core::Error::new(Origin::Core, Kind::Internal, "invalid system time")
+        })?;
+        Ok(Credential::builder(subject.clone())
+            .with_schema(PROJECT_MEMBER_SCHEMA)
+            .with_not_before(now)
+            .valid_for(self.credential_ttl))
+    }
+
     fn parse_enrollers(
         json_or_path: &str,
     ) -> Result<(Option<String>, HashMap<IdentityIdentifier, Enroller>)> {
         match json::from_str::<HashMap<IdentityIdentifier, Enroller>>(json_or_path) {
             Ok(enrollers) => Ok((None, enrollers)),
             Err(_) => {
-                let contents = std::fs::read_to_string(json_or_path)
-                    .map_err(|e| ockam_core::Error::new(Origin::Other, Kind::Io, e))?;
-
-                let enrollers = json::from_str(&contents)
-                    .map_err(|e| ockam_core::Error::new(Origin::Other, Kind::Invalid, e))?;
-
+                let enrollers = read_enrollers_file(Path::new(json_or_path))?;
                 Ok((Some(json_or_path.to_string()), enrollers))
             }
         }
@@ -174,93 +236,152 @@ where
                 ["tokens"] => match self.check_enroller(&req, from).await {
                     Ok(None) => {
                         let att: CreateToken = dec.decode()?;
-                        let otc = OneTimeCode::new();
-                        let res = Response::ok(req.id()).body(&otc).to_vec()?;
-                        let tkn = Token {
-                            attrs: att.into_owned_attributes(),
-                            generated_by: from.clone(),
-                            time: Instant::now(),
-                        };
-                        self.tokens.put(*otc.code(), tkn);
-                        res
+                        match att.max_uses().unwrap_or(1) {
+                            0 => api::bad_request(&req, "token max uses must be positive")
+                                .to_vec()?,
+                            remaining_uses => {
+                                let requestable = att.requestable_attributes();
+                                let mut attrs = att.into_owned_attributes();
+                                attrs.extend(self.enroller_attributes(from).await?);
+                                let otc = self.add_token(attrs, from, remaining_uses, requestable);
+                                Response::ok(req.id()).body(&otc).to_vec()?
+                            }
+                        }
+                    }
+                    Ok(Some(e)) => e,
+                    Err(e) => api::internal_error(&req, &e.to_string()).to_vec()?,
+                },
+                // Enroller wants to create several enrollment tokens at once.
+                ["tokens", "batch"] => match self.check_enroller(&req, from).await {
+                    Ok(None) => {
+                        let att: CreateTokens = dec.decode()?;
+                        match att.count() {
+                            count @ 1..=MAX_TOKENS_PER_REQUEST => {
+                                let mut attrs = att.into_owned_attributes();
+                                attrs.extend(self.enroller_attributes(from).await?);
+                                let otcs: Vec<OneTimeCode> = (0..count)
+                                    .map(|_| self.add_token(attrs.clone(), from, 1, Vec::new()))
+                                    .collect();
+                                Response::ok(req.id()).body(&otcs).to_vec()?
+                            }
+                            _ => {
+                                let msg = format!(
+                                    "token count must be between 1 and {MAX_TOKENS_PER_REQUEST}"
+                                );
+                                api::bad_request(&req, &msg).to_vec()?
+                            }
+                        }
                     }
-                    Ok(Some(e)) => e.to_vec()?,
+                    Ok(Some(e)) => e,
                     Err(e) => api::internal_error(&req, &e.to_string()).to_vec()?,
                 },
                 // Enroller wants to add a member.
                 ["members"] => match self.check_enroller(&req, from).await {
                     Ok(None) => {
                         let add: AddMember = dec.decode()?;
-                        //TODO: fixme:  unify use of hashmap vs btreemap
-                        let attrs = add
+                        let mut attrs: HashMap<String, AttributeValue> = add
                             .attributes()
                             .iter()
-                            .map(|(k, v)| (k.to_string(), v.as_bytes().to_vec()))
+                            .map(|(k, v)| (k.to_string(), v.clone()))
                             .collect();
-                        let entry = AttributesEntry::new(
-                            attrs,
-                            Timestamp::now().unwrap(),
-                            None,
-                            Some(from.clone()),
-                        );
+                        attrs.extend(self.enroller_attributes(from).await?);
+                        let entry = attributes_entry(&attrs, from.clone());
                         self.store.put_attributes(add.member(), entry).await?;
                         Response::ok(req.id()).to_vec()?
                     }
-                    Ok(Some(e)) => e.to_vec()?,
+                    Ok(Some(e)) => e,
                     Err(error) => api::internal_error(&req, &error.to_string()).to_vec()?,
                 },
                 // New member with an enrollment token wants its first credential.
                 ["credential"] if req.has_body() => {
                     let otc: OneTimeCode = dec.decode()?;
-                    if let Some(tkn) = self.tokens.pop(otc.code()) {
-                        if tkn.time.elapsed() > MAX_TOKEN_DURATION {
-                            api::forbidden(&req, "expired token").to_vec()?
-                        } else {
-                            //TODO: fixme:  unify use of hashmap vs btreemap
-                            let attrs = tkn
-                                .attrs
-                                .iter()
-                                .map(|(k, v)| (k.to_string(), v.as_bytes().to_vec()))
-                                .collect();
-                            let entry = AttributesEntry::new(
-                                attrs,
-                                Timestamp::now().unwrap(),
-                                None,
-                                Some(tkn.generated_by),
-                            );
-                            self.store.put_attributes(from, entry).await?;
-                            //TODO: use the entry not the token
-                            let crd = tkn
-                                .attrs
-                                .iter()
-                                .fold(Credential::builder(from.clone()), |crd, (a, v)| {
-                                    crd.with_attribute(a, v.as_bytes())
-                                })
-                                .with_schema(PROJECT_MEMBER_SCHEMA)
-                                .with_attribute(PROJECT_ID, &self.project);
-                            let crd = self.ident.issue_credential(crd).await?;
-                            Response::ok(req.id()).body(crd).to_vec()?
+                    // The code may be followed by the attributes requested by the member.
+                    let requested: HashMap<CowStr, AttributeValue> = if dec.datatype().is_ok() {
+                        dec.decode()?
+                    } else {
+                        HashMap::new()
+                    };
+                    // Expiry takes precedence over any remaining uses, and over the
+                    // requested attributes.
+                    let expired = self
+                        .tokens
+                        .get(otc.code())
+                        .map(|tkn| tkn.time.elapsed() > self.token_duration)
+                        .unwrap_or(false);
+                    // Rejecting the requested attributes doesn't use the token up.
+                    let disallowed = self
+                        .tokens
+                        .get(otc.code())
+                        .map(|tkn| disallowed_attributes(&requested, &tkn.requestable_attrs))
+                        .unwrap_or_default();
+                    if expired {
+                        self.tokens.remove(otc.code());
+                        forbidden(&req, AuthDenyReason::ExpiredToken)?
+                    } else if !disallowed.is_empty() {
+                        let msg =
+                            format!("attributes can't be requested: {}", disallowed.join(", "));
+                        api::bad_request(&req, &msg).to_vec()?
+                    } else if let Some(mut tkn) = self.tokens.remove(otc.code()) {
+                        tkn.remaining_uses = tkn.remaining_uses.saturating_sub(1);
+                        let mut tkn_attrs = tkn.attrs.clone();
+                        // Values set by the enroller take precedence over requested ones.
+                        for (k, v) in requested {
+                            tkn_attrs
+                                .entry(k.to_string())
+                                .or_insert_with(|| v.into_owned());
+                        }
+                        let generated_by = tkn.generated_by.clone();
+                        if tkn.remaining_uses > 0 {
+                            self.tokens.insert(*otc.code(), tkn);
                         }
+                        let entry = attributes_entry(&tkn_attrs, generated_by);
+                        self.store.put_attributes(from, entry).await?;
+                        //TODO: use the entry not the token
+                        let crd = self
+                            .credential_builder(from)?
+                            .with_attributes(tkn_attrs.iter().map(|(a, v)| (a, v.to_bytes())))
+                            .with_attribute(PROJECT_ID, &self.project);
+                        let crd = self.ident.issue_credential(crd).await?;
+                        Response::ok(req.id()).body(crd).to_vec()?
                     } else {
-                        api::forbidden(&req, "unknown token").to_vec()?
+                        forbidden(&req, AuthDenyReason::UnknownToken)?
                     }
                 }
                 // Member wants a credential.
                 ["credential"] => match self.store.get_attributes(from).await {
                     Ok(Some(entry)) => {
-                        let crd = entry
-                            .attrs()
-                            .iter()
-                            .fold(
-                                Credential::builder(from.clone())
-                                    .with_schema(PROJECT_MEMBER_SCHEMA),
-                                |crd, (a, v)| crd.with_attribute(a, v),
-                            )
+                        // Every request gets a credential with a fresh validity window.
+                        let crd = self
+                            .credential_builder(from)?
+                            .with_attributes(entry.attrs())
                             .with_attribute(PROJECT_ID, &self.project);
                         let crd = self.ident.issue_credential(crd).await?;
                         Response::ok(req.id()).body(crd).to_vec()?
                     }
-                    Ok(None) => api::forbidden(&req, "unauthorized member").to_vec()?,
+                    Ok(None) => forbidden(&req, AuthDenyReason::UnauthorizedMember)?,
+                    Err(error) => api::internal_error(&req, &error.to_string()).to_vec()?,
+                },
+                _ => api::unknown_path(&req).to_vec()?,
+            },
+            Some(Method::Delete) => match req.path_segments::<2>().as_slice() {
+                // Enroller wants to revoke a member.
+                ["members", id] => match self.check_enroller(&req, from).await {
+                    Ok(None) => match IdentityIdentifier::try_from(id.to_string()) {
+                        Ok(member) => {
+                            if self.store.get_attributes(&member).await?.is_none() {
+                                Response::not_found(req.id()).to_vec()?
+                            } else {
+                                match self.store.delete_attributes(&member).await {
+                                    Ok(()) => Response::ok(req.id()).to_vec()?,
+                                    Err(error) => {
+                                        api::internal_error(&req, &error.to_string()).to_vec()?
+                                    }
+                                }
+                            }
+                        }
+                        Err(_) => api::bad_request(&req, "invalid member identifier").to_vec()?,
+                    },
+                    Ok(Some(e)) => e,
                     Err(error) => api::internal_error(&req, &error.to_string()).to_vec()?,
                 },
                 _ => api::unknown_path(&req).to_vec()?,
@@ -271,21 +392,39 @@ where
         Ok(res)
     }
 
-    async fn check_enroller<'a>(
+    /// Store a new enrollment token and return its code.
+    ///
+    /// Tokens are kept until they are used up or expire, so expired tokens
+    /// are dropped first to keep the number of stored tokens bounded.
+    fn add_token(
+        &mut self,
+        attrs: HashMap<String, AttributeValue<'static>>,
+        generated_by: &IdentityIdentifier,
+        remaining_uses: u32,
+        requestable_attrs: Vec<String>,
+    ) -> OneTimeCode {
+        let duration = self.token_duration;
+        self.tokens.retain(|_, tkn| tkn.time.elapsed() <= duration);
+        let otc = OneTimeCode::new();
+        let tkn = Token {
+            attrs,
+            generated_by: generated_by.clone(),
+            time: Instant::now(),
+            remaining_uses,
+            requestable_attrs,
+        };
+        self.tokens.insert(*otc.code(), tkn);
+        otc
+    }
+
+    async fn check_enroller(
         &mut self,
-        req: &'a Request<'_>,
+        req: &Request<'_>,
         enroller: &IdentityIdentifier,
-    ) -> Result<Option<ResponseBuilder<Error<'a>>>> {
+    ) -> Result<Option<Vec<u8>>> {
         if self.reload_enrollers && self.filename.is_some() {
             let filename = self.filename.as_ref().unwrap();
-            let path = Path::new(&filename);
-            let contents = std::fs::read_to_string(path)
-                .map_err(|e| ockam_core::Error::new(Origin::Other, Kind::Io, e))?;
-
-            let enrollers: HashMap<IdentityIdentifier, Enroller> = json::from_str(&contents)
-                .map_err(|e| ockam_core::Error::new(Origin::Other, Kind::Invalid, e))?;
-
-            self.enrollers = enrollers;
+            self.enrollers = read_enrollers_file(Path::new(&filename))?;
         }
 
         if self.enrollers.contains_key(enroller) {
@@ -302,8 +441,113 @@ where
             "unauthorised enroller"
         }
 
-        Ok(Some(api::forbidden(req, "unauthorized enroller")))
+        Ok(Some(forbidden(req, AuthDenyReason::UnauthorizedEnroller)?))
+    }
+}
+
+/// Read an enrollers file, which may be annotated with `//` comments and
+/// have trailing commas.
+fn read_enrollers_file(path: &Path) -> Result<HashMap<IdentityIdentifier, Enroller>> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| ockam_core::Error::new(Origin::Other, Kind::Io, e))?;
+    json::from_str(&relax_json(&contents))
+        .map_err(|e| ockam_core::Error::new(Origin::Other, Kind::Invalid, e))
+}
+
+/// Turn a hand-written JSON document into strict JSON, by removing `//`
+/// comments and trailing commas outside of strings.
+fn relax_json(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                out.push(c)
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                // Skip the comment, but keep line numbers of parsing errors right
+                if chars.by_ref().any(|c| c == '\n') {
+                    out.push('\n')
+                }
+            }
+            '}' | ']' => {
+                let end = out.trim_end().len();
+                if out[..end].ends_with(',') {
+                    out.remove(end - 1);
+                }
+                out.push(c)
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Create an attributes entry, recording the type of the non-string values.
+fn attributes_entry<K: AsRef<str>>(
+    attrs: &HashMap<K, AttributeValue<'_>>,
+    attested_by: IdentityIdentifier,
+) -> AttributesEntry {
+    let mut values = BTreeMap::new();
+    let mut types = BTreeMap::new();
+    for (k, v) in attrs {
+        values.insert(k.as_ref().to_string(), v.to_bytes());
+        if v.attr_type() != AttributeType::Str {
+            types.insert(k.as_ref().to_string(), v.attr_type());
+        }
+    }
+    AttributesEntry::new(values, Timestamp::now().unwrap(), None, Some(attested_by))
+        .with_types(types)
+}
+
+/// The requested attributes that the token doesn't allow, sorted by name.
+fn disallowed_attributes(
+    requested: &HashMap<CowStr, AttributeValue>,
+    requestable: &[String],
+) -> Vec<String> {
+    let mut disallowed: Vec<String> = requested
+        .keys()
+        .filter(|k| !requestable.iter().any(|a| *k == a))
+        .map(|k| k.to_string())
+        .collect();
+    disallowed.sort();
+    disallowed
+}
+
+/// Read back a value stored in an attributes entry.
+fn attribute_value(bytes: &[u8], attr_type: AttributeType) -> AttributeValue<'static> {
+    let s = String::from_utf8_lossy(bytes);
+    match attr_type {
+        AttributeType::Int => s.parse().ok().map(AttributeValue::Int),
+        AttributeType::Bool => s.parse().ok().map(AttributeValue::Bool),
+        AttributeType::Str => None,
     }
+    .unwrap_or_else(|| AttributeValue::Str(s.into_owned().into()))
+}
+
+/// Create a forbidden response, with the deny reason appended to the error body.
+fn forbidden(req: &Request, reason: AuthDenyReason) -> Result<Vec<u8>> {
+    let mut buf = api::forbidden(req, reason.message()).to_vec()?;
+    minicbor::encode(reason, &mut buf)?;
+    Ok(buf)
+}
+
+/// Get the reason of an authenticator rejection from an error returned by the [`Client`].
+pub fn deny_reason(err: &ockam_core::Error) -> Option<AuthDenyReason> {
+    std::error::Error::source(err)?
+        .downcast_ref::<AuthDenyReason>()
+        .copied()
 }
 
 pub struct Client {
@@ -339,7 +583,7 @@ impl Client {
     pub async fn add_member(
         &mut self,
         id: IdentityIdentifier,
-        attributes: HashMap<&str, &str>,
+        attributes: HashMap<&str, AttributeValue<'_>>,
     ) -> Result<()> {
         let req = Request::post("/members").body(AddMember::new(id).with_attributes(attributes));
         self.buf = self.request("add-member", "add_member", &req).await?;
@@ -353,8 +597,41 @@ impl Client {
         }
     }
 
-    pub async fn create_token(&mut self, attributes: HashMap<&str, &str>) -> Result<OneTimeCode> {
-        let req = Request::post("/tokens").body(CreateToken::new().with_attributes(attributes));
+    /// Revoke a member, returns `false` if it was unknown.
+    pub async fn delete_member(&mut self, id: &IdentityIdentifier) -> Result<bool> {
+        let req = Request::delete(format!("/members/{id}"));
+        self.buf = self.request("delete-member", None, &req).await?;
+        let mut d = Decoder::new(&self.buf);
+        let res = response("delete-member", &mut d)?;
+        match res.status() {
+            Some(Status::Ok) => Ok(true),
+            Some(Status::NotFound) => Ok(false),
+            _ => Err(error("delete-member", &res, &mut d)),
+        }
+    }
+
+    pub async fn create_token(
+        &mut self,
+        attributes: HashMap<&str, AttributeValue<'_>>,
+        max_uses: Option<u32>,
+    ) -> Result<OneTimeCode> {
+        self.create_token_with_requestable_attributes(attributes, max_uses, Vec::new())
+            .await
+    }
+
+    /// Create an enrollment token whose member can request the `requestable` attributes.
+    pub async fn create_token_with_requestable_attributes(
+        &mut self,
+        attributes: HashMap<&str, AttributeValue<'_>>,
+        max_uses: Option<u32>,
+        requestable: Vec<String>,
+    ) -> Result<OneTimeCode> {
+        let req = Request::post("/tokens").body(
+            CreateToken::new()
+                .with_attributes(attributes)
+                .with_max_uses(max_uses)
+                .with_requestable_attributes(requestable),
+        );
         self.buf = self.request("create-token", "create_token", &req).await?;
         assert_response_match("onetime_code", &self.buf);
         let mut d = Decoder::new(&self.buf);
@@ -366,6 +643,25 @@ impl Client {
         }
     }
 
+    /// Create `count` single-use enrollment tokens sharing the same attributes.
+    pub async fn create_tokens(
+        &mut self,
+        count: u32,
+        attributes: HashMap<&str, AttributeValue<'_>>,
+    ) -> Result<Vec<OneTimeCode>> {
+        let req = Request::post("/tokens/batch")
+            .body(CreateTokens::new(count).with_attributes(attributes));
+        self.buf = self.request("create-tokens", "create_tokens", &req).await?;
+        assert_response_match("onetime_codes", &self.buf);
+        let mut d = Decoder::new(&self.buf);
+        let res = response("create-tokens", &mut d)?;
+        if res.status() == Some(Status::Ok) {
+            Ok(d.decode()?)
+        } else {
+            Err(error("create-tokens", &res, &mut d))
+        }
+    }
+
     pub async fn credential(&mut self) -> Result<Credential> {
         let req = Request::post("/credential");
         self.buf = self.request("new-credential", None, &req).await?;
@@ -380,7 +676,19 @@ impl Client {
     }
 
     pub async fn credential_with(&mut self, c: &OneTimeCode) -> Result<Credential> {
-        let req = Request::post("/credential").body(c);
+        self.credential_with_attributes(c, HashMap::new()).await
+    }
+
+    /// Redeem an enrollment token, requesting attributes the token allows.
+    pub async fn credential_with_attributes(
+        &mut self,
+        c: &OneTimeCode,
+        attributes: HashMap<&str, AttributeValue<'_>>,
+    ) -> Result<Credential> {
+        let req = Request::post("/credential").body(RedeemToken {
+            code: c,
+            attributes,
+        });
         self.buf = self.request("new-credential", None, &req).await?;
         assert_response_match("credential", &self.buf);
         let mut d = Decoder::new(&self.buf);
@@ -418,6 +726,30 @@ impl Client {
     }
 }
 
+/// Body of a token redemption: the enrollment token, followed by the
+/// requested attributes if there are any.
+struct RedeemToken<'a> {
+    code: &'a OneTimeCode,
+    attributes: HashMap<&'a str, AttributeValue<'a>>,
+}
+
+impl<C> Encode<C> for RedeemToken<'_> {
+    fn encode<W>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+        ctx: &mut C,
+    ) -> core::result::Result<(), minicbor::encode::Error<W::Error>>
+    where
+        W: minicbor::encode::Write,
+    {
+        e.encode_with(self.code, ctx)?;
+        if !self.attributes.is_empty() {
+            e.encode_with(&self.attributes, ctx)?;
+        }
+        Ok(())
+    }
+}
+
 /// Decode and log response header.
 fn response(label: &str, dec: &mut Decoder<'_>) -> Result<Response> {
     let res: Response = dec.decode()?;
@@ -447,9 +779,36 @@ fn error(label: &str, res: &Response, dec: &mut Decoder<'_>) -> ockam_core::Erro
             error  = ?err.message(),
             "<- {label}"
         }
+        if let Ok(reason) = dec.decode::<AuthDenyReason>() {
+            let kind = match reason {
+                AuthDenyReason::ExpiredToken | AuthDenyReason::UnknownToken => Kind::Invalid,
+                _ => Kind::Protocol,
+            };
+            return ockam_core::Error::new(Origin::Application, kind, reason);
+        }
         let msg = err.message().unwrap_or(label);
         ockam_core::Error::new(Origin::Application, Kind::Protocol, msg)
     } else {
         ockam_core::Error::new(Origin::Application, Kind::Protocol, label)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::relax_json;
+
+    #[test]
+    fn relaxed_json() {
+        let s = r#"{
+            // an enroller
+            "a": {}, // with a trailing comment
+            "b": ["x", "y",],
+        }"#;
+        let v: serde_json::Value = serde_json::from_str(&relax_json(s)).unwrap();
+        assert_eq!(serde_json::json!({"a": {}, "b": ["x", "y"]}), v);
+
+        // Strings are left untouched
+        let s = r#"{"a": "http://x,}", "b": "\"//\""}"#;
+        assert_eq!(s, relax_json(s));
+    }
+}
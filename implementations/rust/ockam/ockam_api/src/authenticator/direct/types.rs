@@ -1,6 +1,10 @@
 use std::collections::HashMap;
+use std::fmt;
 
-use minicbor::{Decode, Encode};
+use minicbor::data::Type;
+use minicbor::encode::{self, Write};
+use minicbor::{decode, Decode, Decoder, Encode, Encoder};
+use ockam::identity::authenticated_storage::AttributeType;
 use ockam_core::CowStr;
 #[cfg(feature = "tag")]
 use ockam_core::TypeTag;
@@ -14,7 +18,7 @@ pub struct AddMember<'a> {
     #[cfg(feature = "tag")]
     #[n(0)] tag: TypeTag<2820828>,
     #[n(1)] member: IdentityIdentifier,
-    #[b(2)] attributes: HashMap<CowStr<'a>, CowStr<'a>>,
+    #[b(2)] attributes: HashMap<CowStr<'a>, AttributeValue<'a>>,
 }
 
 impl<'a> AddMember<'a> {
@@ -27,7 +31,11 @@ impl<'a> AddMember<'a> {
         }
     }
 
-    pub fn with_attributes<S: Into<CowStr<'a>>>(mut self, attributes: HashMap<S, S>) -> Self {
+    pub fn with_attributes<K, V>(mut self, attributes: HashMap<K, V>) -> Self
+    where
+        K: Into<CowStr<'a>>,
+        V: Into<AttributeValue<'a>>,
+    {
         self.attributes = attributes
             .into_iter()
             .map(|(k, v)| (k.into(), v.into()))
@@ -39,7 +47,7 @@ impl<'a> AddMember<'a> {
         &self.member
     }
 
-    pub fn attributes(&self) -> &HashMap<CowStr, CowStr> {
+    pub fn attributes(&self) -> &HashMap<CowStr, AttributeValue> {
         &self.attributes
     }
 }
@@ -53,7 +61,10 @@ pub struct Enroller {}
 pub struct CreateToken<'a> {
     #[cfg(feature = "tag")]
     #[n(0)] tag: TypeTag<2502742>,
-    #[b(1)] attributes: HashMap<CowStr<'a>, CowStr<'a>>,
+    #[b(1)] attributes: HashMap<CowStr<'a>, AttributeValue<'a>>,
+    #[n(2)] max_uses: Option<u32>,
+    /// Attributes the member can request when redeeming the token
+    #[b(3)] requestable_attributes: Option<Vec<CowStr<'a>>>,
 }
 
 impl<'a> CreateToken<'a> {
@@ -63,10 +74,16 @@ impl<'a> CreateToken<'a> {
             #[cfg(feature = "tag")]
             tag: TypeTag,
             attributes: HashMap::new(),
+            max_uses: None,
+            requestable_attributes: None,
         }
     }
 
-    pub fn with_attributes<S: Into<CowStr<'a>>>(mut self, attributes: HashMap<S, S>) -> Self {
+    pub fn with_attributes<K, V>(mut self, attributes: HashMap<K, V>) -> Self
+    where
+        K: Into<CowStr<'a>>,
+        V: Into<AttributeValue<'a>>,
+    {
         self.attributes = attributes
             .into_iter()
             .map(|(k, v)| (k.into(), v.into()))
@@ -74,10 +91,244 @@ impl<'a> CreateToken<'a> {
         self
     }
 
-    pub fn into_owned_attributes(self) -> HashMap<String, String> {
+    pub fn with_max_uses(mut self, max_uses: impl Into<Option<u32>>) -> Self {
+        self.max_uses = max_uses.into();
+        self
+    }
+
+    pub fn with_requestable_attributes(mut self, attributes: Vec<String>) -> Self {
+        if !attributes.is_empty() {
+            self.requestable_attributes = Some(attributes.into_iter().map(CowStr::from).collect());
+        }
+        self
+    }
+
+    /// Number of times the token can be redeemed, defaults to a single use.
+    pub fn max_uses(&self) -> Option<u32> {
+        self.max_uses
+    }
+
+    /// Attributes the member can request when redeeming the token, none by default.
+    pub fn requestable_attributes(&self) -> Vec<String> {
+        self.requestable_attributes
+            .iter()
+            .flatten()
+            .map(|a| a.to_string())
+            .collect()
+    }
+
+    pub fn into_owned_attributes(self) -> HashMap<String, AttributeValue<'static>> {
+        self.attributes
+            .into_iter()
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect()
+    }
+}
+
+#[derive(Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CreateTokens<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4390727>,
+    #[b(1)] attributes: HashMap<CowStr<'a>, AttributeValue<'a>>,
+    #[n(2)] count: u32,
+}
+
+impl<'a> CreateTokens<'a> {
+    pub fn new(count: u32) -> Self {
+        CreateTokens {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            attributes: HashMap::new(),
+            count,
+        }
+    }
+
+    pub fn with_attributes<K, V>(mut self, attributes: HashMap<K, V>) -> Self
+    where
+        K: Into<CowStr<'a>>,
+        V: Into<AttributeValue<'a>>,
+    {
+        self.attributes = attributes
+            .into_iter()
+            .map(|(k, v)| (k.into(), v.into()))
+            .collect();
+        self
+    }
+
+    /// Number of tokens to create, all sharing the same attributes.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    pub fn into_owned_attributes(self) -> HashMap<String, AttributeValue<'static>> {
         self.attributes
             .into_iter()
             .map(|(k, v)| (k.into_owned(), v.into_owned()))
             .collect()
     }
 }
+
+/// Value of a member attribute.
+///
+/// Strings are encoded as CBOR text, like attributes were before values
+/// were typed, integers and booleans use their native CBOR encoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeValue<'a> {
+    Str(CowStr<'a>),
+    Int(i64),
+    Bool(bool),
+}
+
+impl AttributeValue<'_> {
+    pub fn attr_type(&self) -> AttributeType {
+        match self {
+            AttributeValue::Str(_) => AttributeType::Str,
+            AttributeValue::Int(_) => AttributeType::Int,
+            AttributeValue::Bool(_) => AttributeType::Bool,
+        }
+    }
+
+    /// The value as stored in attribute entries and credentials.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_string().into_bytes()
+    }
+
+    pub fn into_owned(self) -> AttributeValue<'static> {
+        match self {
+            AttributeValue::Str(s) => AttributeValue::Str(s.into_owned().into()),
+            AttributeValue::Int(i) => AttributeValue::Int(i),
+            AttributeValue::Bool(b) => AttributeValue::Bool(b),
+        }
+    }
+}
+
+impl fmt::Display for AttributeValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttributeValue::Str(s) => f.write_str(s),
+            AttributeValue::Int(i) => write!(f, "{i}"),
+            AttributeValue::Bool(b) => write!(f, "{b}"),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for AttributeValue<'a> {
+    fn from(s: &'a str) -> Self {
+        AttributeValue::Str(s.into())
+    }
+}
+
+impl From<String> for AttributeValue<'_> {
+    fn from(s: String) -> Self {
+        AttributeValue::Str(s.into())
+    }
+}
+
+impl From<i64> for AttributeValue<'_> {
+    fn from(i: i64) -> Self {
+        AttributeValue::Int(i)
+    }
+}
+
+impl From<bool> for AttributeValue<'_> {
+    fn from(b: bool) -> Self {
+        AttributeValue::Bool(b)
+    }
+}
+
+impl<C> Encode<C> for AttributeValue<'_> {
+    fn encode<W>(&self, e: &mut Encoder<W>, _: &mut C) -> Result<(), encode::Error<W::Error>>
+    where
+        W: Write,
+    {
+        match self {
+            AttributeValue::Str(s) => e.str(s)?.ok(),
+            AttributeValue::Int(i) => e.i64(*i)?.ok(),
+            AttributeValue::Bool(b) => e.bool(*b)?.ok(),
+        }
+    }
+}
+
+impl<'b, C> Decode<'b, C> for AttributeValue<'b> {
+    fn decode(d: &mut Decoder<'b>, _: &mut C) -> Result<Self, decode::Error> {
+        match d.datatype()? {
+            Type::Bool => Ok(AttributeValue::Bool(d.bool()?)),
+            Type::U8
+            | Type::U16
+            | Type::U32
+            | Type::U64
+            | Type::I8
+            | Type::I16
+            | Type::I32
+            | Type::I64 => Ok(AttributeValue::Int(d.i64()?)),
+            _ => Ok(AttributeValue::Str(d.str()?.into())),
+        }
+    }
+}
+
+/// Why the authenticator rejected a request with `403 Forbidden`.
+///
+/// It is encoded in the response body right after the [`ockam_core::api::Error`],
+/// so clients unaware of it can keep using the error message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum AuthDenyReason {
+    #[n(0)] SecureChannelRequired,
+    #[n(1)] ExpiredToken,
+    #[n(2)] UnknownToken,
+    #[n(3)] UnauthorizedMember,
+    #[n(4)] UnauthorizedEnroller,
+}
+
+impl AuthDenyReason {
+    /// Human readable message, sent as the error message of the response.
+    pub fn message(&self) -> &'static str {
+        match self {
+            AuthDenyReason::SecureChannelRequired => "secure channel required",
+            AuthDenyReason::ExpiredToken => "expired token",
+            AuthDenyReason::UnknownToken => "unknown token",
+            AuthDenyReason::UnauthorizedMember => "unauthorized member",
+            AuthDenyReason::UnauthorizedEnroller => "unauthorized enroller",
+        }
+    }
+}
+
+impl fmt::Display for AuthDenyReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AuthDenyReason {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attribute_values_decode_from_untyped_attributes() {
+        let untyped = HashMap::from([("role", "member")]);
+        let bytes = minicbor::to_vec(&untyped).unwrap();
+        let typed: HashMap<CowStr, AttributeValue> = minicbor::decode(&bytes).unwrap();
+        assert_eq!(
+            Some(&AttributeValue::from("member")),
+            typed.get(&CowStr::from("role"))
+        );
+    }
+
+    #[test]
+    fn attribute_values_roundtrip() {
+        let attrs = HashMap::from([
+            (CowStr::from("role"), AttributeValue::from("member")),
+            (CowStr::from("level"), AttributeValue::Int(-3)),
+            (CowStr::from("admin"), AttributeValue::Bool(true)),
+        ]);
+        let bytes = minicbor::to_vec(&attrs).unwrap();
+        let decoded: HashMap<CowStr, AttributeValue> = minicbor::decode(&bytes).unwrap();
+        assert_eq!(attrs, decoded);
+        assert_eq!(b"-3".to_vec(), decoded[&CowStr::from("level")].to_bytes());
+    }
+}
@@ -86,6 +86,22 @@ impl<B: IdentityAttributeStorageReader, S: IdentityAttributeStorage> IdentityAtt
             )),
         }
     }
+
+    async fn delete_attributes(&self, identity: &IdentityIdentifier) -> Result<()> {
+        trace! {
+            target: "ockam_api::bootstrapped_identities_store",
+            id     = %identity,
+            "delete_attributes"
+        }
+        match self.bootstrapped.get_attributes(identity).await? {
+            None => self.storage.delete_attributes(identity).await,
+            Some(_) => Err(ockam_core::Error::new(
+                Origin::Identity,
+                Kind::Invalid,
+                "cant delete attributes of a bootstrapped identity",
+            )),
+        }
+    }
 }
 
 impl<B: IdentityAttributeStorageReader, S: IdentityAttributeStorage> IdentityAttributeStorage
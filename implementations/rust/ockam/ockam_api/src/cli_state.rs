@@ -1,23 +1,30 @@
+use std::cell::Cell;
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Duration, Instant, SystemTime};
 
+use fs2::FileExt;
 use nix::errno::Errno;
-use ockam::compat::tokio;
 use ockam_identity::change_history::{IdentityChangeHistory, IdentityHistoryComparison};
+use ockam_identity::credential::Credential;
 use ockam_identity::{Identity, IdentityIdentifier, SecureChannelRegistry};
 use ockam_vault::storage::FileStorage;
 use ockam_vault::Vault;
 use rand::random;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use sysinfo::{Pid, System, SystemExt};
 use thiserror::Error;
 
 use crate::cloud::project::Project;
 use crate::lmdb::LmdbStorage;
+use crate::nodes::attributes_storage::AttributesStorageKind;
 use crate::nodes::models::transport::{CreateTransportJson, TransportMode, TransportType};
 
 type Result<T> = std::result::Result<T, CliStateError>;
@@ -38,6 +45,16 @@ pub enum CliStateError {
     Invalid(String),
     #[error("invalid state version {0}")]
     InvalidVersion(String),
+    #[error("timed out waiting for the lock on {0}, another ockam process may be holding it")]
+    LockTimeout(String),
+    #[error("the node was created with --no-api-transport, it can only be reached through an embedded node in its own process")]
+    NoApiTransport,
+    #[error("the {entity} stored in {} is corrupt, run `ockam reset --repair` to remove it", .path.display())]
+    Corrupt {
+        entity: String,
+        path: PathBuf,
+        source: serde_json::Error,
+    },
     #[error("unknown error")]
     Unknown,
 }
@@ -67,6 +84,7 @@ pub struct CliState {
 impl CliState {
     pub fn new() -> Result<Self> {
         let dir = Self::dir()?;
+        // Creating the directories is idempotent and safe to race, so no lock is taken
         std::fs::create_dir_all(dir.join("defaults"))?;
         Ok(Self {
             vaults: VaultsState::new(&dir)?,
@@ -88,13 +106,90 @@ impl CliState {
     }
 
     pub fn delete(&self, force: bool) -> Result<()> {
-        for n in self.nodes.list()? {
-            let _ = n.delete(force);
-        }
+        let _lock = StateLock::acquire()?;
+        self.stop_nodes(force)?;
         std::fs::remove_dir_all(&self.dir)?;
         Ok(())
     }
 
+    /// Delete the nodes and the projects but keep the vaults and, if `keep_identities`
+    /// is set, the identities, so that they can be used again by new nodes.
+    pub fn delete_keeping_vaults(&self, keep_identities: bool, force: bool) -> Result<()> {
+        let _lock = StateLock::acquire()?;
+        self.stop_nodes(force)?;
+        // Nodes which couldn't be loaded are removed along with the others
+        std::fs::remove_dir_all(&self.nodes.dir)?;
+        std::fs::create_dir_all(&self.nodes.dir)?;
+        remove_if_exists(&self.nodes.default_path()?)?;
+
+        std::fs::remove_dir_all(&self.projects.dir)?;
+        std::fs::create_dir_all(self.projects.dir.join("data"))?;
+        remove_if_exists(&self.projects.default_path()?)?;
+
+        if !keep_identities {
+            std::fs::remove_dir_all(&self.identities.dir)?;
+            std::fs::create_dir_all(self.identities.dir.join("data"))?;
+            remove_if_exists(&self.identities.default_path()?)?;
+        }
+        Ok(())
+    }
+
+    /// Stop the processes of the nodes that can be loaded, skipping the corrupt ones
+    fn stop_nodes(&self, force: bool) -> Result<()> {
+        for name in self.nodes.names()? {
+            if let Ok(n) = self.nodes.get(&name) {
+                let _ = n.delete(force);
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove the vaults, identities, nodes and projects whose files can't be read anymore,
+    /// and the default links pointing to a removed entry. Return the removed paths.
+    pub fn repair(&self) -> Result<Vec<PathBuf>> {
+        let _lock = StateLock::acquire()?;
+        let mut removed = vec![];
+        for name in self.vaults.names()? {
+            if let Err(CliStateError::Corrupt { path, .. }) = self.vaults.get(&name) {
+                std::fs::remove_file(&path)?;
+                removed.push(path);
+            }
+        }
+        for name in self.identities.names()? {
+            if let Err(CliStateError::Corrupt { path, .. }) = self.identities.get(&name) {
+                std::fs::remove_file(&path)?;
+                removed.push(path);
+            }
+        }
+        for name in self.nodes.names()? {
+            let path = self.nodes.dir.join(&name);
+            if NodeConfig::try_from(&path).is_err() {
+                std::fs::remove_dir_all(&path)?;
+                removed.push(path);
+            }
+        }
+        for name in self.projects.names()? {
+            let project = self.projects.get(&name)?;
+            if project.is_corrupt()? {
+                std::fs::remove_file(&project.path)?;
+                removed.push(project.path);
+            }
+        }
+        // Done last, as the links may point to an entry removed above
+        for link in [
+            self.vaults.default_path()?,
+            self.identities.default_path()?,
+            self.nodes.default_path()?,
+            self.projects.default_path()?,
+        ] {
+            if link.is_symlink() && !link.exists() {
+                std::fs::remove_file(&link)?;
+                removed.push(link);
+            }
+        }
+        Ok(removed)
+    }
+
     pub fn dir() -> Result<PathBuf> {
         Ok(match std::env::var("OCKAM_HOME") {
             Ok(dir) => PathBuf::from(&dir),
@@ -107,6 +202,22 @@ impl CliState {
     fn defaults_dir() -> Result<PathBuf> {
         Ok(Self::dir()?.join("defaults"))
     }
+
+    fn upgrade_check_path(&self) -> PathBuf {
+        self.dir.join("upgrade_check.json")
+    }
+
+    /// Time at which the CLI last checked for a new release, if ever
+    pub fn last_upgrade_check(&self) -> Option<SystemTime> {
+        let contents = std::fs::read_to_string(self.upgrade_check_path()).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    pub fn set_last_upgrade_check(&self, time: SystemTime) -> Result<()> {
+        let contents = serde_json::to_string(&time)?;
+        let _lock = StateLock::acquire()?;
+        write_atomically(&self.upgrade_check_path(), contents)
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -123,18 +234,19 @@ impl VaultsState {
 
     pub async fn create(&self, name: &str, config: VaultConfig) -> Result<VaultState> {
         let path = {
+            let _lock = StateLock::acquire()?;
             let mut path = self.dir.clone();
             path.push(format!("{name}.json"));
             if path.exists() {
                 return Err(CliStateError::AlreadyExists(format!("vault `{name}`")));
             }
+            let contents = serde_json::to_string(&config)?;
+            write_atomically(&path, contents)?;
+            if !self.default_path()?.exists() {
+                self.set_default(name)?;
+            }
             path
         };
-        let contents = serde_json::to_string(&config)?;
-        std::fs::write(&path, contents)?;
-        if !self.default_path()?.exists() {
-            self.set_default(name)?;
-        }
         config.get().await?;
         Ok(VaultState {
             name: name.to_string(),
@@ -153,22 +265,28 @@ impl VaultsState {
             path
         };
         let name = file_stem(&path)?;
-        let contents = std::fs::read_to_string(&path)?;
-        let config = serde_json::from_str(&contents)?;
+        let config = read_json(&path, &format!("vault `{name}`"))?;
         Ok(VaultState { name, path, config })
     }
 
     pub fn list(&self) -> Result<Vec<VaultState>> {
         let mut vaults = Vec::default();
-        for entry in std::fs::read_dir(&self.dir)? {
-            if let Ok(vault) = self.get(&file_stem(&entry?.path())?) {
+        for name in self.names()? {
+            if let Ok(vault) = self.get(&name) {
                 vaults.push(vault);
             }
         }
         Ok(vaults)
     }
 
+    /// Names of the stored vaults, including the ones that can't be loaded
+    fn names(&self) -> Result<Vec<String>> {
+        json_file_stems(&self.dir)
+    }
+
     pub async fn delete(&self, name: &str) -> Result<()> {
+        let _lock = StateLock::acquire()?;
+
         // Retrieve vault. If doesn't exist do nothing.
         let vault_state = match self.get(name) {
             Ok(v) => v,
@@ -202,12 +320,12 @@ impl VaultsState {
     pub fn default(&self) -> Result<VaultState> {
         let path = std::fs::canonicalize(self.default_path()?)?;
         let name = file_stem(&path)?;
-        let contents = std::fs::read_to_string(&path)?;
-        let config = serde_json::from_str(&contents)?;
+        let config = read_json(&path, &format!("vault `{name}`"))?;
         Ok(VaultState { name, path, config })
     }
 
     pub fn set_default(&self, name: &str) -> Result<VaultState> {
+        let _lock = StateLock::acquire()?;
         let original = {
             let mut path = self.dir.clone();
             path.push(format!("{name}.json"));
@@ -280,25 +398,55 @@ pub struct VaultConfig {
 
     #[serde(default)]
     aws_kms: bool,
+
+    /// AWS region of the KMS keys, taken from the environment if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    aws_region: Option<String>,
+
+    /// Existing KMS key used as the root key of the identities created in this vault
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    aws_key_id: Option<String>,
 }
 
 impl VaultConfig {
     pub fn new(path: PathBuf, aws_kms: bool) -> Result<Self> {
-        Ok(Self { path, aws_kms })
+        Ok(Self {
+            path,
+            aws_kms,
+            aws_region: None,
+            aws_key_id: None,
+        })
     }
 
     pub fn from_name(name: &str) -> Result<Self> {
-        Ok(Self {
-            path: Self::path(name)?,
-            aws_kms: false,
-        })
+        Self::new(Self::path(name)?, false)
+    }
+
+    /// Set the AWS region and key of a KMS vault
+    pub fn with_aws_kms_options(mut self, region: Option<String>, key_id: Option<String>) -> Self {
+        self.aws_region = region;
+        self.aws_key_id = key_id;
+        self
     }
 
     pub async fn get(&self) -> Result<Vault> {
         let vault_storage = FileStorage::create(self.path.clone()).await?;
+        #[allow(unused_mut)]
         let mut vault = Vault::new(Some(Arc::new(vault_storage)));
         if self.aws_kms {
-            vault.enable_aws_kms().await?
+            #[cfg(feature = "aws-kms")]
+            {
+                let mut config = ockam_vault::aws::Config::default();
+                if let Some(region) = &self.aws_region {
+                    config = config.region(region);
+                }
+                vault.enable_aws_kms_with(config).await?
+            }
+            #[cfg(not(feature = "aws-kms"))]
+            return Err(CliStateError::Invalid(
+                "AWS KMS vaults are not supported, ockam was built without the `aws-kms` feature"
+                    .to_string(),
+            ));
         }
         Ok(vault)
     }
@@ -316,6 +464,26 @@ impl VaultConfig {
     pub fn is_aws(&self) -> bool {
         self.aws_kms
     }
+
+    pub fn aws_region(&self) -> Option<&str> {
+        self.aws_region.as_deref()
+    }
+
+    pub fn aws_key_id(&self) -> Option<&str> {
+        self.aws_key_id.as_deref()
+    }
+
+    /// Path of the file holding the vault's secrets
+    pub fn storage_path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of secrets stored in the vault
+    pub async fn secrets_count(&self) -> Result<usize> {
+        // Only read the storage, don't initialize it
+        let vault_storage = FileStorage::new(self.path.clone());
+        Ok(vault_storage.count().await?)
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -331,6 +499,7 @@ impl IdentitiesState {
     }
 
     pub fn create(&self, name: &str, config: IdentityConfig) -> Result<IdentityState> {
+        let _lock = StateLock::acquire()?;
         let path = {
             let mut path = self.dir.clone();
             path.push(format!("{name}.json"));
@@ -340,7 +509,7 @@ impl IdentitiesState {
             path
         };
         let contents = serde_json::to_string(&config)?;
-        std::fs::write(&path, contents)?;
+        write_atomically(&path, contents)?;
         if !self.default_path()?.exists() {
             self.set_default(name)?;
         }
@@ -360,8 +529,7 @@ impl IdentitiesState {
             }
             path
         };
-        let contents = std::fs::read_to_string(&path)?;
-        let config = serde_json::from_str(&contents)?;
+        let config = read_json(&path, &format!("identity `{name}`"))?;
         Ok(IdentityState {
             name: name.to_string(),
             path,
@@ -371,15 +539,22 @@ impl IdentitiesState {
 
     pub fn list(&self) -> Result<Vec<IdentityState>> {
         let mut identities: Vec<IdentityState> = vec![];
-        for entry in std::fs::read_dir(&self.dir)? {
-            if let Ok(identity) = self.get(&file_stem(&entry?.path())?) {
+        for name in self.names()? {
+            if let Ok(identity) = self.get(&name) {
                 identities.push(identity);
             }
         }
         Ok(identities)
     }
 
+    /// Names of the stored identities, including the ones that can't be loaded
+    fn names(&self) -> Result<Vec<String>> {
+        json_file_stems(&self.dir)
+    }
+
     pub async fn delete(&self, name: &str) -> Result<()> {
+        let _lock = StateLock::acquire()?;
+
         // Retrieve identity. If doesn't exist do nothing.
         let identity = match self.get(name) {
             Ok(i) => i,
@@ -391,7 +566,7 @@ impl IdentitiesState {
         identity.in_use()?;
 
         // Remove identity file
-        tokio::fs::remove_file(identity.path).await?;
+        std::fs::remove_file(identity.path)?;
 
         Ok(())
     }
@@ -403,8 +578,7 @@ impl IdentitiesState {
     pub fn default(&self) -> Result<IdentityState> {
         let path = std::fs::canonicalize(self.default_path()?)?;
         let name = file_stem(&path)?;
-        let contents = std::fs::read_to_string(&path)?;
-        let config = serde_json::from_str(&contents)?;
+        let config = read_json(&path, &format!("identity `{name}`"))?;
         Ok(IdentityState { name, path, config })
     }
 
@@ -418,6 +592,7 @@ impl IdentitiesState {
     }
 
     pub fn set_default(&self, name: &str) -> Result<IdentityState> {
+        let _lock = StateLock::acquire()?;
         let original = {
             let mut path = self.dir.clone();
             path.push(format!("{name}.json"));
@@ -449,8 +624,8 @@ pub struct IdentityState {
 impl IdentityState {
     fn persist(&self) -> Result<()> {
         let contents = serde_json::to_string(&self.config)?;
-        std::fs::write(&self.path, contents)?;
-        Ok(())
+        let _lock = StateLock::acquire()?;
+        write_atomically(&self.path, contents)
     }
 
     fn in_use(&self) -> Result<()> {
@@ -502,6 +677,9 @@ pub struct IdentityConfig {
     pub identifier: IdentityIdentifier,
     pub change_history: IdentityChangeHistory,
     pub enrollment_status: Option<EnrollmentStatus>,
+    /// Identities created before this field was introduced don't have it
+    #[serde(default)]
+    pub created_at: Option<SystemTime>,
 }
 
 impl IdentityConfig {
@@ -512,9 +690,17 @@ impl IdentityConfig {
             identifier,
             change_history,
             enrollment_status: None,
+            created_at: Some(SystemTime::now()),
         }
     }
 
+    /// Return the creation time of the identity in seconds since the unix epoch, if known
+    pub fn created_at_secs(&self) -> Option<u64> {
+        self.created_at
+            .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+    }
+
     pub async fn get(
         &self,
         ctx: &ockam::Context,
@@ -598,6 +784,7 @@ impl NodesState {
     }
 
     pub fn set_default(&self, name: &str) -> Result<NodeState> {
+        let _lock = StateLock::acquire()?;
         let original = {
             let mut path = self.dir.clone();
             path.push(name);
@@ -607,11 +794,15 @@ impl NodesState {
             path
         };
         let link = self.default_path()?;
+        // Remove link if it exists
+        let _ = std::fs::remove_file(&link);
+        // Create link to the node
         std::os::unix::fs::symlink(original, link)?;
         self.get(name)
     }
 
     pub fn create(&self, name: &str, mut config: NodeConfig) -> Result<NodeState> {
+        let _lock = StateLock::acquire()?;
         config.name = name.to_string();
         let path = {
             let mut path = self.dir.clone();
@@ -620,7 +811,10 @@ impl NodesState {
             path
         };
         let state = NodeState::new(path, config);
-        std::fs::write(state.path.join("version"), state.config.version.to_string())?;
+        write_atomically(
+            &state.path.join("version"),
+            state.config.version.to_string(),
+        )?;
         state.set_setup(&state.config.setup)?;
         std::fs::File::create(state.socket())?;
         std::fs::File::create(state.stdout_log())?;
@@ -639,8 +833,22 @@ impl NodesState {
         Ok(state)
     }
 
+    /// The nodes which can be loaded. The other ones are skipped, so that they
+    /// don't prevent other nodes from being listed.
     pub fn list(&self) -> Result<Vec<NodeState>> {
         let mut nodes = vec![];
+        for name in self.names()? {
+            match self.get(&name) {
+                Ok(node) => nodes.push(node),
+                Err(e) => tracing::warn!(node = %name, %e, "Skipping a node which can't be loaded"),
+            }
+        }
+        Ok(nodes)
+    }
+
+    /// Names of the stored nodes, including the ones that can't be loaded
+    pub fn names(&self) -> Result<Vec<String>> {
+        let mut names = vec![];
         for entry in std::fs::read_dir(&self.dir)? {
             let entry = entry?;
             if entry.file_type()?.is_dir() {
@@ -650,10 +858,10 @@ impl NodesState {
                         "node's directory has an invalid name",
                     ))
                 })?;
-                nodes.push(self.get(&name)?);
+                names.push(name);
             }
         }
-        Ok(nodes)
+        Ok(names)
     }
 
     pub fn get(&self, name: &str) -> Result<NodeState> {
@@ -670,6 +878,8 @@ impl NodesState {
     }
 
     pub fn delete(&self, name: &str, sigkill: bool) -> Result<()> {
+        let _lock = StateLock::acquire()?;
+
         // Retrieve node. If doesn't exist do nothing.
         let node = match self.get(name) {
             Ok(node) => node,
@@ -681,10 +891,12 @@ impl NodesState {
         if let Ok(default) = self.default() {
             if default.path == node.path {
                 let _ = std::fs::remove_file(self.default_path()?);
-                let mut nodes = self.list()?;
-                nodes.retain(|n| n.path != node.path);
-                if let Some(node) = nodes.first() {
-                    self.set_default(&node.config.name)?;
+                let other = self
+                    .names()?
+                    .into_iter()
+                    .find(|n| *n != node.config.name && self.get(n).is_ok());
+                if let Some(name) = other {
+                    self.set_default(&name)?;
                 }
             }
         }
@@ -707,6 +919,14 @@ impl NodeState {
         Self { path, config }
     }
 
+    /// Lock the state the node is stored in, two levels above its directory
+    fn lock(&self) -> Result<StateLock> {
+        match self.path.parent().and_then(Path::parent) {
+            Some(dir) => StateLock::acquire_in(dir, LOCK_TIMEOUT),
+            None => StateLock::acquire(),
+        }
+    }
+
     pub fn socket(&self) -> PathBuf {
         self.path.join("socket")
     }
@@ -717,8 +937,20 @@ impl NodeState {
 
     pub fn set_setup(&self, setup: &NodeSetupConfig) -> Result<()> {
         let contents = serde_json::to_string(setup)?;
-        std::fs::write(self.path.join("setup.json"), contents)?;
-        Ok(())
+        let _lock = self.lock()?;
+        write_atomically(&self.path.join("setup.json"), contents)
+    }
+
+    /// Modify the setup of the node, holding the lock from the read to the write so
+    /// that concurrent updates aren't lost. Returns the modified setup.
+    pub fn update_setup(
+        &self,
+        f: impl FnOnce(NodeSetupConfig) -> NodeSetupConfig,
+    ) -> Result<NodeSetupConfig> {
+        let _lock = self.lock()?;
+        let setup = f(self.setup()?);
+        self.set_setup(&setup)?;
+        Ok(setup)
     }
 
     pub fn pid(&self) -> Result<Option<i32>> {
@@ -734,8 +966,14 @@ impl NodeState {
     }
 
     pub fn set_pid(&self, pid: i32) -> Result<()> {
-        std::fs::write(self.path.join("pid"), pid.to_string())?;
-        Ok(())
+        let _lock = self.lock()?;
+        write_atomically(&self.path.join("pid"), pid.to_string())
+    }
+
+    /// Mark the node as stopped, without signaling its process
+    pub fn clear_pid(&self) -> Result<()> {
+        let _lock = self.lock()?;
+        remove_if_exists(&self.path.join("pid"))
     }
 
     pub fn is_running(&self) -> bool {
@@ -760,7 +998,37 @@ impl NodeState {
         self.path.join("policies_storage.lmdb")
     }
 
+    pub fn attributes_storage_path(&self) -> PathBuf {
+        self.path.join("attributes_storage.lmdb")
+    }
+
+    /// The membership credential last fetched by the node, if any
+    pub fn credential(&self) -> Result<Option<Credential>> {
+        let path = self.path.join("credential");
+        if !path.exists() {
+            return Ok(None);
+        }
+        let bytes = std::fs::read(path)?;
+        let credential = minicbor::decode(&bytes)
+            .map_err(|e| CliStateError::Invalid(format!("invalid cached credential: {e}")))?;
+        Ok(Some(credential))
+    }
+
+    pub fn set_credential(&self, credential: &Credential) -> Result<()> {
+        let bytes = minicbor::to_vec(credential)
+            .map_err(|e| CliStateError::Invalid(format!("invalid credential: {e}")))?;
+        let _lock = self.lock()?;
+        write_atomically(&self.path.join("credential"), bytes)
+    }
+
+    /// Forget the cached credential, so that the next one is fetched from the authority
+    pub fn clear_credential(&self) -> Result<()> {
+        let _lock = self.lock()?;
+        remove_if_exists(&self.path.join("credential"))
+    }
+
     pub fn kill_process(&self, sigkill: bool) -> Result<()> {
+        let _lock = self.lock()?;
         if let Some(pid) = self.pid()? {
             nix::sys::signal::kill(
                 nix::unistd::Pid::from_raw(pid),
@@ -821,19 +1089,38 @@ impl NodeConfig {
 
     pub async fn vault(&self) -> Result<Vault> {
         let path = std::fs::canonicalize(&self.default_vault)?;
-        let config: VaultConfig = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        let config: VaultConfig = read_json(&path, &format!("vault `{}`", file_stem(&path)?))?;
         config.get().await
     }
 
+    /// Path of the vault used by the node
+    pub fn vault_path(&self) -> &Path {
+        &self.default_vault
+    }
+
+    /// Path of the identity used by the node
+    pub fn identity_path(&self) -> &Path {
+        &self.default_identity
+    }
+
     pub fn identity_config(&self) -> Result<IdentityConfig> {
         let path = std::fs::canonicalize(&self.default_identity)?;
-        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+        read_json(&path, &format!("identity `{}`", file_stem(&path)?))
+    }
+
+    /// Record the change history of the node's identity, after one of its keys was rotated
+    pub fn set_identity_change_history(&self, change_history: IdentityChangeHistory) -> Result<()> {
+        let _lock = StateLock::acquire()?;
+        let path = std::fs::canonicalize(&self.default_identity)?;
+        let mut config = self.identity_config()?;
+        config.change_history = change_history;
+        let contents = serde_json::to_string(&config)?;
+        write_atomically(&path, contents)
     }
 
     pub async fn identity(&self, ctx: &ockam::Context) -> Result<Identity<Vault, LmdbStorage>> {
         let vault = self.vault().await?;
-        let path = std::fs::canonicalize(&self.default_identity)?;
-        let config: IdentityConfig = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        let config = self.identity_config()?;
         config.get(ctx, &vault).await
     }
 }
@@ -936,7 +1223,19 @@ impl FromStr for NodeConfigVersion {
 #[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
 pub struct NodeSetupConfig {
     pub verbose: u8,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    json_logs: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    log_level: Option<String>,
     transports: Vec<CreateTransportJson>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    no_api_transport: bool,
+    #[serde(default)]
+    secure_channel_listeners: Vec<SecureChannelListenerConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    attributes_storage: Option<AttributesStorageKind>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    launch_config: Option<serde_json::Value>,
     // TODO
     // secure_channels: ?,
     // inlets: ?,
@@ -950,7 +1249,30 @@ impl NodeSetupConfig {
         self
     }
 
+    /// Whether the node logs json lines instead of human-readable text
+    pub fn json_logs(&self) -> bool {
+        self.json_logs
+    }
+
+    pub fn set_json_logs(mut self, json_logs: bool) -> Self {
+        self.json_logs = json_logs;
+        self
+    }
+
+    /// The level the node logs at, regardless of its verbosity
+    pub fn log_level(&self) -> Option<&str> {
+        self.log_level.as_deref()
+    }
+
+    pub fn set_log_level(mut self, log_level: Option<String>) -> Self {
+        self.log_level = log_level;
+        self
+    }
+
     pub fn default_tcp_listener(&self) -> Result<&CreateTransportJson> {
+        if self.no_api_transport {
+            return Err(CliStateError::NoApiTransport);
+        }
         self.transports
             .iter()
             .find(|t| t.tt == TransportType::Tcp && t.tm == TransportMode::Listen)
@@ -961,14 +1283,89 @@ impl NodeSetupConfig {
         self.transports.push(transport);
         self
     }
+
+    /// Whether the node API is served over a TCP listener
+    pub fn has_api_transport(&self) -> bool {
+        !self.no_api_transport
+    }
+
+    /// Record that the node doesn't listen for API requests over TCP
+    pub fn set_no_api_transport(mut self) -> Self {
+        self.no_api_transport = true;
+        self
+    }
+
+    pub fn attributes_storage(&self) -> Option<AttributesStorageKind> {
+        self.attributes_storage
+    }
+
+    pub fn set_attributes_storage(mut self, kind: AttributesStorageKind) -> Self {
+        self.attributes_storage = Some(kind);
+        self
+    }
+
+    /// JSON config the node was launched with, to start the same services on restart
+    pub fn launch_config(&self) -> Option<&serde_json::Value> {
+        self.launch_config.as_ref()
+    }
+
+    pub fn set_launch_config(mut self, config: serde_json::Value) -> Self {
+        self.launch_config = Some(config);
+        self
+    }
+
+    pub fn secure_channel_listeners(&self) -> &[SecureChannelListenerConfig] {
+        &self.secure_channel_listeners
+    }
+
+    /// Record a secure channel listener, replacing any previous one at the same address
+    pub fn add_secure_channel_listener(mut self, listener: SecureChannelListenerConfig) -> Self {
+        self.secure_channel_listeners
+            .retain(|l| l.address != listener.address);
+        self.secure_channel_listeners.push(listener);
+        self
+    }
+
+    /// Forget the secure channel listener at the given address
+    pub fn remove_secure_channel_listener(mut self, address: &str) -> Self {
+        self.secure_channel_listeners
+            .retain(|l| l.address != address);
+        self
+    }
+}
+
+/// A secure channel listener to re-create when the node is restarted
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct SecureChannelListenerConfig {
+    pub address: String,
+    pub authorized_identifiers: Option<Vec<IdentityIdentifier>>,
+    pub identity: Option<String>,
+}
+
+impl SecureChannelListenerConfig {
+    pub fn new(
+        address: impl Into<String>,
+        authorized_identifiers: Option<Vec<IdentityIdentifier>>,
+        identity: Option<String>,
+    ) -> Self {
+        Self {
+            address: address.into(),
+            authorized_identifiers,
+            identity,
+        }
+    }
 }
 
 impl TryFrom<&PathBuf> for NodeSetupConfig {
     type Error = CliStateError;
 
     fn try_from(path: &PathBuf) -> std::result::Result<Self, Self::Error> {
-        let contents = std::fs::read_to_string(path)?;
-        Ok(serde_json::from_str(&contents)?)
+        let node = path
+            .parent()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        read_json(path, &format!("setup of node `{node}`"))
     }
 }
 
@@ -985,6 +1382,7 @@ impl ProjectsState {
     }
 
     pub async fn create(&self, name: &str, config: Project<'_>) -> Result<ProjectState> {
+        let _lock = StateLock::acquire()?;
         let path = {
             let mut path = self.dir.clone();
             path.push(format!("{name}.json"));
@@ -994,7 +1392,7 @@ impl ProjectsState {
             path
         };
         let contents = serde_json::to_string(&config)?;
-        std::fs::write(&path, contents)?;
+        write_atomically(&path, contents)?;
         if !self.default_path()?.exists() {
             self.set_default(name)?;
         }
@@ -1014,6 +1412,11 @@ impl ProjectsState {
         Ok(ProjectState { path })
     }
 
+    /// Names of the stored projects, including the ones that can't be loaded
+    fn names(&self) -> Result<Vec<String>> {
+        json_file_stems(&self.dir)
+    }
+
     pub fn default_path(&self) -> Result<PathBuf> {
         Ok(CliState::defaults_dir()?.join("project"))
     }
@@ -1024,6 +1427,7 @@ impl ProjectsState {
     }
 
     pub fn set_default(&self, name: &str) -> Result<ProjectState> {
+        let _lock = StateLock::acquire()?;
         let original = {
             let mut path = self.dir.clone();
             path.push(format!("{name}.json"));
@@ -1055,6 +1459,12 @@ impl ProjectState {
                 ))
             })
     }
+
+    /// Whether the file of the project can't be read as a project anymore
+    fn is_corrupt(&self) -> Result<bool> {
+        let contents = std::fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str::<Project>(&contents).is_err())
+    }
 }
 
 pub fn random_name() -> String {
@@ -1069,6 +1479,117 @@ fn file_stem(path: &Path) -> Result<String> {
         .ok_or_else(|| CliStateError::NotFound(format!("name for {path:?}")))
 }
 
+/// Names of the `.json` files of a directory, without their extension
+fn json_file_stems(dir: &Path) -> Result<Vec<String>> {
+    let mut names = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().map_or(false, |e| e == "json") {
+            names.push(file_stem(&path)?);
+        }
+    }
+    Ok(names)
+}
+
+/// Read a json file, reporting a parsing failure as a corrupt `entity`
+fn read_json<T: DeserializeOwned>(path: &Path, entity: &str) -> Result<T> {
+    let contents = std::fs::read_to_string(path)?;
+    serde_json::from_str(&contents).map_err(|source| CliStateError::Corrupt {
+        entity: entity.to_string(),
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Replace the contents of a file in a single step, so that readers, which don't
+/// take the [`StateLock`], never see a partially written file.
+fn write_atomically(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    file.write_all(contents.as_ref())?;
+    file.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// Name of the lock file, at the root of the state directory
+const LOCK_FILE: &str = "state.lock";
+
+/// How long a writer waits for the lock before giving up
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Interval between two attempts to take the lock
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+thread_local! {
+    /// Number of [`StateLock`]s currently held by this thread
+    static LOCK_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Exclusive advisory lock on the state directory, released when dropped.
+///
+/// It is taken by every function modifying the state, so that concurrent `ockam`
+/// processes don't interleave their writes. The lock is reentrant within a thread,
+/// which lets these functions call each other.
+struct StateLock {
+    /// Only set on the outermost lock of a thread
+    file: Option<File>,
+    /// The reentrancy depth is tracked per thread, so the lock must stay on its thread
+    _not_send: PhantomData<*const ()>,
+}
+
+impl StateLock {
+    fn acquire() -> Result<Self> {
+        Self::acquire_in(&CliState::dir()?, LOCK_TIMEOUT)
+    }
+
+    fn acquire_in(dir: &Path, timeout: Duration) -> Result<Self> {
+        let depth = LOCK_DEPTH.with(|d| d.get());
+        if depth > 0 {
+            LOCK_DEPTH.with(|d| d.set(depth + 1));
+            return Ok(Self {
+                file: None,
+                _not_send: PhantomData,
+            });
+        }
+
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(LOCK_FILE);
+        let file = OpenOptions::new().create(true).write(true).open(&path)?;
+        let started = Instant::now();
+        while let Err(e) = file.try_lock_exclusive() {
+            if e.kind() != fs2::lock_contended_error().kind() {
+                return Err(e.into());
+            }
+            if started.elapsed() >= timeout {
+                return Err(CliStateError::LockTimeout(path.display().to_string()));
+            }
+            std::thread::sleep(LOCK_RETRY_INTERVAL);
+        }
+        LOCK_DEPTH.with(|d| d.set(1));
+        Ok(Self {
+            file: Some(file),
+            _not_send: PhantomData,
+        })
+    }
+}
+
+impl Drop for StateLock {
+    fn drop(&mut self) {
+        LOCK_DEPTH.with(|d| d.set(d.get() - 1));
+        if let Some(file) = &self.file {
+            let _ = file.unlock();
+        }
+    }
+}
+
+/// Remove a file or a (possibly dangling) symlink, if present.
+fn remove_if_exists(path: &Path) -> Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1234,6 +1755,7 @@ mod tests {
                         found_entries.push(format!("{dir_name}/{file_name}"));
                     });
                 }
+                LOCK_FILE => assert!(entry.path().is_file()),
                 _ => panic!("unexpected file"),
             }
         });
@@ -1242,4 +1764,87 @@ mod tests {
         ctx.stop().await?;
         Ok(())
     }
+
+    #[test]
+    fn concurrent_setup_updates_are_not_lost() {
+        // The node is stored as in a state directory, which holds the lock
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nodes").join("n1");
+        std::fs::create_dir_all(&path).unwrap();
+        let config = NodeConfig {
+            name: "n1".to_string(),
+            version: NodeConfigVersion::latest(),
+            default_vault: dir.path().join("vault"),
+            default_identity: dir.path().join("identity"),
+            setup: NodeSetupConfig::default(),
+        };
+        let node = NodeState::new(path, config);
+        node.set_setup(&node.config.setup).unwrap();
+
+        // Each writer records its own listeners, which are lost unless the updates are serialized
+        let writers: Vec<_> = (0..2)
+            .map(|w| {
+                let node = node.clone();
+                std::thread::spawn(move || -> Result<()> {
+                    for i in 0..25 {
+                        let listener =
+                            SecureChannelListenerConfig::new(format!("{w}_{i}"), None, None);
+                        node.update_setup(|s| s.add_secure_channel_listener(listener))?;
+                    }
+                    Ok(())
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap().unwrap();
+        }
+
+        assert_eq!(node.setup().unwrap().secure_channel_listeners().len(), 50);
+    }
+
+    #[test]
+    fn lock_times_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let _lock = StateLock::acquire_in(dir.path(), LOCK_TIMEOUT).unwrap();
+
+        // The lock is reentrant within a thread, so it has to be contended from another one
+        let path = dir.path().to_path_buf();
+        let res = std::thread::spawn(move || {
+            StateLock::acquire_in(&path, Duration::from_millis(100)).map(|_| ())
+        })
+        .join()
+        .unwrap();
+        assert!(matches!(res, Err(CliStateError::LockTimeout(_))));
+    }
+
+    #[test]
+    fn setup_without_api_transport() {
+        let setup = NodeSetupConfig::default().set_no_api_transport();
+        assert!(!setup.has_api_transport());
+        assert!(matches!(
+            setup.default_tcp_listener(),
+            Err(CliStateError::NoApiTransport)
+        ));
+
+        // The flag is persisted, and absent from the setup of nodes with a listener
+        let json = serde_json::to_string(&setup).unwrap();
+        let read: NodeSetupConfig = serde_json::from_str(&json).unwrap();
+        assert!(!read.has_api_transport());
+        let json = serde_json::to_string(&NodeSetupConfig::default()).unwrap();
+        assert!(!json.contains("no_api_transport"));
+    }
+
+    #[test]
+    fn corrupt_file_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("n1").join("setup.json");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "{").unwrap();
+
+        let err = NodeSetupConfig::try_from(&path).unwrap_err();
+        assert!(matches!(&err, CliStateError::Corrupt { path: p, .. } if *p == path));
+        let msg = err.to_string();
+        assert!(msg.contains("setup of node `n1`"));
+        assert!(msg.contains("ockam reset --repair"));
+    }
 }
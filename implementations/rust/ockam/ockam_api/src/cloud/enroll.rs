@@ -42,7 +42,7 @@ mod node {
             let req_wrapper: CloudRequestWrapper<AuthenticateAuth0Token> = dec.decode()?;
             let cloud_route = req_wrapper.route()?;
             let req_body: AuthenticateAuth0Token = req_wrapper.req;
-            let req_builder = Request::post("v0/enroll").body(req_body);
+            let req_builder = || Request::post("v0/enroll").body(&req_body);
             let api_service = "auth0_authenticator";
 
             trace!(target: TARGET, "executing auth0 flow");
@@ -52,7 +52,7 @@ mod node {
                 inner.identity()?.async_try_clone().await?
             };
 
-            self.request_controller(
+            self.request_controller_with_retry(
                 ctx,
                 api_service,
                 None,
@@ -106,7 +106,7 @@ mod node {
             let req_wrapper: CloudRequestWrapper<EnrollmentToken> = dec.decode()?;
             let cloud_route = req_wrapper.route()?;
             let req_body: EnrollmentToken = req_wrapper.req;
-            let req_builder = Request::post("v0/enroll").body(req_body);
+            let req_builder = || Request::post("v0/enroll").body(&req_body);
             let api_service = "enrollment_token_authenticator";
 
             let ident = {
@@ -115,7 +115,7 @@ mod node {
             };
 
             trace!(target: TARGET, "authenticating token");
-            self.request_controller(
+            self.request_controller_with_retry(
                 ctx,
                 api_service,
                 None,
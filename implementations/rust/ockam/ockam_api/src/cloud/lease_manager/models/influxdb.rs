@@ -1,7 +1,30 @@
+use core::time::Duration;
+
 use minicbor::{Decode, Encode};
 use ockam_core::CowStr;
 use serde::{Deserialize, Serialize};
 
+// ======= CREATE TOKEN REQUEST STRUCT =======
+#[derive(Encode, Decode, Debug, Default)]
+#[cbor(map)]
+pub struct CreateTokenRequest {
+    /// Requested lifetime of the token, in seconds
+    #[cbor(n(1))]
+    pub expires_in: Option<u64>,
+}
+
+impl CreateTokenRequest {
+    pub fn new(expires_in: Option<Duration>) -> Self {
+        Self {
+            expires_in: expires_in.map(|d| d.as_secs()),
+        }
+    }
+
+    pub fn expires_in(&self) -> Option<Duration> {
+        self.expires_in.map(Duration::from_secs)
+    }
+}
+
 // ======= TOKEN STRUCT =======
 #[derive(Encode, Decode, Serialize, Deserialize, Debug)]
 #[cbor(map)]
@@ -29,4 +52,10 @@ pub struct Token<'a> {
     #[serde(borrow)]
     #[cbor(b(6))]
     pub status: CowStr<'a>,
+
+    /// Lifetime granted by the server, in seconds
+    #[serde(default)]
+    #[cbor(n(7))]
+    pub expires_in: Option<u64>,
 }
+
@@ -20,7 +20,11 @@ pub mod subscription;
 ///
 /// How to use: when running a command that spawns a background node or use an embedded node
 /// add the env variable. `OCKAM_CONTROLLER_IDENTITY_ID={identity.id-contents} ockam ...`
-pub(crate) const OCKAM_CONTROLLER_IDENTITY_ID: &str = "OCKAM_CONTROLLER_IDENTITY_ID";
+pub const OCKAM_CONTROLLER_IDENTITY_ID: &str = "OCKAM_CONTROLLER_IDENTITY_ID";
+
+/// Maximum number of attempts for idempotent controller requests (e.g. enrollment),
+/// defaults to 3. Set it to 1 to disable retries.
+pub(crate) const OCKAM_CONTROLLER_RETRIES: &str = "OCKAM_CONTROLLER_RETRIES";
 
 pub type ProjectAddress = CowStr<'static>;
 
@@ -74,17 +78,18 @@ impl<'a> BareCloudRequestWrapper<'a> {
 mod node {
     use std::env;
     use std::str::FromStr;
+    use std::time::Duration;
 
-    use minicbor::Encode;
-    use ockam_core::api::RequestBuilder;
-    use ockam_core::{self, route, Address, Result, Route};
+    use minicbor::{Decoder, Encode};
+    use ockam_core::api::{RequestBuilder, Response, Status};
+    use ockam_core::{self, route, Address, AsyncTryClone, Result, Route};
     use ockam_identity::{Identity, IdentityIdentifier, TrustIdentifierPolicy};
     use ockam_node::api::request;
     use ockam_node::Context;
     use ockam_vault::Vault;
     use rust_embed::EmbeddedFile;
 
-    use crate::cloud::OCKAM_CONTROLLER_IDENTITY_ID;
+    use crate::cloud::{OCKAM_CONTROLLER_IDENTITY_ID, OCKAM_CONTROLLER_RETRIES};
     use crate::error::ApiError;
     use crate::lmdb::LmdbStorage;
     use crate::nodes::{NodeManager, NodeManagerWorker};
@@ -92,12 +97,15 @@ mod node {
 
     const TARGET: &str = "ockam_api::nodemanager::service";
 
+    const DEFAULT_CONTROLLER_RETRIES: u32 = 3;
+    const CONTROLLER_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
     impl NodeManager {
         /// Load controller identity id from file.
         ///
         /// If the env var `OCKAM_CONTROLLER_IDENTITY_ID` is set, that will be used to
         /// load the identity instead of the file.
-        pub(crate) fn load_controller_identity_id() -> Result<IdentityIdentifier> {
+        pub fn load_controller_identity_id() -> Result<IdentityIdentifier> {
             if let Ok(s) = env::var(OCKAM_CONTROLLER_IDENTITY_ID) {
                 trace!(idt = %s, "Read controller identity id from env");
                 return IdentityIdentifier::from_str(&s);
@@ -166,5 +174,99 @@ mod node {
             ctx.stop_worker(sc).await?;
             res
         }
+
+        /// Same as `request_controller`, but retries transient failures with a
+        /// jittered exponential backoff. Only use it for idempotent requests.
+        ///
+        /// Transport errors and `500` responses are retried; any other response,
+        /// like `403 Forbidden`, is returned right away.
+        #[allow(clippy::too_many_arguments)]
+        pub(super) async fn request_controller_with_retry<'r, T>(
+            &mut self,
+            ctx: &Context,
+            label: &str,
+            schema: impl Into<Option<&str>>,
+            cloud_route: impl Into<Route>,
+            api_service: &str,
+            req: impl Fn() -> RequestBuilder<'r, T>,
+            ident: Identity<Vault, LmdbStorage>,
+        ) -> Result<Vec<u8>>
+        where
+            T: Encode<()>,
+        {
+            let schema = schema.into();
+            let cloud_route = cloud_route.into();
+            let attempts = controller_retries();
+            let mut attempt = 1;
+            loop {
+                let res = self
+                    .request_controller(
+                        ctx,
+                        label,
+                        schema,
+                        cloud_route.clone(),
+                        api_service,
+                        req(),
+                        ident.async_try_clone().await?,
+                    )
+                    .await;
+                if attempt >= attempts || !is_retryable(&res) {
+                    return res;
+                }
+                let delay = retry_delay(attempt);
+                warn!(target: TARGET, %label, attempt, ?delay, "Controller request failed, retrying");
+                ctx.sleep(delay).await;
+                attempt += 1;
+            }
+        }
+    }
+
+    fn controller_retries() -> u32 {
+        env::var(OCKAM_CONTROLLER_RETRIES)
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(DEFAULT_CONTROLLER_RETRIES)
+    }
+
+    /// Exponential backoff, with up to 50% of random jitter.
+    fn retry_delay(attempt: u32) -> Duration {
+        let backoff = CONTROLLER_RETRY_BASE_DELAY * 2u32.pow(attempt.saturating_sub(1));
+        backoff + backoff.mul_f64(rand::random::<f64>() / 2.0)
+    }
+
+    fn is_retryable(res: &Result<Vec<u8>>) -> bool {
+        match res {
+            Err(_) => true,
+            Ok(body) => matches!(
+                Decoder::new(body).decode::<Response>().map(|r| r.status()),
+                Ok(Some(Status::InternalServerError))
+            ),
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use ockam_core::api::Id;
+
+        use super::*;
+
+        #[test]
+        fn retry_delay_grows_exponentially() {
+            for attempt in 1..4 {
+                let base = CONTROLLER_RETRY_BASE_DELAY * 2u32.pow(attempt - 1);
+                let delay = retry_delay(attempt);
+                assert!(delay >= base && delay <= base.mul_f64(1.5));
+            }
+        }
+
+        #[test]
+        fn only_transient_failures_are_retried() {
+            let response = |status| Response::builder(Id::fresh(), status).to_vec().unwrap();
+            assert!(is_retryable(&Err(ApiError::generic("timeout"))));
+            assert!(is_retryable(&Ok(response(Status::InternalServerError))));
+            assert!(!is_retryable(&Ok(response(Status::Forbidden))));
+            assert!(!is_retryable(&Ok(response(Status::Ok))));
+        }
     }
 }
@@ -34,6 +34,8 @@ pub struct OckamConfig {
     pub dir: Option<PathBuf>,
     #[serde(default = "default_lookup")]
     pub lookup: ConfigLookup,
+    #[serde(default)]
+    pub controller: ControllerConfig,
 }
 
 fn default_lookup() -> ConfigLookup {
@@ -45,6 +47,7 @@ impl ConfigValues for OckamConfig {
         Self {
             dir: Some(Self::dir()),
             lookup: default_lookup(),
+            controller: ControllerConfig::default(),
         }
     }
 }
@@ -64,6 +67,14 @@ impl OckamConfig {
     }
 }
 
+/// Settings of the connection to the Orchestrator controller
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ControllerConfig {
+    /// Route to the controller, used instead of the default one when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub route: Option<MultiAddr>,
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct AuthoritiesConfig {
     authorities: BTreeMap<IdentityIdentifier, Authority>,
@@ -2,7 +2,31 @@ use ockam::{Any, Context, Result, Routed, Worker};
 use ockam_core::NeutralMessage;
 use tracing as log;
 
-pub struct Echoer;
+use crate::rate_limit::RateLimiter;
+use crate::util::message_too_large;
+
+#[derive(Default)]
+pub struct Echoer {
+    limiter: Option<RateLimiter>,
+    max_message_size: Option<usize>,
+}
+
+impl Echoer {
+    /// Create an echoer handling at most `rate_limit` messages per second,
+    /// or any number of messages if `None`.
+    pub fn new(rate_limit: Option<u32>) -> Self {
+        Self {
+            limiter: rate_limit.map(RateLimiter::new),
+            max_message_size: None,
+        }
+    }
+
+    /// Reject the messages larger than `max_message_size` bytes, if set.
+    pub fn with_max_message_size(mut self, max_message_size: Option<usize>) -> Self {
+        self.max_message_size = max_message_size;
+        self
+    }
+}
 
 #[ockam::worker]
 impl Worker for Echoer {
@@ -10,6 +34,18 @@ impl Worker for Echoer {
     type Message = Any;
 
     async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        if let Some(limiter) = &mut self.limiter {
+            if !limiter.try_acquire() {
+                log::warn!(from = %msg.sender()?, "rate limit exceeded, dropping message");
+                return Ok(());
+            }
+        }
+        let address = msg.msg_addr().address().to_string();
+        let size = msg.payload().len();
+        if let Some(error) = message_too_large(&address, size, self.max_message_size)? {
+            log::warn!(from = %msg.sender()?, size, "message too large");
+            return ctx.send(msg.return_route(), error).await;
+        }
         log::debug!(to = %msg.sender()?, "echoing back");
         ctx.send(msg.return_route(), NeutralMessage::from(msg.take_payload()))
             .await
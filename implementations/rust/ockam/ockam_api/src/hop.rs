@@ -1,6 +1,22 @@
 use ockam::{Any, Context, Result, Routed, Worker};
+use tracing as log;
 
-pub struct Hop;
+use crate::rate_limit::RateLimiter;
+
+#[derive(Default)]
+pub struct Hop {
+    limiter: Option<RateLimiter>,
+}
+
+impl Hop {
+    /// Create a hop forwarding at most `rate_limit` messages per second,
+    /// or any number of messages if `None`.
+    pub fn new(rate_limit: Option<u32>) -> Self {
+        Self {
+            limiter: rate_limit.map(RateLimiter::new),
+        }
+    }
+}
 
 #[ockam::worker]
 impl Worker for Hop {
@@ -10,6 +26,13 @@ impl Worker for Hop {
     /// This handle function takes any incoming message and forwards
     /// it to the next hop in it's onward route
     async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        if let Some(limiter) = &mut self.limiter {
+            if !limiter.try_acquire() {
+                log::warn!(from = %msg.sender()?, "rate limit exceeded, dropping message");
+                return Ok(());
+            }
+        }
+
         // Some type conversion
         let mut message = msg.into_local_message();
         let transport_message = message.transport_mut();
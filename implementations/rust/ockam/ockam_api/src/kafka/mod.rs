@@ -13,7 +13,8 @@ pub(crate) use portal_listener::KafkaPortalListener;
 
 pub const KAFKA_INTERCEPTOR_ADDRESS: &str = "kafka_interceptor";
 pub const KAFKA_BOOTSTRAP_ADDRESS: &str = "kafka_bootstrap";
+const KAFKA_OUTLET_PREFIX: &str = "kafka_outlet";
 
 pub fn kafka_outlet_address(broker_id: i32) -> Address {
-    Address::from_string(format!("kafka_outlet_{}", broker_id))
+    Address::prefixed(KAFKA_OUTLET_PREFIX, &broker_id.to_string())
 }
@@ -12,7 +12,9 @@ pub mod kafka;
 pub mod nodes;
 pub mod okta;
 pub mod port_range;
+pub mod rate_limit;
 pub mod uppercase;
+pub mod util;
 pub mod vault;
 pub mod verifier;
 
@@ -43,6 +45,10 @@ impl DefaultAddress {
     pub const OKTA_IDENTITY_PROVIDER: &'static str = "okta";
     pub const KAFKA_CONSUMER: &'static str = "kafka_consumer";
     pub const KAFKA_PRODUCER: &'static str = "kafka_producer";
+
+    /// Prefix of the addresses of forwarders created at rust nodes,
+    /// see [`ockam_core::Address::prefixed`]
+    pub const FORWARDER_PREFIX: &'static str = "forward_to";
 }
 
 pub mod actions {
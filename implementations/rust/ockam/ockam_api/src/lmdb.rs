@@ -181,6 +181,27 @@ impl PolicyStorage for LmdbStorage {
         };
         task::spawn_blocking(t).await.map_err(map_join_err)?
     }
+
+    async fn all_policies(&self) -> Result<Vec<(Resource, Action, Expr)>> {
+        let d = self.clone();
+        let t = move || {
+            let tx = d.env.begin_ro_txn().map_err(map_lmdb_err)?;
+            let mut c = tx.open_ro_cursor(d.map).map_err(map_lmdb_err)?;
+            let mut xs = Vec::new();
+            for entry in c.iter_start() {
+                let (k, v) = entry.map_err(map_lmdb_err)?;
+                let ks = str::from_utf8(k).map_err(from_utf8_err)?;
+                if let Some((r, a)) = ks.split_once(':') {
+                    let x: PolicyEntry = minicbor::decode(v)?;
+                    xs.push((Resource::new(r), Action::new(a), x.expr.into_owned()))
+                } else {
+                    log::warn!(key = %ks, "malformed key in policy database")
+                }
+            }
+            Ok(xs)
+        };
+        task::spawn_blocking(t).await.map_err(map_join_err)?
+    }
 }
 
 fn map_join_err(err: JoinError) -> Error {
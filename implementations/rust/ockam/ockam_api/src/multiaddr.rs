@@ -102,9 +102,51 @@ pub fn multiaddr_to_route(ma: &MultiAddr) -> Option<Route> {
     Some(rb.into())
 }
 
+/// Environment variable setting the maximum number of hops of the routes built from multiaddrs
+pub const OCKAM_MAX_ROUTE_HOPS: &str = "OCKAM_MAX_ROUTE_HOPS";
+
+/// Maximum number of hops of the routes built from multiaddrs, by default
+pub const DEFAULT_MAX_ROUTE_HOPS: usize = 32;
+
+/// The maximum number of hops of the routes built from multiaddrs, which is
+/// `$OCKAM_MAX_ROUTE_HOPS` if it's set to a positive number.
+pub fn max_route_hops() -> usize {
+    parse_max_route_hops(std::env::var(OCKAM_MAX_ROUTE_HOPS).ok().as_deref())
+}
+
+fn parse_max_route_hops(value: Option<&str>) -> usize {
+    value
+        .and_then(|s| s.trim().parse().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_MAX_ROUTE_HOPS)
+}
+
+/// Try to convert a multi-address to an Ockam route of at most
+/// [`max_route_hops`] hops.
 pub fn try_multiaddr_to_route(ma: &MultiAddr) -> Result<Route, Error> {
-    multiaddr_to_route(ma)
-        .ok_or_else(|| ApiError::message(format!("could not convert {ma} to route")))
+    try_multiaddr_to_route_with_max_hops(ma, max_route_hops())
+}
+
+/// Try to convert a multi-address to an Ockam route of at most `max_hops` hops.
+pub fn try_multiaddr_to_route_with_max_hops(
+    ma: &MultiAddr,
+    max_hops: usize,
+) -> Result<Route, Error> {
+    let route = multiaddr_to_route(ma)
+        .ok_or_else(|| ApiError::message(format!("could not convert {ma} to route")))?;
+    check_route_hops(&route, max_hops)?;
+    Ok(route)
+}
+
+/// Check that a route has at most `max_hops` hops.
+pub fn check_route_hops(route: &Route, max_hops: usize) -> Result<(), Error> {
+    if route.len() > max_hops {
+        return Err(ApiError::message(format!(
+            "the route has {} hops, more than the maximum of {max_hops}",
+            route.len()
+        )));
+    }
+    Ok(())
 }
 
 /// Try to convert a multiaddr to an Ockam Address
@@ -227,6 +269,29 @@ pub fn is_local_node(ma: &MultiAddr) -> anyhow::Result<bool> {
     }
 }
 
+#[test]
+fn route_hops_are_limited() {
+    let short: MultiAddr = "/service/a/service/b".parse().unwrap();
+    assert_eq!(try_multiaddr_to_route(&short).unwrap().len(), 2);
+
+    let long: MultiAddr = "/service/hop"
+        .repeat(DEFAULT_MAX_ROUTE_HOPS + 1)
+        .parse()
+        .unwrap();
+    let err = try_multiaddr_to_route_with_max_hops(&long, DEFAULT_MAX_ROUTE_HOPS).unwrap_err();
+    assert!(err.to_string().contains("more than the maximum"), "{err}");
+
+    let ma: MultiAddr = "/service/hop".repeat(4).parse().unwrap();
+    assert!(try_multiaddr_to_route_with_max_hops(&ma, 4).is_ok());
+    assert!(try_multiaddr_to_route_with_max_hops(&ma, 3).is_err());
+
+    // The maximum is configurable, to a positive number
+    assert_eq!(parse_max_route_hops(Some("64")), 64);
+    assert_eq!(parse_max_route_hops(Some("0")), DEFAULT_MAX_ROUTE_HOPS);
+    assert_eq!(parse_max_route_hops(Some("many")), DEFAULT_MAX_ROUTE_HOPS);
+    assert_eq!(parse_max_route_hops(None), DEFAULT_MAX_ROUTE_HOPS);
+}
+
 #[test]
 fn clean_multiaddr_simple() {
     let addr: MultiAddr = "/project/hub/service/echoer".parse().unwrap();
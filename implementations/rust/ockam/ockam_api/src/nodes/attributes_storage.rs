@@ -0,0 +1,83 @@
+//! Storage backends for the attributes of the identities known to a node
+
+use std::fmt;
+use std::str::FromStr;
+
+use ockam_core::compat::boxed::Box;
+use ockam_core::{async_trait, Result};
+use ockam_identity::authenticated_storage::mem::InMemoryStorage;
+use ockam_identity::authenticated_storage::AuthenticatedStorage;
+use serde::{Deserialize, Serialize};
+
+use crate::lmdb::LmdbStorage;
+
+/// Attributes storage selected when creating a node
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributesStorageKind {
+    /// Attributes are lost when the node stops
+    Memory,
+    /// Attributes are stored in an LMDB database in the node's directory
+    Lmdb,
+}
+
+impl FromStr for AttributesStorageKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "memory" => Ok(Self::Memory),
+            "lmdb" => Ok(Self::Lmdb),
+            _ => Err(format!(
+                "unknown attributes storage `{s}`, expected `memory` or `lmdb`"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for AttributesStorageKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Memory => f.write_str("memory"),
+            Self::Lmdb => f.write_str("lmdb"),
+        }
+    }
+}
+
+/// The storage backing a node's identity attributes
+#[derive(Clone)]
+pub enum NodeAttributesStorage {
+    Memory(InMemoryStorage),
+    Lmdb(LmdbStorage),
+}
+
+#[async_trait]
+impl AuthenticatedStorage for NodeAttributesStorage {
+    async fn get(&self, id: &str, key: &str) -> Result<Option<Vec<u8>>> {
+        match self {
+            Self::Memory(s) => s.get(id, key).await,
+            Self::Lmdb(s) => s.get(id, key).await,
+        }
+    }
+
+    async fn set(&self, id: &str, key: String, val: Vec<u8>) -> Result<()> {
+        match self {
+            Self::Memory(s) => s.set(id, key, val).await,
+            Self::Lmdb(s) => s.set(id, key, val).await,
+        }
+    }
+
+    async fn del(&self, id: &str, key: &str) -> Result<()> {
+        match self {
+            Self::Memory(s) => s.del(id, key).await,
+            Self::Lmdb(s) => s.del(id, key).await,
+        }
+    }
+
+    async fn keys(&self, namespace: &str) -> Result<Vec<String>> {
+        match self {
+            Self::Memory(s) => s.keys(namespace).await,
+            Self::Lmdb(s) => s.keys(namespace).await,
+        }
+    }
+}
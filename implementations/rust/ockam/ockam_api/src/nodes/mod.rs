@@ -1,3 +1,4 @@
+pub mod attributes_storage;
 pub mod config;
 pub mod registry;
 
@@ -44,3 +44,86 @@ impl<'a> NodeStatus<'a> {
         }
     }
 }
+
+/// Response body for a node health check
+#[derive(Debug, Clone, Decode, Encode, serde::Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct NodeHealth<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<1736940>,
+    #[b(1)] pub node_name: CowStr<'a>,
+    /// Seconds since the node manager was started
+    #[n(2)] pub uptime: u64,
+    /// Whether the node has at least one listening transport
+    #[n(3)] pub transport_listening: bool,
+    #[n(4)] pub services: u32,
+    /// The last error the node manager failed a request with
+    #[b(5)] pub last_error: Option<CowStr<'a>>,
+}
+
+impl<'a> NodeHealth<'a> {
+    pub fn new(
+        node_name: impl Into<CowStr<'a>>,
+        uptime: u64,
+        transport_listening: bool,
+        services: u32,
+        last_error: Option<impl Into<CowStr<'a>>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            node_name: node_name.into(),
+            uptime,
+            transport_listening,
+            services,
+            last_error: last_error.map(Into::into),
+        }
+    }
+}
+
+/// Summary of a node as recorded in the CLI state
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct NodeListItem {
+    pub name: String,
+    pub pid: Option<i32>,
+    pub tcp_listener_address: Option<String>,
+    pub status: NodeListStatus,
+    pub is_default: bool,
+}
+
+impl NodeListItem {
+    pub fn new(
+        name: impl Into<String>,
+        pid: Option<i32>,
+        tcp_listener_address: Option<String>,
+        status: NodeListStatus,
+        is_default: bool,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            pid,
+            tcp_listener_address,
+            status,
+            is_default,
+        }
+    }
+}
+
+/// Liveness of a node's process
+#[derive(Copy, Clone, Debug, serde::Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeListStatus {
+    Running,
+    Stopped,
+}
+
+impl core::fmt::Display for NodeListStatus {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::Running => "running",
+            Self::Stopped => "stopped",
+        })
+    }
+}
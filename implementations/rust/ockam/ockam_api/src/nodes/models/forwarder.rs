@@ -22,7 +22,10 @@ pub struct CreateForwarder<'a> {
     /// An authorised identity for secure channels.
     /// Only set for non-project addresses as for projects the project's
     /// authorised identity will be used.
-    #[n(4)] authorized: Option<IdentityIdentifier>
+    #[n(4)] authorized: Option<IdentityIdentifier>,
+    /// Re-create the forwarder when its secure channel is lost.
+    /// Defaults to `true` when not set.
+    #[n(5)] reconnect: Option<bool>
 }
 
 impl<'a> CreateForwarder<'a> {
@@ -34,6 +37,7 @@ impl<'a> CreateForwarder<'a> {
             alias: alias.map(|s| s.into()),
             at_rust_node: false,
             authorized: None,
+            reconnect: None,
         }
     }
 
@@ -50,9 +54,15 @@ impl<'a> CreateForwarder<'a> {
             alias: alias.map(|s| s.into()),
             at_rust_node,
             authorized: auth,
+            reconnect: None,
         }
     }
 
+    pub fn with_reconnect(mut self, reconnect: bool) -> Self {
+        self.reconnect = Some(reconnect);
+        self
+    }
+
     pub fn address(&self) -> &MultiAddr {
         &self.address
     }
@@ -68,6 +78,10 @@ impl<'a> CreateForwarder<'a> {
     pub fn authorized(&self) -> Option<IdentityIdentifier> {
         self.authorized.clone()
     }
+
+    pub fn reconnect(&self) -> bool {
+        self.reconnect.unwrap_or(true)
+    }
 }
 
 /// Response body when creating a forwarder
@@ -81,6 +95,12 @@ pub struct ForwarderInfo<'a> {
     #[b(1)] forwarding_route: CowStr<'a>,
     #[b(2)] remote_address: CowStr<'a>,
     #[b(3)] worker_address: CowStr<'a>,
+    /// Alias the forwarder was created with
+    #[b(4)] alias: Option<CowStr<'a>>,
+    /// Route to the node the forwarder was created at
+    #[b(5)] at: Option<CowStr<'a>>,
+    /// Whether the forwarder was created at a project
+    #[n(6)] at_project: Option<bool>,
 }
 
 impl<'a> ForwarderInfo<'a> {
@@ -91,6 +111,30 @@ impl<'a> ForwarderInfo<'a> {
     pub fn remote_address(&'a self) -> &'a str {
         &self.remote_address
     }
+
+    pub fn worker_address(&'a self) -> &'a str {
+        &self.worker_address
+    }
+
+    pub fn alias(&'a self) -> Option<&'a str> {
+        self.alias.as_deref()
+    }
+
+    pub fn at(&'a self) -> Option<&'a str> {
+        self.at.as_deref()
+    }
+
+    pub fn at_project(&self) -> bool {
+        self.at_project.unwrap_or(false)
+    }
+
+    /// Record how the forwarder was created
+    pub fn with_origin(mut self, alias: Option<&str>, at: &MultiAddr, at_project: bool) -> Self {
+        self.alias = alias.map(|a| a.to_string().into());
+        self.at = Some(at.to_string().into());
+        self.at_project = Some(at_project);
+        self
+    }
 }
 
 impl<'a> From<RemoteForwarderInfo> for ForwarderInfo<'a> {
@@ -101,6 +145,9 @@ impl<'a> From<RemoteForwarderInfo> for ForwarderInfo<'a> {
             forwarding_route: inner.forwarding_route().to_string().into(),
             remote_address: inner.remote_address().to_string().into(),
             worker_address: inner.worker_address().to_string().into(),
+            alias: None,
+            at: None,
+            at_project: None,
         }
     }
 }
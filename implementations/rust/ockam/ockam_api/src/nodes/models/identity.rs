@@ -13,6 +13,9 @@ pub struct LongIdentityResponse<'a> {
     #[serde(skip)]
     #[n(0)] tag: TypeTag<7961643>,
     #[b(1)] pub identity: CowBytes<'a>,
+    /// Creation time of the identity, in seconds since the unix epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[n(2)] pub created_at: Option<u64>,
 }
 
 impl<'a> LongIdentityResponse<'a> {
@@ -21,8 +24,14 @@ impl<'a> LongIdentityResponse<'a> {
             #[cfg(feature = "tag")]
             tag: TypeTag,
             identity: CowBytes(identity.into()),
+            created_at: None,
         }
     }
+
+    pub fn with_created_at(mut self, created_at: Option<u64>) -> Self {
+        self.created_at = created_at;
+        self
+    }
 }
 
 #[derive(Debug, Clone, Decode, Encode, Serialize)]
@@ -33,6 +42,15 @@ pub struct ShortIdentityResponse<'a> {
     #[serde(skip)]
     #[n(0)] tag: TypeTag<5773131>,
     #[b(1)] pub identity_id: Cow<'a, str>,
+    /// Type of the identity's root public key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[b(2)] pub key_type: Option<Cow<'a, str>>,
+    /// Number of entries in the identity's change history
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[n(3)] pub change_history_len: Option<u32>,
+    /// Creation time of the identity, in seconds since the unix epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[n(4)] pub created_at: Option<u64>,
 }
 
 impl<'a> ShortIdentityResponse<'a> {
@@ -41,6 +59,24 @@ impl<'a> ShortIdentityResponse<'a> {
             #[cfg(feature = "tag")]
             tag: TypeTag,
             identity_id: identity_id.into(),
+            key_type: None,
+            change_history_len: None,
+            created_at: None,
         }
     }
+
+    pub fn with_key_type(mut self, key_type: Option<impl Into<Cow<'a, str>>>) -> Self {
+        self.key_type = key_type.map(Into::into);
+        self
+    }
+
+    pub fn with_change_history_len(mut self, len: u32) -> Self {
+        self.change_history_len = Some(len);
+        self
+    }
+
+    pub fn with_created_at(mut self, created_at: Option<u64>) -> Self {
+        self.created_at = created_at;
+        self
+    }
 }
@@ -8,6 +8,7 @@ pub mod forwarder;
 pub mod identity;
 pub mod policy;
 pub mod portal;
+pub mod schema;
 pub mod secure_channel;
 pub mod services;
 pub mod transport;
@@ -1,5 +1,5 @@
 use minicbor::{Decode, Encode};
-use ockam_abac::{Action, Expr};
+use ockam_abac::{Action, Expr, Resource};
 #[cfg(feature = "tag")]
 use ockam_core::TypeTag;
 
@@ -48,3 +48,26 @@ impl PolicyList {
         &self.expressions
     }
 }
+
+#[derive(Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ResourcePolicyList {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6413807>,
+    #[n(1)] policies: Vec<(Resource, Action, Expr)>,
+}
+
+impl ResourcePolicyList {
+    pub fn new(p: Vec<(Resource, Action, Expr)>) -> Self {
+        ResourcePolicyList {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            policies: p,
+        }
+    }
+
+    pub fn policies(&self) -> &[(Resource, Action, Expr)] {
+        &self.policies
+    }
+}
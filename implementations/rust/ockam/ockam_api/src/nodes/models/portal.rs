@@ -9,6 +9,7 @@ use ockam_core::CowStr;
 use ockam_core::TypeTag;
 use ockam_identity::IdentityIdentifier;
 use ockam_multiaddr::MultiAddr;
+use serde::Serialize;
 
 /// Request body to create an inlet or outlet
 #[derive(Clone, Debug, Decode, Encode)]
@@ -31,7 +32,12 @@ pub struct CreateInlet<'a> {
     /// An authorised identity for secure channels.
     /// Only set for non-project addresses as for projects the project's
     /// authorised identity will be used.
-    #[n(5)] authorized: Option<IdentityIdentifier>
+    #[n(5)] authorized: Option<IdentityIdentifier>,
+    /// PEM file of the certificate chain presented to TLS clients.
+    /// The inlet terminates TLS when set, along with `tls_key`.
+    #[b(6)] tls_cert: Option<CowStr<'a>>,
+    /// PEM file of the private key of `tls_cert`
+    #[b(7)] tls_key: Option<CowStr<'a>>,
 }
 
 impl<'a> CreateInlet<'a> {
@@ -44,6 +50,8 @@ impl<'a> CreateInlet<'a> {
             alias: None,
             check_credential,
             authorized: None,
+            tls_cert: None,
+            tls_key: None,
         }
     }
 
@@ -61,6 +69,8 @@ impl<'a> CreateInlet<'a> {
             alias: None,
             check_credential,
             authorized: auth,
+            tls_cert: None,
+            tls_key: None,
         }
     }
 
@@ -68,6 +78,11 @@ impl<'a> CreateInlet<'a> {
         self.alias = Some(CowStr(a.into()))
     }
 
+    pub fn set_tls(&mut self, cert: impl Into<Cow<'a, str>>, key: impl Into<Cow<'a, str>>) {
+        self.tls_cert = Some(CowStr(cert.into()));
+        self.tls_key = Some(CowStr(key.into()));
+    }
+
     pub fn listen_addr(&self) -> SocketAddr {
         self.listen_addr
     }
@@ -87,6 +102,14 @@ impl<'a> CreateInlet<'a> {
     pub fn check_credential(&self) -> Option<bool> {
         self.check_credential
     }
+
+    pub fn tls_cert(&self) -> Option<&str> {
+        self.tls_cert.as_deref()
+    }
+
+    pub fn tls_key(&self) -> Option<&str> {
+        self.tls_key.as_deref()
+    }
 }
 
 /// Request body to create an inlet or outlet
@@ -105,6 +128,17 @@ pub struct CreateOutlet<'a> {
     /// Enable credential authorization.
     /// Defaults to the Node's `enable-credential-checks` value passed upon creation.
     #[n(4)] pub check_credential: Option<bool>,
+    /// Originate TLS to `tcp_addr`
+    #[n(5)] pub tls: Option<bool>,
+    /// PEM file of the CA certificates validating the certificate of `tcp_addr`.
+    /// Defaults to the platform's trusted roots.
+    #[b(6)] pub tls_ca_cert: Option<CowStr<'a>>,
+    /// The name the certificate of `tcp_addr` must be valid for.
+    /// Required when `tls` is set.
+    #[b(7)] pub tls_server_name: Option<CowStr<'a>>,
+    /// Only admit the identities of this list, authenticated by a secure channel.
+    /// Replaces the default access control of the outlet.
+    #[b(8)] pub allowed_identities: Option<Vec<CowStr<'a>>>,
 }
 
 impl<'a> CreateOutlet<'a> {
@@ -121,16 +155,38 @@ impl<'a> CreateOutlet<'a> {
             worker_addr: worker_addr.into(),
             alias: alias.into(),
             check_credential,
+            tls: None,
+            tls_ca_cert: None,
+            tls_server_name: None,
+            allowed_identities: None,
         }
     }
+
+    /// Only admit these identities, instead of using the default access control
+    pub fn with_allowed_identities(mut self, ids: Vec<IdentityIdentifier>) -> Self {
+        self.allowed_identities = Some(ids.into_iter().map(|id| id.to_string().into()).collect());
+        self
+    }
+
+    pub fn with_tls(
+        mut self,
+        ca_cert: Option<impl Into<Cow<'a, str>>>,
+        server_name: impl Into<Cow<'a, str>>,
+    ) -> Self {
+        self.tls = Some(true);
+        self.tls_ca_cert = ca_cert.map(|c| CowStr(c.into()));
+        self.tls_server_name = Some(CowStr(server_name.into()));
+        self
+    }
 }
 
 /// Response body when interacting with a portal endpoint
-#[derive(Clone, Debug, Decode, Encode)]
+#[derive(Clone, Debug, Decode, Encode, Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct InletStatus<'a> {
     #[cfg(feature = "tag")]
+    #[serde(skip)]
     #[n(0)] tag: TypeTag<9302588>,
     #[b(1)] pub bind_addr: CowStr<'a>,
     #[b(2)] pub worker_addr: CowStr<'a>,
@@ -173,11 +229,12 @@ impl<'a> InletStatus<'a> {
 }
 
 /// Response body when interacting with a portal endpoint
-#[derive(Clone, Debug, Decode, Encode)]
+#[derive(Clone, Debug, Decode, Encode, Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct OutletStatus<'a> {
     #[cfg(feature = "tag")]
+    #[serde(skip)]
     #[n(0)] tag: TypeTag<4012569>,
     #[b(1)] pub tcp_addr: CowStr<'a>,
     #[b(2)] pub worker_addr: CowStr<'a>,
@@ -216,11 +273,12 @@ impl<'a> OutletStatus<'a> {
 }
 
 /// Response body when returning a list of Inlets
-#[derive(Debug, Clone, Decode, Encode)]
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct InletList<'a> {
     #[cfg(feature = "tag")]
+    #[serde(skip)]
     #[n(0)] tag: TypeTag<8401504>,
     #[b(1)] pub list: Vec<InletStatus<'a>>
 }
@@ -236,11 +294,12 @@ impl<'a> InletList<'a> {
 }
 
 /// Response body when returning a list of Outlets
-#[derive(Debug, Clone, Decode, Encode)]
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct OutletList<'a> {
     #[cfg(feature = "tag")]
+    #[serde(skip)]
     #[n(0)] tag: TypeTag<8708916>,
     #[b(1)] pub list: Vec<OutletStatus<'a>>
 }
@@ -0,0 +1,722 @@
+//! Description of the node manager API models, for clients not written in Rust
+//!
+//! The models are CBOR maps whose keys are the field indexes. They are described
+//! with JSON Schema, where each property is named after the index of a field and
+//! titled with its name. Key 0 holds the type tag of a model when it is enabled.
+
+use serde_json::{json, Map, Value};
+
+/// JSON Schema dialect of the generated documents
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Rust integer type of a field, which determines the range of its values
+pub trait IntegerType {
+    const SIGNED: bool;
+}
+
+macro_rules! integer_types {
+    ($signed:literal: $($t:ty),*) => {
+        $(impl IntegerType for $t {
+            const SIGNED: bool = $signed;
+        })*
+    };
+}
+
+integer_types!(false: u8, u16, u32, u64, usize);
+integer_types!(true: i8, i16, i32, i64, isize);
+
+/// Type of the value of a field
+#[derive(Debug, Clone, Copy)]
+pub enum FieldType {
+    String,
+    Bytes,
+    /// Integer, which is never negative unless it is signed
+    Integer {
+        signed: bool,
+    },
+    Boolean,
+    /// Array of seconds and nanoseconds
+    Duration,
+    /// Enum without fields, encoded as the index of its variant
+    Index(&'static [&'static str]),
+    /// Enum without fields, encoded as an array of the index of its variant and an empty array
+    Variant(&'static [&'static str]),
+    Array(&'static FieldType),
+    /// Another model
+    Model(&'static str),
+}
+
+impl FieldType {
+    /// Type of a field whose Rust type is the integer type `T`
+    pub const fn integer<T: IntegerType>() -> Self {
+        FieldType::Integer { signed: T::SIGNED }
+    }
+
+    fn json_schema(&self) -> Value {
+        match self {
+            FieldType::String => json!({ "type": "string" }),
+            FieldType::Bytes => json!({ "type": "string", "contentEncoding": "base16" }),
+            FieldType::Integer { signed: true } => json!({ "type": "integer" }),
+            FieldType::Integer { signed: false } => json!({ "type": "integer", "minimum": 0 }),
+            FieldType::Boolean => json!({ "type": "boolean" }),
+            FieldType::Duration => json!({
+                "type": "array",
+                "prefixItems": [
+                    { "type": "integer", "minimum": 0 },
+                    { "type": "integer", "minimum": 0, "maximum": 999_999_999 }
+                ],
+                "items": false,
+                "description": "seconds and nanoseconds"
+            }),
+            FieldType::Index(variants) => json!({
+                "type": "integer",
+                "enum": (0..variants.len()).collect::<Vec<_>>(),
+                "description": variants_description(variants)
+            }),
+            FieldType::Variant(variants) => json!({
+                "type": "array",
+                "prefixItems": [
+                    { "type": "integer", "enum": (0..variants.len()).collect::<Vec<_>>() },
+                    { "type": "array", "maxItems": 0 }
+                ],
+                "items": false,
+                "description": variants_description(variants)
+            }),
+            FieldType::Array(item) => json!({ "type": "array", "items": item.json_schema() }),
+            FieldType::Model(name) => json!({ "$ref": format!("#/$defs/{name}") }),
+        }
+    }
+}
+
+fn variants_description(variants: &[&str]) -> String {
+    variants
+        .iter()
+        .enumerate()
+        .map(|(i, v)| format!("{i}: {v}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Field of a model
+#[derive(Debug, Clone, Copy)]
+pub struct FieldSchema {
+    pub index: u8,
+    pub name: &'static str,
+    pub ty: FieldType,
+    /// Optional fields are absent when they have no value
+    pub optional: bool,
+    pub description: Option<&'static str>,
+}
+
+impl FieldSchema {
+    const fn required(index: u8, name: &'static str, ty: FieldType) -> Self {
+        Self {
+            index,
+            name,
+            ty,
+            optional: false,
+            description: None,
+        }
+    }
+
+    const fn optional(index: u8, name: &'static str, ty: FieldType) -> Self {
+        Self {
+            index,
+            name,
+            ty,
+            optional: true,
+            description: None,
+        }
+    }
+
+    const fn describe(mut self, description: &'static str) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    fn json_schema(&self) -> Value {
+        let mut schema = self.ty.json_schema();
+        let obj = schema.as_object_mut().expect("field schemas are objects");
+        obj.insert("title".to_string(), self.name.into());
+        if let Some(description) = self.description {
+            obj.insert("description".to_string(), description.into());
+        }
+        schema
+    }
+}
+
+/// Request or response body of the node manager API
+#[derive(Debug, Clone, Copy)]
+pub struct ModelSchema {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Value of the type tag of the model
+    pub type_tag: u64,
+    pub fields: &'static [FieldSchema],
+}
+
+impl ModelSchema {
+    pub fn json_schema(&self) -> Value {
+        let properties: Map<String, Value> = self
+            .fields
+            .iter()
+            .map(|f| (f.index.to_string(), f.json_schema()))
+            .collect();
+        let required: Vec<String> = self
+            .fields
+            .iter()
+            .filter(|f| !f.optional)
+            .map(|f| f.index.to_string())
+            .collect();
+        json!({
+            "title": self.name,
+            "description": self.description,
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "x-type-tag": self.type_tag,
+        })
+    }
+}
+
+/// Find the model with the given name
+pub fn find(name: &str) -> Option<&'static ModelSchema> {
+    MODELS.iter().find(|m| m.name == name)
+}
+
+/// JSON Schema document defining all the models
+pub fn json_schema() -> Value {
+    let defs: Map<String, Value> = MODELS
+        .iter()
+        .map(|m| (m.name.to_string(), m.json_schema()))
+        .collect();
+    json!({ "$schema": JSON_SCHEMA_DIALECT, "$defs": defs })
+}
+
+/// JSON Schema document of a single model, with the models it refers to
+pub fn model_json_schema(model: &ModelSchema) -> Value {
+    let mut schema = model.json_schema();
+    let defs: Map<String, Value> = referenced_models(model)
+        .into_iter()
+        .map(|m| (m.name.to_string(), m.json_schema()))
+        .collect();
+    let obj = schema.as_object_mut().expect("model schemas are objects");
+    obj.insert("$schema".to_string(), JSON_SCHEMA_DIALECT.into());
+    if !defs.is_empty() {
+        obj.insert("$defs".to_string(), defs.into());
+    }
+    schema
+}
+
+fn referenced_models(model: &ModelSchema) -> Vec<&'static ModelSchema> {
+    fn model_name(ty: &FieldType) -> Option<&'static str> {
+        match ty {
+            FieldType::Model(name) => Some(name),
+            FieldType::Array(item) => model_name(item),
+            _ => None,
+        }
+    }
+    let mut models: Vec<&'static ModelSchema> = vec![];
+    let mut pending: Vec<&ModelSchema> = vec![model];
+    while let Some(current) = pending.pop() {
+        for m in current
+            .fields
+            .iter()
+            .filter_map(|f| model_name(&f.ty))
+            .filter_map(find)
+        {
+            if models.iter().all(|known| known.name != m.name) {
+                models.push(m);
+                pending.push(m);
+            }
+        }
+    }
+    models
+}
+
+const TRANSPORT_TYPE: FieldType = FieldType::Index(&["Tcp", "Ble", "WebSocket"]);
+const TRANSPORT_MODE: FieldType = FieldType::Variant(&["Listen", "Connect"]);
+const CREDENTIAL_EXCHANGE_MODE: FieldType = FieldType::Index(&["None", "Oneway", "Mutual"]);
+const STRINGS: FieldType = FieldType::Array(&FieldType::String);
+
+/// Models of the transport, services, identity and secure channel endpoints
+pub const MODELS: &[ModelSchema] = &[
+    // Transports
+    ModelSchema {
+        name: "CreateTransport",
+        description: "Request body when instructing a node to create a transport",
+        type_tag: 1503320,
+        fields: &[
+            FieldSchema::required(1, "tt", TRANSPORT_TYPE),
+            FieldSchema::required(2, "tm", TRANSPORT_MODE),
+            FieldSchema::required(3, "addr", FieldType::String),
+            FieldSchema::optional(4, "keepalive", FieldType::integer::<u64>())
+                .describe("Interval in seconds of the heartbeats sent on a connection"),
+            FieldSchema::optional(5, "connect_timeout", FieldType::integer::<u64>()).describe(
+                "Maximum number of milliseconds to wait for a connection to be established",
+            ),
+        ],
+    },
+    ModelSchema {
+        name: "DeleteTransport",
+        description: "Request to delete a transport",
+        type_tag: 4739996,
+        fields: &[FieldSchema::required(1, "tid", FieldType::String)],
+    },
+    ModelSchema {
+        name: "TransportStatus",
+        description: "Response body when interacting with a transport",
+        type_tag: 1581592,
+        fields: &[
+            FieldSchema::required(2, "tt", TRANSPORT_TYPE),
+            FieldSchema::required(3, "tm", TRANSPORT_MODE),
+            FieldSchema::required(4, "payload", FieldType::String),
+            FieldSchema::required(5, "tid", FieldType::String),
+        ],
+    },
+    ModelSchema {
+        name: "TransportList",
+        description: "Response body when listing transports",
+        type_tag: 5212817,
+        fields: &[FieldSchema::required(
+            1,
+            "list",
+            FieldType::Array(&FieldType::Model("TransportStatus")),
+        )],
+    },
+    // Services
+    ModelSchema {
+        name: "StartVaultServiceRequest",
+        description: "Request body when instructing a node to start a Vault service",
+        type_tag: 9798850,
+        fields: &[FieldSchema::required(1, "addr", FieldType::String)],
+    },
+    ModelSchema {
+        name: "StartIdentityServiceRequest",
+        description: "Request body when instructing a node to start an Identity service",
+        type_tag: 6129106,
+        fields: &[FieldSchema::required(1, "addr", FieldType::String)],
+    },
+    ModelSchema {
+        name: "StartAuthenticatedServiceRequest",
+        description: "Request body when instructing a node to start an Authenticated service",
+        type_tag: 5179596,
+        fields: &[FieldSchema::required(1, "addr", FieldType::String)],
+    },
+    ModelSchema {
+        name: "StartUppercaseServiceRequest",
+        description: "Request body when instructing a node to start an Uppercase service",
+        type_tag: 8177400,
+        fields: &[
+            FieldSchema::required(1, "addr", FieldType::String),
+            FieldSchema::optional(2, "max_message_size", FieldType::integer::<u32>())
+                .describe("Maximum size in bytes of the handled messages"),
+        ],
+    },
+    ModelSchema {
+        name: "StartEchoerServiceRequest",
+        description: "Request body when instructing a node to start an Echoer service",
+        type_tag: 7636656,
+        fields: &[
+            FieldSchema::required(1, "addr", FieldType::String),
+            FieldSchema::optional(2, "rate_limit", FieldType::integer::<u32>())
+                .describe("Maximum number of messages handled per second"),
+            FieldSchema::optional(3, "max_message_size", FieldType::integer::<u32>())
+                .describe("Maximum size in bytes of the handled messages"),
+        ],
+    },
+    ModelSchema {
+        name: "StartHopServiceRequest",
+        description: "Request body when instructing a node to start a Hop service",
+        type_tag: 7361428,
+        fields: &[
+            FieldSchema::required(1, "addr", FieldType::String),
+            FieldSchema::optional(2, "rate_limit", FieldType::integer::<u32>())
+                .describe("Maximum number of messages handled per second"),
+        ],
+    },
+    ModelSchema {
+        name: "StartCredentialsService",
+        description: "Request body when instructing a node to start a Credentials service",
+        type_tag: 6467937,
+        fields: &[
+            FieldSchema::required(1, "addr", FieldType::String),
+            FieldSchema::required(2, "oneway", FieldType::Boolean),
+        ],
+    },
+    ModelSchema {
+        name: "ServiceStatus",
+        description: "Response body when interacting with a service",
+        type_tag: 8542064,
+        fields: &[
+            FieldSchema::required(2, "addr", FieldType::String),
+            FieldSchema::required(3, "service_type", FieldType::String),
+        ],
+    },
+    ModelSchema {
+        name: "ServiceList",
+        description: "Response body for listing services",
+        type_tag: 9587601,
+        fields: &[FieldSchema::required(
+            1,
+            "list",
+            FieldType::Array(&FieldType::Model("ServiceStatus")),
+        )],
+    },
+    // Identities
+    ModelSchema {
+        name: "LongIdentityResponse",
+        description: "Response body with the exported identity of a node",
+        type_tag: 7961643,
+        fields: &[
+            FieldSchema::required(1, "identity", FieldType::Bytes),
+            FieldSchema::optional(2, "created_at", FieldType::integer::<u64>())
+                .describe("Creation time of the identity, in seconds since the unix epoch"),
+        ],
+    },
+    ModelSchema {
+        name: "ShortIdentityResponse",
+        description: "Response body with the identifier of the identity of a node",
+        type_tag: 5773131,
+        fields: &[
+            FieldSchema::required(1, "identity_id", FieldType::String),
+            FieldSchema::optional(2, "key_type", FieldType::String)
+                .describe("Type of the identity's root public key"),
+            FieldSchema::optional(3, "change_history_len", FieldType::integer::<u32>())
+                .describe("Number of entries in the identity's change history"),
+            FieldSchema::optional(4, "created_at", FieldType::integer::<u64>())
+                .describe("Creation time of the identity, in seconds since the unix epoch"),
+        ],
+    },
+    ModelSchema {
+        name: "Credential",
+        description: "Credential signed by an authority",
+        type_tag: 3796735,
+        fields: &[
+            FieldSchema::required(1, "data", FieldType::Bytes)
+                .describe("CBOR-encoded credential data"),
+            FieldSchema::required(2, "signature", FieldType::Bytes),
+        ],
+    },
+    // Secure channels
+    ModelSchema {
+        name: "CreateSecureChannelRequest",
+        description: "Request body when instructing a node to create a Secure Channel",
+        type_tag: 6300395,
+        fields: &[
+            FieldSchema::required(1, "addr", FieldType::String),
+            FieldSchema::optional(2, "authorized_identifiers", STRINGS),
+            FieldSchema::required(3, "credential_exchange_mode", CREDENTIAL_EXCHANGE_MODE),
+            FieldSchema::optional(4, "timeout", FieldType::Duration),
+            FieldSchema::optional(5, "identity", FieldType::String),
+            FieldSchema::optional(6, "credential", FieldType::Model("Credential"))
+                .describe("Credential presented instead of the one of the node"),
+        ],
+    },
+    ModelSchema {
+        name: "CreateSecureChannelResponse",
+        description: "Response body when instructing a node to create a Secure Channel",
+        type_tag: 6056513,
+        fields: &[FieldSchema::required(1, "addr", FieldType::String)],
+    },
+    ModelSchema {
+        name: "CreateSecureChannelListenerRequest",
+        description: "Request body when instructing a node to create a Secure Channel Listener",
+        type_tag: 8112242,
+        fields: &[
+            FieldSchema::required(1, "addr", FieldType::String),
+            FieldSchema::optional(2, "authorized_identifiers", STRINGS),
+            FieldSchema::optional(3, "identity", FieldType::String),
+        ],
+    },
+    ModelSchema {
+        name: "DeleteSecureChannelRequest",
+        description: "Request body when instructing a node to delete a Secure Channel",
+        type_tag: 8472592,
+        fields: &[FieldSchema::required(1, "channel", FieldType::String)],
+    },
+    ModelSchema {
+        name: "DeleteSecureChannelResponse",
+        description: "Response body when instructing a node to delete a Secure Channel",
+        type_tag: 6953395,
+        fields: &[FieldSchema::optional(1, "channel", FieldType::String)],
+    },
+    ModelSchema {
+        name: "ShowSecureChannelRequest",
+        description: "Request body when asking a node about a Secure Channel",
+        type_tag: 3277982,
+        fields: &[FieldSchema::required(1, "channel", FieldType::String)],
+    },
+    ModelSchema {
+        name: "ShowSecureChannelResponse",
+        description: "Response body when asking a node about a Secure Channel",
+        type_tag: 4566220,
+        fields: &[
+            FieldSchema::optional(1, "channel", FieldType::String),
+            FieldSchema::optional(2, "route", FieldType::String),
+            FieldSchema::optional(4, "authorized_identifiers", STRINGS),
+            FieldSchema::optional(5, "decryptor", FieldType::String),
+            FieldSchema::optional(6, "remote_identity", FieldType::String),
+            FieldSchema::optional(7, "created_at", FieldType::integer::<u64>())
+                .describe("Unix timestamp, in seconds"),
+        ],
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::models::identity::{LongIdentityResponse, ShortIdentityResponse};
+    use crate::nodes::models::secure_channel::{
+        CreateSecureChannelListenerRequest, CreateSecureChannelRequest,
+        CreateSecureChannelResponse, CredentialExchangeMode, DeleteSecureChannelRequest,
+        DeleteSecureChannelResponse, ShowSecureChannelRequest, ShowSecureChannelResponse,
+    };
+    use crate::nodes::models::services::{
+        ServiceList, ServiceStatus, StartAuthenticatedServiceRequest, StartCredentialsService,
+        StartEchoerServiceRequest, StartHopServiceRequest, StartIdentityServiceRequest,
+        StartUppercaseServiceRequest, StartVaultServiceRequest,
+    };
+    use crate::nodes::models::transport::{
+        CreateTransport, DeleteTransport, TransportList, TransportMode, TransportStatus,
+        TransportType,
+    };
+    use minicbor::data::Type;
+    use minicbor::{Decoder, Encode};
+    use ockam_core::Address;
+    use ockam_identity::credential::Credential;
+    use std::collections::BTreeSet;
+    use std::time::Duration;
+
+    /// Indexes of the fields of an encoded model, except the type tag
+    fn encoded_indexes(bytes: &[u8]) -> BTreeSet<u8> {
+        let mut dec = Decoder::new(bytes);
+        let len = dec.map().unwrap().unwrap();
+        let mut indexes = BTreeSet::new();
+        for _ in 0..len {
+            indexes.insert(dec.u8().unwrap());
+            dec.skip().unwrap();
+        }
+        indexes.remove(&0);
+        indexes
+    }
+
+    /// Check that the next encoded value has the described type, and skip it
+    fn check_encoded_value(ty: &FieldType, dec: &mut Decoder, field: &str) {
+        let datatype = dec.datatype().unwrap();
+        let unsigned = matches!(datatype, Type::U8 | Type::U16 | Type::U32 | Type::U64);
+        let expected = match ty {
+            FieldType::String => datatype == Type::String,
+            FieldType::Bytes => datatype == Type::Bytes,
+            FieldType::Integer { signed: false } | FieldType::Index(_) => unsigned,
+            FieldType::Integer { signed: true } => {
+                unsigned || matches!(datatype, Type::I8 | Type::I16 | Type::I32 | Type::I64)
+            }
+            FieldType::Boolean => datatype == Type::Bool,
+            FieldType::Duration | FieldType::Variant(_) | FieldType::Array(_) => {
+                datatype == Type::Array
+            }
+            FieldType::Model(_) => datatype == Type::Map,
+        };
+        assert!(
+            expected,
+            "{field} is described as {ty:?} but encoded as {datatype:?}"
+        );
+        match ty {
+            FieldType::Array(item) => {
+                let len = dec.array().unwrap().unwrap();
+                for _ in 0..len {
+                    check_encoded_value(item, dec, field);
+                }
+            }
+            FieldType::Duration => {
+                dec.array().unwrap();
+                check_encoded_value(&FieldType::integer::<u64>(), dec, field);
+                check_encoded_value(&FieldType::integer::<u32>(), dec, field);
+            }
+            _ => dec.skip().unwrap(),
+        }
+    }
+
+    fn encode<T: Encode<()>>(model: T) -> Vec<u8> {
+        minicbor::to_vec(model).unwrap()
+    }
+
+    fn credential() -> Credential {
+        let mut bytes = vec![];
+        minicbor::Encoder::new(&mut bytes)
+            .map(3)
+            .unwrap()
+            .u8(0)
+            .unwrap()
+            .u64(3796735)
+            .unwrap()
+            .u8(1)
+            .unwrap()
+            .bytes(b"data")
+            .unwrap()
+            .u8(2)
+            .unwrap()
+            .bytes(b"signature")
+            .unwrap();
+        minicbor::decode(&bytes).unwrap()
+    }
+
+    /// Every described model, encoded with all its fields set
+    fn encoded_models() -> Vec<(&'static str, Vec<u8>)> {
+        let addr = Address::from("addr");
+        let transport = CreateTransport::new(TransportType::Tcp, TransportMode::Listen, "addr")
+            .with_keepalive(Duration::from_secs(10))
+            .with_connect_timeout(Duration::from_secs(5));
+        let status = || TransportStatus::new(TransportType::Tcp, TransportMode::Listen, "a", "t");
+
+        let mut channel = CreateSecureChannelRequest::new(
+            &"/service/api".parse().unwrap(),
+            Some(vec![]),
+            CredentialExchangeMode::Mutual,
+            Some("identity".to_string()),
+        )
+        .with_credential(credential());
+        channel.timeout = Some(Duration::from_secs(1));
+        let listener =
+            CreateSecureChannelListenerRequest::new(&addr, Some(vec![]), Some("i".to_string()));
+        let mut show = ShowSecureChannelResponse::new(None);
+        show.channel = Some("channel".into());
+        show.route = Some("route".into());
+        show.authorized_identifiers = Some(vec![]);
+        show.decryptor = Some("decryptor".into());
+        show.remote_identity = Some("P123".into());
+        show.created_at = Some(1);
+
+        vec![
+            ("CreateTransport", encode(transport)),
+            ("DeleteTransport", encode(DeleteTransport::new("tid"))),
+            ("TransportStatus", encode(status())),
+            ("TransportList", encode(TransportList::new(vec![status()]))),
+            (
+                "StartVaultServiceRequest",
+                encode(StartVaultServiceRequest::new("addr")),
+            ),
+            (
+                "StartIdentityServiceRequest",
+                encode(StartIdentityServiceRequest::new("addr")),
+            ),
+            (
+                "StartAuthenticatedServiceRequest",
+                encode(StartAuthenticatedServiceRequest::new("addr")),
+            ),
+            (
+                "StartUppercaseServiceRequest",
+                encode(StartUppercaseServiceRequest::new("addr").with_max_message_size(1)),
+            ),
+            (
+                "StartEchoerServiceRequest",
+                encode(
+                    StartEchoerServiceRequest::new("addr")
+                        .with_rate_limit(1)
+                        .with_max_message_size(1),
+                ),
+            ),
+            (
+                "StartHopServiceRequest",
+                encode(StartHopServiceRequest::new("addr").with_rate_limit(1)),
+            ),
+            (
+                "StartCredentialsService",
+                encode(StartCredentialsService::new("addr", true)),
+            ),
+            ("ServiceStatus", encode(ServiceStatus::new("addr", "type"))),
+            (
+                "ServiceList",
+                encode(ServiceList::new(vec![ServiceStatus::new("addr", "type")])),
+            ),
+            (
+                "LongIdentityResponse",
+                encode(LongIdentityResponse::new(vec![1u8]).with_created_at(Some(1))),
+            ),
+            (
+                "ShortIdentityResponse",
+                encode(
+                    ShortIdentityResponse::new("P123")
+                        .with_key_type(Some("Ed25519"))
+                        .with_change_history_len(1)
+                        .with_created_at(Some(1)),
+                ),
+            ),
+            ("Credential", encode(credential())),
+            ("CreateSecureChannelRequest", encode(channel)),
+            (
+                "CreateSecureChannelResponse",
+                encode(CreateSecureChannelResponse::new(&addr)),
+            ),
+            ("CreateSecureChannelListenerRequest", encode(listener)),
+            (
+                "DeleteSecureChannelRequest",
+                encode(DeleteSecureChannelRequest::new(&addr)),
+            ),
+            (
+                "DeleteSecureChannelResponse",
+                encode(DeleteSecureChannelResponse::new(Some(addr.clone()))),
+            ),
+            (
+                "ShowSecureChannelRequest",
+                encode(ShowSecureChannelRequest::new(&addr)),
+            ),
+            ("ShowSecureChannelResponse", encode(show)),
+        ]
+    }
+
+    #[test]
+    fn described_fields_match_the_encoded_models() {
+        let models = encoded_models();
+        for (name, bytes) in &models {
+            let schema = find(name).unwrap();
+            let described: BTreeSet<u8> = schema.fields.iter().map(|f| f.index).collect();
+            assert_eq!(described, encoded_indexes(bytes), "fields of {name}");
+        }
+
+        // No described model is left unchecked
+        let checked: BTreeSet<&str> = models.iter().map(|(name, _)| *name).collect();
+        let described: BTreeSet<&str> = MODELS.iter().map(|m| m.name).collect();
+        assert_eq!(checked, described);
+    }
+
+    #[test]
+    fn described_types_match_the_encoded_values() {
+        for (name, bytes) in &encoded_models() {
+            let schema = find(name).unwrap();
+            let mut dec = Decoder::new(bytes);
+            let len = dec.map().unwrap().unwrap();
+            for _ in 0..len {
+                let index = dec.u8().unwrap();
+                match schema.fields.iter().find(|f| f.index == index) {
+                    Some(f) => check_encoded_value(&f.ty, &mut dec, &format!("{name}.{}", f.name)),
+                    None => dec.skip().unwrap(),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unsigned_integers_have_a_minimum() {
+        let schema = model_json_schema(find("StartEchoerServiceRequest").unwrap());
+        assert_eq!(schema["properties"]["2"]["minimum"], 0);
+        assert!(FieldType::integer::<i64>()
+            .json_schema()
+            .get("minimum")
+            .is_none());
+    }
+
+    #[test]
+    fn referenced_models_are_defined() {
+        let schema = json_schema();
+        let defs = schema["$defs"].as_object().unwrap();
+        assert_eq!(defs.len(), MODELS.len());
+
+        let single = model_json_schema(find("CreateSecureChannelRequest").unwrap());
+        assert_eq!(single["$schema"], JSON_SCHEMA_DIALECT);
+        assert!(single["$defs"]["Credential"].is_object());
+        assert_eq!(single["properties"]["6"]["$ref"], "#/$defs/Credential");
+        assert_eq!(single["required"], json!(["1", "3"]));
+    }
+}
@@ -1,10 +1,11 @@
-use std::time::Duration;
+use std::time::{Duration, UNIX_EPOCH};
 
 use minicbor::{Decode, Encode};
 use ockam_core::compat::borrow::Cow;
 #[cfg(feature = "tag")]
 use ockam_core::TypeTag;
 use ockam_core::{route, Address, CowStr, Result};
+use ockam_identity::credential::Credential;
 use ockam_identity::IdentityIdentifier;
 use ockam_multiaddr::MultiAddr;
 use serde::Serialize;
@@ -34,6 +35,7 @@ pub struct CreateSecureChannelRequest<'a> {
     #[n(3)] pub credential_exchange_mode: CredentialExchangeMode,
     #[n(4)] pub timeout: Option<Duration>,
     #[b(5)] pub identity: Option<CowStr<'a>>,
+    #[n(6)] pub credential: Option<Credential>,
 }
 
 impl<'a> CreateSecureChannelRequest<'a> {
@@ -52,8 +54,15 @@ impl<'a> CreateSecureChannelRequest<'a> {
             credential_exchange_mode,
             timeout: None,
             identity: identity.map(|x| x.into()),
+            credential: None,
         }
     }
+
+    /// Present this credential, instead of the one of the node, once the channel is created
+    pub fn with_credential(mut self, credential: Credential) -> Self {
+        self.credential = Some(credential);
+        self
+    }
 }
 
 /// Response body when instructing a node to create a Secure Channel
@@ -185,6 +194,11 @@ pub struct ShowSecureChannelResponse<'a> {
     #[b(1)] pub channel: Option<Cow<'a, str>>,
     #[b(2)] pub route: Option<Cow<'a, str>>,
     #[b(4)] pub authorized_identifiers: Option<Vec<CowStr<'a>>>,
+    // The fields below are not sent by older nodes
+    #[b(5)] pub decryptor: Option<CowStr<'a>>,
+    #[b(6)] pub remote_identity: Option<CowStr<'a>>,
+    /// Unix timestamp, in seconds
+    #[n(7)] pub created_at: Option<u64>,
 }
 
 impl<'a> ShowSecureChannelResponse<'a> {
@@ -200,6 +214,18 @@ impl<'a> ShowSecureChannelResponse<'a> {
                         .map(|ids| ids.iter().map(|iid| iid.to_string().into()).collect())
                 })
                 .unwrap_or(None),
+            decryptor: info
+                .and_then(|info| info.decryptor_addr())
+                .map(|addr| addr.to_string().into()),
+            remote_identity: info
+                .and_then(|info| info.their_id())
+                .map(|id| id.to_string().into()),
+            created_at: info.and_then(|info| {
+                info.created_at()
+                    .duration_since(UNIX_EPOCH)
+                    .ok()
+                    .map(|d| d.as_secs())
+            }),
         }
     }
 }
@@ -181,6 +181,8 @@ pub struct StartUppercaseServiceRequest<'a> {
     #[cfg(feature = "tag")]
     #[n(0)] tag: TypeTag<8177400>,
     #[b(1)] pub addr: CowStr<'a>,
+    /// Maximum size in bytes of the handled messages, unlimited if absent
+    #[n(2)] pub max_message_size: Option<u32>,
 }
 
 impl<'a> StartUppercaseServiceRequest<'a> {
@@ -189,8 +191,14 @@ impl<'a> StartUppercaseServiceRequest<'a> {
             #[cfg(feature = "tag")]
             tag: TypeTag,
             addr: addr.into(),
+            max_message_size: None,
         }
     }
+
+    pub fn with_max_message_size(mut self, bytes: u32) -> Self {
+        self.max_message_size = Some(bytes);
+        self
+    }
 }
 
 /// Request body when instructing a node to start an Echoer service
@@ -201,6 +209,10 @@ pub struct StartEchoerServiceRequest<'a> {
     #[cfg(feature = "tag")]
     #[n(0)] tag: TypeTag<7636656>,
     #[b(1)] pub addr: CowStr<'a>,
+    /// Maximum number of messages handled per second, unlimited if absent
+    #[n(2)] pub rate_limit: Option<u32>,
+    /// Maximum size in bytes of the handled messages, unlimited if absent
+    #[n(3)] pub max_message_size: Option<u32>,
 }
 
 impl<'a> StartEchoerServiceRequest<'a> {
@@ -209,8 +221,20 @@ impl<'a> StartEchoerServiceRequest<'a> {
             #[cfg(feature = "tag")]
             tag: TypeTag,
             addr: addr.into(),
+            rate_limit: None,
+            max_message_size: None,
         }
     }
+
+    pub fn with_rate_limit(mut self, per_second: u32) -> Self {
+        self.rate_limit = Some(per_second);
+        self
+    }
+
+    pub fn with_max_message_size(mut self, bytes: u32) -> Self {
+        self.max_message_size = Some(bytes);
+        self
+    }
 }
 
 /// Request body when instructing a node to start a Hop service
@@ -221,6 +245,8 @@ pub struct StartHopServiceRequest<'a> {
     #[cfg(feature = "tag")]
     #[n(0)] tag: TypeTag<7361428>,
     #[b(1)] pub addr: CowStr<'a>,
+    /// Maximum number of messages handled per second, unlimited if absent
+    #[n(2)] pub rate_limit: Option<u32>,
 }
 
 impl<'a> StartHopServiceRequest<'a> {
@@ -229,8 +255,14 @@ impl<'a> StartHopServiceRequest<'a> {
             #[cfg(feature = "tag")]
             tag: TypeTag,
             addr: addr.into(),
+            rate_limit: None,
         }
     }
+
+    pub fn with_rate_limit(mut self, per_second: u32) -> Self {
+        self.rate_limit = Some(per_second);
+        self
+    }
 }
 
 #[derive(Debug, Clone, Decode, Encode)]
@@ -243,7 +275,13 @@ pub struct StartAuthenticatorRequest<'a> {
     #[b(2)] enrollers: CowStr<'a>,
     #[b(3)] proj: CowBytes<'a>,
     // FIXME: test id old format still matches with this
-    #[n(4)] reload_enrollers: bool
+    #[n(4)] reload_enrollers: bool,
+    /// Validity of enrollment tokens, in seconds
+    #[n(5)] token_duration: Option<u64>,
+    /// Validity of issued credentials, in seconds
+    #[n(6)] credential_ttl: Option<u64>,
+    /// Attributes of the enrollers copied to the members they enroll
+    #[b(7)] inherited_attributes: Option<Vec<CowStr<'a>>>,
 }
 
 impl<'a> StartAuthenticatorRequest<'a> {
@@ -260,9 +298,29 @@ impl<'a> StartAuthenticatorRequest<'a> {
             enrollers: enrollers.into(),
             reload_enrollers,
             proj: proj.into(),
+            token_duration: None,
+            credential_ttl: None,
+            inherited_attributes: None,
         }
     }
 
+    pub fn with_token_duration(mut self, secs: Option<u64>) -> Self {
+        self.token_duration = secs;
+        self
+    }
+
+    pub fn with_credential_ttl(mut self, secs: Option<u64>) -> Self {
+        self.credential_ttl = secs;
+        self
+    }
+
+    pub fn with_inherited_attributes(mut self, attributes: Vec<String>) -> Self {
+        if !attributes.is_empty() {
+            self.inherited_attributes = Some(attributes.into_iter().map(CowStr::from).collect());
+        }
+        self
+    }
+
     pub fn address(&'a self) -> &'a str {
         &self.addr
     }
@@ -278,6 +336,22 @@ impl<'a> StartAuthenticatorRequest<'a> {
     pub fn project(&'a self) -> &'a [u8] {
         &self.proj
     }
+
+    pub fn token_duration(&self) -> Option<u64> {
+        self.token_duration
+    }
+
+    pub fn credential_ttl(&self) -> Option<u64> {
+        self.credential_ttl
+    }
+
+    pub fn inherited_attributes(&self) -> Vec<String> {
+        self.inherited_attributes
+            .iter()
+            .flatten()
+            .map(|a| a.to_string())
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, Decode, Encode)]
@@ -1,4 +1,5 @@
 use std::fmt::{self, Display};
+use std::time::Duration;
 
 use minicbor::{Decode, Encode};
 #[cfg(feature = "tag")]
@@ -25,6 +26,10 @@ pub struct CreateTransport<'a> {
     #[n(2)] pub tm: TransportMode,
     /// The address payload for the transport
     #[b(3)] pub addr: CowStr<'a>,
+    /// Interval in seconds of the heartbeats sent on a connection, if any
+    #[n(4)] pub keepalive: Option<u64>,
+    /// Maximum number of milliseconds to wait for a connection to be established
+    #[n(5)] pub connect_timeout: Option<u64>,
 }
 
 impl<'a> CreateTransport<'a> {
@@ -35,8 +40,20 @@ impl<'a> CreateTransport<'a> {
             tt,
             tm,
             addr: addr.into(),
+            keepalive: None,
+            connect_timeout: None,
         }
     }
+
+    pub fn with_keepalive(mut self, interval: Duration) -> Self {
+        self.keepalive = Some(interval.as_secs());
+        self
+    }
+
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX));
+        self
+    }
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
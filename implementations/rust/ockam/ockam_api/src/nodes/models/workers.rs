@@ -2,6 +2,7 @@ use minicbor::{Decode, Encode};
 use ockam_core::CowStr;
 #[cfg(feature = "tag")]
 use ockam_core::TypeTag;
+use serde::Serialize;
 
 #[derive(Debug, Clone, Decode, Encode)]
 #[rustfmt::skip]
@@ -41,3 +42,35 @@ impl<'a> WorkerList<'a> {
         }
     }
 }
+
+/// Response body for showing a single worker
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct WorkerDetails<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<4915702>,
+    #[b(1)] pub addr: CowStr<'a>,
+    #[n(2)] pub mailboxes: u32,
+    #[b(3)] pub incoming_access_control: CowStr<'a>,
+    #[b(4)] pub outgoing_access_control: CowStr<'a>,
+}
+
+impl<'a> WorkerDetails<'a> {
+    pub fn new(
+        addr: impl Into<CowStr<'a>>,
+        mailboxes: u32,
+        incoming_access_control: impl Into<CowStr<'a>>,
+        outgoing_access_control: impl Into<CowStr<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            addr: addr.into(),
+            mailboxes,
+            incoming_access_control: incoming_access_control.into(),
+            outgoing_access_control: outgoing_access_control.into(),
+        }
+    }
+}
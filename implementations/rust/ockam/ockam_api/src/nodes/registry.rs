@@ -1,8 +1,12 @@
+use std::time::SystemTime;
+
 use ockam_core::compat::collections::BTreeMap;
 use ockam_core::{Address, Route};
-use ockam_identity::IdentityIdentifier;
+use ockam_identity::{IdentityIdentifier, SecureChannelRegistryEntry};
 
+use crate::nodes::models::forwarder::ForwarderInfo;
 use crate::nodes::service::Alias;
+use crate::session::Key;
 
 #[derive(Default)]
 pub(crate) struct SecureChannelRegistry {
@@ -23,9 +27,14 @@ impl SecureChannelRegistry {
         addr: Address,
         route: Route,
         authorized_identifiers: Option<Vec<IdentityIdentifier>>,
+        entry: Option<SecureChannelRegistryEntry>,
     ) {
-        self.channels
-            .push(SecureChannelInfo::new(route, addr, authorized_identifiers))
+        self.channels.push(SecureChannelInfo::new(
+            route,
+            addr,
+            authorized_identifiers,
+            entry,
+        ))
     }
 
     pub fn remove_by_addr(&mut self, addr: &Address) {
@@ -44,6 +53,10 @@ pub struct SecureChannelInfo {
     // Local address of the created channel
     addr: Address,
     authorized_identifiers: Option<Vec<IdentityIdentifier>>,
+    // Identity-level information, absent if the channel could not be found
+    // in the identity's registry after its creation
+    entry: Option<SecureChannelRegistryEntry>,
+    created_at: SystemTime,
 }
 
 impl SecureChannelInfo {
@@ -51,11 +64,14 @@ impl SecureChannelInfo {
         route: Route,
         addr: Address,
         authorized_identifiers: Option<Vec<IdentityIdentifier>>,
+        entry: Option<SecureChannelRegistryEntry>,
     ) -> Self {
         Self {
             addr,
             route,
             authorized_identifiers,
+            entry,
+            created_at: SystemTime::now(),
         }
     }
 
@@ -70,6 +86,18 @@ impl SecureChannelInfo {
     pub fn authorized_identifiers(&self) -> Option<&Vec<IdentityIdentifier>> {
         self.authorized_identifiers.as_ref()
     }
+
+    pub fn decryptor_addr(&self) -> Option<&Address> {
+        self.entry.as_ref().map(|e| e.decryptor_messaging_address())
+    }
+
+    pub fn their_id(&self) -> Option<&IdentityIdentifier> {
+        self.entry.as_ref().map(|e| e.their_id())
+    }
+
+    pub fn created_at(&self) -> SystemTime {
+        self.created_at
+    }
 }
 
 #[derive(Default)]
@@ -128,6 +156,8 @@ pub(crate) struct InletInfo {
     pub(crate) bind_addr: String,
     pub(crate) worker_addr: Address,
     pub(crate) outlet_route: Route,
+    /// The session which recreates the inlet when its route goes down
+    pub(crate) session: Option<Key>,
 }
 
 impl InletInfo {
@@ -144,6 +174,7 @@ impl InletInfo {
             bind_addr: bind_addr.to_owned(),
             worker_addr,
             outlet_route: outlet_route.to_owned(),
+            session: None,
         }
     }
 }
@@ -166,6 +197,12 @@ impl OutletInfo {
     }
 }
 
+pub(crate) struct ForwarderEntry {
+    pub(crate) info: ForwarderInfo<'static>,
+    /// The session which recreates the forwarder when its route goes down
+    pub(crate) session: Option<Key>,
+}
+
 #[derive(Default)]
 pub(crate) struct Registry {
     pub(crate) secure_channels: SecureChannelRegistry,
@@ -186,4 +223,24 @@ pub(crate) struct Registry {
     // FIXME: wow this is a terrible way to store data
     pub(crate) inlets: BTreeMap<Alias, InletInfo>,
     pub(crate) outlets: BTreeMap<Alias, OutletInfo>,
+    pub(crate) forwarders: BTreeMap<Alias, ForwarderEntry>,
+}
+
+impl Registry {
+    /// Number of services currently started on the node
+    pub(crate) fn services_count(&self) -> usize {
+        let count = self.vault_services.len()
+            + self.identity_services.len()
+            + self.authenticated_services.len()
+            + self.okta_identity_provider_services.len()
+            + self.uppercase_services.len()
+            + self.echoer_services.len()
+            + self.kafka_services.len()
+            + self.hop_services.len()
+            + self.verifier_services.len()
+            + self.credentials_services.len();
+        #[cfg(feature = "direct-authenticator")]
+        let count = count + self.authenticator_service.len();
+        count
+    }
 }
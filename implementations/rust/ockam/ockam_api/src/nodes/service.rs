@@ -3,7 +3,7 @@
 use std::collections::BTreeMap;
 use std::error::Error as _;
 use std::path::PathBuf;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use minicbor::Decoder;
 use ockam::compat::asynchronous::RwLock;
@@ -16,6 +16,7 @@ use ockam_core::compat::string::String;
 use ockam_core::compat::sync::{Arc, Mutex};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{AllowAll, AsyncTryClone};
+use ockam_identity::authenticated_storage::mem::InMemoryStorage;
 use ockam_identity::authenticated_storage::AuthenticatedAttributeStorage;
 use ockam_multiaddr::proto::{Project, Secure};
 use ockam_multiaddr::{MultiAddr, Protocol};
@@ -23,6 +24,7 @@ use ockam_node::tokio;
 use ockam_node::tokio::task::JoinHandle;
 use ockam_vault::Vault;
 
+use super::attributes_storage::{AttributesStorageKind, NodeAttributesStorage};
 use super::models::secure_channel::CredentialExchangeMode;
 use super::registry::Registry;
 use crate::bootstrapped_identities_store::{BootstrapedIdentityStore, PreTrustedIdentities};
@@ -31,7 +33,7 @@ use crate::config::cli::AuthoritiesConfig;
 use crate::config::lookup::ProjectLookup;
 use crate::error::ApiError;
 use crate::lmdb::LmdbStorage;
-use crate::nodes::models::base::NodeStatus;
+use crate::nodes::models::base::{NodeHealth, NodeStatus};
 use crate::nodes::models::transport::{TransportMode, TransportType};
 use crate::nodes::models::workers::{WorkerList, WorkerStatus};
 use crate::session::util::starts_with_host_tcp_secure;
@@ -47,6 +49,7 @@ mod portals;
 mod secure_channel;
 mod services;
 mod transport;
+mod workers;
 
 const TARGET: &str = "ockam_api::nodemanager::service";
 
@@ -68,6 +71,10 @@ pub(crate) fn map_multiaddr_err(_err: ockam_multiaddr::Error) -> ockam_core::Err
     invalid_multiaddr_error()
 }
 
+/// Credentials issued by an authority before it rotated its key stay valid
+/// until they expire.
+pub(crate) const ACCEPT_PREVIOUS_AUTHORITY_KEYS: bool = true;
+
 pub(crate) struct Authorities(Vec<AuthorityInfo>);
 
 impl Authorities {
@@ -110,18 +117,24 @@ pub struct NodeManager {
     medic: JoinHandle<Result<(), ockam_core::Error>>,
     policies: LmdbStorage,
     token: Option<OneTimeCode>,
-    attributes_storage:
-        BootstrapedIdentityStore<PreTrustedIdentities, AuthenticatedAttributeStorage<LmdbStorage>>,
+    attributes_storage: BootstrapedIdentityStore<
+        PreTrustedIdentities,
+        AuthenticatedAttributeStorage<NodeAttributesStorage>,
+    >,
 }
 
 pub struct NodeManagerWorker {
     node_manager: Arc<RwLock<NodeManager>>,
+    started_at: Instant,
+    last_error: Option<String>,
 }
 
 impl NodeManagerWorker {
     pub fn new(node_manager: NodeManager) -> Self {
         NodeManagerWorker {
             node_manager: Arc::new(RwLock::new(node_manager)),
+            started_at: Instant::now(),
+            last_error: None,
         }
     }
 
@@ -162,6 +175,7 @@ pub struct NodeManagerGeneralOptions {
     node_name: String,
     skip_defaults: bool,
     pre_trusted_identities: Option<PreTrustedIdentities>,
+    attributes_storage: Option<AttributesStorageKind>,
 }
 
 impl NodeManagerGeneralOptions {
@@ -174,8 +188,19 @@ impl NodeManagerGeneralOptions {
             node_name,
             skip_defaults,
             pre_trusted_identities,
+            attributes_storage: None,
         }
     }
+
+    /// Store identity attributes in the given backend instead of the
+    /// identities storage shared by all the nodes.
+    pub fn with_attributes_storage(
+        mut self,
+        kind: impl Into<Option<AttributesStorageKind>>,
+    ) -> Self {
+        self.attributes_storage = kind.into();
+        self
+    }
 }
 
 pub struct NodeManagerProjectsOptions<'a> {
@@ -202,7 +227,7 @@ impl<'a> NodeManagerProjectsOptions<'a> {
 }
 
 pub struct NodeManagerTransportOptions {
-    api_transport: (TransportType, TransportMode, String),
+    api_transport: Option<(TransportType, TransportMode, String)>,
     tcp_transport: TcpTransport,
 }
 
@@ -212,7 +237,15 @@ impl NodeManagerTransportOptions {
         tcp_transport: TcpTransport,
     ) -> Self {
         Self {
-            api_transport,
+            api_transport: Some(api_transport),
+            tcp_transport,
+        }
+    }
+
+    /// Options for a node which doesn't serve its API over a transport
+    pub fn without_api_transport(tcp_transport: TcpTransport) -> Self {
+        Self {
+            api_transport: None,
             tcp_transport,
         }
     }
@@ -226,25 +259,36 @@ impl NodeManager {
         projects_options: NodeManagerProjectsOptions<'_>,
         transport_options: NodeManagerTransportOptions,
     ) -> Result<Self> {
-        let api_transport_id = random_alias();
         let mut transports = BTreeMap::new();
-        transports.insert(api_transport_id.clone(), transport_options.api_transport);
+        if let Some(api_transport) = transport_options.api_transport {
+            transports.insert(random_alias(), api_transport);
+        }
 
         let cli_state = CliState::new()?;
         let node_state = cli_state.nodes.get(&general_options.node_name)?;
 
         let authenticated_storage = cli_state.identities.authenticated_storage().await?;
 
+        let attributes_backend = match general_options.attributes_storage {
+            None => NodeAttributesStorage::Lmdb(authenticated_storage.clone()),
+            Some(AttributesStorageKind::Memory) => {
+                NodeAttributesStorage::Memory(InMemoryStorage::new())
+            }
+            Some(AttributesStorageKind::Lmdb) => NodeAttributesStorage::Lmdb(
+                LmdbStorage::new(&node_state.attributes_storage_path()).await?,
+            ),
+        };
+
         //TODO: fix this.  Either don't require it to be a bootstrappedidentitystore (and use the
         //trait instead),  or pass it from the general_options always.
         let attributes_storage = match general_options.pre_trusted_identities {
             None => BootstrapedIdentityStore::new(
                 PreTrustedIdentities::new_from_string("{}")?,
-                AuthenticatedAttributeStorage::new(authenticated_storage.clone()),
+                AuthenticatedAttributeStorage::new(attributes_backend),
             ),
             Some(f) => BootstrapedIdentityStore::new(
                 f,
-                AuthenticatedAttributeStorage::new(authenticated_storage.clone()),
+                AuthenticatedAttributeStorage::new(attributes_backend),
             ),
         };
 
@@ -289,7 +333,7 @@ impl NodeManager {
 
         // Always start the echoer service as ockam_api::Medic assumes it will be
         // started unconditionally on every node. It's used for liveness checks.
-        s.start_echoer_service_impl(ctx, DefaultAddress::ECHO_SERVICE.into())
+        s.start_echoer_service_impl(ctx, DefaultAddress::ECHO_SERVICE.into(), None, None)
             .await?;
 
         Ok(s)
@@ -320,9 +364,9 @@ impl NodeManager {
             .await?;
         self.start_authenticated_service_impl(ctx, DefaultAddress::AUTHENTICATED_SERVICE.into())
             .await?;
-        self.start_uppercase_service_impl(ctx, DefaultAddress::UPPERCASE_SERVICE.into())
+        self.start_uppercase_service_impl(ctx, DefaultAddress::UPPERCASE_SERVICE.into(), None)
             .await?;
-        self.start_hop_service_impl(ctx, DefaultAddress::HOP_SERVICE.into())
+        self.start_hop_service_impl(ctx, DefaultAddress::HOP_SERVICE.into(), None)
             .await?;
 
         ForwardingService::create(
@@ -373,7 +417,7 @@ impl NodeManager {
                 let i = Some(vec![i]);
                 let m = CredentialExchangeMode::Oneway;
                 let w = self
-                    .create_secure_channel_impl(r, i, m, timeout, None, ctx)
+                    .create_secure_channel_impl(r, i, m, timeout, None, None, ctx)
                     .await?;
                 let a = MultiAddr::default().try_with(addr.iter().skip(1))?;
                 return Ok((try_address_to_multiaddr(&w)?, a));
@@ -387,7 +431,7 @@ impl NodeManager {
             let i = auth.clone().map(|i| vec![i]);
             let m = CredentialExchangeMode::Mutual;
             let w = self
-                .create_secure_channel_impl(r, i, m, timeout, None, ctx)
+                .create_secure_channel_impl(r, i, m, timeout, None, None, ctx)
                 .await?;
             return Ok((try_address_to_multiaddr(&w)?, b));
         }
@@ -399,7 +443,7 @@ impl NodeManager {
             let i = auth.clone().map(|i| vec![i]);
             let m = CredentialExchangeMode::Mutual;
             let w = self
-                .create_secure_channel_impl(r, i, m, timeout, None, ctx)
+                .create_secure_channel_impl(r, i, m, timeout, None, None, ctx)
                 .await?;
             return Ok((try_address_to_multiaddr(&w)?, MultiAddr::default()));
         }
@@ -467,6 +511,22 @@ impl NodeManagerWorker {
                     ))
                     .to_vec()?
             }
+            (Get, ["node", "health"]) => {
+                let node_manager = self.node_manager.read().await;
+                let transport_listening = node_manager
+                    .transports
+                    .values()
+                    .any(|(_, mode, _)| *mode == TransportMode::Listen);
+                Response::ok(req.id())
+                    .body(NodeHealth::new(
+                        &node_manager.node_name,
+                        self.started_at.elapsed().as_secs(),
+                        transport_listening,
+                        node_manager.registry.services_count() as u32,
+                        self.last_error.as_deref(),
+                    ))
+                    .to_vec()?
+            }
 
             // ==*== Tcp Connection ==*==
             // TODO: Get all tcp connections
@@ -531,6 +591,10 @@ impl NodeManagerWorker {
                 .create_secure_channel_listener(req, dec, ctx)
                 .await?
                 .to_vec()?,
+            (Delete, ["node", "secure_channel_listener", addr]) => self
+                .delete_secure_channel_listener(ctx, req, addr)
+                .await?
+                .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?,
 
             // ==*== Services ==*==
             (Post, ["node", "services", DefaultAddress::VAULT_SERVICE]) => {
@@ -557,6 +621,10 @@ impl NodeManagerWorker {
                 .start_authenticator_service(ctx, req, dec)
                 .await?
                 .to_vec()?,
+            (
+                Post,
+                ["node", "services", DefaultAddress::AUTHENTICATOR, "actions", "rotate_key"],
+            ) => self.rotate_authenticator_key(req).await?.to_vec()?,
             (Post, ["node", "services", DefaultAddress::VERIFIER]) => {
                 self.start_verifier_service(ctx, req, dec).await?.to_vec()?
             }
@@ -580,7 +648,15 @@ impl NodeManagerWorker {
             }
 
             // ==*== Forwarder commands ==*==
+            (Get, ["node", "forwarder"]) => {
+                let node_manager = self.node_manager.read().await;
+                self.get_forwarders(req, &node_manager.registry).to_vec()?
+            }
             (Post, ["node", "forwarder"]) => self.create_forwarder(ctx, req.id(), dec).await?,
+            (Delete, ["node", "forwarder", alias]) => self
+                .delete_forwarder(ctx, req, alias)
+                .await?
+                .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?,
 
             // ==*== Inlets & Outlets ==*==
             (Get, ["node", "inlet"]) => {
@@ -593,7 +669,14 @@ impl NodeManagerWorker {
             }
             (Post, ["node", "inlet"]) => self.create_inlet(req, dec, ctx).await?.to_vec()?,
             (Post, ["node", "outlet"]) => self.create_outlet(req, dec).await?.to_vec()?,
-            (Delete, ["node", "portal"]) => todo!(),
+            (Delete, ["node", "inlet", alias]) => self
+                .delete_inlet(req, alias)
+                .await?
+                .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?,
+            (Delete, ["node", "outlet", alias]) => self
+                .delete_outlet(req, alias)
+                .await?
+                .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?,
 
             // ==*== Workers ==*==
             (Get, ["node", "workers"]) => {
@@ -608,6 +691,10 @@ impl NodeManagerWorker {
                     .body(WorkerList::new(list))
                     .to_vec()?
             }
+            (Get, ["node", "workers", addr]) => self
+                .show_worker(ctx, req, addr)
+                .await?
+                .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?,
 
             (Post, ["policy", resource, action]) => self
                 .node_manager
@@ -615,14 +702,14 @@ impl NodeManagerWorker {
                 .await
                 .add_policy(resource, action, req, dec)
                 .await?
-                .to_vec()?,
+                .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?,
             (Get, ["policy", resource]) => self
                 .node_manager
                 .read()
                 .await
                 .list_policies(req, resource)
                 .await?
-                .to_vec()?,
+                .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?,
             (Get, ["policy", resource, action]) => self
                 .node_manager
                 .read()
@@ -630,13 +717,27 @@ impl NodeManagerWorker {
                 .get_policy(req, resource, action)
                 .await?
                 .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?,
-            (Delete, ["policy", resource, action]) => self
+            (Get, ["policy"]) => self
                 .node_manager
                 .read()
                 .await
-                .del_policy(req, resource, action)
+                .list_all_policies(req)
                 .await?
                 .to_vec()?,
+            (Delete, ["policy", resource]) => self
+                .node_manager
+                .read()
+                .await
+                .del_policy(req, resource, None)
+                .await?
+                .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?,
+            (Delete, ["policy", resource, action]) => self
+                .node_manager
+                .read()
+                .await
+                .del_policy(req, resource, Some(action))
+                .await?
+                .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?,
 
             // ==*== Spaces ==*==
             (Post, ["v0", "spaces"]) => self.create_space(ctx, dec).await?,
@@ -749,6 +850,7 @@ impl Worker for NodeManagerWorker {
                     cause  = ?err.source(),
                     "failed to handle request"
                 }
+                self.last_error = Some(err.to_string());
                 let err =
                     Error::new(req.path()).with_message(format!("failed to handle request: {err}"));
                 Response::builder(req.id(), Status::InternalServerError)
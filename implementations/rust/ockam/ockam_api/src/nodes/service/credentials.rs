@@ -1,21 +1,26 @@
 use std::str::FromStr;
+use std::time::Duration;
 
 use either::Either;
 use minicbor::Decoder;
 use ockam::Result;
 use ockam_core::api::{Error, Request, Response, ResponseBuilder};
 use ockam_core::{route, AsyncTryClone};
-use ockam_identity::credential::Credential;
+use ockam_identity::credential::{Credential, CredentialData, Timestamp, Unverified};
 use ockam_multiaddr::MultiAddr;
 
 use super::NodeManagerWorker;
 use crate::authenticator::direct::Client;
+use crate::cli_state::CliState;
 use crate::error::ApiError;
 use crate::nodes::models::credentials::{GetCredentialRequest, PresentCredentialRequest};
-use crate::nodes::service::map_multiaddr_err;
+use crate::nodes::service::{map_multiaddr_err, ACCEPT_PREVIOUS_AUTHORITY_KEYS};
 use crate::nodes::NodeManager;
 use crate::{multiaddr_to_route, DefaultAddress};
 
+/// A cached credential expiring within this margin is fetched again
+const CREDENTIAL_REFRESH_MARGIN: Duration = Duration::from_secs(5 * 60);
+
 impl NodeManager {
     pub(super) async fn get_credential_impl(&mut self, overwrite: bool) -> Result<()> {
         debug!("Credential check: looking for identity");
@@ -25,6 +30,33 @@ impl NodeManager {
             return Err(ApiError::generic("credential already exists"));
         }
 
+        // Reuse the credential fetched by a previous run of this node, if still valid
+        let node_state = CliState::new()?.nodes.get(&self.node_name)?;
+        if !overwrite {
+            match node_state.credential() {
+                Ok(Some(credential)) if is_fresh(&credential) => {
+                    let authorities = self.authorities()?;
+                    let verified = identity
+                        .verify_self_credential(
+                            &credential,
+                            authorities.public_identities().iter(),
+                            ACCEPT_PREVIOUS_AUTHORITY_KEYS,
+                        )
+                        .await;
+                    match verified {
+                        Ok(()) => {
+                            debug!("Reusing the cached credential");
+                            identity.set_credential(credential).await;
+                            return Ok(());
+                        }
+                        Err(e) => warn!(%e, "the cached credential can't be verified"),
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => warn!(%e, "ignoring the cached credential"),
+            }
+        }
+
         debug!("Credential check: looking for authorities...");
         let authorities = self.authorities()?;
 
@@ -67,16 +99,39 @@ impl NodeManager {
         debug!("Got credential");
 
         identity
-            .verify_self_credential(&credential, authorities.public_identities().iter())
+            .verify_self_credential(
+                &credential,
+                authorities.public_identities().iter(),
+                ACCEPT_PREVIOUS_AUTHORITY_KEYS,
+            )
             .await?;
         debug!("Verified self credential");
 
+        if let Err(e) = node_state.set_credential(&credential) {
+            warn!(%e, "failed to cache the credential");
+        }
         identity.set_credential(credential.to_owned()).await;
 
         Ok(())
     }
 }
 
+/// Is the credential valid beyond [`CREDENTIAL_REFRESH_MARGIN`]?
+fn is_fresh(credential: &Credential) -> bool {
+    let expires_at = match CredentialData::<Unverified>::try_from(credential) {
+        Ok(data) => data.unverified_expires_at(),
+        Err(_) => return false,
+    };
+    match Timestamp::now() {
+        Some(now) => expires_beyond_margin(expires_at.unix_time(), now.unix_time()),
+        None => false,
+    }
+}
+
+fn expires_beyond_margin(expires_at: u64, now: u64) -> bool {
+    expires_at.saturating_sub(now) > CREDENTIAL_REFRESH_MARGIN.as_secs()
+}
+
 impl NodeManagerWorker {
     pub(super) async fn get_credential(
         &mut self,
@@ -123,6 +178,7 @@ impl NodeManagerWorker {
                 .present_credential_mutual(
                     route,
                     &node_manager.authorities()?.public_identities(),
+                    ACCEPT_PREVIOUS_AUTHORITY_KEYS,
                     &node_manager.attributes_storage,
                 )
                 .await?;
@@ -132,3 +188,19 @@ impl NodeManagerWorker {
         Ok(response)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn credential_is_refreshed_within_the_margin() {
+        let now = 1_000_000;
+        let margin = CREDENTIAL_REFRESH_MARGIN.as_secs();
+        assert!(expires_beyond_margin(now + margin + 1, now));
+        assert!(expires_beyond_margin(now + 3600 * 24, now));
+        assert!(!expires_beyond_margin(now + margin, now));
+        assert!(!expires_beyond_margin(now + 1, now));
+        assert!(!expires_beyond_margin(now - 1, now));
+    }
+}
@@ -1,21 +1,24 @@
 use std::sync::Arc;
 
+use either::Either;
 use minicbor::Decoder;
 use ockam::compat::asynchronous::RwLock;
 use ockam::remote::RemoteForwarder;
 use ockam::Result;
-use ockam_core::api::{Id, Response, Status};
-use ockam_core::{AllowAll, AsyncTryClone};
+use ockam_core::api::{Error, Id, Request, Response, ResponseBuilder, Status};
+use ockam_core::{Address, AllowAll, AsyncTryClone};
 use ockam_identity::IdentityIdentifier;
-use ockam_multiaddr::MultiAddr;
+use ockam_multiaddr::proto::Project;
+use ockam_multiaddr::{MultiAddr, Protocol};
 use ockam_node::tokio::time::timeout;
 use ockam_node::Context;
 
 use super::{NodeManager, NodeManagerWorker};
 use crate::error::ApiError;
 use crate::nodes::models::forwarder::{CreateForwarder, ForwarderInfo};
+use crate::nodes::registry::{ForwarderEntry, Registry};
 use crate::session::{util, Replacer, Session};
-use crate::{multiaddr_to_route, try_multiaddr_to_addr};
+use crate::{multiaddr_to_route, try_multiaddr_to_addr, DefaultAddress};
 
 impl NodeManagerWorker {
     pub(super) async fn create_forwarder(
@@ -47,36 +50,49 @@ impl NodeManagerWorker {
             } else {
                 RemoteForwarder::create(ctx, route, AllowAll /* FIXME: @ac */).await
             }
+        } else if let Some(alias) = req.alias() {
+            RemoteForwarder::create_static(ctx, route, alias, AllowAll /* FIXME: @ac */).await
         } else {
-            let f = if let Some(alias) = req.alias() {
-                RemoteForwarder::create_static(ctx, route, alias, AllowAll /* FIXME: @ac */).await
-            } else {
-                RemoteForwarder::create(ctx, route, AllowAll /* FIXME: @ac */).await
-            };
-            if f.is_ok() && !sec_chan.is_empty() {
-                let ctx = Arc::new(ctx.async_try_clone().await?);
-                let repl = replacer(
-                    manager,
-                    ctx,
-                    req.address().clone(),
-                    req.alias().map(|a| a.to_string()),
-                    req.authorized(),
-                );
-                let mut s = Session::new(sec_chan);
-                s.set_replacer(repl);
-                node_manager.sessions.lock().unwrap().add(s);
-            }
-            f
+            RemoteForwarder::create(ctx, route, AllowAll /* FIXME: @ac */).await
         };
 
+        // Supervise the secure channel so that the forwarder is created again
+        // at the remote node when the channel is lost, e.g. after a restart
+        let mut session = None;
+        if forwarder.is_ok() && req.reconnect() && !sec_chan.is_empty() {
+            let ctx = Arc::new(ctx.async_try_clone().await?);
+            let repl = replacer(
+                manager,
+                ctx,
+                req.address().clone(),
+                req.alias().map(|a| a.to_string()),
+                req.authorized(),
+                req.at_rust_node(),
+            );
+            let mut s = Session::new(sec_chan);
+            s.set_replacer(repl);
+            session = Some(node_manager.sessions.lock().unwrap().add(s));
+        }
+
         match forwarder {
             Ok(info) => {
-                let b = ForwarderInfo::from(info);
+                let at_project = req.address().matches(0, &[Project::CODE.into()]);
+                let b =
+                    ForwarderInfo::from(info).with_origin(req.alias(), req.address(), at_project);
                 debug!(
                     forwarding_route = %b.forwarding_route(),
                     remote_address = %b.remote_address(),
                     "CreateForwarder request processed, sending back response"
                 );
+                let alias = req
+                    .alias()
+                    .map(|a| a.to_string())
+                    .unwrap_or_else(|| b.remote_address().to_string());
+                let entry = ForwarderEntry {
+                    info: b.clone(),
+                    session,
+                };
+                node_manager.registry.forwarders.insert(alias, entry);
                 Ok(Response::ok(rid).body(b).to_vec()?)
             }
             Err(err) => {
@@ -89,6 +105,60 @@ impl NodeManagerWorker {
     }
 }
 
+impl NodeManagerWorker {
+    pub(super) fn get_forwarders(
+        &self,
+        req: &Request<'_>,
+        registry: &Registry,
+    ) -> ResponseBuilder<Vec<ForwarderInfo<'static>>> {
+        Response::ok(req.id()).body(
+            registry
+                .forwarders
+                .values()
+                .map(|entry| entry.info.clone())
+                .collect(),
+        )
+    }
+
+    pub(super) async fn delete_forwarder<'a>(
+        &mut self,
+        ctx: &Context,
+        req: &'a Request<'_>,
+        alias: &str,
+    ) -> Result<Either<ResponseBuilder<Error<'a>>, ResponseBuilder<ForwarderInfo<'static>>>> {
+        let mut node_manager = self.node_manager.write().await;
+        debug!(%alias, "Handling DeleteForwarder request");
+
+        // Forwarders at rust nodes are created with a prefixed address
+        let forwarders = &mut node_manager.registry.forwarders;
+        let prefixed = Address::prefixed(DefaultAddress::FORWARDER_PREFIX, alias);
+        let entry = match forwarders.remove(alias) {
+            Some(entry) => entry,
+            None => match forwarders.remove(prefixed.address()) {
+                Some(entry) => entry,
+                None => {
+                    let mut err = Error::new(req.path()).with_message("forwarder not found");
+                    if let Some(m) = req.method() {
+                        err.set_method(m)
+                    }
+                    return Ok(Either::Left(Response::not_found(req.id()).body(err)));
+                }
+            },
+        };
+
+        // Remove the session first so that it doesn't recreate the forwarder
+        if let Some(key) = &entry.session {
+            node_manager.sessions.lock().unwrap().remove(key);
+        }
+        let worker_addr = Address::from_string(entry.info.worker_address());
+        if let Err(err) = ctx.stop_worker(worker_addr).await {
+            warn!(%alias, %err, "Failed to stop forwarder worker");
+        }
+
+        Ok(Either::Right(Response::ok(req.id()).body(entry.info)))
+    }
+}
+
 /// Create a session replacer.
 ///
 /// This returns a function that accepts the previous ping address (e.g.
@@ -100,6 +170,7 @@ fn replacer(
     addr: MultiAddr,
     alias: Option<String>,
     auth: Option<IdentityIdentifier>,
+    at_rust_node: bool,
 ) -> Replacer {
     Box::new(move |prev| {
         let ctx = ctx.clone();
@@ -118,11 +189,29 @@ fn replacer(
                 let a = sec.clone().try_with(&rest)?;
                 let r = multiaddr_to_route(&a)
                     .ok_or_else(|| ApiError::message(format!("invalid multiaddr: {a}")))?;
-                if let Some(alias) = &alias {
-                    RemoteForwarder::create_static(&ctx, r, alias, AllowAll /* FIXME: @ac */)
-                        .await?;
-                } else {
-                    RemoteForwarder::create(&ctx, r, AllowAll /* FIXME: @ac */).await?;
+                let info = match &alias {
+                    Some(alias) if at_rust_node => {
+                        RemoteForwarder::create_static_without_heartbeats(
+                            &ctx, r, alias, AllowAll, /* FIXME: @ac */
+                        )
+                        .await?
+                    }
+                    Some(alias) => {
+                        RemoteForwarder::create_static(
+                            &ctx, r, alias, AllowAll, /* FIXME: @ac */
+                        )
+                        .await?
+                    }
+                    None => RemoteForwarder::create(&ctx, r, AllowAll /* FIXME: @ac */).await?,
+                };
+                // Keep the registry pointing at the new forwarder worker
+                let entry = alias
+                    .as_ref()
+                    .and_then(|a| this.registry.forwarders.get_mut(a));
+                if let Some(entry) = entry {
+                    let at_project = entry.info.at_project();
+                    entry.info =
+                        ForwarderInfo::from(info).with_origin(alias.as_deref(), &addr, at_project);
                 }
                 Ok(sec)
             };
@@ -64,7 +64,13 @@ mod node {
 
             let res: Result<Vec<u8>> = ctx.send_and_receive(route, msg).await;
             match res {
-                Ok(r) => Ok(Response::builder(req.id(), Status::Ok).body(r).to_vec()?),
+                Ok(r) => match crate::util::error_response(&r) {
+                    // A service rejected the message
+                    Some((status, err)) => {
+                        Ok(Response::builder(req.id(), status).body(err).to_vec()?)
+                    }
+                    None => Ok(Response::builder(req.id(), Status::Ok).body(r).to_vec()?),
+                },
                 Err(err) => {
                     error!(target: TARGET, ?err, "Failed to send message");
                     Ok(Response::builder(req.id(), Status::InternalServerError)
@@ -1,25 +1,29 @@
 use either::Either;
 use minicbor::Decoder;
-use ockam_abac::{Action, PolicyStorage, Resource};
+use ockam_abac::{Action, ParseError, PolicyStorage, Resource};
 use ockam_core::api::{Error, Request, Response, ResponseBuilder};
 use ockam_core::Result;
 
 use super::NodeManager;
-use crate::nodes::models::policy::{Policy, PolicyList};
+use crate::nodes::models::policy::{Policy, PolicyList, ResourcePolicyList};
+
+type PolicyResponse<'a, T> = Either<ResponseBuilder<Error<'a>>, ResponseBuilder<T>>;
 
 impl NodeManager {
-    pub(super) async fn add_policy(
+    pub(super) async fn add_policy<'a>(
         &self,
         resource: &str,
         action: &str,
-        req: &Request<'_>,
+        req: &'a Request<'_>,
         dec: &mut Decoder<'_>,
-    ) -> Result<ResponseBuilder<()>> {
+    ) -> Result<PolicyResponse<'a, ()>> {
+        let (r, a) = match parse_names(resource, action) {
+            Ok(names) => names,
+            Err(e) => return Ok(Either::Left(invalid_name(req, e))),
+        };
         let p: Policy = dec.decode()?;
-        let r = Resource::new(resource);
-        let a = Action::new(action);
         self.policies.set_policy(&r, &a, p.expression()).await?;
-        Ok(Response::ok(req.id()))
+        Ok(Either::Right(Response::ok(req.id())))
     }
 
     pub(super) async fn get_policy<'a>(
@@ -27,9 +31,11 @@ impl NodeManager {
         req: &'a Request<'_>,
         resource: &str,
         action: &str,
-    ) -> Result<Either<ResponseBuilder<Error<'a>>, ResponseBuilder<Policy>>> {
-        let r = Resource::new(resource);
-        let a = Action::new(action);
+    ) -> Result<PolicyResponse<'a, Policy>> {
+        let (r, a) = match parse_names(resource, action) {
+            Ok(names) => names,
+            Err(e) => return Ok(Either::Left(invalid_name(req, e))),
+        };
         if let Some(e) = self.policies.get_policy(&r, &a).await? {
             Ok(Either::Right(Response::ok(req.id()).body(Policy::new(e))))
         } else {
@@ -41,25 +47,66 @@ impl NodeManager {
         }
     }
 
-    pub(super) async fn list_policies(
+    pub(super) async fn list_policies<'a>(
         &self,
-        req: &Request<'_>,
+        req: &'a Request<'_>,
         res: &str,
-    ) -> Result<ResponseBuilder<PolicyList>> {
-        let r = Resource::new(res);
+    ) -> Result<PolicyResponse<'a, PolicyList>> {
+        let r = match Resource::try_new(res) {
+            Ok(r) => r,
+            Err(e) => return Ok(Either::Left(invalid_name(req, e))),
+        };
         let p = self.policies.policies(&r).await?;
-        Ok(Response::ok(req.id()).body(PolicyList::new(p)))
+        Ok(Either::Right(
+            Response::ok(req.id()).body(PolicyList::new(p)),
+        ))
     }
 
-    pub(super) async fn del_policy(
+    pub(super) async fn list_all_policies(
         &self,
         req: &Request<'_>,
+    ) -> Result<ResponseBuilder<ResourcePolicyList>> {
+        let p = self.policies.all_policies().await?;
+        Ok(Response::ok(req.id()).body(ResourcePolicyList::new(p)))
+    }
+
+    /// Delete the policy of an action on a resource, or of all its actions
+    /// when no action is given. The deleted policies are returned.
+    pub(super) async fn del_policy<'a>(
+        &self,
+        req: &'a Request<'_>,
         res: &str,
-        act: &str,
-    ) -> Result<ResponseBuilder<()>> {
-        let r = Resource::new(res);
-        let a = Action::new(act);
-        self.policies.del_policy(&r, &a).await?;
-        Ok(Response::ok(req.id()))
+        act: Option<&str>,
+    ) -> Result<PolicyResponse<'a, PolicyList>> {
+        let r = match Resource::try_new(res) {
+            Ok(r) => r,
+            Err(e) => return Ok(Either::Left(invalid_name(req, e))),
+        };
+        let a = match act.map(Action::try_new).transpose() {
+            Ok(a) => a,
+            Err(e) => return Ok(Either::Left(invalid_name(req, e))),
+        };
+        let mut deleted = self.policies.policies(&r).await?;
+        if let Some(a) = &a {
+            deleted.retain(|(x, _)| x == a)
+        }
+        for (a, _) in &deleted {
+            self.policies.del_policy(&r, a).await?;
+        }
+        Ok(Either::Right(
+            Response::ok(req.id()).body(PolicyList::new(deleted)),
+        ))
+    }
+}
+
+fn parse_names(resource: &str, action: &str) -> Result<(Resource, Action), ParseError> {
+    Ok((Resource::try_new(resource)?, Action::try_new(action)?))
+}
+
+fn invalid_name<'a>(req: &'a Request<'_>, e: ParseError) -> ResponseBuilder<Error<'a>> {
+    let mut err = Error::new(req.path()).with_message(e.to_string());
+    if let Some(m) = req.method() {
+        err.set_method(m)
     }
+    Response::bad_request(req.id()).body(err)
 }
@@ -1,17 +1,24 @@
+use std::net::SocketAddr;
+#[cfg(feature = "tls")]
+use std::path::Path;
 use std::sync::Arc;
 
+use either::Either;
 use minicbor::Decoder;
 use ockam::compat::asynchronous::RwLock;
 use ockam::compat::tokio::time::timeout;
-use ockam::{Address, AsyncTryClone, Result};
+use ockam::{Address, AsyncTryClone, Result, Route, TcpTransport};
 use ockam_abac::expr::{eq, ident, str};
 use ockam_abac::{Action, Env, PolicyAccessControl, PolicyStorage, Resource};
-use ockam_core::api::{Request, Response, ResponseBuilder};
+use ockam_core::api::{Error, Request, Response, ResponseBuilder};
 use ockam_core::{AllowAll, IncomingAccessControl};
+use ockam_identity::access_control::IdentityAccessControlBuilder;
 use ockam_identity::IdentityIdentifier;
 use ockam_multiaddr::proto::{Project, Secure, Service};
 use ockam_multiaddr::{MultiAddr, Protocol};
 use ockam_node::Context;
+#[cfg(feature = "tls")]
+use ockam_transport_tcp::{TlsInletConfig, TlsOutletConfig};
 
 use super::{NodeManager, NodeManagerWorker};
 use crate::error::ApiError;
@@ -105,6 +112,89 @@ impl NodeManagerWorker {
         ))
     }
 
+    pub(super) async fn delete_inlet<'a>(
+        &mut self,
+        req: &'a Request<'_>,
+        alias: &str,
+    ) -> Result<Either<ResponseBuilder<Error<'a>>, ResponseBuilder<InletStatus<'a>>>> {
+        let mut node_manager = self.node_manager.write().await;
+        info!(%alias, "Handling request to delete inlet portal");
+
+        let info = match node_manager.registry.inlets.remove(alias) {
+            Some(info) => info,
+            None => {
+                let mut err = Error::new(req.path()).with_message("inlet not found");
+                if let Some(m) = req.method() {
+                    err.set_method(m)
+                }
+                return Ok(Either::Left(Response::not_found(req.id()).body(err)));
+            }
+        };
+
+        // If a session recreated the inlet, its current worker is kept in the
+        // session data rather than in the registry:
+        let mut worker_addr = info.worker_addr.clone();
+        if let Some(key) = info.session {
+            if let Some(s) = node_manager.sessions.lock().unwrap().remove(&key) {
+                if let Some(wa) = s.data().get::<Address>(INLET_WORKER) {
+                    worker_addr = wa
+                }
+            }
+        }
+
+        // Inlets which failed to start are registered without a worker
+        if !worker_addr.address().is_empty() {
+            node_manager.tcp_transport.stop_inlet(worker_addr).await?;
+        }
+
+        Ok(Either::Right(Response::ok(req.id()).body(
+            InletStatus::new(
+                info.bind_addr,
+                info.worker_addr.to_string(),
+                alias.to_string(),
+                None,
+                info.outlet_route.to_string(),
+            ),
+        )))
+    }
+
+    pub(super) async fn delete_outlet<'a>(
+        &mut self,
+        req: &'a Request<'_>,
+        alias: &str,
+    ) -> Result<Either<ResponseBuilder<Error<'a>>, ResponseBuilder<OutletStatus<'a>>>> {
+        let mut node_manager = self.node_manager.write().await;
+        info!(%alias, "Handling request to delete outlet portal");
+
+        let info = match node_manager.registry.outlets.remove(alias) {
+            Some(info) => info,
+            None => {
+                let mut err = Error::new(req.path()).with_message("outlet not found");
+                if let Some(m) = req.method() {
+                    err.set_method(m)
+                }
+                return Ok(Either::Left(Response::not_found(req.id()).body(err)));
+            }
+        };
+
+        // Outlets which failed to start are registered without a worker
+        if !info.worker_addr.address().is_empty() {
+            node_manager
+                .tcp_transport
+                .stop_outlet(info.worker_addr.clone())
+                .await?;
+        }
+
+        Ok(Either::Right(Response::ok(req.id()).body(
+            OutletStatus::new(
+                info.tcp_addr,
+                info.worker_addr.to_string(),
+                alias.to_string(),
+                None,
+            ),
+        )))
+    }
+
     pub(super) async fn create_inlet<'a>(
         &mut self,
         req: &Request<'_>,
@@ -117,6 +207,18 @@ impl NodeManagerWorker {
         let req: CreateInlet = dec.decode()?;
 
         let listen_addr = req.listen_addr().to_string();
+        let tls = match (req.tls_cert(), req.tls_key()) {
+            (Some(cert), Some(key)) => Some(InletTls {
+                cert: cert.to_string(),
+                key: key.to_string(),
+            }),
+            (None, None) => None,
+            _ => {
+                return Ok(Response::bad_request(rid).body(InletStatus::bad_request(
+                    "a TLS inlet needs both a certificate and a private key",
+                )))
+            }
+        };
         let alias = req
             .alias()
             .map(|a| a.to_string())
@@ -190,22 +292,18 @@ impl NodeManagerWorker {
             .access_control(&resource, &actions::HANDLE_MESSAGE, project_id)
             .await?;
 
-        let res = node_manager
-            .tcp_transport
-            .create_inlet_impl(
-                listen_addr.clone(),
-                outlet_route.clone(),
-                access_control.clone(),
-            )
-            .await;
+        let res = create_tcp_inlet(
+            &node_manager.tcp_transport,
+            listen_addr.clone(),
+            outlet_route.clone(),
+            access_control.clone(),
+            tls.as_ref(),
+        )
+        .await;
 
         Ok(match res {
             Ok((worker_addr, _)) => {
-                // TODO: Use better way to store inlets?
-                node_manager.registry.inlets.insert(
-                    alias.clone(),
-                    InletInfo::new(&listen_addr, Some(&worker_addr), &outlet_route),
-                );
+                let mut info = InletInfo::new(&listen_addr, Some(&worker_addr), &outlet_route);
                 if !outer.is_empty() {
                     let mut s = Session::new(without_outlet_address(rest));
                     s.data().put(INLET_WORKER, worker_addr.clone());
@@ -218,11 +316,14 @@ impl NodeManagerWorker {
                         req.outlet_addr().clone(),
                         req.authorized(),
                         access_control.clone(),
+                        tls,
                         ctx,
                     );
                     s.set_replacer(repl);
-                    node_manager.sessions.lock().unwrap().add(s);
+                    info.session = Some(node_manager.sessions.lock().unwrap().add(s));
                 }
+                // TODO: Use better way to store inlets?
+                node_manager.registry.inlets.insert(alias.clone(), info);
 
                 Response::ok(rid).body(InletStatus::new(
                     listen_addr,
@@ -262,9 +363,17 @@ impl NodeManagerWorker {
             worker_addr,
             alias,
             check_credential,
+            tls,
+            tls_ca_cert,
+            tls_server_name,
+            allowed_identities,
             ..
         } = dec.decode()?;
         let tcp_addr = tcp_addr.to_string();
+        let tls = tls.unwrap_or(false).then(|| OutletTls {
+            ca_cert: tls_ca_cert.map(|c| c.to_string()),
+            server_name: tls_server_name.map(|n| n.to_string()),
+        });
         let resource = alias
             .as_deref()
             .map(Resource::new)
@@ -274,24 +383,51 @@ impl NodeManagerWorker {
         info!("Handling request to create outlet portal");
         let worker_addr = Address::from(worker_addr.as_ref());
 
-        let check_credential = match check_credential {
-            Some(b) => b,
-            None => node_manager.enable_credential_checks,
-        };
-        let project_id = if check_credential {
-            Some(node_manager.project_id()?.to_string())
-        } else {
-            None
+        // An explicit list of identities replaces the default access control,
+        // which checks credentials or allows everyone
+        let access_control: Arc<dyn IncomingAccessControl> = match allowed_identities {
+            Some(ids) if !ids.is_empty() => {
+                let mut identifiers = Vec::with_capacity(ids.len());
+                for id in ids.iter() {
+                    match IdentityIdentifier::try_from(id.0.as_ref()) {
+                        Ok(identifier) => identifiers.push(identifier),
+                        Err(_) => {
+                            let msg = format!("invalid allowed identity: {}", id.0);
+                            return Ok(Response::bad_request(req.id()).body(OutletStatus::new(
+                                tcp_addr,
+                                worker_addr.to_string(),
+                                alias,
+                                Some(msg.into()),
+                            )));
+                        }
+                    }
+                }
+                Arc::new(IdentityAccessControlBuilder::new_with_ids(identifiers))
+            }
+            _ => {
+                let check_credential = match check_credential {
+                    Some(b) => b,
+                    None => node_manager.enable_credential_checks,
+                };
+                let project_id = if check_credential {
+                    Some(node_manager.project_id()?.to_string())
+                } else {
+                    None
+                };
+                node_manager
+                    .access_control(&resource, &actions::HANDLE_MESSAGE, project_id)
+                    .await?
+            }
         };
 
-        let access_control = node_manager
-            .access_control(&resource, &actions::HANDLE_MESSAGE, project_id)
-            .await?;
-
-        let res = node_manager
-            .tcp_transport
-            .create_outlet_impl(worker_addr.clone(), tcp_addr.clone(), access_control)
-            .await;
+        let res = create_tcp_outlet(
+            &node_manager.tcp_transport,
+            worker_addr.clone(),
+            tcp_addr.clone(),
+            access_control,
+            tls,
+        )
+        .await;
 
         Ok(match res {
             Ok(_) => {
@@ -338,10 +474,12 @@ fn replacer(
     addr: MultiAddr,
     auth: Option<IdentityIdentifier>,
     access: Arc<dyn IncomingAccessControl>,
+    tls: Option<InletTls>,
     ctx: Arc<Context>,
 ) -> Replacer {
     Box::new(move |prev| {
         let addr = addr.clone();
+        let tls = tls.clone();
         let auth = auth.clone();
         let bind = bind.clone();
         let manager = manager.clone();
@@ -394,9 +532,7 @@ fn replacer(
                 }
 
                 // Finally attempt to create a new inlet using the new route:
-                let wa = this
-                    .tcp_transport
-                    .create_inlet_impl(bind, r, access)
+                let wa = create_tcp_inlet(&this.tcp_transport, bind, r, access, tls.as_ref())
                     .await?
                     .0;
                 data.put(INLET_WORKER, wa);
@@ -420,6 +556,72 @@ fn replacer(
     })
 }
 
+/// Files of the certificate chain and private key of an inlet terminating TLS
+#[derive(Clone)]
+struct InletTls {
+    cert: String,
+    key: String,
+}
+
+/// TLS settings of an outlet originating TLS
+struct OutletTls {
+    ca_cert: Option<String>,
+    server_name: Option<String>,
+}
+
+/// Create a tcp inlet, terminating TLS if `tls` is set.
+///
+/// The certificate and key are read again each time the inlet is
+/// (re-)created, so renewed files are picked up.
+async fn create_tcp_inlet(
+    tcp: &TcpTransport,
+    bind: String,
+    route: Route,
+    access: Arc<dyn IncomingAccessControl>,
+    tls: Option<&InletTls>,
+) -> Result<(Address, SocketAddr)> {
+    match tls {
+        None => tcp.create_inlet_impl(bind, route, access).await,
+        #[cfg(feature = "tls")]
+        Some(tls) => {
+            let config = TlsInletConfig::from_pem_files(&tls.cert, &tls.key)?;
+            tcp.create_inlet_with_tls(bind, route, config, access).await
+        }
+        #[cfg(not(feature = "tls"))]
+        Some(_) => Err(ApiError::generic(
+            "TLS portals are not supported by this node",
+        )),
+    }
+}
+
+/// Create a tcp outlet, originating TLS if `tls` is set.
+async fn create_tcp_outlet(
+    tcp: &TcpTransport,
+    worker_addr: Address,
+    tcp_addr: String,
+    access: Arc<dyn IncomingAccessControl>,
+    tls: Option<OutletTls>,
+) -> Result<()> {
+    match tls {
+        None => tcp.create_outlet_impl(worker_addr, tcp_addr, access).await,
+        #[cfg(feature = "tls")]
+        Some(tls) => {
+            // `tcp_addr` is an IP address, which can't be verified against a certificate
+            let server_name = tls
+                .server_name
+                .as_deref()
+                .ok_or_else(|| ApiError::generic("a TLS server name is required"))?;
+            let config = TlsOutletConfig::new(tls.ca_cert.as_deref().map(Path::new), server_name)?;
+            tcp.create_outlet_with_tls(worker_addr, tcp_addr, config, access)
+                .await
+        }
+        #[cfg(not(feature = "tls"))]
+        Some(_) => Err(ApiError::generic(
+            "TLS portals are not supported by this node",
+        )),
+    }
+}
+
 fn without_outlet_address(mut addr: MultiAddr) -> MultiAddr {
     if let Some(p) = addr.last() {
         if let Some(a) = p.cast::<Service>() {
@@ -1,16 +1,18 @@
 use std::time::Duration;
 
+use either::Either;
 use minicbor::Decoder;
 use ockam::identity::TrustEveryonePolicy;
 use ockam::{Address, Result, Route};
-use ockam_core::api::{Request, Response, ResponseBuilder};
+use ockam_core::api::{Error, Request, Response, ResponseBuilder};
 use ockam_core::{route, AsyncTryClone, CowStr};
+use ockam_identity::credential::Credential;
 use ockam_identity::{Identity, IdentityIdentifier, TrustMultiIdentifiersPolicy};
 use ockam_multiaddr::MultiAddr;
 use ockam_node::Context;
 use ockam_vault::Vault;
 
-use super::{map_multiaddr_err, NodeManagerWorker};
+use super::{map_multiaddr_err, NodeManagerWorker, ACCEPT_PREVIOUS_AUTHORITY_KEYS};
 use crate::cli_state::CliState;
 use crate::error::ApiError;
 use crate::lmdb::LmdbStorage;
@@ -80,13 +82,20 @@ impl NodeManager {
 
         debug!(%sc_route, %sc_addr, "Created secure channel");
 
-        self.registry
-            .secure_channels
-            .insert(sc_addr.clone(), sc_route, authorized_identifiers);
+        let entry = identity
+            .secure_channel_registry()
+            .get_channel_by_encryptor_address(&sc_addr);
+        self.registry.secure_channels.insert(
+            sc_addr.clone(),
+            sc_route,
+            authorized_identifiers,
+            entry,
+        );
 
         Ok(sc_addr)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(super) async fn create_secure_channel_impl(
         &mut self,
         sc_route: Route,
@@ -94,6 +103,7 @@ impl NodeManager {
         credential_exchange_mode: CredentialExchangeMode,
         timeout: Option<Duration>,
         identity_name: Option<CowStr<'_>>,
+        credential: Option<Credential>,
         ctx: &Context,
     ) -> Result<Address> {
         let identity = if let Some(identity) = identity_name {
@@ -114,10 +124,19 @@ impl NodeManager {
             .create_secure_channel_internal(&identity, sc_route, authorized_identifiers, timeout)
             .await?;
 
-        let actual_exchange_mode = if self.enable_credential_checks {
-            credential_exchange_mode
-        } else {
-            CredentialExchangeMode::None
+        let actual_exchange_mode = match credential_exchange_mode {
+            // A credential given with the request is always presented, and the listener's
+            // one can only be verified if this node has been configured with authorities
+            CredentialExchangeMode::Mutual if credential.is_some() => {
+                if self.authorities().is_ok() {
+                    CredentialExchangeMode::Mutual
+                } else {
+                    CredentialExchangeMode::Oneway
+                }
+            }
+            _ if credential.is_some() => CredentialExchangeMode::Oneway,
+            mode if self.enable_credential_checks => mode,
+            _ => CredentialExchangeMode::None,
         };
 
         match actual_exchange_mode {
@@ -126,26 +145,47 @@ impl NodeManager {
             }
             CredentialExchangeMode::Oneway => {
                 debug!(%sc_addr, "One-way credential presentation");
-                self.get_credential_if_needed().await?;
-                identity
-                    .present_credential(route![
-                        sc_addr.clone(),
-                        DefaultAddress::CREDENTIALS_SERVICE
-                    ])
-                    .await?;
+                let route = route![sc_addr.clone(), DefaultAddress::CREDENTIALS_SERVICE];
+                match &credential {
+                    Some(credential) => {
+                        identity.present_given_credential(route, credential).await?
+                    }
+                    None => {
+                        self.get_credential_if_needed().await?;
+                        identity.present_credential(route).await?
+                    }
+                }
                 debug!(%sc_addr, "One-way credential presentation success");
             }
             CredentialExchangeMode::Mutual => {
                 debug!(%sc_addr, "Mutual credential presentation");
-                self.get_credential_if_needed().await?;
-                let authorities = self.authorities()?;
-                identity
-                    .present_credential_mutual(
-                        route![sc_addr.clone(), DefaultAddress::CREDENTIALS_SERVICE],
-                        &authorities.public_identities(),
-                        &self.attributes_storage,
-                    )
-                    .await?;
+                let route = route![sc_addr.clone(), DefaultAddress::CREDENTIALS_SERVICE];
+                match &credential {
+                    Some(credential) => {
+                        let authorities = self.authorities()?;
+                        identity
+                            .present_given_credential_mutual(
+                                route,
+                                credential,
+                                &authorities.public_identities(),
+                                ACCEPT_PREVIOUS_AUTHORITY_KEYS,
+                                &self.attributes_storage,
+                            )
+                            .await?
+                    }
+                    None => {
+                        self.get_credential_if_needed().await?;
+                        let authorities = self.authorities()?;
+                        identity
+                            .present_credential_mutual(
+                                route,
+                                &authorities.public_identities(),
+                                ACCEPT_PREVIOUS_AUTHORITY_KEYS,
+                                &self.attributes_storage,
+                            )
+                            .await?
+                    }
+                }
                 debug!(%sc_addr, "Mutual credential presentation success");
             }
         }
@@ -205,6 +245,9 @@ impl NodeManager {
 
     pub(super) async fn delete_secure_channel(&mut self, addr: &Address) -> Result<()> {
         debug!(%addr, "deleting secure channel");
+        if self.registry.secure_channels.get_by_addr(addr).is_none() {
+            return Err(ApiError::generic("No such secure channel"));
+        }
         let identity = self.identity()?;
         identity.stop_secure_channel(addr).await?;
         self.registry.secure_channels.remove_by_addr(addr);
@@ -255,6 +298,7 @@ impl NodeManagerWorker {
             credential_exchange_mode,
             timeout,
             identity,
+            credential,
             ..
         } = dec.decode()?;
 
@@ -284,6 +328,7 @@ impl NodeManagerWorker {
                 credential_exchange_mode,
                 timeout,
                 identity,
+                credential,
                 ctx,
             )
             .await?;
@@ -374,4 +419,31 @@ impl NodeManagerWorker {
 
         Ok(response)
     }
+
+    pub(super) async fn delete_secure_channel_listener<'a>(
+        &mut self,
+        ctx: &Context,
+        req: &'a Request<'_>,
+        addr: &str,
+    ) -> Result<Either<ResponseBuilder<Error<'a>>, ResponseBuilder<()>>> {
+        let mut node_manager = self.node_manager.write().await;
+        let addr = Address::from(addr);
+        info!(%addr, "Handling request to delete secure channel listener");
+
+        if node_manager
+            .registry
+            .secure_channel_listeners
+            .remove(&addr)
+            .is_none()
+        {
+            let mut err = Error::new(req.path()).with_message("secure channel listener not found");
+            if let Some(m) = req.method() {
+                err.set_method(m)
+            }
+            return Ok(Either::Left(Response::not_found(req.id()).body(err)));
+        }
+        ctx.stop_worker(addr).await?;
+
+        Ok(Either::Right(Response::ok(req.id())))
+    }
 }
@@ -4,13 +4,14 @@ use ockam_core::api::{Request, Response, ResponseBuilder};
 use ockam_core::{AllowAll, Route};
 use ockam_multiaddr::MultiAddr;
 
-use super::NodeManagerWorker;
+use super::{NodeManagerWorker, ACCEPT_PREVIOUS_AUTHORITY_KEYS};
 use crate::auth::Server;
 use crate::echoer::Echoer;
 use crate::error::ApiError;
 use crate::hop::Hop;
 use crate::identity::IdentityService;
 use crate::kafka::{KafkaPortalListener, KAFKA_BOOTSTRAP_ADDRESS, KAFKA_INTERCEPTOR_ADDRESS};
+use crate::nodes::models::identity::LongIdentityResponse;
 use crate::nodes::models::services::{
     ServiceList,
     ServiceStatus,
@@ -39,7 +40,7 @@ use crate::nodes::NodeManager;
 use crate::port_range::PortRange;
 use crate::uppercase::Uppercase;
 use crate::vault::VaultService;
-use crate::{try_multiaddr_to_route, DefaultAddress};
+use crate::{check_route_hops, max_route_hops, try_multiaddr_to_route, DefaultAddress};
 
 impl NodeManager {
     pub(super) async fn start_vault_service_impl(
@@ -116,6 +117,7 @@ impl NodeManager {
                 authorities.public_identities(),
                 addr.clone(),
                 !oneway,
+                ACCEPT_PREVIOUS_AUTHORITY_KEYS,
                 self.attributes_storage.async_try_clone().await?,
             )
             .await?;
@@ -159,6 +161,7 @@ impl NodeManager {
         &mut self,
         ctx: &Context,
         addr: Address,
+        max_message_size: Option<u32>,
     ) -> Result<()> {
         if self.registry.uppercase_services.contains_key(&addr) {
             return Err(ApiError::generic(
@@ -168,7 +171,7 @@ impl NodeManager {
 
         ctx.start_worker(
             addr.clone(),
-            Uppercase,
+            Uppercase::new(max_message_size.map(|n| n as usize)),
             AllowAll, // FIXME: @ac
             AllowAll,
         )
@@ -185,14 +188,19 @@ impl NodeManager {
         &mut self,
         ctx: &Context,
         addr: Address,
+        rate_limit: Option<u32>,
+        max_message_size: Option<u32>,
     ) -> Result<()> {
         if self.registry.echoer_services.contains_key(&addr) {
             return Err(ApiError::generic("Echoer service exists at this address"));
         }
+        if rate_limit == Some(0) {
+            return Err(ApiError::generic("The rate limit must be greater than 0"));
+        }
 
         ctx.start_worker(
             addr.clone(),
-            Echoer,
+            Echoer::new(rate_limit).with_max_message_size(max_message_size.map(|n| n as usize)),
             AllowAll, // FIXME: @ac
             AllowAll,
         )
@@ -209,14 +217,18 @@ impl NodeManager {
         &mut self,
         ctx: &Context,
         addr: Address,
+        rate_limit: Option<u32>,
     ) -> Result<()> {
         if self.registry.hop_services.contains_key(&addr) {
             return Err(ApiError::generic("Hop service exists at this address"));
         }
+        if rate_limit == Some(0) {
+            return Err(ApiError::generic("The rate limit must be greater than 0"));
+        }
 
         ctx.start_worker(
             addr.clone(),
-            Hop,
+            Hop::new(rate_limit),
             AllowAll, // FIXME: @ac
             AllowAll,
         )
@@ -228,6 +240,7 @@ impl NodeManager {
     }
 
     #[cfg(feature = "direct-authenticator")]
+    #[allow(clippy::too_many_arguments)]
     pub(super) async fn start_direct_authenticator_service_impl(
         &mut self,
         ctx: &Context,
@@ -235,11 +248,21 @@ impl NodeManager {
         enrollers: &str,
         reload_enrollers: bool,
         proj: &[u8],
+        token_duration: Option<u64>,
+        credential_ttl: Option<u64>,
+        inherited_attributes: Vec<String>,
     ) -> Result<()> {
         use crate::nodes::registry::AuthenticatorServiceInfo;
+        use ockam::identity::credential::MAX_CREDENTIAL_VALIDITY;
         if self.registry.authenticator_service.contains_key(&addr) {
             return Err(ApiError::generic("Authenticator service already started"));
         }
+        if credential_ttl.unwrap_or(0) > MAX_CREDENTIAL_VALIDITY.as_secs() {
+            return Err(ApiError::generic(&format!(
+                "Credential ttl can't exceed {} seconds",
+                MAX_CREDENTIAL_VALIDITY.as_secs()
+            )));
+        }
         let db = self.attributes_storage.async_try_clone().await?;
         let id = self.identity()?.async_try_clone().await?;
         let au = crate::authenticator::direct::Server::new(
@@ -249,7 +272,16 @@ impl NodeManager {
             reload_enrollers,
             id,
         )
-        .await?;
+        .await?
+        .with_inherited_attributes(inherited_attributes);
+        let au = match token_duration {
+            Some(secs) => au.with_token_duration(std::time::Duration::from_secs(secs)),
+            None => au,
+        };
+        let au = match credential_ttl {
+            Some(secs) => au.with_credential_ttl(std::time::Duration::from_secs(secs)),
+            None => au,
+        };
         ctx.start_worker(
             addr.clone(),
             au,
@@ -263,6 +295,33 @@ impl NodeManager {
         Ok(())
     }
 
+    /// Rotate the root key of the node's identity, which signs the credentials
+    /// issued by its authenticator, and return the new change history.
+    ///
+    /// The authenticator shares the identity of the node, so that it signs
+    /// the next credentials, including those of pending enrollment tokens,
+    /// with the new key.
+    #[cfg(feature = "direct-authenticator")]
+    pub(super) async fn rotate_authenticator_key_impl(&mut self) -> Result<Vec<u8>> {
+        use crate::cli_state::CliState;
+        if self.registry.authenticator_service.is_empty() {
+            return Err(ApiError::generic(
+                "No authenticator service is running on this node",
+            ));
+        }
+        let identity = self.identity()?;
+        identity.rotate_root_key().await?;
+        let change_history = identity.change_history().await;
+
+        // The rotated key must be used when the node is restarted
+        CliState::new()?
+            .nodes
+            .get(&self.node_name)?
+            .config
+            .set_identity_change_history(change_history.clone())?;
+        change_history.export()
+    }
+
     pub(super) async fn start_okta_identity_provider_service_impl(
         &mut self,
         ctx: &Context,
@@ -316,16 +375,19 @@ impl NodeManager {
             .clone()
             .modify()
             .prepend(listener_address.clone())
-            .append(Address::from_string(KAFKA_INTERCEPTOR_ADDRESS))
+            .append(Address::new_local(KAFKA_INTERCEPTOR_ADDRESS))
             .into();
+        let bootstrap_route: Route = interceptor_route
+            .clone()
+            .modify()
+            .append(Address::new_local(KAFKA_BOOTSTRAP_ADDRESS))
+            .into();
+        check_route_hops(&bootstrap_route, max_route_hops())?;
 
         self.tcp_transport
             .create_inlet(
                 format!("{}:{}", &bind_ip, proxied_bootstrap_port),
-                interceptor_route
-                    .clone()
-                    .modify()
-                    .append(Address::from_string(KAFKA_BOOTSTRAP_ADDRESS)),
+                bootstrap_route,
                 AllowAll,
             )
             .await?;
@@ -398,7 +460,9 @@ impl NodeManagerWorker {
         let mut node_manager = self.node_manager.write().await;
         let req_body: StartUppercaseServiceRequest = dec.decode()?;
         let addr = req_body.addr.to_string().into();
-        node_manager.start_uppercase_service_impl(ctx, addr).await?;
+        node_manager
+            .start_uppercase_service_impl(ctx, addr, req_body.max_message_size)
+            .await?;
         Ok(Response::ok(req.id()))
     }
 
@@ -411,7 +475,9 @@ impl NodeManagerWorker {
         let mut node_manager = self.node_manager.write().await;
         let req_body: StartEchoerServiceRequest = dec.decode()?;
         let addr = req_body.addr.to_string().into();
-        node_manager.start_echoer_service_impl(ctx, addr).await?;
+        node_manager
+            .start_echoer_service_impl(ctx, addr, req_body.rate_limit, req_body.max_message_size)
+            .await?;
         Ok(Response::ok(req.id()))
     }
 
@@ -424,7 +490,9 @@ impl NodeManagerWorker {
         let mut node_manager = self.node_manager.write().await;
         let req_body: StartHopServiceRequest = dec.decode()?;
         let addr = req_body.addr.to_string().into();
-        node_manager.start_hop_service_impl(ctx, addr).await?;
+        node_manager
+            .start_hop_service_impl(ctx, addr, req_body.rate_limit)
+            .await?;
         Ok(Response::ok(req.id()))
     }
 
@@ -450,6 +518,9 @@ impl NodeManagerWorker {
                     body.enrollers(),
                     body.reload_enrollers(),
                     body.project(),
+                    body.token_duration(),
+                    body.credential_ttl(),
+                    body.inherited_attributes(),
                 )
                 .await?;
         }
@@ -457,6 +528,21 @@ impl NodeManagerWorker {
         Ok(Response::ok(req.id()))
     }
 
+    pub(super) async fn rotate_authenticator_key(
+        &mut self,
+        req: &Request<'_>,
+    ) -> Result<ResponseBuilder<LongIdentityResponse<'static>>> {
+        let mut node_manager = self.node_manager.write().await;
+        #[cfg(not(feature = "direct-authenticator"))]
+        return Err(ApiError::generic("Direct authenticator not available"));
+
+        #[cfg(feature = "direct-authenticator")]
+        {
+            let change_history = node_manager.rotate_authenticator_key_impl().await?;
+            Ok(Response::ok(req.id()).body(LongIdentityResponse::new(change_history)))
+        }
+    }
+
     pub(super) async fn start_okta_identity_provider_service<'a>(
         &mut self,
         ctx: &Context,
@@ -494,7 +580,8 @@ impl NodeManagerWorker {
         }
 
         let vault = node_manager.vault.async_try_clone().await?;
-        let vs = crate::verifier::Verifier::new(vault);
+        let vs = crate::verifier::Verifier::new(vault)
+            .with_previous_keys(ACCEPT_PREVIOUS_AUTHORITY_KEYS);
         ctx.start_worker(
             addr.clone(),
             vs,
@@ -1,8 +1,11 @@
 use std::collections::BTreeMap;
+use std::time::Duration;
 
 use minicbor::Decoder;
 use ockam::Result;
 use ockam_core::api::{Request, Response, ResponseBuilder};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_node::tokio::time::timeout;
 
 use super::NodeManagerWorker;
 use crate::nodes::models::transport::{
@@ -37,7 +40,14 @@ impl NodeManagerWorker {
         dec: &mut Decoder<'_>,
     ) -> Result<ResponseBuilder<TransportStatus<'a>>> {
         let mut node_manager = self.node_manager.write().await;
-        let CreateTransport { tt, tm, addr, .. } = dec.decode()?;
+        let CreateTransport {
+            tt,
+            tm,
+            addr,
+            keepalive,
+            connect_timeout,
+            ..
+        } = dec.decode()?;
 
         use TransportMode::*;
 
@@ -55,11 +65,23 @@ impl NodeManagerWorker {
                 .listen(&addr)
                 .await
                 .map(|socket| socket.to_string()),
-            (Tcp, Connect) => node_manager
-                .tcp_transport
-                .connect(&addr)
-                .await
-                .map(|ockam_addr| ockam_addr.to_string()),
+            (Tcp, Connect) => {
+                let keepalive = Duration::from_secs(keepalive.unwrap_or_default());
+                let connect = node_manager
+                    .tcp_transport
+                    .connect_with_keepalive(&addr, keepalive);
+                let res = match connect_timeout.map(Duration::from_millis) {
+                    Some(t) => timeout(t, connect).await.unwrap_or_else(|_| {
+                        Err(ockam_core::Error::new(
+                            Origin::Transport,
+                            Kind::Timeout,
+                            format!("connection to {addr} timed out after {}ms", t.as_millis()),
+                        ))
+                    }),
+                    None => connect.await,
+                };
+                res.map(|ockam_addr| ockam_addr.to_string())
+            }
             _ => unimplemented!(),
         };
 
@@ -0,0 +1,39 @@
+use either::Either;
+use ockam::{Address, Result};
+use ockam_core::api::{Error, Request, Response, ResponseBuilder};
+use ockam_core::errcode::Kind;
+use ockam_node::Context;
+
+use super::NodeManagerWorker;
+use crate::nodes::models::workers::WorkerDetails;
+
+impl NodeManagerWorker {
+    pub(super) async fn show_worker<'a>(
+        &self,
+        ctx: &Context,
+        req: &'a Request<'_>,
+        addr: &str,
+    ) -> Result<Either<ResponseBuilder<Error<'a>>, ResponseBuilder<WorkerDetails<'a>>>> {
+        let mailboxes = match ctx.worker_mailboxes(&Address::from(addr)).await {
+            Ok(mailboxes) => mailboxes,
+            Err(e) if e.code().kind == Kind::NotFound => {
+                let mut err = Error::new(req.path()).with_message("no such worker");
+                if let Some(m) = req.method() {
+                    err.set_method(m)
+                }
+                return Ok(Either::Left(Response::not_found(req.id()).body(err)));
+            }
+            Err(e) => return Err(e),
+        };
+
+        let main = mailboxes.main_mailbox();
+        Ok(Either::Right(Response::ok(req.id()).body(
+            WorkerDetails::new(
+                main.address().address().to_string(),
+                mailboxes.addresses().len() as u32,
+                format!("{:?}", main.incoming_access_control()),
+                format!("{:?}", main.outgoing_access_control()),
+            ),
+        )))
+    }
+}
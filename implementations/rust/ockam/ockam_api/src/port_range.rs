@@ -1,3 +1,4 @@
+use core::ops::RangeInclusive;
 use core::str::FromStr;
 
 use ockam_core::compat::fmt::Formatter;
@@ -35,6 +36,35 @@ impl PortRange {
     pub fn end(&self) -> u16 {
         self.end
     }
+
+    /// Whether the port is part of the range
+    pub fn contains(&self, port: u16) -> bool {
+        (self.start..=self.end).contains(&port)
+    }
+
+    /// Number of ports in the range, up to 65536 for the range `0-65535`
+    pub fn len(&self) -> u32 {
+        u32::from(self.end - self.start) + 1
+    }
+
+    /// Always false, a range has at least one port
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Iterate over the ports of the range, in ascending order
+    pub fn iter(&self) -> RangeInclusive<u16> {
+        self.start..=self.end
+    }
+}
+
+impl IntoIterator for PortRange {
+    type Item = u16;
+    type IntoIter = RangeInclusive<u16>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
 }
 
 impl TryFrom<(u16, u16)> for PortRange {
@@ -89,3 +119,43 @@ fn port_range_parse() -> () {
     assert!(PortRange::try_from("10-").is_err());
     assert!(PortRange::try_from("10,10,30,40").is_err());
 }
+
+#[test]
+fn port_range_contains() {
+    let range = PortRange::new(10, 20).unwrap();
+    assert!(range.contains(10));
+    assert!(range.contains(15));
+    assert!(range.contains(20));
+    assert!(!range.contains(9));
+    assert!(!range.contains(21));
+
+    let single = PortRange::try_from((10, 10)).unwrap();
+    assert!(single.contains(10));
+    assert!(!single.contains(11));
+}
+
+#[test]
+fn port_range_iter() {
+    let range = PortRange::new(10, 20).unwrap();
+    assert_eq!(11, range.len());
+    assert_eq!(11, range.iter().count());
+    assert_eq!(Some(10), range.iter().next());
+    assert_eq!(Some(20), range.into_iter().last());
+
+    // The range always has at least one port
+    let single = PortRange::new(10, 10).unwrap();
+    assert_eq!(1, single.len());
+    assert!(!single.is_empty());
+    assert_eq!(vec![10], single.into_iter().collect::<Vec<_>>());
+
+    // Boundaries of the port numbers
+    let full = PortRange::new(0, u16::MAX).unwrap();
+    assert_eq!(65536, full.len());
+    assert_eq!(65536, full.iter().count());
+    assert!(full.contains(0) && full.contains(u16::MAX));
+    assert_eq!(Some(u16::MAX), full.iter().last());
+
+    // Inverted ranges are rejected
+    assert!(PortRange::try_from((20, 10)).is_err());
+    assert!(PortRange::try_from((u16::MAX, 0)).is_err());
+}
@@ -0,0 +1,68 @@
+use std::time::Instant;
+
+/// A token bucket limiting the number of messages handled per second.
+///
+/// The bucket holds at most one second worth of tokens, which allows short
+/// bursts of up to `per_second` messages before throttling kicks in.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    per_second: u32,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Create a full bucket allowing `per_second` messages per second.
+    pub fn new(per_second: u32) -> Self {
+        Self {
+            per_second,
+            tokens: per_second as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Take a token for a message, return `false` if the rate is exceeded.
+    pub fn try_acquire(&mut self) -> bool {
+        self.try_acquire_at(Instant::now())
+    }
+
+    fn try_acquire_at(&mut self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.last_refill = now;
+        let capacity = self.per_second as f64;
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * capacity).min(capacity);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn drops_messages_beyond_the_rate() {
+        let start = Instant::now();
+        let mut limiter = RateLimiter::new(3);
+        limiter.last_refill = start;
+        let accepted = (0..10).filter(|_| limiter.try_acquire_at(start)).count();
+        assert_eq!(accepted, 3);
+
+        // Half a second later, half of the bucket has been refilled
+        let later = start + Duration::from_millis(500);
+        assert!(limiter.try_acquire_at(later));
+        assert!(!limiter.try_acquire_at(later));
+
+        // The bucket never holds more than one second worth of tokens
+        let much_later = later + Duration::from_secs(60);
+        let accepted = (0..10)
+            .filter(|_| limiter.try_acquire_at(much_later))
+            .count();
+        assert_eq!(accepted, 3);
+    }
+}
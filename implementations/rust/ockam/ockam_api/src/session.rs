@@ -10,14 +10,15 @@ use ockam_node::tokio::sync::mpsc;
 use ockam_node::tokio::task::JoinSet;
 use ockam_node::tokio::time::{timeout, Duration};
 use ockam_node::{tokio, Context};
-pub use sessions::{Data, Replacer, Session, Sessions};
-use sessions::{Key, Ping, Status};
+pub use sessions::{Data, Key, Replacer, Session, Sessions};
+use sessions::{Ping, Status};
 use tracing as log;
 
 use crate::{multiaddr_to_route, DefaultAddress};
 
 const MAX_FAILURES: usize = 3;
 const DELAY: Duration = Duration::from_secs(3);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
 
 #[derive(Debug)]
 pub struct Medic {
@@ -146,9 +147,14 @@ impl Medic {
                         let mut sessions = self.sessions.lock().unwrap();
                         if let Some(s) = sessions.session_mut(&k) {
                             log::warn!(key = %k, err = %e, "replacing session failed");
+                            s.add_failed_replacement();
+                            let d = backoff(s.failed_replacements());
                             let f = s.replacement(s.ping_address().clone());
-                            log::info!(key = %k, "replacing session");
-                            self.replacements.spawn(async move { (k, f.await) });
+                            log::info!(key = %k, delay = ?d, "replacing session");
+                            self.replacements.spawn(async move {
+                                tokio::time::sleep(d).await;
+                                (k, f.await)
+                            });
                         }
                     }
                     Some(Ok((k, Ok(a)))) => {
@@ -158,6 +164,7 @@ impl Medic {
                             s.set_status(Status::Up);
                             s.set_ping_address(a);
                             s.clear_pings();
+                            s.clear_failed_replacements();
                         }
                     }
                 },
@@ -175,6 +182,13 @@ impl Medic {
     }
 }
 
+/// Delay before retrying a failed replacement.
+///
+/// It doubles with every consecutive failure, up to `MAX_BACKOFF`.
+fn backoff(failures: u32) -> Duration {
+    DELAY.saturating_mul(1 << failures.min(5)).min(MAX_BACKOFF)
+}
+
 impl Message {
     fn new(k: Key) -> Self {
         Self {
@@ -27,6 +27,7 @@ pub struct Session {
     status: Status,
     replace: Replacer,
     pings: Vec<Ping>,
+    failed_replacements: u32,
 }
 
 #[derive(Debug, Clone)]
@@ -45,6 +46,7 @@ impl fmt::Debug for Session {
             .field("addr", &self.addr)
             .field("status", &self.status)
             .field("pings", &self.pings)
+            .field("failed_replacements", &self.failed_replacements)
             .finish()
     }
 }
@@ -77,6 +79,17 @@ impl Sessions {
         self.map.get_mut(k)
     }
 
+    pub fn remove(&mut self, k: &Key) -> Option<Session> {
+        let s = self.map.remove(k)?;
+        log::debug! {
+            target: "ockam_api::session",
+            key = %k,
+            addr = %s.ping_address(),
+            "session removed"
+        }
+        Some(s)
+    }
+
     #[allow(unused)]
     pub fn iter(&self) -> impl Iterator<Item = (&Key, &Session)> + '_ {
         self.map.iter()
@@ -96,6 +109,7 @@ impl Session {
             status: Status::Up,
             replace: Box::new(move |r| Box::pin(async move { Ok(r) })),
             pings: Vec::new(),
+            failed_replacements: 0,
         }
     }
 
@@ -142,6 +156,19 @@ impl Session {
     pub fn clear_pings(&mut self) {
         self.pings.clear()
     }
+
+    /// Number of replacements which failed since the session was last up.
+    pub fn failed_replacements(&self) -> u32 {
+        self.failed_replacements
+    }
+
+    pub fn add_failed_replacement(&mut self) {
+        self.failed_replacements = self.failed_replacements.saturating_add(1)
+    }
+
+    pub fn clear_failed_replacements(&mut self) {
+        self.failed_replacements = 0
+    }
 }
 
 impl Data {
@@ -1,6 +1,20 @@
 use ockam::{Context, Result, Routed, Worker};
+use tracing as log;
 
-pub struct Uppercase;
+use crate::util::message_too_large;
+
+#[derive(Default)]
+pub struct Uppercase {
+    max_message_size: Option<usize>,
+}
+
+impl Uppercase {
+    /// Create an uppercase service rejecting messages larger than
+    /// `max_message_size` bytes, or accepting any message if `None`.
+    pub fn new(max_message_size: Option<usize>) -> Self {
+        Self { max_message_size }
+    }
+}
 
 #[ockam::worker]
 impl Worker for Uppercase {
@@ -8,6 +22,12 @@ impl Worker for Uppercase {
     type Context = Context;
 
     async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        let address = msg.msg_addr().address().to_string();
+        let size = msg.payload().len();
+        if let Some(error) = message_too_large(&address, size, self.max_message_size)? {
+            log::warn!(from = %msg.sender()?, size, "message too large");
+            return ctx.send(msg.return_route(), error).await;
+        }
         ctx.send(msg.return_route(), msg.body().to_uppercase())
             .await
     }
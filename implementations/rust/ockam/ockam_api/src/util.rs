@@ -0,0 +1,35 @@
+use minicbor::Decoder;
+use ockam_core::api::{Error, Id, Response, Status};
+use ockam_core::Result;
+
+/// An error response rejecting a message of `size` bytes sent to the service
+/// at `address`, if the message is larger than `max_size`.
+///
+/// The response doesn't answer a request, so it refers to the default [`Id`].
+pub fn message_too_large(
+    address: &str,
+    size: usize,
+    max_size: Option<usize>,
+) -> Result<Option<Vec<u8>>> {
+    match max_size {
+        Some(max_size) if size > max_size => {
+            let msg =
+                format!("the message size ({size} bytes) exceeds the maximum of {max_size} bytes");
+            let err = Error::new(address).with_message(msg);
+            let res = Response::bad_request(Id::default()).body(err);
+            Ok(Some(res.to_vec()?))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// The status and error of a reply, if the reply is an error response of a service.
+pub fn error_response(reply: &[u8]) -> Option<(Status, Error<'_>)> {
+    let mut dec = Decoder::new(reply);
+    let res: Response = dec.decode().ok()?;
+    match res.status() {
+        Some(Status::Ok) | None => None,
+        Some(status) if res.has_body() => Some((status, dec.decode().ok()?)),
+        Some(_) => None,
+    }
+}
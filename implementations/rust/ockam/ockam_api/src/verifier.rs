@@ -1,19 +1,33 @@
 pub mod types;
 
+use std::collections::BTreeMap;
+
 use either::Either;
 use minicbor::Decoder;
 use ockam_core::api::{self, Error, Id, Method, Request, Response, ResponseBuilder};
-use ockam_core::{self, Result, Routed, Worker};
+use ockam_core::{self, CowBytes, Result, Routed, Worker};
 use ockam_identity::credential::{Credential, CredentialData, Verified};
-use ockam_identity::{IdentityVault, PublicIdentity};
+use ockam_identity::{IdentityIdentifier, IdentityVault, PublicIdentity};
 use ockam_node::Context;
 use tracing::trace;
 
 use self::types::{VerifyRequest, VerifyResponse};
 
+/// Outcome of the verification of a credential.
+#[derive(Debug)]
+pub enum Verification {
+    /// The credential was issued by a trusted authority and is valid.
+    Valid(CredentialData<Verified>),
+    /// The issuer of the credential is not a trusted authority.
+    UnauthorisedIssuer,
+    /// The credential was rejected, e.g. because of its signature or expiry.
+    Invalid(ockam_core::Error),
+}
+
 #[derive(Debug)]
 pub struct Verifier<V> {
     vault: V,
+    previous_keys: bool,
 }
 
 #[ockam_core::worker]
@@ -35,7 +49,16 @@ where
     V: IdentityVault,
 {
     pub fn new(vault: V) -> Self {
-        Self { vault }
+        Self {
+            vault,
+            previous_keys: false,
+        }
+    }
+
+    /// Also accept credentials signed with a key the authority has since rotated.
+    pub fn with_previous_keys(mut self, previous_keys: bool) -> Self {
+        self.previous_keys = previous_keys;
+        self
     }
 
     async fn on_request(&mut self, data: &[u8]) -> Result<Vec<u8>> {
@@ -84,33 +107,55 @@ where
         Ok(res)
     }
 
-    async fn verify<'a>(
+    /// Verify a credential issued to `subject` against a set of trusted authorities,
+    /// given as exported identities.
+    pub async fn verify_credential(
         &self,
-        id: Id,
-        req: &'a VerifyRequest<'a>,
         cre: &Credential,
-    ) -> Result<Either<ResponseBuilder<Error<'_>>, CredentialData<Verified>>> {
+        subject: &IdentityIdentifier,
+        authorities: &BTreeMap<IdentityIdentifier, CowBytes<'_>>,
+    ) -> Result<Verification> {
         let data = CredentialData::try_from(cre)?;
 
-        let ident = if let Some(ident) = req.authority(data.unverfied_issuer()) {
+        let ident = if let Some(ident) = authorities.get(data.unverfied_issuer()) {
             PublicIdentity::import(ident, &self.vault).await?
         } else {
-            let err = Error::new("/verify").with_message("unauthorised issuer");
-            return Ok(Either::Left(Response::unauthorized(id).body(err)));
+            return Ok(Verification::UnauthorisedIssuer);
+        };
+
+        let verified = if self.previous_keys {
+            ident
+                .verify_credential_with_previous_keys(cre, subject, &self.vault)
+                .await
+        } else {
+            ident.verify_credential(cre, subject, &self.vault).await
         };
+        match verified {
+            Ok(data) => Ok(Verification::Valid(data)),
+            Err(err) => Ok(Verification::Invalid(err)),
+        }
+    }
 
-        let data = match ident
-            .verify_credential(cre, req.subject(), &self.vault)
-            .await
+    async fn verify<'a>(
+        &self,
+        id: Id,
+        req: &'a VerifyRequest<'a>,
+        cre: &Credential,
+    ) -> Result<Either<ResponseBuilder<Error<'_>>, CredentialData<Verified>>> {
+        match self
+            .verify_credential(cre, req.subject(), req.authorities())
+            .await?
         {
-            Ok(data) => data,
-            Err(err) => {
+            Verification::Valid(data) => Ok(Either::Right(data)),
+            Verification::UnauthorisedIssuer => {
+                let err = Error::new("/verify").with_message("unauthorised issuer");
+                Ok(Either::Left(Response::unauthorized(id).body(err)))
+            }
+            Verification::Invalid(err) => {
                 let err = Error::new("/verify")
                     .with_message(format!("error verifying a credential: {err}"));
-                return Ok(Either::Left(Response::forbidden(id).body(err)));
+                Ok(Either::Left(Response::forbidden(id).body(err)))
             }
-        };
-
-        Ok(Either::Right(data))
+        }
     }
 }
@@ -1,18 +1,116 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::time::Duration;
 
 use ockam::authenticated_storage::AuthenticatedAttributeStorage;
 use ockam::identity::authenticated_storage::mem::InMemoryStorage;
+use ockam::identity::authenticated_storage::{
+    AttributeType, AttributesEntry, IdentityAttributeStorageReader, IdentityAttributeStorageWriter,
+};
+use ockam::identity::credential::Timestamp;
 use ockam::identity::Identity;
 use ockam::route;
 use ockam::vault::Vault;
 use ockam_api::authenticator::direct;
-use ockam_api::authenticator::direct::types::Enroller;
+use ockam_api::authenticator::direct::types::{AttributeValue, AuthDenyReason, Enroller};
+use ockam_api::verifier::{Verification, Verifier};
 use ockam_core::compat::rand::random_string;
-use ockam_core::{AllowAll, AsyncTryClone, Result};
+use ockam_core::{AllowAll, AsyncTryClone, CowBytes, Result};
 use ockam_identity::{IdentityIdentifier, PublicIdentity, TrustEveryonePolicy};
 use ockam_node::Context;
 use tempfile::NamedTempFile;
 
+type AuthServer =
+    direct::Server<InMemoryStorage, AuthenticatedAttributeStorage<InMemoryStorage>, Vault>;
+
+/// An authority of `project42`, running a direct authenticator behind a
+/// secure channel listener.
+struct Authority {
+    identity: Identity<Vault, InMemoryStorage>,
+    api_worker_addr: String,
+    auth_worker_addr: String,
+}
+
+impl Authority {
+    /// Create the authority and start its authenticator, storing the attributes
+    /// of the members in `store`. `configure` is applied to the authenticator
+    /// before it is started.
+    async fn start(
+        ctx: &Context,
+        enrollers: &str,
+        reload_enrollers: bool,
+        store: InMemoryStorage,
+        configure: impl FnOnce(AuthServer) -> AuthServer,
+    ) -> Result<Self> {
+        let identity = Identity::create(ctx, &Vault::create()).await?;
+        let api_worker_addr = random_string();
+        identity
+            .create_secure_channel_listener(&api_worker_addr, TrustEveryonePolicy)
+            .await?;
+        let mut authority = Authority {
+            identity,
+            api_worker_addr,
+            auth_worker_addr: random_string(),
+        };
+        authority
+            .start_authenticator(ctx, enrollers, reload_enrollers, store, configure)
+            .await?;
+        Ok(authority)
+    }
+
+    /// Start an authenticator of this authority at a new address.
+    async fn start_authenticator(
+        &mut self,
+        ctx: &Context,
+        enrollers: &str,
+        reload_enrollers: bool,
+        store: InMemoryStorage,
+        configure: impl FnOnce(AuthServer) -> AuthServer,
+    ) -> Result<()> {
+        let auth = direct::Server::new(
+            b"project42".to_vec(),
+            AuthenticatedAttributeStorage::new(store),
+            enrollers,
+            reload_enrollers,
+            self.identity.async_try_clone().await?,
+        )
+        .await?;
+        self.auth_worker_addr = random_string();
+        ctx.start_worker(
+            &self.auth_worker_addr,
+            configure(auth),
+            AllowAll, // Auth checks happen inside the worker
+            AllowAll,
+        )
+        .await
+    }
+
+    /// Connect to the authenticator through a secure channel from `identity`.
+    async fn client(
+        &self,
+        ctx: &Context,
+        identity: &Identity<Vault, InMemoryStorage>,
+    ) -> Result<direct::Client> {
+        let channel = identity
+            .create_secure_channel(&self.api_worker_addr, TrustEveryonePolicy)
+            .await?;
+        direct::Client::new(route![channel, &self.auth_worker_addr], ctx).await
+    }
+
+    /// The current public identity of the authority, to verify credentials with.
+    async fn public_identity(&self) -> Result<PublicIdentity> {
+        PublicIdentity::import(&self.identity.export().await?, &Vault::create()).await
+    }
+}
+
+/// Create an enroller identity, and an enrollers configuration listing it.
+async fn enroller(ctx: &Context) -> Result<(Identity<Vault, InMemoryStorage>, String)> {
+    let enroller = Identity::create(ctx, &Vault::create()).await?;
+    let enrollers = [(enroller.identifier().clone(), Enroller::default())];
+    let enrollers_config = serde_json::to_string(&HashMap::from(enrollers)).unwrap();
+    Ok((enroller, enrollers_config))
+}
+
 #[ockam_macros::test]
 async fn credential(ctx: &mut Context) -> Result<()> {
     let mut tmpf = NamedTempFile::new().unwrap();
@@ -61,10 +159,14 @@ async fn credential(ctx: &mut Context) -> Result<()> {
     let mut c = direct::Client::new(route![e2a.address(), &auth_worker_addr], ctx).await?;
 
     // Enroller is not configured -> fail
-    assert!(c
+    let err = c
         .add_member(member.identifier().clone(), HashMap::new())
         .await
-        .is_err());
+        .unwrap_err();
+    assert_eq!(
+        Some(AuthDenyReason::UnauthorizedEnroller),
+        direct::deny_reason(&err)
+    );
 
     // Configure enroller
     let enrollers = [(enroller.identifier().clone(), Enroller::default())];
@@ -74,7 +176,7 @@ async fn credential(ctx: &mut Context) -> Result<()> {
     // Re-create client with new auth worker address
     let mut c = direct::Client::new(route![e2a.address(), &auth_worker_addr], ctx).await?;
 
-    let member_attrs = HashMap::from([("role", "member")]);
+    let member_attrs = HashMap::from([("role", "member".into())]);
     c.add_member(member.identifier().clone(), member_attrs)
         .await?;
 
@@ -103,6 +205,33 @@ async fn credential(ctx: &mut Context) -> Result<()> {
     ctx.stop().await
 }
 
+#[ockam_macros::test]
+async fn commented_enrollers_file(ctx: &mut Context) -> Result<()> {
+    // Create an enroller identity, and an annotated enrollers file:
+    let enroller = Identity::create(ctx, &Vault::create()).await?;
+    let mut tmpf = NamedTempFile::new().unwrap();
+    write!(
+        tmpf,
+        r#"{{
+            // Operations team enroller
+            "{}": {{}}, // added for the rollout
+        }}"#,
+        enroller.identifier()
+    )
+    .unwrap();
+
+    // Create the authority, reloading the enrollers file on each request:
+    let enrollers = tmpf.path().to_str().expect("path should be a string");
+    let authority = Authority::start(ctx, enrollers, true, InMemoryStorage::new(), |s| s).await?;
+
+    // The enroller is recognized:
+    let mut c = authority.client(ctx, &enroller).await?;
+    c.create_token(HashMap::from([("role", "member".into())]), None)
+        .await?;
+
+    ctx.stop().await
+}
+
 #[ockam_macros::test]
 async fn json_config(ctx: &mut Context) -> Result<()> {
     let api_worker_addr = random_string();
@@ -172,7 +301,7 @@ async fn json_config(ctx: &mut Context) -> Result<()> {
     let mut c = direct::Client::new(route![e2a.address(), &auth_worker_addr], ctx).await?;
 
     // Add member successfully
-    let member_attrs = HashMap::from([("role", "member")]);
+    let member_attrs = HashMap::from([("role", "member".into())]);
     c.add_member(member.identifier().clone(), member_attrs)
         .await?;
 
@@ -200,3 +329,420 @@ async fn json_config(ctx: &mut Context) -> Result<()> {
 
     ctx.stop().await
 }
+
+#[ockam_macros::test]
+async fn expired_token(ctx: &mut Context) -> Result<()> {
+    // Create the authority with short-lived enrollment tokens:
+    let (enroller, enrollers_config) = enroller(ctx).await?;
+    let authority = Authority::start(ctx, &enrollers_config, false, InMemoryStorage::new(), |s| {
+        s.with_token_duration(Duration::from_secs(1))
+    })
+    .await?;
+
+    // Create a member identity:
+    let member = Identity::create(ctx, &Vault::create()).await?;
+
+    // Create an enrollment token via the enroller's connection:
+    let mut c = authority.client(ctx, &enroller).await?;
+    let otc = c
+        .create_token(HashMap::from([("role", "member".into())]), None)
+        .await?;
+    let otc2 = c
+        .create_token(HashMap::from([("role", "member".into())]), None)
+        .await?;
+
+    // Let the tokens expire:
+    ctx.sleep(Duration::from_secs(2)).await;
+
+    // Redeeming the expired token fails:
+    let mut c = authority.client(ctx, &member).await?;
+    let err = c.credential_with(&otc).await.unwrap_err();
+    assert_eq!(
+        Some(AuthDenyReason::ExpiredToken),
+        direct::deny_reason(&err)
+    );
+
+    // Even when requesting attributes the token doesn't allow:
+    let requested = HashMap::from([("level", "admin".into())]);
+    let err = c
+        .credential_with_attributes(&otc2, requested)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        Some(AuthDenyReason::ExpiredToken),
+        direct::deny_reason(&err)
+    );
+
+    // The token was consumed, so it's now unknown:
+    let err = c.credential_with(&otc).await.unwrap_err();
+    assert_eq!(
+        Some(AuthDenyReason::UnknownToken),
+        direct::deny_reason(&err)
+    );
+
+    // Expired tokens are dropped when new tokens are created:
+    let mut e = authority.client(ctx, &enroller).await?;
+    let otc = e
+        .create_token(HashMap::from([("role", "member".into())]), None)
+        .await?;
+    ctx.sleep(Duration::from_secs(2)).await;
+    e.create_token(HashMap::from([("role", "member".into())]), None)
+        .await?;
+    let err = c.credential_with(&otc).await.unwrap_err();
+    assert_eq!(
+        Some(AuthDenyReason::UnknownToken),
+        direct::deny_reason(&err)
+    );
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn multi_use_token(ctx: &mut Context) -> Result<()> {
+    // Create the authority:
+    let (enroller, enrollers_config) = enroller(ctx).await?;
+    let authority =
+        Authority::start(ctx, &enrollers_config, false, InMemoryStorage::new(), |s| s).await?;
+
+    // Create an enrollment token redeemable twice:
+    let mut c = authority.client(ctx, &enroller).await?;
+    let otc = c
+        .create_token(HashMap::from([("role", "member".into())]), Some(2))
+        .await?;
+
+    // Two members can redeem it, a third one can't:
+    for expected_ok in [true, true, false] {
+        let member = Identity::create(ctx, &Vault::create()).await?;
+        let mut c = authority.client(ctx, &member).await?;
+        assert_eq!(expected_ok, c.credential_with(&otc).await.is_ok());
+    }
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn batch_tokens(ctx: &mut Context) -> Result<()> {
+    // Create the authority:
+    let (enroller, enrollers_config) = enroller(ctx).await?;
+    let authority =
+        Authority::start(ctx, &enrollers_config, false, InMemoryStorage::new(), |s| s).await?;
+
+    // Create a batch of enrollment tokens:
+    let mut c = authority.client(ctx, &enroller).await?;
+    let otcs = c
+        .create_tokens(10, HashMap::from([("role", "member".into())]))
+        .await?;
+    assert_eq!(10, otcs.len());
+
+    // Too large batches are rejected:
+    let attrs = HashMap::from([("role", "member".into())]);
+    assert!(c
+        .create_tokens(direct::MAX_TOKENS_PER_REQUEST + 1, attrs)
+        .await
+        .is_err());
+
+    // Each token can be redeemed once, by a different member:
+    for otc in &otcs {
+        let member = Identity::create(ctx, &Vault::create()).await?;
+        let mut c = authority.client(ctx, &member).await?;
+        c.credential_with(otc).await?;
+        let err = c.credential_with(otc).await.unwrap_err();
+        assert_eq!(
+            Some(AuthDenyReason::UnknownToken),
+            direct::deny_reason(&err)
+        );
+    }
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn typed_attributes(ctx: &mut Context) -> Result<()> {
+    // Create the authority, keeping access to its attributes storage:
+    let (enroller, enrollers_config) = enroller(ctx).await?;
+    let store = InMemoryStorage::new();
+    let authority = Authority::start(ctx, &enrollers_config, false, store.clone(), |s| s).await?;
+
+    // Create an enrollment token with a boolean attribute:
+    let mut c = authority.client(ctx, &enroller).await?;
+    let attrs = HashMap::from([
+        ("role", "member".into()),
+        ("admin", AttributeValue::Bool(true)),
+    ]);
+    let otc = c.create_token(attrs, None).await?;
+
+    // Enroll a member with the token:
+    let member = Identity::create(ctx, &Vault::create()).await?;
+    let mut c = authority.client(ctx, &member).await?;
+    let cred = c.credential_with(&otc).await?;
+
+    // The credential holds the value of the attribute:
+    let pkey = authority.public_identity().await?;
+    let data = pkey
+        .verify_credential(&cred, member.identifier(), &Vault::create())
+        .await?;
+    assert_eq!(Some(b"true".as_slice()), data.attributes().get("admin"));
+
+    // The stored entry records its type:
+    let entry = AuthenticatedAttributeStorage::new(store)
+        .get_attributes(member.identifier())
+        .await?
+        .expect("member attributes should be stored");
+    assert_eq!(Some(&b"true".to_vec()), entry.attrs().get("admin"));
+    assert_eq!(AttributeType::Bool, entry.attr_type("admin"));
+    assert_eq!(AttributeType::Str, entry.attr_type("role"));
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn credential_ttl(ctx: &mut Context) -> Result<()> {
+    // Create the authority with one hour credentials:
+    let (enroller, enrollers_config) = enroller(ctx).await?;
+    let ttl = Duration::from_secs(3600);
+    let authority = Authority::start(ctx, &enrollers_config, false, InMemoryStorage::new(), |s| {
+        s.with_credential_ttl(ttl)
+    })
+    .await?;
+
+    // Add a member:
+    let member = Identity::create(ctx, &Vault::create()).await?;
+    let mut c = authority.client(ctx, &enroller).await?;
+    c.add_member(
+        member.identifier().clone(),
+        HashMap::from([("role", "member".into())]),
+    )
+    .await?;
+
+    let mut c = authority.client(ctx, &member).await?;
+    let pkey = authority.public_identity().await?;
+
+    // The credential is valid from now on, for the configured ttl:
+    let before = Timestamp::now().unwrap().unix_time();
+    let cred = c.credential().await?;
+    let after = Timestamp::now().unwrap().unix_time();
+    let data = pkey
+        .verify_credential(&cred, member.identifier(), &Vault::create())
+        .await?;
+    let not_before = data.not_before().unix_time();
+    assert!(before <= not_before && not_before <= after);
+    assert_eq!(not_before + ttl.as_secs(), data.expires_at().unix_time());
+
+    // Asking again gets a credential with a fresh validity window:
+    ctx.sleep(Duration::from_secs(1)).await;
+    let cred = c.credential().await?;
+    let renewed = pkey
+        .verify_credential(&cred, member.identifier(), &Vault::create())
+        .await?;
+    assert!(renewed.not_before() > data.not_before());
+    assert!(renewed.expires_at() > data.expires_at());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn inherited_attributes(ctx: &mut Context) -> Result<()> {
+    // Create an enroller identity, with its own attributes:
+    let (enroller, enrollers_config) = enroller(ctx).await?;
+    let store = InMemoryStorage::new();
+    let attributes = AuthenticatedAttributeStorage::new(store.clone());
+    let enroller_attrs = BTreeMap::from([
+        ("org".to_string(), b"acme".to_vec()),
+        ("level".to_string(), b"3".to_vec()),
+        ("team".to_string(), b"ops".to_vec()),
+    ]);
+    let entry = AttributesEntry::new(enroller_attrs, Timestamp::now().unwrap(), None, None)
+        .with_types(BTreeMap::from([("level".to_string(), AttributeType::Int)]));
+    attributes
+        .put_attributes(enroller.identifier(), entry)
+        .await?;
+
+    // Create the authority, with members inheriting the `org` and `level` attributes:
+    let authority = Authority::start(ctx, &enrollers_config, false, store, |s| {
+        s.with_inherited_attributes(vec!["org".to_string(), "level".to_string()])
+    })
+    .await?;
+
+    // Add a member, trying to give it another org:
+    let mut c = authority.client(ctx, &enroller).await?;
+    let member = Identity::create(ctx, &Vault::create()).await?;
+    let member_attrs = HashMap::from([("role", "member".into()), ("org", "other".into())]);
+    c.add_member(member.identifier().clone(), member_attrs)
+        .await?;
+
+    // The member inherits the listed attributes of the enroller, with their type:
+    let entry = attributes
+        .get_attributes(member.identifier())
+        .await?
+        .expect("member attributes should be stored");
+    assert_eq!(Some(&b"acme".to_vec()), entry.attrs().get("org"));
+    assert_eq!(Some(&b"3".to_vec()), entry.attrs().get("level"));
+    assert_eq!(AttributeType::Int, entry.attr_type("level"));
+    assert_eq!(Some(&b"member".to_vec()), entry.attrs().get("role"));
+    assert_eq!(None, entry.attrs().get("team"));
+    assert_eq!(Some(enroller.identifier().clone()), entry.attested_by());
+
+    // Members enrolled with a token inherit them too:
+    let otc = c
+        .create_token(HashMap::from([("role", "member".into())]), None)
+        .await?;
+    let member = Identity::create(ctx, &Vault::create()).await?;
+    let mut c = authority.client(ctx, &member).await?;
+    let cred = c.credential_with(&otc).await?;
+    let pkey = authority.public_identity().await?;
+    let data = pkey
+        .verify_credential(&cred, member.identifier(), &Vault::create())
+        .await?;
+    assert_eq!(Some(b"acme".as_slice()), data.attributes().get("org"));
+    assert_eq!(Some(b"3".as_slice()), data.attributes().get("level"));
+    assert_eq!(None, data.attributes().get("team"));
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn deleted_member(ctx: &mut Context) -> Result<()> {
+    // Create the authority:
+    let (enroller, enrollers_config) = enroller(ctx).await?;
+    let store = InMemoryStorage::new();
+    let authority = Authority::start(ctx, &enrollers_config, false, store.clone(), |s| s).await?;
+
+    // Add a member, who can get a credential:
+    let member = Identity::create(ctx, &Vault::create()).await?;
+    let mut e = authority.client(ctx, &enroller).await?;
+    e.add_member(
+        member.identifier().clone(),
+        HashMap::from([("role", "member".into())]),
+    )
+    .await?;
+    let mut c = authority.client(ctx, &member).await?;
+    c.credential().await?;
+
+    // Only enrollers can delete members:
+    let err = c.delete_member(member.identifier()).await.unwrap_err();
+    assert_eq!(
+        Some(AuthDenyReason::UnauthorizedEnroller),
+        direct::deny_reason(&err)
+    );
+
+    // Delete the member:
+    assert!(e.delete_member(member.identifier()).await?);
+    let storage = AuthenticatedAttributeStorage::new(store);
+    assert!(storage.get_attributes(member.identifier()).await?.is_none());
+    assert!(!e.delete_member(member.identifier()).await?);
+
+    // The member can't get a credential anymore:
+    let err = c.credential().await.unwrap_err();
+    assert_eq!(
+        Some(AuthDenyReason::UnauthorizedMember),
+        direct::deny_reason(&err)
+    );
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn requested_attributes(ctx: &mut Context) -> Result<()> {
+    // Create the authority:
+    let (enroller, enrollers_config) = enroller(ctx).await?;
+    let store = InMemoryStorage::new();
+    let authority = Authority::start(ctx, &enrollers_config, false, store.clone(), |s| s).await?;
+
+    // Create a token whose member can request a team, but not the role:
+    let mut c = authority.client(ctx, &enroller).await?;
+    let otc = c
+        .create_token_with_requestable_attributes(
+            HashMap::from([("role", "member".into())]),
+            None,
+            vec!["team".to_string()],
+        )
+        .await?;
+
+    let member = Identity::create(ctx, &Vault::create()).await?;
+    let mut c = authority.client(ctx, &member).await?;
+
+    // A disallowed attribute is rejected, without using the token up:
+    let requested = HashMap::from([("team", "blue".into()), ("role", "admin".into())]);
+    let err = c
+        .credential_with_attributes(&otc, requested)
+        .await
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("attributes can't be requested: role"));
+
+    // An allowed attribute is recorded and added to the credential:
+    let requested = HashMap::from([("team", "blue".into())]);
+    let cred = c.credential_with_attributes(&otc, requested).await?;
+    let pkey = authority.public_identity().await?;
+    let data = pkey
+        .verify_credential(&cred, member.identifier(), &Vault::create())
+        .await?;
+    assert_eq!(Some(b"blue".as_slice()), data.attributes().get("team"));
+    assert_eq!(Some(b"member".as_slice()), data.attributes().get("role"));
+    let storage = AuthenticatedAttributeStorage::new(store);
+    let entry = storage.get_attributes(member.identifier()).await?.unwrap();
+    assert_eq!(Some(&b"blue".to_vec()), entry.attrs().get("team"));
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn rotated_key(ctx: &mut Context) -> Result<()> {
+    // Create the authority, sharing its identity with the authenticator:
+    let (enroller, enrollers_config) = enroller(ctx).await?;
+    let authority =
+        Authority::start(ctx, &enrollers_config, false, InMemoryStorage::new(), |s| s).await?;
+
+    // Create two tokens before the rotation:
+    let mut c = authority.client(ctx, &enroller).await?;
+    let otc1 = c
+        .create_token(HashMap::from([("role", "member".into())]), None)
+        .await?;
+    let otc2 = c
+        .create_token(HashMap::from([("role", "member".into())]), None)
+        .await?;
+
+    // The first member gets its credential before the rotation:
+    let m1 = Identity::create(ctx, &Vault::create()).await?;
+    let mut c = authority.client(ctx, &m1).await?;
+    let cred1 = c.credential_with(&otc1).await?;
+    let pkey_before = authority.public_identity().await?;
+
+    authority.identity.rotate_root_key().await?;
+
+    // The second one redeems its token after the rotation:
+    let m2 = Identity::create(ctx, &Vault::create()).await?;
+    let mut c = authority.client(ctx, &m2).await?;
+    let cred2 = c.credential_with(&otc2).await?;
+
+    // Both credentials verify against the change history of the authority,
+    // the first one only when the previous keys are accepted:
+    let pkey = authority.public_identity().await?;
+    assert!(pkey
+        .verify_credential(&cred1, m1.identifier(), &Vault::create())
+        .await
+        .is_err());
+    pkey.verify_credential_with_previous_keys(&cred1, m1.identifier(), &Vault::create())
+        .await?;
+    pkey.verify_credential(&cred2, m2.identifier(), &Vault::create())
+        .await?;
+
+    // The second one was signed with the new key:
+    assert!(pkey_before
+        .verify_credential(&cred2, m2.identifier(), &Vault::create())
+        .await
+        .is_err());
+
+    // A verifier accepting previous keys, as nodes run it, accepts both:
+    let authorities = BTreeMap::from([(pkey.identifier().clone(), CowBytes::from(pkey.export()?))]);
+    let verifier = Verifier::new(Vault::create()).with_previous_keys(true);
+    for (cred, member) in [(&cred1, &m1), (&cred2, &m2)] {
+        let verification = verifier
+            .verify_credential(cred, member.identifier(), &authorities)
+            .await?;
+        assert!(matches!(verification, Verification::Valid(_)));
+    }
+
+    ctx.stop().await
+}
@@ -1,4 +1,5 @@
 use ockam_api::auth;
+use ockam_api::auth::types::ListRequest;
 use ockam_api::bootstrapped_identities_store::PreTrustedIdentities;
 use ockam_core::{AllowAll, Result};
 use ockam_node::Context;
@@ -34,5 +35,39 @@ async fn auth_smoke(ctx: &mut Context) -> Result<()> {
 
     assert_eq!(2, client.list().await?.len());
 
+    // Page through the entries, ordered by identifier
+    let page = client.list_page(0, Some(1)).await?;
+    assert_eq!(2, page.total());
+    assert_eq!(Some(1), page.next_offset());
+    assert_eq!(
+        "P624ed0b2e5a2be82e267ead6b3279f683616b66de9537a23e45343c95cbb357a",
+        String::from(&page.entries()[0].0)
+    );
+    let page = client.list_page(1, Some(1)).await?;
+    assert_eq!(None, page.next_offset());
+    assert_eq!(
+        "P624ed0b2e5a2be82e267ead6b3279f683616b66de9537a23e45343c95cbb357b",
+        String::from(&page.entries()[0].0)
+    );
+    assert!(client.list_page(5, Some(1)).await?.entries().is_empty());
+
+    // Filter the entries by attribute value
+    let page = client
+        .query(ListRequest::new(0, None).with_filter("attr", "value2"))
+        .await?;
+    assert_eq!(1, page.total());
+    assert_eq!(
+        "P624ed0b2e5a2be82e267ead6b3279f683616b66de9537a23e45343c95cbb357b",
+        String::from(&page.entries()[0].0)
+    );
+    let page = client
+        .query(
+            ListRequest::new(0, None)
+                .with_filter("attr", "value")
+                .with_filter("other", "value"),
+        )
+        .await?;
+    assert!(page.entries().is_empty());
+
     ctx.stop().await
 }
@@ -0,0 +1,51 @@
+use core::time::Duration;
+
+use ockam_api::echoer::Echoer;
+use ockam_api::uppercase::Uppercase;
+use ockam_api::util::error_response;
+use ockam_core::api::Status;
+use ockam_core::{route, AllowAll, Result};
+use ockam_node::Context;
+
+#[ockam_macros::test]
+async fn uppercase_rejects_oversized_messages(ctx: &mut Context) -> Result<()> {
+    ctx.start_worker("uppercase", Uppercase::new(Some(16)), AllowAll, AllowAll)
+        .await?;
+
+    let reply: String = ctx
+        .send_and_receive(route!["uppercase"], "hello".to_string())
+        .await?;
+    assert_eq!(reply, "HELLO");
+
+    let reply: Vec<u8> = ctx
+        .send_and_receive(route!["uppercase"], "a".repeat(64))
+        .await?;
+    assert_rejected(&reply);
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn echoer_rejects_oversized_messages(ctx: &mut Context) -> Result<()> {
+    let echoer = Echoer::new(None).with_max_message_size(Some(16));
+    ctx.start_worker("echoer", echoer, AllowAll, AllowAll)
+        .await?;
+
+    ctx.send(route!["echoer"], "a".repeat(64)).await?;
+    let reply = ctx
+        .receive_duration_timeout::<Vec<u8>>(Duration::from_secs(1))
+        .await?
+        .take()
+        .body();
+    assert_rejected(&reply);
+
+    ctx.stop().await
+}
+
+/// Check that a reply is an error response rejecting an oversized message.
+fn assert_rejected(reply: &[u8]) {
+    let (status, err) = error_response(reply).expect("an error response");
+    assert_eq!(status, Status::BadRequest);
+    let msg = err.message().unwrap_or_default();
+    assert!(msg.starts_with("the message size"), "{msg}");
+}
@@ -0,0 +1,27 @@
+use core::time::Duration;
+
+use ockam_api::echoer::Echoer;
+use ockam_core::{route, AllowAll, Result};
+use ockam_node::Context;
+
+#[ockam_macros::test]
+async fn echoer_drops_messages_beyond_the_rate(ctx: &mut Context) -> Result<()> {
+    ctx.start_worker("echoer", Echoer::new(Some(2)), AllowAll, AllowAll)
+        .await?;
+
+    for i in 0..5 {
+        ctx.send(route!["echoer"], format!("message {i}")).await?;
+    }
+
+    // Only the first two messages fit in the bucket, the others are dropped
+    let mut received = Vec::new();
+    while let Ok(msg) = ctx
+        .receive_duration_timeout::<String>(Duration::from_millis(200))
+        .await
+    {
+        received.push(msg.take().body());
+    }
+    assert_eq!(received, vec!["message 0", "message 1"]);
+
+    ctx.stop().await
+}
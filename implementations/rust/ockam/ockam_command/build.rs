@@ -1,4 +1,9 @@
 use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[allow(dead_code)]
+#[path = "src/util/civil.rs"]
+mod civil;
 
 fn hash() {
     let output = Command::new("git")
@@ -9,6 +14,38 @@ fn hash() {
     println!("cargo:rustc-env=GIT_HASH={git_hash}");
 }
 
+/// The build date is the one of SOURCE_DATE_EPOCH if it is set, so that
+/// reproducible builds are possible, or else the current date
+fn build_date() {
+    let secs = match std::env::var("SOURCE_DATE_EPOCH") {
+        Ok(epoch) => epoch.trim().parse().expect("invalid SOURCE_DATE_EPOCH"),
+        Err(_) => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+    };
+    println!("cargo:rustc-env=BUILD_DATE={}", format_utc(secs));
+}
+
+/// Format a unix timestamp as `%Y-%m-%dT%H:%M:%SZ`
+fn format_utc(secs: u64) -> String {
+    let (days, time) = (secs / 86400, secs % 86400);
+    let (year, month, day) = civil::civil_from_days(days as i64);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+fn target() {
+    let target = std::env::var("TARGET").unwrap_or_default();
+    println!("cargo:rustc-env=BUILD_TARGET={target}");
+}
+
 fn main() {
     hash();
+    build_date();
+    target();
 }
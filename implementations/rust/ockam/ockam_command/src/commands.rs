@@ -6,6 +6,7 @@ pub(crate) mod credential;
 pub(crate) mod enroll;
 pub(crate) mod forwarder;
 pub(crate) mod identity;
+pub(crate) mod kafka;
 pub(crate) mod lease;
 pub(crate) mod manpages;
 pub(crate) mod message;
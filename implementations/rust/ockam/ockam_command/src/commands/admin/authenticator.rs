@@ -0,0 +1,72 @@
+use clap::{Args, Subcommand};
+use ockam::Context;
+use ockam_api::nodes::models::identity::LongIdentityResponse;
+use serde::Serialize;
+
+use crate::commands::node::default_node_name;
+use crate::util::output::Output;
+use crate::util::{api, extract_address_value, node_rpc, print_output, Rpc};
+use crate::{help, CommandGlobalOpts};
+
+const HELP_DETAIL: &str = "";
+
+/// Manage the authenticator service of a node
+#[derive(Clone, Debug, Args)]
+#[command(hide = help::hide(), after_long_help = help::template(HELP_DETAIL))]
+pub struct AuthenticatorCommand {
+    #[command(subcommand)]
+    subcommand: AuthenticatorSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum AuthenticatorSubcommand {
+    /// Rotate the key signing the credentials issued by the authenticator.
+    ///
+    /// The rotation is recorded in the change history of the node's identity:
+    /// the credentials issued before still verify against the new history,
+    /// while the ones issued from now on only verify against it. The members
+    /// must be given the printed identity as the identity of their authority.
+    RotateKey {
+        /// Node running the authenticator service
+        #[arg(long, value_name = "NODE", default_value_t = default_node_name())]
+        at: String,
+    },
+}
+
+impl AuthenticatorCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        match self.subcommand {
+            AuthenticatorSubcommand::RotateKey { at } => node_rpc(rotate_key, (options, at)),
+        }
+    }
+}
+
+/// The identity of an authenticator after the rotation of its key
+#[derive(Serialize)]
+struct RotatedKey {
+    node: String,
+    /// Hex encoded change history, as expected for the authority identity of a project
+    identity: String,
+}
+
+impl Output for RotatedKey {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!(
+            "Rotated the key of the authenticator on node {}, its identity is now:\n{}",
+            self.node, self.identity
+        ))
+    }
+}
+
+async fn rotate_key(ctx: Context, (opts, at): (CommandGlobalOpts, String)) -> crate::Result<()> {
+    let node = extract_address_value(&at)?;
+    let mut rpc = Rpc::background(&ctx, &opts, &node)?;
+    rpc.request(api::rotate_authenticator_key()).await?;
+    let res = rpc.parse_response::<LongIdentityResponse>()?;
+    let output = RotatedKey {
+        node,
+        identity: hex::encode(res.identity.0.as_ref()),
+    };
+    print_output(output, &opts.global_args.output_format)?;
+    Ok(())
+}
@@ -3,6 +3,8 @@ use clap::{Args, Subcommand};
 use crate::util::api::CloudOpts;
 use crate::{help, CommandGlobalOpts};
 
+mod authenticator;
+mod schema;
 mod subscription;
 
 const HELP_DETAIL: &str = "";
@@ -21,12 +23,18 @@ pub struct AdminCommand {
 pub enum AdminSubCommand {
     #[command(display_order = 800)]
     Subscription(subscription::SubscriptionCommand),
+    #[command(display_order = 800)]
+    Schema(schema::SchemaCommand),
+    #[command(display_order = 800)]
+    Authenticator(authenticator::AuthenticatorCommand),
 }
 
 impl AdminCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         match self.subcommand {
             AdminSubCommand::Subscription(c) => c.run(options),
+            AdminSubCommand::Schema(c) => c.run(options),
+            AdminSubCommand::Authenticator(c) => c.run(options),
         }
     }
 }
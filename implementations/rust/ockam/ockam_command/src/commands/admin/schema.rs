@@ -0,0 +1,47 @@
+use anyhow::anyhow;
+use clap::Args;
+use ockam_api::nodes::models::schema;
+
+use crate::util::exitcode;
+use crate::{help, CommandGlobalOpts};
+
+const HELP_DETAIL: &str = "";
+
+/// Print the JSON Schema of the node API request and response bodies.
+/// Their properties are named after the CBOR keys of the fields.
+#[derive(Clone, Debug, Args)]
+#[command(hide = help::hide(), after_long_help = help::template(HELP_DETAIL))]
+pub struct SchemaCommand {
+    /// Name of the model to describe, all the models are described if omitted
+    model: Option<String>,
+}
+
+impl SchemaCommand {
+    pub fn run(self, _options: CommandGlobalOpts) {
+        if let Err(e) = run_impl(self) {
+            eprintln!("{e:?}");
+            std::process::exit(e.code());
+        }
+    }
+}
+
+fn run_impl(cmd: SchemaCommand) -> crate::Result<()> {
+    let document = match &cmd.model {
+        None => schema::json_schema(),
+        Some(name) => {
+            let model = schema::find(name).ok_or_else(|| {
+                let names: Vec<&str> = schema::MODELS.iter().map(|m| m.name).collect();
+                crate::Error::new(
+                    exitcode::USAGE,
+                    anyhow!(
+                        "Unknown model '{name}', expected one of: {}",
+                        names.join(", ")
+                    ),
+                )
+            })?;
+            schema::model_json_schema(model)
+        }
+    };
+    println!("{}", serde_json::to_string_pretty(&document)?);
+    Ok(())
+}
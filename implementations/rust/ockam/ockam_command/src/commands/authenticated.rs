@@ -4,14 +4,20 @@ use clap::{Args, Subcommand};
 use ockam::compat::collections::HashMap;
 use ockam::{Context, TcpTransport};
 use ockam_api::auth;
+use ockam_api::auth::types::ListRequest;
+use ockam_core::api::{Request, Status};
 use ockam_identity::authenticated_storage::AttributesEntry;
 use ockam_identity::IdentityIdentifier;
 use ockam_multiaddr::MultiAddr;
 use termimad::minimad::TextTemplate;
 use termimad::MadSkin;
 
+use crate::commands::node::util::{delete_embedded_node, start_embedded_node};
+use crate::commands::project::util::secure_authority_address;
 use crate::help;
-use crate::util::embedded_node;
+use crate::util::api::{CloudOpts, ProjectOpts};
+use crate::util::{embedded_node, node_rpc, RpcBuilder};
+use crate::CommandGlobalOpts;
 
 const HELP_DETAIL: &str = "";
 
@@ -50,13 +56,51 @@ pub enum AuthenticatedSubcommand {
     List {
         /// Address to connect to.
         addr: MultiAddr,
+
+        /// Maximum number of entries to list
+        #[arg(long)]
+        limit: Option<u32>,
+
+        /// Number of entries to skip, entries are ordered by identifier
+        #[arg(long)]
+        offset: Option<u32>,
+
+        /// Only list the identities having this attribute value, e.g. `role=member`.
+        /// Can be repeated, identities must then match all the filters
+        #[arg(long = "filter", value_name = "KEY=VALUE", value_parser = parse_filter)]
+        filters: Vec<(String, String)>,
     },
+
+    /// Revoke the membership of an identity, as an enroller of the authority.
+    Delete(DeleteCommand),
+}
+
+#[derive(Clone, Debug, Args)]
+pub struct DeleteCommand {
+    #[command(flatten)]
+    cloud_opts: CloudOpts,
+
+    #[command(flatten)]
+    project_opts: ProjectOpts,
+
+    /// Identifier of the identity to delete
+    #[arg(value_name = "IDENTITY_IDENTIFIER")]
+    id: IdentityIdentifier,
+
+    /// Address of the authenticator
+    #[arg(long, short, default_value = "/project/default/service/authenticator")]
+    to: MultiAddr,
 }
 
 impl AuthenticatedCommand {
-    pub fn run(self) {
-        if let Err(e) = embedded_node(run_impl, self.subcommand) {
-            eprintln!("Ockam node failed: {e:?}",);
+    pub fn run(self, options: CommandGlobalOpts) {
+        match self.subcommand {
+            AuthenticatedSubcommand::Delete(cmd) => node_rpc(delete_member, (options, cmd)),
+            subcommand => {
+                if let Err(e) = embedded_node(run_impl, subcommand) {
+                    eprintln!("Ockam node failed: {e:?}",);
+                }
+            }
         }
     }
 }
@@ -72,15 +116,74 @@ async fn run_impl(ctx: Context, cmd: AuthenticatedSubcommand) -> crate::Result<(
                 println!("Not found");
             }
         }
-        AuthenticatedSubcommand::List { addr } => {
+        AuthenticatedSubcommand::List {
+            addr,
+            limit,
+            offset,
+            filters,
+        } => {
             let mut c = client(addr, &ctx).await?;
-            print_entries(&c.list().await?);
+            if limit.is_none() && offset.is_none() && filters.is_empty() {
+                print_entries(&c.list().await?);
+            } else {
+                let list = filters.iter().fold(
+                    ListRequest::new(offset.unwrap_or(0), *limit),
+                    |list, (k, v)| list.with_filter(k, v),
+                );
+                let page = c.query(list).await?;
+                print_entries(page.entries());
+                if let Some(next) = page.next_offset() {
+                    println!(
+                        "More results available ({} in total), use `--offset {next}` to list them",
+                        page.total()
+                    );
+                }
+            }
         }
+        AuthenticatedSubcommand::Delete(_) => unreachable!("run by `delete_member`"),
     }
 
     Ok(())
 }
 
+async fn delete_member(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, DeleteCommand),
+) -> crate::Result<()> {
+    let node_name = start_embedded_node(&ctx, &opts, Some(&cmd.project_opts)).await?;
+    let res = delete_member_with_node(&ctx, &opts, &cmd, &node_name).await;
+    delete_embedded_node(&opts, &node_name).await;
+    res
+}
+
+/// Ask the authenticator to delete the member. Only enrollers are allowed to,
+/// so the request goes through a secure channel to the project authority.
+async fn delete_member_with_node(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    cmd: &DeleteCommand,
+    node_name: &str,
+) -> crate::Result<()> {
+    let to = secure_authority_address(
+        ctx,
+        opts,
+        node_name,
+        &cmd.to,
+        cmd.cloud_opts.identity.clone(),
+    )
+    .await?;
+    let mut rpc = RpcBuilder::new(ctx, opts, node_name).to(&to)?.build();
+    rpc.request(Request::delete(format!("/members/{}", cmd.id))).await?;
+    let (res, _) = rpc.check_response()?;
+    if res.status() == Some(Status::NotFound) {
+        println!("Not found");
+    } else {
+        rpc.is_ok()?;
+        println!("Deleted {}", cmd.id);
+    }
+    Ok(())
+}
+
 fn print_entries(entries: &[(IdentityIdentifier, AttributesEntry)]) {
     let template = TextTemplate::from(LIST_VIEW);
     let model: Vec<_> = entries
@@ -119,6 +222,13 @@ fn print_entries(entries: &[(IdentityIdentifier, AttributesEntry)]) {
     skin.print_expander(expander);
 }
 
+fn parse_filter(s: &str) -> Result<(String, String)> {
+    match s.split_once('=') {
+        Some((k, v)) if !k.is_empty() => Ok((k.to_string(), v.to_string())),
+        _ => Err(anyhow!("expected KEY=VALUE, got `{s}`")),
+    }
+}
+
 async fn client(addr: &MultiAddr, ctx: &Context) -> Result<auth::Client> {
     let to = ockam_api::multiaddr_to_route(addr)
         .ok_or_else(|| anyhow!("failed to parse address: {addr}"))?;
@@ -1,8 +1,12 @@
+use std::fs;
 use std::io;
+use std::path::{Path, PathBuf};
 
+use anyhow::{anyhow, Context};
 use clap::{Args, CommandFactory};
 use clap_complete::{generate, Shell};
 
+use crate::util::exitcode;
 use crate::{help, OckamCommand};
 
 const HELP_DETAIL: &str = include_str!("../../constants/completion/help_detail.txt");
@@ -11,18 +15,146 @@ const HELP_DETAIL: &str = include_str!("../../constants/completion/help_detail.t
 #[derive(Clone, Debug, Args)]
 #[command(arg_required_else_help = true, after_long_help = help::template(HELP_DETAIL))]
 pub struct CompletionCommand {
-    /// The type of shell (bash, zsh, fish)
-    #[arg(display_order = 900, long, short)]
-    shell: Shell,
+    /// The type of shell (bash, zsh, fish, elvish, powershell).
+    /// Detected from `$SHELL` when installing the completions
+    #[arg(display_order = 900, long, short, required_unless_present = "install")]
+    shell: Option<Shell>,
+
+    /// Write the completions to the conventional location of the shell
+    /// instead of printing them
+    #[arg(display_order = 901, long)]
+    install: bool,
+
+    /// Overwrite the completion file if it already exists
+    #[arg(display_order = 902, long, requires = "install")]
+    force: bool,
 }
 
 impl CompletionCommand {
     pub fn run(self) {
+        if let Err(e) = run_impl(self) {
+            eprintln!("{e:?}");
+            std::process::exit(e.code());
+        }
+    }
+}
+
+fn run_impl(cmd: CompletionCommand) -> crate::Result<()> {
+    if !cmd.install {
+        // `shell` is required by clap when `--install` is not set
+        let shell = cmd.shell.expect("--shell is required");
         generate(
-            self.shell,
+            shell,
             &mut OckamCommand::command(),
             "ockam",
             &mut io::stdout(),
+        );
+        return Ok(());
+    }
+
+    let shell = match cmd.shell.or_else(Shell::from_env) {
+        Some(shell) => shell,
+        None => {
+            return Err(crate::Error::new(
+                exitcode::USAGE,
+                anyhow!("Couldn't detect the shell from $SHELL, please use the --shell argument"),
+            ))
+        }
+    };
+    let home = dirs::home_dir().ok_or_else(|| {
+        crate::Error::new(
+            exitcode::CONFIG,
+            anyhow!("Couldn't find the home directory of the current user"),
         )
+    })?;
+    let path = install_path(shell, &home)?;
+    if path.exists() && !cmd.force {
+        return Err(crate::Error::new(
+            exitcode::CANTCREAT,
+            anyhow!(
+                "{} already exists, use --force to overwrite it",
+                path.display()
+            ),
+        ));
+    }
+
+    let mut script = Vec::new();
+    generate(shell, &mut OckamCommand::command(), "ockam", &mut script);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory {}", dir.display()))?;
+    }
+    fs::write(&path, script).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    println!("Completions for {shell} written to {}", path.display());
+    if let Some(hint) = install_hint(shell, &path) {
+        println!("{hint}");
+    }
+    Ok(())
+}
+
+/// Conventional location of the completion file of a shell, relative to the user's home
+fn install_path(shell: Shell, home: &Path) -> crate::Result<PathBuf> {
+    let path = match shell {
+        Shell::Bash => home.join(".bash_completion.d").join("ockam"),
+        Shell::Zsh => home.join(".zfunc").join("_ockam"),
+        Shell::Fish => home
+            .join(".config")
+            .join("fish")
+            .join("completions")
+            .join("ockam.fish"),
+        Shell::Elvish => home
+            .join(".config")
+            .join("elvish")
+            .join("lib")
+            .join("ockam.elv"),
+        Shell::PowerShell => home.join(".config").join("powershell").join("ockam.ps1"),
+        _ => {
+            return Err(crate::Error::new(
+                exitcode::USAGE,
+                anyhow!("Installing completions for {shell} is not supported"),
+            ))
+        }
+    };
+    Ok(path)
+}
+
+/// Configuration the user must add for the shell to load the installed file, if any
+fn install_hint(shell: Shell, path: &Path) -> Option<String> {
+    let dir = path.parent()?.display();
+    match shell {
+        Shell::Bash => Some(format!(
+            "Add `source {}` to your ~/.bashrc to enable them",
+            path.display()
+        )),
+        Shell::Zsh => Some(format!(
+            "Add `fpath=({dir} $fpath)` before `compinit` in your ~/.zshrc to enable them"
+        )),
+        Shell::Elvish => Some("Add `use ockam` to your rc.elv to enable them".to_string()),
+        Shell::PowerShell => Some(format!(
+            "Add `. {}` to your PowerShell profile to enable them",
+            path.display()
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn install_paths() {
+        let home = Path::new("/home/user");
+        let path = |shell| install_path(shell, home).unwrap();
+        assert_eq!(
+            path(Shell::Bash),
+            Path::new("/home/user/.bash_completion.d/ockam")
+        );
+        assert_eq!(path(Shell::Zsh), Path::new("/home/user/.zfunc/_ockam"));
+        assert_eq!(
+            path(Shell::Fish),
+            Path::new("/home/user/.config/fish/completions/ockam.fish")
+        );
     }
 }
@@ -1,5 +1,6 @@
 use clap::Args;
 
+use super::keys::ConfigKey;
 use crate::CommandGlobalOpts;
 
 #[derive(Clone, Debug, Args)]
@@ -14,7 +15,10 @@ impl GetDefaultNodeCommand {
     }
 }
 
-fn run_impl(_opts: CommandGlobalOpts) -> crate::Result<()> {
-    // TODO: get from opts.state.nodes().default()
-    todo!()
+fn run_impl(opts: CommandGlobalOpts) -> crate::Result<()> {
+    match ConfigKey::DefaultNode.get(&opts) {
+        Some(name) => println!("{name}"),
+        None => println!("No default node"),
+    }
+    Ok(())
 }
@@ -0,0 +1,47 @@
+use clap::Args;
+use serde::Serialize;
+
+use super::keys::ConfigKey;
+use crate::util::output::Output;
+use crate::util::print_output;
+use crate::CommandGlobalOpts;
+
+#[derive(Clone, Debug, Args)]
+pub struct GetKeyCommand {
+    /// Configuration key, e.g. `default_node` or `controller.route`
+    pub key: String,
+}
+
+impl GetKeyCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        if let Err(e) = run_impl(options, self) {
+            eprintln!("{e}");
+            std::process::exit(e.code());
+        }
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: GetKeyCommand) -> crate::Result<()> {
+    let key: ConfigKey = cmd.key.parse()?;
+    let output = ConfigValue {
+        key: key.name(),
+        value: key.get(&opts),
+    };
+    print_output(output, &opts.global_args.output_format)?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfigValue {
+    pub key: &'static str,
+    pub value: Option<String>,
+}
+
+impl Output for ConfigValue {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(match &self.value {
+            Some(value) => value.clone(),
+            None => format!("{} is not set", self.key),
+        })
+    }
+}
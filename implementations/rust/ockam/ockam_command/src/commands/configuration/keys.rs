@@ -0,0 +1,114 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use ockam_multiaddr::MultiAddr;
+
+use crate::util::exitcode;
+use crate::CommandGlobalOpts;
+
+/// A key of the configuration, read by `configuration get-key` and written by `configuration set`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigKey {
+    /// Name of the node used by commands which don't specify one
+    DefaultNode,
+    /// Route to the Orchestrator controller, overridden by `$OCKAM_CONTROLLER_ADDR`
+    ControllerRoute,
+}
+
+impl ConfigKey {
+    pub const ALL: [ConfigKey; 2] = [ConfigKey::DefaultNode, ConfigKey::ControllerRoute];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConfigKey::DefaultNode => "default_node",
+            ConfigKey::ControllerRoute => "controller.route",
+        }
+    }
+
+    /// The value of the key, if it is set
+    pub fn get(&self, opts: &CommandGlobalOpts) -> Option<String> {
+        match self {
+            ConfigKey::DefaultNode => opts.state.nodes.default().ok().map(|n| n.config.name),
+            ConfigKey::ControllerRoute => opts
+                .config
+                .inner()
+                .controller
+                .route
+                .as_ref()
+                .map(|r| r.to_string()),
+        }
+    }
+
+    /// Validate a value and store it
+    pub fn set(&self, opts: &CommandGlobalOpts, value: &str) -> crate::Result<()> {
+        match self {
+            ConfigKey::DefaultNode => {
+                opts.state
+                    .nodes
+                    .set_default(value)
+                    .map_err(|e| self.invalid_value(e))?;
+            }
+            ConfigKey::ControllerRoute => {
+                let route = MultiAddr::from_str(value).map_err(|e| self.invalid_value(e))?;
+                opts.config.write().controller.route = Some(route);
+                opts.config.persist_config_updates()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn invalid_value(&self, err: impl fmt::Display) -> crate::Error {
+        crate::Error::new(
+            exitcode::USAGE,
+            anyhow!("Invalid value for `{self}`: {err}"),
+        )
+    }
+}
+
+impl fmt::Display for ConfigKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ConfigKey {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        ConfigKey::ALL
+            .into_iter()
+            .find(|k| k.name() == s)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = ConfigKey::ALL.iter().map(|k| k.name()).collect();
+                crate::Error::new(
+                    exitcode::USAGE,
+                    anyhow!(
+                        "Unknown configuration key `{s}`. Valid keys are: {}",
+                        valid.join(", ")
+                    ),
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_round_trip() {
+        for key in ConfigKey::ALL {
+            assert_eq!(key.name().parse::<ConfigKey>().unwrap(), key);
+        }
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let err = "controller.address".parse::<ConfigKey>().unwrap_err();
+        assert_eq!(err.code(), exitcode::USAGE);
+        assert!(err
+            .to_string()
+            .contains("Valid keys are: default_node, controller.route"));
+    }
+}
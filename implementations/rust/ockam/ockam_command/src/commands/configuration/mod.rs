@@ -1,12 +1,17 @@
 mod get;
 mod get_default_node;
+mod get_key;
+mod keys;
 mod list;
+mod set;
 mod set_default_node;
 
 use clap::{Args, Subcommand};
 use get::GetCommand;
 use get_default_node::GetDefaultNodeCommand;
+use get_key::GetKeyCommand;
 use list::ListCommand;
+use set::SetCommand;
 use set_default_node::SetDefaultNodeCommand;
 
 use crate::{help, CommandGlobalOpts};
@@ -24,7 +29,9 @@ pub struct ConfigurationCommand {
 pub enum ConfigurationSubcommand {
     Get(GetCommand),
     GetDefaultNode(GetDefaultNodeCommand),
+    GetKey(GetKeyCommand),
     List(ListCommand),
+    Set(SetCommand),
     SetDefaultNode(SetDefaultNodeCommand),
 }
 
@@ -33,7 +40,9 @@ impl ConfigurationCommand {
         match self.subcommand {
             ConfigurationSubcommand::Get(c) => c.run(options),
             ConfigurationSubcommand::GetDefaultNode(c) => c.run(options),
+            ConfigurationSubcommand::GetKey(c) => c.run(options),
             ConfigurationSubcommand::List(c) => c.run(options),
+            ConfigurationSubcommand::Set(c) => c.run(options),
             ConfigurationSubcommand::SetDefaultNode(c) => c.run(options),
         }
     }
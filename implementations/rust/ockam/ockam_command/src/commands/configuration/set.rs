@@ -0,0 +1,27 @@
+use clap::Args;
+
+use super::keys::ConfigKey;
+use crate::CommandGlobalOpts;
+
+#[derive(Clone, Debug, Args)]
+pub struct SetCommand {
+    /// Configuration key, e.g. `default_node` or `controller.route`
+    pub key: String,
+
+    /// New value of the key
+    pub value: String,
+}
+
+impl SetCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        if let Err(e) = run_impl(options, self) {
+            eprintln!("{e}");
+            std::process::exit(e.code());
+        }
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: SetCommand) -> crate::Result<()> {
+    let key: ConfigKey = cmd.key.parse()?;
+    key.set(&opts, &cmd.value)
+}
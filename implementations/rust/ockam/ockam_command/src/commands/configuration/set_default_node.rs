@@ -1,5 +1,6 @@
 use clap::Args;
 
+use super::keys::ConfigKey;
 use crate::CommandGlobalOpts;
 
 #[derive(Clone, Debug, Args)]
@@ -17,7 +18,6 @@ impl SetDefaultNodeCommand {
     }
 }
 
-fn run_impl(_name: &str, _options: &CommandGlobalOpts) -> crate::Result<()> {
-    // TODO: add symlink to options.state.defaults().node
-    todo!()
+fn run_impl(name: &str, options: &CommandGlobalOpts) -> crate::Result<()> {
+    ConfigKey::DefaultNode.set(options, name)
 }
@@ -1,7 +1,13 @@
+use std::collections::BTreeMap;
+
+use anyhow::anyhow;
 use clap::Args;
+use ockam::identity::credential::{Credential, CredentialData, Unverified};
 use ockam::Context;
+use serde::Serialize;
 
 use crate::commands::node::NodeOpts;
+use crate::util::output::Output;
 use crate::util::{api, node_rpc, Rpc};
 use crate::CommandGlobalOpts;
 
@@ -20,6 +26,69 @@ impl GetCredentialCommand {
     }
 }
 
+/// A credential, as printed by `credential get` with a non-plain output format
+///
+/// Attribute values are printed as JSON strings when they are valid UTF-8,
+/// which is the case of the attributes set by Ockam authorities (`project_id`
+/// and the attributes given when enrolling a member).
+/// Any other value is printed as `{"hex": "<hex encoded bytes>"}`.
+#[derive(Debug, Serialize)]
+pub struct CredentialOutput {
+    #[serde(skip)]
+    credential: Credential,
+    pub issuer: String,
+    pub subject: String,
+    pub schema_id: Option<u64>,
+    pub attributes: BTreeMap<String, AttributeValue>,
+    /// Unix timestamp, in seconds
+    pub expires_at: u64,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum AttributeValue {
+    String(String),
+    Bytes { hex: String },
+}
+
+impl From<&[u8]> for AttributeValue {
+    fn from(value: &[u8]) -> Self {
+        match std::str::from_utf8(value) {
+            Ok(s) => AttributeValue::String(s.to_string()),
+            Err(_) => AttributeValue::Bytes {
+                hex: hex::encode(value),
+            },
+        }
+    }
+}
+
+impl TryFrom<Credential> for CredentialOutput {
+    type Error = crate::Error;
+
+    fn try_from(credential: Credential) -> crate::Result<Self> {
+        let data = CredentialData::<Unverified>::try_from(&credential)
+            .map_err(|e| anyhow!("Failed to decode the credential: {e}"))?;
+        Ok(CredentialOutput {
+            issuer: data.unverfied_issuer().to_string(),
+            subject: data.unverified_subject().to_string(),
+            schema_id: data.unverified_schema().map(u64::from),
+            attributes: data
+                .unverified_attributes()
+                .iter()
+                .map(|(k, v)| (k.clone(), AttributeValue::from(v.as_slice())))
+                .collect(),
+            expires_at: data.unverified_expires_at().unix_time(),
+            credential,
+        })
+    }
+}
+
+impl Output for CredentialOutput {
+    fn output(&self) -> anyhow::Result<String> {
+        self.credential.output()
+    }
+}
+
 async fn rpc(
     mut ctx: Context,
     (opts, cmd): (CommandGlobalOpts, GetCredentialCommand),
@@ -35,5 +104,49 @@ async fn run_impl(
     let mut rpc = Rpc::background(ctx, &opts, &cmd.node_opts.api_node)?;
     rpc.request(api::credentials::get_credential(cmd.overwrite))
         .await?;
+    let credential = rpc.parse_response::<Credential>()?;
+    rpc.print_response(CredentialOutput::try_from(credential)?)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use ockam::identity::credential::SchemaId;
+    use ockam_identity::Identity;
+    use ockam_vault::Vault;
+
+    use super::*;
+
+    #[ockam_macros::test(crate = "ockam")]
+    async fn credential_json_schema(ctx: &mut Context) -> ockam::Result<()> {
+        let vault = Vault::create();
+        let issuer = Identity::create(ctx, &vault).await?;
+        let subject = Identity::create(ctx, &vault).await?;
+
+        let builder = Credential::builder(subject.identifier().clone())
+            .with_schema(SchemaId(1))
+            .with_attribute("project_id", b"p1")
+            .with_attribute("key", &[0xff, 0x00]);
+        let credential = issuer.issue_credential(builder).await?;
+
+        let output = CredentialOutput::try_from(credential).unwrap();
+        let mut json = serde_json::to_value(&output).unwrap();
+        assert!(json["expires_at"].as_u64().is_some());
+        json.as_object_mut().unwrap().remove("expires_at");
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "issuer": issuer.identifier().to_string(),
+                "subject": subject.identifier().to_string(),
+                "schema_id": 1,
+                "attributes": {
+                    "project_id": "p1",
+                    "key": {"hex": "ff00"},
+                },
+            })
+        );
+
+        ctx.stop().await?;
+        Ok(())
+    }
+}
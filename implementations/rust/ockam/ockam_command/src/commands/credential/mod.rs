@@ -1,11 +1,18 @@
 pub(crate) mod get_credential;
 pub(crate) mod present_credential;
+pub(crate) mod verify;
 
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::{anyhow, Context as _};
 use clap::{Args, Subcommand};
 pub(crate) use get_credential::GetCredentialCommand;
+use ockam::identity::credential::Credential;
 pub(crate) use present_credential::PresentCredentialCommand;
+pub(crate) use verify::VerifyCommand;
 
-use crate::{help, CommandGlobalOpts};
+use crate::{exitcode, help, CommandGlobalOpts};
 
 const HELP_DETAIL: &str = "";
 
@@ -25,6 +32,7 @@ pub struct CredentialCommand {
 pub enum CredentialSubcommand {
     Get(GetCredentialCommand),
     Present(PresentCredentialCommand),
+    Verify(VerifyCommand),
 }
 
 impl CredentialCommand {
@@ -32,6 +40,26 @@ impl CredentialCommand {
         match self.subcommand {
             CredentialSubcommand::Get(c) => c.run(options),
             CredentialSubcommand::Present(c) => c.run(options),
+            CredentialSubcommand::Verify(c) => c.run(options),
         }
     }
 }
+
+/// Read a hex-encoded credential, as printed by `--output json`, or a raw CBOR one.
+pub(crate) fn read_credential(path: &Path) -> crate::Result<Credential> {
+    let mut contents = Vec::new();
+    if path == Path::new("-") {
+        std::io::stdin()
+            .read_to_end(&mut contents)
+            .context("Failed to read the credential from stdin")?;
+    } else {
+        contents = std::fs::read(path)
+            .with_context(|| format!("Failed to read the credential from {}", path.display()))?;
+    }
+    let decoded = std::str::from_utf8(&contents)
+        .ok()
+        .and_then(|s| hex::decode(s.trim().trim_matches('"')).ok());
+    let bytes = decoded.unwrap_or(contents);
+    minicbor::decode(&bytes)
+        .map_err(|e| crate::Error::new(exitcode::DATAERR, anyhow!("Invalid credential: {e}")))
+}
@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use clap::Args;
+use ockam::identity::credential::{Attributes, CredentialData};
+use ockam::identity::{IdentityIdentifier, PublicIdentity};
+use ockam::Context;
+use ockam_api::verifier::{Verification, Verifier};
+use ockam_core::CowBytes;
+use ockam_vault::Vault;
+use serde::Serialize;
+
+use super::read_credential;
+use crate::util::output::Output;
+use crate::util::{node_rpc, print_output};
+use crate::{exitcode, CommandGlobalOpts};
+
+/// Verify a credential against a set of trusted authorities
+#[derive(Clone, Debug, Args)]
+pub struct VerifyCommand {
+    /// File containing the hex-encoded credential, `-` to read it from stdin
+    #[arg(value_name = "PATH", default_value = "-")]
+    credential: PathBuf,
+
+    /// Hex-encoded identity of a trusted authority, as printed by
+    /// `ockam identity show --full --encoding hex`. Can be repeated
+    #[arg(long = "authority", value_name = "IDENTITY", required = true)]
+    authorities: Vec<String>,
+
+    /// Identifier the credential must have been issued to, defaults to
+    /// the subject of the credential
+    #[arg(long, value_name = "IDENTIFIER")]
+    subject: Option<IdentityIdentifier>,
+}
+
+impl VerifyCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    _ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, VerifyCommand),
+) -> crate::Result<()> {
+    let credential = read_credential(&cmd.credential)?;
+    let data = CredentialData::try_from(&credential)
+        .map_err(|e| crate::Error::new(exitcode::DATAERR, anyhow!("Invalid credential: {e}")))?;
+
+    let vault = Vault::default();
+    let invalid = |e: &dyn std::fmt::Display| {
+        crate::Error::new(
+            exitcode::DATAERR,
+            anyhow!("Invalid authority identity: {e}"),
+        )
+    };
+    let mut authorities = BTreeMap::new();
+    for authority in &cmd.authorities {
+        let exported = hex::decode(authority.trim()).map_err(|e| invalid(&e))?;
+        let identity = PublicIdentity::import(&exported, &vault)
+            .await
+            .map_err(|e| invalid(&e))?;
+        authorities.insert(identity.identifier().clone(), CowBytes::from(exported));
+    }
+
+    let subject = cmd
+        .subject
+        .unwrap_or_else(|| data.unverified_subject().clone());
+    let verification = Verifier::new(vault)
+        .with_previous_keys(true)
+        .verify_credential(&credential, &subject, &authorities)
+        .await?;
+
+    let reason = match &verification {
+        Verification::Valid(_) => None,
+        Verification::UnauthorisedIssuer => Some("the issuer is not a trusted authority".into()),
+        Verification::Invalid(e) => Some(e.to_string()),
+    };
+    let output = VerifyOutput {
+        valid: reason.is_none(),
+        issuer: data.unverfied_issuer().to_string(),
+        subject: data.unverified_subject().to_string(),
+        expires_at: data.unverified_expires_at().unix_time(),
+        attributes: attributes(data.unverified_attributes()),
+        reason: reason.clone(),
+    };
+    print_output(output, &opts.global_args.output_format)?;
+
+    match reason {
+        None => Ok(()),
+        Some(reason) => Err(crate::Error::new(
+            exitcode::DATAERR,
+            anyhow!("Invalid credential: {reason}"),
+        )),
+    }
+}
+
+fn attributes(attributes: &Attributes) -> BTreeMap<String, String> {
+    attributes
+        .iter()
+        .map(|(k, v)| {
+            let v = std::str::from_utf8(v).unwrap_or("**binary**");
+            (k.clone(), v.to_string())
+        })
+        .collect()
+}
+
+#[derive(Serialize)]
+struct VerifyOutput {
+    valid: bool,
+    issuer: String,
+    subject: String,
+    expires_at: u64,
+    attributes: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+impl Output for VerifyOutput {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut w = String::new();
+        if self.valid {
+            writeln!(w, "Credential is valid")?;
+        } else {
+            writeln!(w, "Credential is NOT valid")?;
+        }
+        if let Some(reason) = &self.reason {
+            writeln!(w, "  Reason: {reason}")?;
+        }
+        writeln!(w, "  Issuer: {}", self.issuer)?;
+        writeln!(w, "  Subject: {}", self.subject)?;
+        writeln!(w, "  Expires: {}", self.expires_at)?;
+        write!(w, "  Attributes:")?;
+        if self.attributes.is_empty() {
+            write!(w, " none")?;
+        }
+        for (k, v) in &self.attributes {
+            write!(w, "\n    {k}: {v}")?;
+        }
+        Ok(w)
+    }
+}
@@ -1,5 +1,8 @@
 use std::borrow::Borrow;
+use std::fmt;
 use std::io::stdin;
+use std::str::FromStr;
+use std::time::SystemTime;
 
 use anyhow::anyhow;
 use clap::Args;
@@ -10,7 +13,8 @@ use ockam_api::cloud::project::{OktaAuth0, Project};
 use ockam_api::cloud::space::Space;
 use ockam_core::api::Status;
 use reqwest::StatusCode;
-use tokio::time::{sleep, Duration};
+use serde::{Deserialize, Serialize};
+use tokio::time::{sleep, Duration, Instant};
 use tokio_retry::strategy::ExponentialBackoff;
 use tokio_retry::Retry;
 use tracing::{debug, info};
@@ -20,7 +24,7 @@ use crate::commands::project::util::{check_project_readiness, project_enroll_adm
 use crate::commands::space::util::config;
 use crate::util::api::CloudOpts;
 use crate::util::output::Output;
-use crate::util::{api, node_rpc, RpcBuilder};
+use crate::util::{api, exitcode, node_rpc, print_output, RpcBuilder, Spinner};
 use crate::{help, CommandGlobalOpts, Result};
 
 const HELP_DETAIL: &str = "";
@@ -31,6 +35,10 @@ const HELP_DETAIL: &str = "";
 pub struct EnrollCommand {
     #[command(flatten)]
     pub cloud_opts: CloudOpts,
+
+    /// Enroll without a browser, using the enrollment ticket stored in this file
+    #[arg(long, value_name = "FILE", value_parser = parse_ticket)]
+    pub ticket: Option<EnrollmentTicket>,
 }
 
 impl EnrollCommand {
@@ -44,17 +52,30 @@ async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, EnrollCommand)) -> R
 }
 
 async fn run_impl(ctx: &Context, opts: CommandGlobalOpts, cmd: EnrollCommand) -> Result<()> {
+    if let Some(ticket) = &cmd.ticket {
+        return enroll_with_ticket(ctx, &opts, &cmd, ticket).await;
+    }
+
     let node_name = start_embedded_node(ctx, &opts, None).await?;
+    let res = enroll_with_node(ctx, &opts, &cmd, &node_name).await;
+    // The embedded node is deleted whether the enrollment succeeded or not
+    delete_embedded_node(&opts, &node_name).await;
+    res
+}
 
-    enroll(ctx, &opts, &cmd, &node_name).await?;
+async fn enroll_with_node(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    cmd: &EnrollCommand,
+    node_name: &str,
+) -> Result<()> {
+    enroll(ctx, opts, cmd, node_name).await?;
 
     let cloud_opts = cmd.cloud_opts.clone();
-    let space = default_space(ctx, &opts, &cloud_opts, &node_name).await?;
-    default_project(ctx, &opts, &cloud_opts, &node_name, &space).await?;
-    update_enrolled_identity(ctx, &opts, &node_name).await?;
-    enroll_admin_to_all_their_projects(ctx, &opts, &cloud_opts, &node_name).await?;
-    delete_embedded_node(&opts, &node_name).await;
-
+    let space = default_space(ctx, opts, &cloud_opts, node_name).await?;
+    default_project(ctx, opts, &cloud_opts, node_name, &space).await?;
+    update_enrolled_identity(ctx, opts, node_name).await?;
+    enroll_admin_to_all_their_projects(ctx, opts, &cloud_opts, node_name).await?;
     Ok(())
 }
 
@@ -79,7 +100,7 @@ async fn enroll(
     node_name: &str,
 ) -> anyhow::Result<()> {
     let auth0 = Auth0Service::new(Auth0Provider::Auth0);
-    let token = auth0.token().await?;
+    let token = auth0.token(opts).await?;
     let mut rpc = RpcBuilder::new(ctx, opts, node_name).build();
     rpc.request(api::enroll::auth0(cmd.clone(), token)).await?;
     let (res, dec) = rpc.check_response()?;
@@ -95,6 +116,149 @@ async fn enroll(
     }
 }
 
+/// Enroll the default identity with an enrollment ticket, skipping the browser flow
+async fn enroll_with_ticket(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    cmd: &EnrollCommand,
+    ticket: &EnrollmentTicket,
+) -> Result<()> {
+    if ticket.is_expired_at(SystemTime::now()) {
+        return Err(crate::Error::new(
+            exitcode::NOPERM,
+            anyhow!("The enrollment ticket has expired"),
+        ));
+    }
+
+    let node_name = start_embedded_node(ctx, opts, None).await?;
+    let res = enroll_with_ticket_at(ctx, opts, cmd, ticket, &node_name).await;
+    delete_embedded_node(opts, &node_name).await;
+    print_output(res?, &opts.global_args.output_format)?;
+    Ok(())
+}
+
+async fn enroll_with_ticket_at(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    cmd: &EnrollCommand,
+    ticket: &EnrollmentTicket,
+    node_name: &str,
+) -> Result<TicketEnrollment> {
+    let mut rpc = RpcBuilder::new(ctx, opts, node_name).build();
+    rpc.request(api::enroll::token(cmd, ticket.token.clone()))
+        .await?;
+    let (res, dec) = rpc.check_response()?;
+    let status = match res.status() {
+        Some(Status::Ok) => TicketEnrollmentStatus::Enrolled,
+        Some(Status::BadRequest) => TicketEnrollmentStatus::AlreadyEnrolled,
+        Some(Status::Unauthorized) | Some(Status::Forbidden) => {
+            return Err(crate::Error::new(
+                exitcode::NOPERM,
+                anyhow!("The enrollment ticket was rejected, it may be expired or already used"),
+            ));
+        }
+        _ => {
+            eprintln!("{}", rpc.parse_err_msg(res, dec));
+            return Err(anyhow!("Failed to enroll").into());
+        }
+    };
+    update_enrolled_identity(ctx, opts, node_name).await?;
+    let identity = opts
+        .state
+        .nodes
+        .get(node_name)?
+        .config
+        .identity_config()?
+        .identifier
+        .to_string();
+    Ok(TicketEnrollment { identity, status })
+}
+
+/// A token issued ahead of time to enroll an identity without a browser.
+///
+/// The ticket file holds either the bare token, or a json object with the token and
+/// an optional expiry time, in seconds since the unix epoch:
+/// `{"token": "...", "expires_at": 1700000000}`
+#[derive(Clone, Deserialize)]
+pub struct EnrollmentTicket {
+    token: String,
+    #[serde(default)]
+    expires_at: Option<u64>,
+}
+
+impl EnrollmentTicket {
+    fn is_expired_at(&self, now: SystemTime) -> bool {
+        match self.expires_at {
+            Some(expires_at) => now
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(false, |now| now.as_secs() >= expires_at),
+            None => false,
+        }
+    }
+}
+
+impl FromStr for EnrollmentTicket {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        let ticket = if s.starts_with('{') {
+            serde_json::from_str(s).map_err(|e| anyhow!("Malformed enrollment ticket: {e}"))?
+        } else {
+            EnrollmentTicket {
+                token: s.to_string(),
+                expires_at: None,
+            }
+        };
+        if ticket.token.is_empty() || ticket.token.contains(char::is_whitespace) {
+            return Err(anyhow!(
+                "Malformed enrollment ticket: the token must be a single non-empty word"
+            ));
+        }
+        Ok(ticket)
+    }
+}
+
+// The token is a secret, and commands are logged with their arguments
+impl fmt::Debug for EnrollmentTicket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EnrollmentTicket")
+            .field("expires_at", &self.expires_at)
+            .finish_non_exhaustive()
+    }
+}
+
+fn parse_ticket(path: &str) -> anyhow::Result<EnrollmentTicket> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Can't read the enrollment ticket {path}: {e}"))?;
+    contents.parse()
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum TicketEnrollmentStatus {
+    Enrolled,
+    AlreadyEnrolled,
+}
+
+/// Result of an enrollment with a ticket
+#[derive(Serialize)]
+struct TicketEnrollment {
+    identity: String,
+    status: TicketEnrollmentStatus,
+}
+
+impl Output for TicketEnrollment {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(match self.status {
+            TicketEnrollmentStatus::Enrolled => format!("Enrolled identity {}", self.identity),
+            TicketEnrollmentStatus::AlreadyEnrolled => {
+                format!("Identity {} is already enrolled", self.identity)
+            }
+        })
+    }
+}
+
 async fn default_space<'a>(
     ctx: &Context,
     opts: &CommandGlobalOpts,
@@ -247,7 +411,7 @@ impl Auth0Service {
         &self.0
     }
 
-    pub(crate) async fn token(&self) -> Result<Auth0Token> {
+    pub(crate) async fn token(&self, opts: &CommandGlobalOpts) -> Result<Auth0Token> {
         let dc = self.device_code().await?;
 
         eprint!(
@@ -283,7 +447,7 @@ impl Auth0Service {
             );
         }
 
-        self.poll_token(dc).await
+        self.poll_token(dc, opts).await
     }
 
     /// Request device code
@@ -321,11 +485,18 @@ impl Auth0Service {
         }
     }
 
-    /// Poll for token until it's ready
-    async fn poll_token<'a>(&'a self, dc: DeviceCode<'a>) -> Result<Auth0Token> {
+    /// Poll for token until it's ready, or the device code expires
+    async fn poll_token<'a>(
+        &'a self,
+        dc: DeviceCode<'a>,
+        opts: &CommandGlobalOpts,
+    ) -> Result<Auth0Token> {
         let client = self.provider().build_http_client()?;
+        let deadline = Instant::now() + Duration::from_secs(dc.expires_in as u64);
+        let mut spinner = Spinner::new(opts, "Waiting for browser authorization...");
         let token;
         loop {
+            spinner.tick();
             let res = client
                 .post(self.provider().token_request_url())
                 .header("content-type", "application/x-www-form-urlencoded")
@@ -344,6 +515,7 @@ impl Auth0Service {
                         .await
                         .map_err(|e| anyhow!(e.to_string()))?;
                     debug!(?token, "token response received");
+                    spinner.finish();
                     eprintln!("{} Token received, processing...", ">".light_green());
                     return Ok(token);
                 }
@@ -355,6 +527,12 @@ impl Auth0Service {
                     match err.error.borrow() {
                         "authorization_pending" | "invalid_request" | "slow_down" => {
                             debug!(?err, "tokens not yet received");
+                            if Instant::now() >= deadline {
+                                spinner.finish();
+                                return Err(
+                                    anyhow!("timed out waiting for browser authorization").into()
+                                );
+                            }
                             sleep(Duration::from_secs(dc.interval as u64)).await;
                             continue;
                         }
@@ -395,3 +573,44 @@ async fn update_enrolled_identity(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn parse_enrollment_ticket() {
+        let ticket: EnrollmentTicket = "abcd1234\n".parse().unwrap();
+        assert_eq!(ticket.token, "abcd1234");
+        assert_eq!(ticket.expires_at, None);
+
+        let ticket: EnrollmentTicket = r#"{"token": "abcd1234", "expires_at": 1700000000}"#
+            .parse()
+            .unwrap();
+        assert_eq!(ticket.token, "abcd1234");
+        assert_eq!(ticket.expires_at, Some(1700000000));
+
+        assert!("".parse::<EnrollmentTicket>().is_err());
+        assert!("two words".parse::<EnrollmentTicket>().is_err());
+        assert!(r#"{"expires_at": 1700000000}"#.parse::<EnrollmentTicket>().is_err());
+
+        // The token isn't printed with the command
+        assert!(!format!("{ticket:?}").contains("abcd1234"));
+    }
+
+    #[test]
+    fn expired_enrollment_ticket() {
+        let ticket: EnrollmentTicket = r#"{"token": "abcd1234", "expires_at": 1700000000}"#
+            .parse()
+            .unwrap();
+        let expiry = SystemTime::UNIX_EPOCH + Duration::from_secs(1700000000);
+        assert!(!ticket.is_expired_at(expiry - Duration::from_secs(1)));
+        assert!(ticket.is_expired_at(expiry));
+        assert!(ticket.is_expired_at(SystemTime::now()));
+
+        let ticket: EnrollmentTicket = "abcd1234".parse().unwrap();
+        assert!(!ticket.is_expired_at(SystemTime::now()));
+    }
+}
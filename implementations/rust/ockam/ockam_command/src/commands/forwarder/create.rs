@@ -1,19 +1,26 @@
+use std::fmt::Display;
+use std::time::Duration;
+
 use anyhow::{anyhow, Context as _};
 use clap::Args;
 use ockam::identity::IdentityIdentifier;
-use ockam::{Context, TcpTransport};
-use ockam_api::is_local_node;
+use ockam::{Context, TcpTransport, TCP};
 use ockam_api::nodes::models::forwarder::{CreateForwarder, ForwarderInfo};
+use ockam_api::{is_local_node, multiaddr_to_route, DefaultAddress};
 use ockam_core::api::Request;
+use ockam_core::Address;
 use ockam_multiaddr::proto::Project;
 use ockam_multiaddr::{MultiAddr, Protocol};
 use rand::prelude::random;
 
 use crate::commands::forwarder::HELP_DETAIL;
 use crate::util::output::Output;
-use crate::util::{extract_address_value, node_rpc, process_multi_addr, RpcBuilder};
+use crate::util::{exitcode, extract_address_value, node_rpc, process_multi_addr, RpcBuilder};
 use crate::{help, CommandGlobalOpts, Result};
 
+/// Maximum time given to the `--at` route to accept a connection
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Create Forwarders
 #[derive(Clone, Debug, Args)]
 #[command(
@@ -36,6 +43,10 @@ pub struct CreateCommand {
     /// Authorized identity for secure channel connection (optional)
     #[arg(long, id = "AUTHORIZED", display_order = 900)]
     authorized: Option<IdentityIdentifier>,
+
+    /// Don't re-create the forwarder when its secure channel to the remote node is lost
+    #[arg(long, display_order = 900)]
+    no_reconnect: bool,
 }
 
 impl CreateCommand {
@@ -50,10 +61,15 @@ async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, CreateCommand)) -> R
     let at_rust_node = is_local_node(&cmd.at).context("Argument --at is not valid")?;
 
     let ma = process_multi_addr(&cmd.at, &opts.state)?;
+    if !cmd.at.matches(0, &[Project::CODE.into()]) {
+        validate_at_route(&tcp, &cmd.at, &ma, at_rust_node).await?;
+    }
 
     let req = {
         let alias = if at_rust_node {
-            format!("forward_to_{}", cmd.forwarder_name)
+            Address::prefixed(DefaultAddress::FORWARDER_PREFIX, &cmd.forwarder_name)
+                .address()
+                .to_string()
         } else {
             cmd.forwarder_name.clone()
         };
@@ -64,7 +80,8 @@ async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, CreateCommand)) -> R
             CreateForwarder::at_project(ma, Some(alias))
         } else {
             CreateForwarder::at_node(ma, Some(alias), at_rust_node, cmd.authorized)
-        };
+        }
+        .with_reconnect(!cmd.no_reconnect);
         Request::post("/node/forwarder").body(body)
     };
 
@@ -75,6 +92,49 @@ async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, CreateCommand)) -> R
     Ok(())
 }
 
+/// Check that the `--at` route can be used before asking the node to create the forwarder.
+///
+/// Routes to remote nodes are probed by connecting to their first TCP hop from here, so
+/// that an unreachable node is reported right away rather than by the node manager.
+async fn validate_at_route(
+    tcp: &TcpTransport,
+    at: &MultiAddr,
+    ma: &MultiAddr,
+    at_rust_node: bool,
+) -> Result<()> {
+    let route = multiaddr_to_route(ma).ok_or_else(|| {
+        crate::Error::new(
+            exitcode::USAGE,
+            anyhow!("cannot reach --at route {at}: it can't be converted to a route"),
+        )
+    })?;
+    if at_rust_node {
+        return Ok(());
+    }
+    let peer = match route.next() {
+        Ok(addr) if addr.transport_type() == TCP => addr.address().to_string(),
+        _ => return Ok(()),
+    };
+    match tokio::time::timeout(PROBE_TIMEOUT, tcp.connect(&peer)).await {
+        Ok(Ok(_)) => {
+            let _ = tcp.disconnect(&peer).await;
+            Ok(())
+        }
+        Ok(Err(e)) => Err(cannot_reach(at, e)),
+        Err(_) => Err(cannot_reach(
+            at,
+            format!("no answer after {}s", PROBE_TIMEOUT.as_secs()),
+        )),
+    }
+}
+
+fn cannot_reach(at: &MultiAddr, reason: impl Display) -> crate::Error {
+    crate::Error::new(
+        exitcode::UNAVAILABLE,
+        anyhow!("cannot reach --at route {at}: {reason}"),
+    )
+}
+
 impl Output for ForwarderInfo<'_> {
     fn output(&self) -> anyhow::Result<String> {
         Ok(format!("/service/{}", self.remote_address()))
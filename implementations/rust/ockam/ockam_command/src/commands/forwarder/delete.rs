@@ -0,0 +1,50 @@
+use anyhow::anyhow;
+use clap::Args;
+use ockam_core::api::Status;
+
+use crate::commands::node::NodeOpts;
+use crate::util::{api, extract_address_value, node_rpc, Rpc};
+use crate::{exitcode, CommandGlobalOpts};
+
+/// Delete a Forwarder
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true)]
+pub struct DeleteCommand {
+    #[command(flatten)]
+    node_opts: NodeOpts,
+
+    /// Name of the forwarder
+    pub forwarder_name: String,
+}
+
+impl DeleteCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    ctx: ockam::Context,
+    (opts, cmd): (CommandGlobalOpts, DeleteCommand),
+) -> crate::Result<()> {
+    let node_name = extract_address_value(&cmd.node_opts.api_node)?;
+
+    let mut rpc = Rpc::background(&ctx, &opts, &node_name)?;
+    rpc.request(api::delete_forwarder(&cmd.forwarder_name))
+        .await?;
+    let (res, _) = rpc.check_response()?;
+    if res.status() == Some(Status::NotFound) {
+        return Err(crate::Error::new(
+            exitcode::UNAVAILABLE,
+            anyhow!(
+                "No forwarder named `{}` at node {}",
+                cmd.forwarder_name,
+                node_name
+            ),
+        ));
+    }
+    rpc.is_ok()?;
+
+    println!("Forwarder `{}` successfully deleted", cmd.forwarder_name);
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use core::fmt::Write;
+
+use clap::Args;
+use ockam_api::nodes::models::forwarder::ForwarderInfo;
+
+use crate::commands::node::NodeOpts;
+use crate::util::output::Output;
+use crate::util::{api, extract_address_value, node_rpc, Rpc};
+use crate::CommandGlobalOpts;
+
+/// List Forwarders
+#[derive(Clone, Debug, Args)]
+pub struct ListCommand {
+    #[command(flatten)]
+    node_opts: NodeOpts,
+}
+
+impl ListCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    ctx: ockam::Context,
+    (opts, cmd): (CommandGlobalOpts, ListCommand),
+) -> crate::Result<()> {
+    let node_name = extract_address_value(&cmd.node_opts.api_node)?;
+    let mut rpc = Rpc::background(&ctx, &opts, &node_name)?;
+    rpc.request(api::list_forwarders()).await?;
+    rpc.parse_and_print_response::<Vec<ForwarderInfo>>()?;
+    Ok(())
+}
+
+impl Output for Vec<ForwarderInfo<'_>> {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.is_empty() {
+            return Ok("No forwarders found".to_string());
+        }
+        let mut w = String::new();
+        for (idx, f) in self.iter().enumerate() {
+            if idx > 0 {
+                writeln!(w)?;
+            }
+            writeln!(w, "Forwarder {}:", f.alias().unwrap_or_default())?;
+            writeln!(w, "  Remote Address: {}", f.output()?)?;
+            writeln!(w, "  At: {}", f.at().unwrap_or_default())?;
+            write!(
+                w,
+                "  At Project: {}",
+                if f.at_project() { "yes" } else { "no" }
+            )?;
+        }
+        Ok(w)
+    }
+}
@@ -1,9 +1,9 @@
 use clap::Args;
 use ockam::Context;
 use ockam_api::cli_state::{self, VaultConfig};
-use ockam_identity::Identity;
 use rand::prelude::random;
 
+use crate::commands::identity::{create_identity, KeyType};
 use crate::util::node_rpc;
 use crate::CommandGlobalOpts;
 
@@ -15,6 +15,11 @@ pub struct CreateCommand {
     /// Vault name to store the identity key
     #[arg(long)]
     vault: Option<String>,
+
+    /// Type of the identity key.
+    /// Defaults to P-256 for AWS KMS vaults, and to Ed25519 otherwise
+    #[arg(long, value_enum)]
+    key_type: Option<KeyType>,
 }
 
 impl CreateCommand {
@@ -42,13 +47,7 @@ async fn run_impl(
     } else {
         options.state.vaults.default()?.config
     };
-    let vault = vault_config.get().await?;
-    let identity = Identity::create_ext(
-        &ctx,
-        &options.state.identities.authenticated_storage().await?,
-        &vault,
-    )
-    .await?;
+    let identity = create_identity(&ctx, &options, &vault_config, cmd.key_type).await?;
     let identity_config = cli_state::IdentityConfig::new(&identity).await;
     options
         .state
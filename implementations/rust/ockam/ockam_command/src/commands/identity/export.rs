@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use anyhow::Context as _;
+use clap::Args;
+use colorful::Colorful;
+use ockam::Context;
+use ockam_core::vault::{AsymmetricVault, Secret, SecretAttributes, SecretVault};
+use ockam_identity::IdentityIdentifier;
+use serde::{Deserialize, Serialize};
+
+use crate::util::node_rpc;
+use crate::CommandGlobalOpts;
+
+#[derive(Clone, Debug, Args)]
+pub struct ExportCommand {
+    /// Name of the identity to export
+    name: String,
+
+    /// Path of the file to write the identity to
+    #[arg(long, value_name = "PATH")]
+    file: PathBuf,
+
+    /// Also export the identity's secret key. Anyone with access to the
+    /// exported file will be able to impersonate the identity
+    #[arg(long)]
+    include_secret: bool,
+
+    /// Vault holding the identity key, defaults to the default vault
+    #[arg(long, requires = "include_secret")]
+    vault: Option<String>,
+}
+
+/// Portable description of an identity, as written to the export file
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ExportedIdentity {
+    pub(crate) identifier: IdentityIdentifier,
+    /// Hex encoded change history
+    pub(crate) change_history: String,
+    pub(crate) created_at: Option<SystemTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) secret: Option<ExportedSecret>,
+}
+
+/// The identity's current root key
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ExportedSecret {
+    pub(crate) attributes: SecretAttributes,
+    pub(crate) secret: Secret,
+}
+
+impl ExportCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    _ctx: Context,
+    (options, cmd): (CommandGlobalOpts, ExportCommand),
+) -> crate::Result<()> {
+    let state = options.state.identities.get(&cmd.name)?;
+    let change_history = &state.config.change_history;
+
+    let secret = if cmd.include_secret {
+        let vault_config = if let Some(vault_name) = &cmd.vault {
+            options.state.vaults.get(vault_name)?.config
+        } else {
+            options.state.vaults.default()?.config
+        };
+        let vault = vault_config.get().await?;
+        let public_key = change_history.get_root_public_key()?;
+        let key_id = vault.compute_key_id_for_public_key(&public_key).await?;
+        let attributes = vault.secret_attributes_get(&key_id).await?;
+        let secret = vault.secret_export(&key_id).await?;
+        eprintln!(
+            "{}",
+            "Warning: the exported file contains the identity's secret key, keep it safe".yellow()
+        );
+        Some(ExportedSecret { attributes, secret })
+    } else {
+        None
+    };
+
+    let exported = ExportedIdentity {
+        identifier: state.config.identifier.clone(),
+        change_history: hex::encode(change_history.export()?),
+        created_at: state.config.created_at,
+        secret,
+    };
+    std::fs::write(&cmd.file, serde_json::to_string_pretty(&exported)?)
+        .with_context(|| format!("Failed to write {}", cmd.file.display()))?;
+    println!(
+        "Identity {} exported to {}",
+        exported.identifier,
+        cmd.file.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context as _};
+use clap::Args;
+use ockam::Context;
+use ockam_api::cli_state::IdentityConfig;
+use ockam_core::vault::{AsymmetricVault, SecretVault};
+use ockam_identity::change_history::IdentityChangeHistory;
+use rand::prelude::random;
+
+use crate::commands::identity::export::ExportedIdentity;
+use crate::util::node_rpc;
+use crate::{exitcode, CommandGlobalOpts};
+
+#[derive(Clone, Debug, Args)]
+pub struct ImportCommand {
+    /// Path of a file written by `ockam identity export`
+    file: PathBuf,
+
+    /// Name of the imported identity
+    #[arg(long, hide_default_value = true, default_value_t = hex::encode(&random::<[u8;4]>()))]
+    name: String,
+
+    /// Vault to store the identity key in, defaults to the default vault.
+    /// If the file has no secret key, the vault must already hold it
+    #[arg(long)]
+    vault: Option<String>,
+}
+
+impl ImportCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    _ctx: Context,
+    (options, cmd): (CommandGlobalOpts, ImportCommand),
+) -> crate::Result<()> {
+    let contents = std::fs::read_to_string(&cmd.file)
+        .with_context(|| format!("Failed to read {}", cmd.file.display()))?;
+    let exported: ExportedIdentity = serde_json::from_str(&contents)
+        .with_context(|| format!("Invalid identity file {}", cmd.file.display()))?;
+    let change_history = hex::decode(&exported.change_history)
+        .context("Invalid change history encoding")
+        .and_then(|data| IdentityChangeHistory::import(&data).map_err(|e| anyhow!(e)))
+        .map_err(|e| crate::Error::new(exitcode::DATAERR, e))?;
+
+    let vault_config = if let Some(vault_name) = &cmd.vault {
+        options.state.vaults.get(vault_name)?.config
+    } else {
+        options.state.vaults.default()?.config
+    };
+    let vault = vault_config.get().await?;
+
+    if !change_history.verify_all_existing_changes(&vault).await? {
+        return Err(crate::Error::new(
+            exitcode::DATAERR,
+            anyhow!("The identity change history can't be verified"),
+        ));
+    }
+    let identifier = change_history.compute_identity_id(&vault).await?;
+    if identifier != exported.identifier {
+        return Err(crate::Error::new(
+            exitcode::DATAERR,
+            anyhow!(
+                "The change history belongs to identity {identifier}, not {}",
+                exported.identifier
+            ),
+        ));
+    }
+
+    match exported.secret {
+        Some(s) => {
+            vault.secret_import(s.secret, s.attributes).await?;
+        }
+        None => {
+            let public_key = change_history.get_root_public_key()?;
+            let key_id = vault.compute_key_id_for_public_key(&public_key).await?;
+            if vault.secret_attributes_get(&key_id).await.is_err() {
+                return Err(crate::Error::new(
+                    exitcode::DATAERR,
+                    anyhow!(
+                        "The vault doesn't hold the secret key of identity {identifier}. \
+                        Export the identity with `--include-secret` or import it into the vault it was created with"
+                    ),
+                ));
+            }
+        }
+    }
+
+    let config = IdentityConfig {
+        identifier,
+        change_history,
+        enrollment_status: None,
+        created_at: exported.created_at,
+    };
+    options.state.identities.create(&cmd.name, config)?;
+    println!("Identity imported: {}", exported.identifier);
+    Ok(())
+}
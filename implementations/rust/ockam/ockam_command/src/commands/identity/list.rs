@@ -45,7 +45,7 @@ fn run_impl(opts: CommandGlobalOpts, cmd: ListCommand) -> crate::Result<()> {
             println!("{:2}{}", "", &output.output()?);
         } else {
             let output = ShortIdentityResponse::new(state.config.identifier.to_string());
-            println!("{:2}Identifier: {}", "", &output.output()?);
+            println!("{:2}{}", "", &output.output()?);
         };
         if idx < idts.len() - 1 {
             println!();
@@ -1,16 +1,29 @@
 mod create;
 mod default;
 mod delete;
+mod export;
+mod import;
 mod list;
 mod show;
 
-use clap::{Args, Subcommand};
+use anyhow::anyhow;
+use clap::{Args, Subcommand, ValueEnum};
 pub(crate) use create::CreateCommand;
 pub(crate) use delete::DeleteCommand;
+pub(crate) use export::ExportCommand;
+pub(crate) use import::ImportCommand;
 pub(crate) use list::ListCommand;
 pub(crate) use show::ShowCommand;
 
+use ockam::Context;
+use ockam_api::cli_state::VaultConfig;
+use ockam_api::lmdb::LmdbStorage;
+use ockam_core::vault::{Secret, SecretAttributes, SecretPersistence, SecretType, SecretVault};
+use ockam_identity::{Identity, IdentityStateConst, KeyAttributes};
+use ockam_vault::Vault;
+
 use crate::commands::identity::default::DefaultCommand;
+use crate::util::exitcode;
 use crate::CommandGlobalOpts;
 
 /// Manage Identities
@@ -32,6 +45,10 @@ pub enum IdentitySubcommand {
     Default(DefaultCommand),
     /// Delete an identity
     Delete(DeleteCommand),
+    /// Export an identity to a file
+    Export(ExportCommand),
+    /// Import an identity from a file written by `ockam identity export`
+    Import(ImportCommand),
 }
 
 impl IdentityCommand {
@@ -42,6 +59,84 @@ impl IdentityCommand {
             IdentitySubcommand::List(c) => c.run(options),
             IdentitySubcommand::Delete(c) => c.run(options),
             IdentitySubcommand::Default(c) => c.run(options),
+            IdentitySubcommand::Export(c) => c.run(options),
+            IdentitySubcommand::Import(c) => c.run(options),
+        }
+    }
+}
+
+/// Type of the root key of a new identity
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum KeyType {
+    #[default]
+    Ed25519,
+    P256,
+}
+
+impl From<KeyType> for SecretType {
+    fn from(k: KeyType) -> Self {
+        match k {
+            KeyType::Ed25519 => SecretType::Ed25519,
+            KeyType::P256 => SecretType::NistP256,
         }
     }
 }
+
+/// Create an identity whose root key is stored in the given vault.
+///
+/// AWS KMS vaults only hold P-256 keys, so that's the default key type of their
+/// identities. If such a vault was created with an existing KMS key, that key
+/// is used as the root key.
+pub async fn create_identity(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    vault_config: &VaultConfig,
+    key_type: Option<KeyType>,
+) -> crate::Result<Identity<Vault, LmdbStorage>> {
+    let vault = vault_config.get().await?;
+    let key_type = if vault_config.is_aws() {
+        if key_type == Some(KeyType::Ed25519) {
+            return Err(crate::Error::new(
+                exitcode::USAGE,
+                anyhow!("AWS KMS vaults only support P-256 identity keys"),
+            ));
+        }
+        if let Some(key_id) = vault_config.aws_key_id() {
+            return create_identity_with_kms_key(ctx, opts, &vault, key_id).await;
+        }
+        KeyType::P256
+    } else {
+        key_type.unwrap_or_default()
+    };
+    let identity = Identity::create_with_key_type_ext(
+        ctx,
+        &opts.state.identities.authenticated_storage().await?,
+        &vault,
+        key_type.into(),
+    )
+    .await?;
+    Ok(identity)
+}
+
+/// Create an identity whose root key is an existing AWS KMS key
+pub async fn create_identity_with_kms_key(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    vault: &Vault,
+    key_id: &str,
+) -> crate::Result<Identity<Vault, LmdbStorage>> {
+    let attrs = SecretAttributes::new(SecretType::NistP256, SecretPersistence::Persistent, 32);
+    let kid = vault
+        .secret_import(Secret::Aws(key_id.to_string()), attrs)
+        .await?;
+    let attrs = KeyAttributes::new(IdentityStateConst::ROOT_LABEL.to_string(), attrs);
+    let identity = Identity::create_with_external_key_ext(
+        ctx,
+        &opts.state.identities.authenticated_storage().await?,
+        vault,
+        &kid,
+        attrs,
+    )
+    .await?;
+    Ok(identity)
+}
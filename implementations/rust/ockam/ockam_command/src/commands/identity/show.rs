@@ -1,13 +1,15 @@
 use core::fmt::Write;
 
 use anyhow::anyhow;
+use base64::Engine;
 use clap::{Args, ValueEnum};
 use ockam_api::cli_state::CliState;
 use ockam_api::nodes::models::identity::{LongIdentityResponse, ShortIdentityResponse};
 use ockam_identity::change_history::IdentityChangeHistory;
+use serde::Serialize;
 
 use crate::util::output::Output;
-use crate::util::print_output;
+use crate::util::{fmt_timestamp, print_output};
 use crate::CommandGlobalOpts;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -29,6 +31,14 @@ pub struct ShowCommand {
     //      for `full` (change history) identity.
     #[arg(long, value_enum, requires = "full")]
     encoding: Option<Encoding>,
+
+    /// Print the encoded change history of the identity, in hex
+    #[arg(long, conflicts_with = "full")]
+    change_history: bool,
+
+    /// Print the change history in base64 instead of hex
+    #[arg(long, requires = "change_history")]
+    base64: bool,
 }
 
 impl ShowCommand {
@@ -47,16 +57,29 @@ fn run_impl(opts: CommandGlobalOpts, cmd: ShowCommand) -> crate::Result<()> {
         );
     }
     let state = opts.state.identities.get(&cmd.name)?;
-    if cmd.full {
+    if cmd.change_history {
+        let change_history = state.config.change_history.export()?;
+        let output = ChangeHistoryOutput::new(&change_history, cmd.base64);
+        print_output(output, &opts.global_args.output_format)?;
+    } else if cmd.full {
         let identity = state.config.change_history.export()?;
         if Some(Encoding::Hex) == cmd.encoding {
             print_output(identity, &opts.global_args.output_format)?;
         } else {
-            let output = LongIdentityResponse::new(identity);
+            let output =
+                LongIdentityResponse::new(identity).with_created_at(state.config.created_at_secs());
             print_output(output, &opts.global_args.output_format)?;
         }
     } else {
-        let output = ShortIdentityResponse::new(state.config.identifier.to_string());
+        let history = &state.config.change_history;
+        let key_type = history
+            .get_root_public_key()
+            .ok()
+            .map(|k| format!("{:?}", k.stype()));
+        let output = ShortIdentityResponse::new(state.config.identifier.to_string())
+            .with_key_type(key_type)
+            .with_change_history_len(history.as_ref().len() as u32)
+            .with_created_at(state.config.created_at_secs());
         print_output(output, &opts.global_args.output_format)?;
     }
     Ok(())
@@ -66,25 +89,115 @@ impl Output for LongIdentityResponse<'_> {
     fn output(&self) -> anyhow::Result<String> {
         let mut w = String::new();
         let id: IdentityChangeHistory = serde_bare::from_slice(self.identity.0.as_ref())?;
+        if let Ok(key) = id.get_root_public_key() {
+            writeln!(w, "Key Type: {:?}", key.stype())?;
+        }
+        if let Some(created_at) = self.created_at {
+            writeln!(w, "Created At: {}", fmt_timestamp(created_at))?;
+        }
         write!(w, "{id}")?;
         Ok(w)
     }
 }
 
+/// The encoded change history of an identity.
+///
+/// It is always hex encoded in JSON, and wrapped on several lines
+/// in plain output, as long histories make for very long lines.
+#[derive(Serialize)]
+struct ChangeHistoryOutput {
+    change_history: String,
+    #[serde(skip)]
+    plain: String,
+}
+
+impl ChangeHistoryOutput {
+    /// Number of characters per line of plain output
+    const LINE_WIDTH: usize = 64;
+
+    fn new(change_history: &[u8], base64: bool) -> Self {
+        let hex = hex::encode(change_history);
+        let plain = if base64 {
+            base64::engine::general_purpose::STANDARD.encode(change_history)
+        } else {
+            hex.clone()
+        };
+        Self {
+            change_history: hex,
+            plain,
+        }
+    }
+}
+
+impl Output for ChangeHistoryOutput {
+    fn output(&self) -> anyhow::Result<String> {
+        // Both encodings are ASCII, so chunks are valid UTF-8
+        let lines = self
+            .plain
+            .as_bytes()
+            .chunks(Self::LINE_WIDTH)
+            .map(core::str::from_utf8)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(lines.join("\n"))
+    }
+}
+
 impl Output for ShortIdentityResponse<'_> {
     fn output(&self) -> anyhow::Result<String> {
         let mut w = String::new();
-        write!(w, "{}", self.identity_id)?;
-        Ok(w)
+        writeln!(w, "Identifier: {}", self.identity_id)?;
+        if let Some(key_type) = &self.key_type {
+            writeln!(w, "Key Type: {key_type}")?;
+        }
+        if let Some(len) = self.change_history_len {
+            writeln!(w, "Changes: {len}")?;
+        }
+        if let Some(created_at) = self.created_at {
+            writeln!(w, "Created At: {}", fmt_timestamp(created_at))?;
+        }
+        Ok(w.trim_end().to_string())
     }
 }
 
 fn default_identity_name() -> String {
-    let state = CliState::new().expect("Failed to load CLI state. Try running 'ockam reset'");
-    state
-        .identities
-        .default()
+    CliState::new()
+        .ok()
+        .and_then(|s| s.identities.default().ok())
         .map(|i| i.name)
         // Return empty string so we can return a proper error message from the command
-        .unwrap_or_else(|_| "".to_string())
+        .unwrap_or_else(|| "".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn change_history_output() {
+        let change_history: Vec<u8> = (0..=255).collect();
+
+        let output = ChangeHistoryOutput::new(&change_history, false);
+        let plain = output.output().unwrap();
+        assert!(plain
+            .lines()
+            .all(|l| l.len() <= ChangeHistoryOutput::LINE_WIDTH));
+        assert_eq!(
+            change_history,
+            hex::decode(plain.replace('\n', "")).unwrap()
+        );
+        let json = serde_json::to_value(output).unwrap();
+        assert_eq!(
+            serde_json::json!({ "change_history": hex::encode(&change_history) }),
+            json
+        );
+
+        let plain = ChangeHistoryOutput::new(&change_history, true)
+            .output()
+            .unwrap();
+        assert!(plain.lines().count() > 1);
+        let decoded = base64::engine::general_purpose::STANDARD
+            .decode(plain.replace('\n', ""))
+            .unwrap();
+        assert_eq!(change_history, decoded);
+    }
 }
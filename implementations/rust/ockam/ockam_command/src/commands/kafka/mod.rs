@@ -0,0 +1,248 @@
+use std::net::SocketAddrV4;
+
+use anyhow::anyhow;
+use clap::{Args, Subcommand};
+use ockam::{Context, TcpTransport};
+use ockam_api::nodes::models::services::{
+    StartKafkaConsumerRequest, StartKafkaProducerRequest, StartServiceRequest,
+};
+use ockam_api::port_range::PortRange;
+use ockam_api::DefaultAddress;
+use ockam_core::api::Request;
+use ockam_multiaddr::MultiAddr;
+use serde::Serialize;
+
+use crate::commands::node::NodeOpts;
+use crate::util::output::Output;
+use crate::util::{exitcode, extract_address_value, node_rpc, process_multi_addr, RpcBuilder};
+use crate::{help, CommandGlobalOpts};
+
+const HELP_DETAIL: &str = "";
+
+/// Number of ports reserved for the brokers when `--brokers-port-range` is not set
+const DEFAULT_BROKERS_PORT_COUNT: u16 = 100;
+
+/// Options shared by the Kafka consumer and producer commands
+#[derive(Clone, Debug, Args)]
+pub struct KafkaServiceOpts {
+    #[command(flatten)]
+    pub node_opts: NodeOpts,
+
+    /// Local address the Kafka clients connect to, to reach the bootstrap server
+    #[arg(long, value_name = "SOCKET_ADDRESS", default_value = "127.0.0.1:4000")]
+    pub bootstrap_server: SocketAddrV4,
+
+    /// Local ports used for the Kafka brokers, e.g. `4001-4100`.
+    /// Defaults to the 100 ports following the bootstrap server port
+    #[arg(long, value_name = "START-END")]
+    pub brokers_port_range: Option<String>,
+
+    /// Route to the node forwarding the Kafka traffic to the brokers
+    #[arg(long, value_name = "ROUTE")]
+    pub to: MultiAddr,
+}
+
+impl KafkaServiceOpts {
+    /// The brokers port range, which must not contain the bootstrap server port
+    fn brokers_port_range(&self) -> crate::Result<PortRange> {
+        let bootstrap_port = self.bootstrap_server.port();
+        let range = match &self.brokers_port_range {
+            Some(range) => PortRange::try_from(range.as_str()).map_err(|e| {
+                crate::Error::new(
+                    exitcode::USAGE,
+                    anyhow!("Invalid --brokers-port-range `{range}`: {e}"),
+                )
+            })?,
+            None => {
+                let start = bootstrap_port.checked_add(1);
+                let end = start.and_then(|p| p.checked_add(DEFAULT_BROKERS_PORT_COUNT - 1));
+                match (start, end) {
+                    (Some(start), Some(end)) => PortRange::new(start, end)?,
+                    _ => {
+                        return Err(crate::Error::new(
+                            exitcode::USAGE,
+                            anyhow!(
+                                "No default brokers port range after port {bootstrap_port}, \
+                                 please set --brokers-port-range"
+                            ),
+                        ))
+                    }
+                }
+            }
+        };
+        if (range.start()..=range.end()).contains(&bootstrap_port) {
+            return Err(crate::Error::new(
+                exitcode::USAGE,
+                anyhow!(
+                    "The brokers port range {range} contains the bootstrap server port \
+                     {bootstrap_port}"
+                ),
+            ));
+        }
+        Ok(range)
+    }
+}
+
+/// The Kafka services a node can run, managed by the `kafka-consumer`
+/// and `kafka-producer` commands
+#[derive(Clone, Copy, Debug)]
+pub enum KafkaServiceKind {
+    Consumer,
+    Producer,
+}
+
+impl KafkaServiceKind {
+    fn name(&self) -> &'static str {
+        match self {
+            KafkaServiceKind::Consumer => "consumer",
+            KafkaServiceKind::Producer => "producer",
+        }
+    }
+
+    fn default_address(&self) -> &'static str {
+        match self {
+            KafkaServiceKind::Consumer => DefaultAddress::KAFKA_CONSUMER,
+            KafkaServiceKind::Producer => DefaultAddress::KAFKA_PRODUCER,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    after_long_help = help::template(HELP_DETAIL)
+)]
+pub struct KafkaCommand {
+    #[command(subcommand)]
+    subcommand: KafkaSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum KafkaSubcommand {
+    /// Start the Kafka service on a node
+    Create(CreateCommand),
+}
+
+impl KafkaCommand {
+    pub fn run(self, options: CommandGlobalOpts, kind: KafkaServiceKind) {
+        match self.subcommand {
+            KafkaSubcommand::Create(c) => c.run(options, kind),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Args)]
+pub struct CreateCommand {
+    /// Address of the Kafka service on the node.
+    /// Defaults to `kafka_consumer` or `kafka_producer`
+    #[arg(long)]
+    addr: Option<String>,
+
+    #[command(flatten)]
+    kafka: KafkaServiceOpts,
+}
+
+impl CreateCommand {
+    pub fn run(self, options: CommandGlobalOpts, kind: KafkaServiceKind) {
+        node_rpc(rpc, (options, self, kind));
+    }
+}
+
+/// A started Kafka consumer or producer
+#[derive(Debug, Serialize)]
+pub struct KafkaServiceOutput {
+    #[serde(skip)]
+    kind: &'static str,
+    pub address: String,
+    pub bootstrap_server: String,
+    pub brokers_port_range: String,
+    pub to: String,
+}
+
+impl Output for KafkaServiceOutput {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!(
+            "Kafka {} started at /service/{}\n\
+             Point your Kafka clients at the bootstrap server {}",
+            self.kind, self.address, self.bootstrap_server
+        ))
+    }
+}
+
+/// Start a Kafka service on the node and print the bootstrap server address
+async fn rpc(
+    ctx: Context,
+    (opts, cmd, kind): (CommandGlobalOpts, CreateCommand, KafkaServiceKind),
+) -> crate::Result<()> {
+    let kafka = &cmd.kafka;
+    let addr = cmd
+        .addr
+        .unwrap_or_else(|| kind.default_address().to_string());
+    let port_range = kafka.brokers_port_range()?;
+    let to = process_multi_addr(&kafka.to, &opts.state)?;
+    let node = extract_address_value(&kafka.node_opts.api_node)?;
+
+    let tcp = TcpTransport::create(&ctx).await?;
+    let mut rpc = RpcBuilder::new(&ctx, &opts, &node).tcp(&tcp)?.build();
+    let (ip, port) = (*kafka.bootstrap_server.ip(), kafka.bootstrap_server.port());
+    match kind {
+        KafkaServiceKind::Consumer => {
+            let payload = StartKafkaConsumerRequest::new(ip, port, port_range, to.clone());
+            let payload = StartServiceRequest::new(payload, &addr);
+            rpc.request(Request::post("/node/services/kafka_consumer").body(payload))
+                .await?
+        }
+        KafkaServiceKind::Producer => {
+            let payload = StartKafkaProducerRequest::new(ip, port, port_range, to.clone());
+            let payload = StartServiceRequest::new(payload, &addr);
+            rpc.request(Request::post("/node/services/kafka_producer").body(payload))
+                .await?
+        }
+    }
+    rpc.is_ok()?;
+
+    rpc.print_response(KafkaServiceOutput {
+        kind: kind.name(),
+        address: addr,
+        bootstrap_server: kafka.bootstrap_server.to_string(),
+        brokers_port_range: port_range.to_string(),
+        to: to.to_string(),
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opts(bootstrap_server: &str, brokers_port_range: Option<&str>) -> KafkaServiceOpts {
+        KafkaServiceOpts {
+            node_opts: NodeOpts {
+                api_node: "n1".to_string(),
+            },
+            bootstrap_server: bootstrap_server.parse().unwrap(),
+            brokers_port_range: brokers_port_range.map(|r| r.to_string()),
+            to: "/service/forward_to_kafka".parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn brokers_port_range() {
+        let range = opts("127.0.0.1:4000", None).brokers_port_range().unwrap();
+        assert_eq!((range.start(), range.end()), (4001, 4100));
+
+        let range = opts("127.0.0.1:4000", Some("5000-5010"))
+            .brokers_port_range()
+            .unwrap();
+        assert_eq!((range.start(), range.end()), (5000, 5010));
+
+        // Invalid ranges
+        for range in ["5010-5000", "5000", "a-b", "3990-4010"] {
+            assert!(opts("127.0.0.1:4000", Some(range))
+                .brokers_port_range()
+                .is_err());
+        }
+        assert!(opts("127.0.0.1:65500", None).brokers_port_range().is_err());
+    }
+}
@@ -1,8 +1,10 @@
 use std::str::FromStr;
+use std::time::Duration;
 
 use clap::Args;
+use colorful::Colorful;
 use ockam::Context;
-use ockam_api::cloud::lease_manager::models::influxdb::Token;
+use ockam_api::cloud::lease_manager::models::influxdb::{CreateTokenRequest, Token};
 use ockam_core::api::Request;
 use ockam_multiaddr::MultiAddr;
 use termimad::minimad::TextTemplate;
@@ -10,8 +12,8 @@ use termimad::MadSkin;
 
 use super::TOKEN_VIEW;
 use crate::util::api::{CloudOpts, ProjectOpts};
-use crate::util::node_rpc;
 use crate::util::orchestrator_api::OrchestratorApiBuilder;
+use crate::util::{node_rpc, parse_duration};
 use crate::{help, CommandGlobalOpts};
 
 const HELP_DETAIL: &str = "";
@@ -19,17 +21,27 @@ const HELP_DETAIL: &str = "";
 /// Create a token within the lease token manager
 #[derive(Clone, Debug, Args)]
 #[command(help_template = help::template(HELP_DETAIL))]
-pub struct CreateCommand {}
+pub struct CreateCommand {
+    /// Requested lifetime of the token, such as `30m` or `2h`.
+    /// The server may grant a shorter lifetime than requested
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    expires_in: Option<Duration>,
+}
 
 impl CreateCommand {
     pub fn run(self, options: CommandGlobalOpts, cloud_opts: CloudOpts, project_opts: ProjectOpts) {
-        node_rpc(run_impl, (options, cloud_opts, project_opts));
+        node_rpc(run_impl, (options, cloud_opts, project_opts, self));
     }
 }
 
 async fn run_impl(
     ctx: Context,
-    (opts, cloud_opts, project_opts): (CommandGlobalOpts, CloudOpts, ProjectOpts),
+    (opts, cloud_opts, project_opts, cmd): (
+        CommandGlobalOpts,
+        CloudOpts,
+        ProjectOpts,
+        CreateCommand,
+    ),
 ) -> crate::Result<()> {
     let mut orchestrator_client = OrchestratorApiBuilder::new(&ctx, &opts, &project_opts)
         .as_identity(cloud_opts.identity.clone())
@@ -38,10 +50,22 @@ async fn run_impl(
         .build(&MultiAddr::from_str("/service/influxdb_token_lease")?)
         .await?;
 
-    let req = Request::post("/");
+    let req = Request::post("/").body(CreateTokenRequest::new(cmd.expires_in));
 
     let resp_token: Token = orchestrator_client.request_with_response(req).await?;
 
+    if let (Some(requested), Some(granted)) = (cmd.expires_in, resp_token.expires_in) {
+        if granted < requested.as_secs() {
+            let message = format!(
+                "The requested lifetime of {}s exceeds the maximum allowed by the server, \
+                 the token expires in {}s instead",
+                requested.as_secs(),
+                granted
+            );
+            eprintln!("{}", message.yellow());
+        }
+    }
+
     let token_template = TextTemplate::from(TOKEN_VIEW);
     let mut expander = token_template.expander();
     expander
@@ -67,6 +67,7 @@ async fn run_impl(
              expires,
              token,
              status,
+             ..
          }| {
             expander
                 .sub("token")
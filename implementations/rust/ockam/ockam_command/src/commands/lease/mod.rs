@@ -6,10 +6,14 @@ mod show;
 use clap::{Args, Subcommand};
 pub use create::CreateCommand;
 pub use list::ListCommand;
+use ockam_api::cloud::lease_manager::models::influxdb::Token;
 pub use show::ShowCommand;
+use termimad::minimad::TextTemplate;
+use termimad::{FmtText, MadSkin};
 
 use self::revoke::RevokeCommand;
 use crate::util::api::{CloudOpts, ProjectOpts};
+use crate::util::output::Output;
 use crate::CommandGlobalOpts;
 
 #[derive(Clone, Debug, Args)]
@@ -43,6 +47,22 @@ const TOKEN_VIEW: &str = r#"
 > **Status:** ${status}
 "#;
 
+impl Output for Token {
+    fn output(&self) -> anyhow::Result<String> {
+        let token_template = TextTemplate::from(TOKEN_VIEW);
+        let mut expander = token_template.expander();
+        expander
+            .set("id", &self.id)
+            .set("issued_for", &self.issued_for)
+            .set("created_at", &self.created_at)
+            .set("expires_at", &self.expires)
+            .set("token", &self.token)
+            .set("status", &self.status);
+        let skin = MadSkin::default();
+        Ok(FmtText::from_text(&skin, expander.expand(), None).to_string())
+    }
+}
+
 impl LeaseCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         match self.subcommand {
@@ -1,18 +1,16 @@
 use std::str::FromStr;
 
+use anyhow::anyhow;
 use clap::Args;
 use ockam::Context;
 use ockam_api::cloud::lease_manager::models::influxdb::Token;
 use ockam_core::api::Request;
 use ockam_multiaddr::MultiAddr;
-use termimad::minimad::TextTemplate;
-use termimad::MadSkin;
 
-use super::TOKEN_VIEW;
 use crate::util::api::{CloudOpts, ProjectOpts};
-use crate::util::node_rpc;
 use crate::util::orchestrator_api::OrchestratorApiBuilder;
-use crate::{help, CommandGlobalOpts};
+use crate::util::{node_rpc, print_output};
+use crate::{exitcode, help, CommandGlobalOpts, OutputFormat};
 
 const HELP_DETAIL: &str = "";
 
@@ -23,6 +21,10 @@ pub struct ShowCommand {
     /// ID of the token to retrieve
     #[arg(short, long, value_name = "TOKEN_ID")]
     pub token_id: String,
+
+    /// Only print the token value, e.g. to use it as a credential in a script
+    #[arg(long)]
+    pub raw: bool,
 }
 
 impl ShowCommand {
@@ -35,6 +37,13 @@ async fn run_impl(
     ctx: Context,
     (opts, cloud_opts, cmd, project_opts): (CommandGlobalOpts, CloudOpts, ShowCommand, ProjectOpts),
 ) -> crate::Result<()> {
+    if cmd.raw && opts.global_args.output_format != OutputFormat::Plain {
+        return Err(crate::Error::new(
+            exitcode::USAGE,
+            anyhow!("--raw can't be used together with --output"),
+        ));
+    }
+
     let mut orchestrator_client = OrchestratorApiBuilder::new(&ctx, &opts, &project_opts)
         .as_identity(cloud_opts.identity.clone())
         .with_new_embbeded_node()
@@ -46,20 +55,10 @@ async fn run_impl(
 
     let resp_token: Token = orchestrator_client.request_with_response(req).await?;
 
-    let token_template = TextTemplate::from(TOKEN_VIEW);
-    let mut expander = token_template.expander();
-
-    expander
-        .set("id", &resp_token.id)
-        .set("issued_for", &resp_token.issued_for)
-        .set("created_at", &resp_token.created_at)
-        .set("expires_at", &resp_token.expires)
-        .set("token", &resp_token.token)
-        .set("status", &resp_token.status);
-
-    let skin = MadSkin::default();
-
-    skin.print_expander(expander);
-
+    if cmd.raw {
+        println!("{}", resp_token.token);
+        return Ok(());
+    }
+    print_output(resp_token, &opts.global_args.output_format)?;
     Ok(())
 }
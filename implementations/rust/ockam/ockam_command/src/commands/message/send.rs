@@ -1,20 +1,33 @@
-use anyhow::Context as _;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Context as _};
 use clap::Args;
+use futures::StreamExt;
+use ockam::identity::IdentityIdentifier;
 use ockam::{Context, TcpTransport};
-use ockam_api::clean_multiaddr;
-use ockam_api::nodes::models::secure_channel::CredentialExchangeMode;
+use ockam_api::nodes::models::secure_channel::{
+    CreateSecureChannelRequest, CreateSecureChannelResponse, CredentialExchangeMode,
+};
 use ockam_api::nodes::service::message::SendMessage;
+use ockam_api::{clean_multiaddr, DefaultAddress};
 use ockam_core::api::{Request, RequestBuilder};
+use ockam_multiaddr::proto::Service;
 use ockam_multiaddr::MultiAddr;
+use regex::Regex;
+use serde::Serialize;
 
 use crate::commands::message::HELP_DETAIL;
 use crate::commands::node::util::{
     delete_embedded_node,
     start_embedded_node_with_vault_and_identity,
 };
+use crate::commands::project::util::delete_secure_channel;
 use crate::util::api::{CloudOpts, ProjectOpts};
-use crate::util::{extract_address_value, node_rpc, RpcBuilder};
-use crate::{help, CommandGlobalOpts, Result};
+use crate::util::{exitcode, extract_address_value, node_rpc, Rpc, RpcBuilder};
+use crate::{help, CommandGlobalOpts, OutputFormat, Result};
 
 /// Send messages
 #[derive(Clone, Debug, Args)]
@@ -25,14 +38,66 @@ pub struct SendCommand {
     from: Option<String>,
 
     /// The route to send the message to
-    #[arg(short, long, value_name = "ROUTE")]
-    pub to: MultiAddr,
+    #[arg(short, long, value_name = "ROUTE", required_unless_present = "to_all")]
+    pub to: Option<MultiAddr>,
+
+    /// Send the message once to each of the routes listed in FILE, one per line.
+    /// A failure to reach a route doesn't stop the others
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["to", "count"])]
+    pub to_all: Option<PathBuf>,
 
     /// Override Default Timeout
     #[arg(long, value_name = "TIMEOUT")]
     pub timeout: Option<u64>,
 
-    pub message: String,
+    /// The message to send, use `--body-file` instead for binary payloads
+    #[arg(required_unless_present = "body_file")]
+    pub message: Option<String>,
+
+    /// Read the message from a file, or from stdin if PATH is `-`.
+    /// The bytes are sent as they are. Can't be used with MESSAGE
+    #[arg(long, value_name = "PATH", conflicts_with = "message")]
+    pub body_file: Option<PathBuf>,
+
+    /// Number of times to send the message
+    #[arg(
+        long,
+        value_name = "COUNT",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub count: u32,
+
+    /// Milliseconds to wait between two sends when `--count` is greater than 1
+    #[arg(long, value_name = "MILLISECONDS", default_value_t = 1000)]
+    pub interval: u64,
+
+    /// Fail unless the reply matches this regular expression, after printing it.
+    /// The reply is matched as UTF-8 text, with invalid sequences of binary
+    /// replies replaced by U+FFFD
+    #[arg(
+        long,
+        value_name = "REGEX",
+        value_parser = parse_expect,
+        conflicts_with_all = ["expect_exact", "to_all"]
+    )]
+    pub expect: Option<Regex>,
+
+    /// Fail unless the reply is exactly this string, after printing it.
+    /// A binary reply never matches
+    #[arg(long, value_name = "STRING", conflicts_with = "to_all")]
+    pub expect_exact: Option<String>,
+
+    /// Send the messages through a secure channel to the last node of the
+    /// `--to` route, created with the listener of that node and deleted afterwards.
+    /// The route must end with the services to reach on that node,
+    /// e.g. `/node/n1/service/uppercase`
+    #[arg(long, conflicts_with = "to_all")]
+    pub secure_channel: bool,
+
+    /// Identifiers authorized to be presented by the node's secure channel listener
+    #[arg(long, value_name = "IDENTIFIER", requires = "secure_channel")]
+    pub authorized: Option<Vec<IdentityIdentifier>>,
 
     #[command(flatten)]
     cloud_opts: CloudOpts,
@@ -45,13 +110,40 @@ impl SendCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         node_rpc(rpc, (options, self))
     }
+
+    /// Check a reply against `--expect` or `--expect-exact`
+    fn check_reply(&self, reply: &[u8]) -> Result<()> {
+        let expected = match (&self.expect, &self.expect_exact) {
+            (Some(pattern), _) if !pattern.is_match(&String::from_utf8_lossy(reply)) => {
+                format!("the pattern `{pattern}`")
+            }
+            (None, Some(exact)) if reply != exact.as_bytes() => format!("`{exact}`"),
+            _ => return Ok(()),
+        };
+        Err(crate::Error::new(
+            exitcode::DATAERR,
+            anyhow!("The reply doesn't match {expected}"),
+        ))
+    }
+}
+
+fn parse_expect(pattern: &str) -> anyhow::Result<Regex> {
+    Regex::new(pattern).context("Invalid regular expression")
 }
 
 async fn rpc(mut ctx: Context, (opts, cmd): (CommandGlobalOpts, SendCommand)) -> Result<()> {
     async fn go(ctx: &mut Context, opts: &CommandGlobalOpts, cmd: SendCommand) -> Result<()> {
-        // Process `--to` Multiaddr
-        let (to, meta) =
-            clean_multiaddr(&cmd.to, &opts.state).context("Argument '--to' is invalid")?;
+        // Read the body and the targets first, so that an unreadable file doesn't leave an embedded node behind
+        let body = match (&cmd.message, &cmd.body_file) {
+            (_, Some(path)) => read_body(path)?,
+            (Some(message), None) => message.as_bytes().to_vec(),
+            // Prevented by clap's `required_unless_present`
+            (None, None) => unreachable!(),
+        };
+        let targets = match &cmd.to_all {
+            Some(path) => Some(read_targets(path)?),
+            None => None,
+        };
 
         // Setup environment depending on whether we are sending the message from an embedded node or a background node
         let (api_node, tcp) = if let Some(node) = &cmd.from {
@@ -69,42 +161,346 @@ async fn rpc(mut ctx: Context, (opts, cmd): (CommandGlobalOpts, SendCommand)) ->
             .await?;
             (api_node, None)
         };
+        let sender = Sender {
+            ctx,
+            opts,
+            cmd: &cmd,
+            api_node: &api_node,
+            tcp: tcp.as_ref(),
+        };
+
+        // Ctrl-C stops the sends, every record printed so far is already flushed
+        let result = tokio::select! {
+            r = async {
+                match targets {
+                    Some(targets) => sender.send_to_all(targets, &body).await,
+                    None => sender.send(&body).await,
+                }
+            } => r,
+            _ = tokio::signal::ctrl_c() => Ok(()),
+        };
+
+        // only delete node in case 'from' is empty and embedded node was started before
+        if cmd.from.is_none() {
+            delete_embedded_node(opts, &api_node).await;
+        }
+
+        result
+    }
+    go(&mut ctx, &opts, cmd).await
+}
+
+/// Maximum number of targets a message is sent to at the same time with `--to-all`
+const MAX_CONCURRENT_SENDS: usize = 8;
+
+/// Everything needed to send messages from the api node
+struct Sender<'a> {
+    ctx: &'a Context,
+    opts: &'a CommandGlobalOpts,
+    cmd: &'a SendCommand,
+    api_node: &'a str,
+    tcp: Option<&'a TcpTransport>,
+}
+
+impl<'a> Sender<'a> {
+    /// Send the message `--count` times to the `--to` route, through a
+    /// secure channel with `--secure-channel`
+    async fn send(&self, body: &[u8]) -> Result<()> {
+        // Prevented by clap's `required_unless_present`
+        let to = self.cmd.to.as_ref().expect("required by clap");
+        let to = self.resolve(to).await?;
+        if !self.cmd.secure_channel {
+            return self.send_repeatedly(&to, body).await;
+        }
+
+        // The channel is deleted whether the messages could be sent or not
+        let (channel, to) = self.create_secure_channel(&to).await?;
+        let result = self.send_repeatedly(&to, body).await;
+        let deleted =
+            delete_secure_channel(self.ctx, self.opts, self.api_node, self.tcp, &channel).await;
+        result.and(deleted)
+    }
+
+    /// Send the message `--count` times to a resolved route
+    async fn send_repeatedly(&self, to: &MultiAddr, body: &[u8]) -> Result<()> {
+        let mut rpc = self.rpc()?;
+        for i in 0..self.cmd.count {
+            if i > 0 {
+                tokio::time::sleep(Duration::from_millis(self.cmd.interval)).await;
+            }
+            let (res, latency) = send_once(&mut rpc, to, body).await?;
+            let checked = self.cmd.check_reply(&res);
+            print_response(self.opts, res, latency, self.cmd.count > 1)?;
+            checked?;
+        }
+        Ok(())
+    }
+
+    /// Send the message once to every target, a few of them at a time.
+    ///
+    /// A failing target doesn't stop the others, but makes the command fail
+    /// once all the targets have been tried.
+    async fn send_to_all(&self, targets: Vec<MultiAddr>, body: &[u8]) -> Result<()> {
+        let total = targets.len();
+        let mut records = futures::stream::iter(targets)
+            .map(|target| async move {
+                let result = async {
+                    let to = self.resolve(&target).await?;
+                    let mut rpc = self.rpc()?;
+                    send_once(&mut rpc, &to, body).await
+                }
+                .await;
+                (target, result)
+            })
+            .buffered(MAX_CONCURRENT_SENDS);
+
+        let mut failed = 0;
+        while let Some((target, result)) = records.next().await {
+            if result.is_err() {
+                failed += 1;
+            }
+            print_target_response(self.opts, &target, result)?;
+        }
+        if failed > 0 {
+            return Err(crate::Error::new(
+                exitcode::UNAVAILABLE,
+                anyhow!("Failed to send the message to {failed} of {total} targets"),
+            ));
+        }
+        Ok(())
+    }
 
-        // Replace `/project/<name>` occurrences with their respective secure channel addresses
+    /// Replace `/project/<name>` occurrences with their respective secure channel addresses
+    async fn resolve(&self, to: &MultiAddr) -> Result<MultiAddr> {
+        let (to, meta) =
+            clean_multiaddr(to, &self.opts.state).context("Argument '--to' is invalid")?;
         let projects_sc =
             crate::commands::project::util::get_projects_secure_channels_from_config_lookup(
-                ctx,
-                opts,
+                self.ctx,
+                self.opts,
                 &meta,
-                &cmd.cloud_opts.route(),
-                &api_node,
-                tcp.as_ref(),
+                &self.cmd.cloud_opts.route(),
+                self.api_node,
+                self.tcp,
                 CredentialExchangeMode::Oneway,
             )
             .await?;
-        let to = crate::commands::project::util::clean_projects_multiaddr(to, projects_sc)?;
-
-        // Send request
-        let mut rpc = RpcBuilder::new(ctx, opts, &api_node)
-            .tcp(tcp.as_ref())?
-            .build();
-        rpc.request(req(&to, &cmd.message)).await?;
-        let res = rpc.parse_response::<Vec<u8>>()?;
-        println!(
-            "{}",
-            String::from_utf8(res).context("Received content is not a valid utf8 string")?
+        Ok(crate::commands::project::util::clean_projects_multiaddr(
+            to,
+            projects_sc,
+        )?)
+    }
+
+    /// Create a secure channel to the listener of the node reached by `to`,
+    /// and return its address with the route to the services behind it
+    async fn create_secure_channel(&self, to: &MultiAddr) -> Result<(MultiAddr, MultiAddr)> {
+        let (node, services) = split_at_services(to).ok_or_else(|| {
+            crate::Error::new(
+                exitcode::USAGE,
+                anyhow!("The route `{to}` must reach a node and end with services on that node"),
+            )
+        })?;
+        let mut listener = node;
+        listener.push_back(Service::new(DefaultAddress::SECURE_CHANNEL_LISTENER))?;
+
+        let payload = CreateSecureChannelRequest::new(
+            &listener,
+            self.cmd.authorized.clone(),
+            CredentialExchangeMode::Mutual,
+            self.cmd.cloud_opts.identity.clone(),
         );
+        let mut rpc = self.rpc()?;
+        rpc.request(Request::post("/node/secure_channel").body(payload))
+            .await?;
+        let channel = rpc
+            .parse_response::<CreateSecureChannelResponse>()?
+            .addr()?;
+        let to = channel.clone().concat(&services)?;
+        Ok((channel, to))
+    }
 
-        // only delete node in case 'from' is empty and embedded node was started before
-        if cmd.from.is_none() {
-            delete_embedded_node(opts, rpc.node_name()).await;
+    fn rpc(&self) -> Result<Rpc<'a>> {
+        Ok(RpcBuilder::new(self.ctx, self.opts, self.api_node)
+            .tcp(self.tcp)?
+            .build())
+    }
+}
+
+/// Split a route before the services that end it, if it also has a node part
+fn split_at_services(to: &MultiAddr) -> Option<(MultiAddr, MultiAddr)> {
+    let at = to
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.code() != Service::CODE)
+        .last()?
+        .0;
+    let (node, services) = to.split(at + 1);
+    (!services.is_empty()).then_some((node, services))
+}
+
+/// Send the message once and wait for the response
+async fn send_once(rpc: &mut Rpc<'_>, to: &MultiAddr, body: &[u8]) -> Result<(Vec<u8>, Duration)> {
+    let started = Instant::now();
+    rpc.request(req(to, body)).await?;
+    let latency = started.elapsed();
+    let res = rpc.parse_response::<Vec<u8>>()?;
+    Ok((res, latency))
+}
+
+/// A single round-trip, printed as one line per message under `--output json`,
+/// or as one document per message under `--output yaml`
+#[derive(Serialize)]
+struct SendRecord {
+    /// Milliseconds since the UNIX epoch at which the response was received
+    timestamp: u64,
+    latency_ms: f64,
+    response_bytes: usize,
+}
+
+/// Print the response, or the metrics of the round-trip when sending several
+/// messages with a structured output format
+fn print_response(
+    opts: &CommandGlobalOpts,
+    res: Vec<u8>,
+    latency: Duration,
+    metrics: bool,
+) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    let record = || SendRecord {
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default(),
+        latency_ms: latency.as_secs_f64() * 1000.0,
+        response_bytes: res.len(),
+    };
+    match &opts.global_args.output_format {
+        OutputFormat::Json if metrics => {
+            writeln!(stdout, "{}", serde_json::to_string(&record())?)?;
         }
+        OutputFormat::Yaml if metrics => {
+            write!(stdout, "---\n{}", serde_yaml::to_string(&record())?)?;
+        }
+        _ => {
+            writeln!(
+                stdout,
+                "{}",
+                String::from_utf8(res).context("Received content is not a valid utf8 string")?
+            )?;
+        }
+    }
+    stdout.flush()?;
+    Ok(())
+}
 
-        Ok(())
+/// The outcome of sending the message to one of the `--to-all` targets
+#[derive(Serialize)]
+struct TargetRecord {
+    target: String,
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latency_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_bytes: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn print_target_response(
+    opts: &CommandGlobalOpts,
+    target: &MultiAddr,
+    result: Result<(Vec<u8>, Duration)>,
+) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    if opts.global_args.output_format == OutputFormat::Json {
+        let record = match result {
+            Ok((res, latency)) => TargetRecord {
+                target: target.to_string(),
+                success: true,
+                latency_ms: Some(latency.as_secs_f64() * 1000.0),
+                response_bytes: Some(res.len()),
+                error: None,
+            },
+            Err(e) => TargetRecord {
+                target: target.to_string(),
+                success: false,
+                latency_ms: None,
+                response_bytes: None,
+                error: Some(e.to_string()),
+            },
+        };
+        writeln!(stdout, "{}", serde_json::to_string(&record)?)?;
+    } else {
+        match result {
+            Ok((res, _)) => writeln!(stdout, "{target}: {}", String::from_utf8_lossy(&res))?,
+            Err(e) => writeln!(stdout, "{target}: failed: {e}")?,
+        }
     }
-    go(&mut ctx, &opts, cmd).await
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Read the `--to-all` targets, one route per line.
+/// Empty lines and lines starting with `#` are skipped
+fn read_targets(path: &Path) -> Result<Vec<MultiAddr>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read the targets from {}", path.display()))?;
+    let targets = contents
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            MultiAddr::from_str(line)
+                .with_context(|| format!("Invalid route `{line}` at {}:{}", path.display(), i + 1))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    if targets.is_empty() {
+        return Err(crate::Error::new(
+            exitcode::USAGE,
+            anyhow!("No targets found in {}", path.display()),
+        ));
+    }
+    Ok(targets)
+}
+
+/// Read the message body from a file, or from stdin for `-`
+fn read_body(path: &Path) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+    if path == Path::new("-") {
+        std::io::stdin()
+            .read_to_end(&mut body)
+            .context("Failed to read the message from stdin")?;
+    } else {
+        body = std::fs::read(path)
+            .with_context(|| format!("Failed to read the message from {}", path.display()))?;
+    }
+    Ok(body)
 }
 
-pub(crate) fn req<'a>(to: &'a MultiAddr, message: &'a str) -> RequestBuilder<'a, SendMessage<'a>> {
-    Request::post("v0/message").body(SendMessage::new(to, message.as_bytes()))
+pub(crate) fn req<'a>(to: &'a MultiAddr, message: &'a [u8]) -> RequestBuilder<'a, SendMessage<'a>> {
+    Request::post("v0/message").body(SendMessage::new(to, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_route_at_services() {
+        let to = MultiAddr::from_str("/ip4/127.0.0.1/tcp/4000/service/hop/service/echo").unwrap();
+        let (node, services) = split_at_services(&to).unwrap();
+        assert_eq!(node.to_string(), "/ip4/127.0.0.1/tcp/4000");
+        assert_eq!(services.to_string(), "/service/hop/service/echo");
+
+        // The last node of the route is the one reached through the channel
+        let to = MultiAddr::from_str("/node/n1/service/hop/node/n2/service/echo").unwrap();
+        let (node, services) = split_at_services(&to).unwrap();
+        assert_eq!(node.to_string(), "/node/n1/service/hop/node/n2");
+        assert_eq!(services.to_string(), "/service/echo");
+
+        // No service behind the node, or no node at all
+        assert!(split_at_services(&MultiAddr::from_str("/node/n1").unwrap()).is_none());
+        assert!(split_at_services(&MultiAddr::from_str("/service/echo").unwrap()).is_none());
+    }
 }
@@ -1,6 +1,8 @@
-use std::net::{IpAddr, SocketAddr};
-use std::path::PathBuf;
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
 
 use anyhow::{anyhow, Context as _};
 use clap::Args;
@@ -8,6 +10,8 @@ use minicbor::Decoder;
 use ockam::identity::credential::{Credential, OneTimeCode};
 use ockam::{Address, AsyncTryClone, Context, TcpTransport, TCP};
 use ockam_api::bootstrapped_identities_store::PreTrustedIdentities;
+use ockam_api::cli_state::NodeState;
+use ockam_api::nodes::attributes_storage::AttributesStorageKind;
 use ockam_api::nodes::models::transport::{CreateTransportJson, TransportMode, TransportType};
 use ockam_api::nodes::service::{
     NodeManagerGeneralOptions,
@@ -18,11 +22,15 @@ use ockam_api::nodes::{NodeManager, NodeManagerWorker, NODEMANAGER_ADDR};
 use ockam_core::api::{Response, Status};
 use ockam_core::{AllowAll, LOCAL};
 use rand::prelude::random;
+use serde::Serialize;
 use tokio::io::AsyncBufReadExt;
-use tracing::error;
+use tokio::signal::unix::{signal, SignalKind};
+use tracing::{error, info};
+use tracing_subscriber::filter::LevelFilter;
 
 use super::util::delete_node;
-use crate::commands::node::show::print_query_status;
+use crate::commands::identity::KeyType;
+use crate::commands::node::show::{print_query_status, query_node_details};
 use crate::commands::node::util::{
     add_project_authority_from_project_info,
     init_node_state,
@@ -34,18 +42,25 @@ use crate::commands::secure_channel::listener::create as secure_channel_listener
 use crate::commands::service::start;
 use crate::config::project::ProjectInfo;
 use crate::config::service::Config;
+use crate::util::output::Output;
 use crate::util::{
     api,
     bind_to_port_check,
     exitcode,
     find_available_port,
+    listen_with_retries,
     parse_node_name,
+    print_output,
     BackgroundNode,
     ForegroundNode,
+    LogLevel,
     RpcBuilder,
 };
 use crate::{help, CommandGlobalOpts};
 
+/// Maximum time given to the workers of a foreground node to stop
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Create a node
 #[derive(Clone, Debug, Args)]
 #[command(after_long_help = help::template(HELP_DETAIL))]
@@ -62,7 +77,17 @@ pub struct CreateCommand {
     #[arg(display_order = 900, long = "exit-on-eof", short)]
     pub exit_on_eof: bool,
 
-    /// TCP listener address
+    /// Log json lines, with the fields of the current spans, instead of
+    /// human-readable text. Can also be set with OCKAM_LOG_FORMAT=json.
+    #[arg(display_order = 900, long)]
+    pub json_logs: bool,
+
+    /// Level of the node logs, regardless of the verbosity of this command
+    #[arg(display_order = 900, long, value_enum)]
+    pub log_level: Option<LogLevel>,
+
+    /// TCP listener address, either IPv4 or IPv6 (e.g. `[::1]:6001`).
+    /// A `0` port is replaced by an available one.
     #[arg(
         display_order = 900,
         long,
@@ -72,6 +97,16 @@ pub struct CreateCommand {
     )]
     pub tcp_listener_address: String,
 
+    /// Don't create a TCP listener for the node API.
+    /// Other ockam commands can't send requests to such a node, which can only
+    /// be reached by the workers running in its own process.
+    #[arg(
+        display_order = 900,
+        long,
+        conflicts_with_all = ["SOCKET_ADDRESS", "if_not_exists", "launch_config", "token"]
+    )]
+    pub no_api_transport: bool,
+
     /// ockam_command started a child process to run this node in foreground.
     #[arg(display_order = 900, long, hide = true)]
     pub child_process: bool,
@@ -80,6 +115,11 @@ pub struct CreateCommand {
     #[arg(long = "enrollment-token", value_name = "ENROLLMENT_TOKEN", value_parser = otc_parser)]
     token: Option<OneTimeCode>,
 
+    /// Fetch a new membership credential, instead of reusing the one cached
+    /// by a previous start of the node while it's still valid.
+    #[arg(long, requires = "token")]
+    pub force_credential_refresh: bool,
+
     /// JSON config to setup a foreground node
     ///
     /// This argument is currently ignored on background nodes.  Node
@@ -104,6 +144,34 @@ pub struct CreateCommand {
 
     #[arg(long = "identity", value_name = "IDENTITY")]
     identity: Option<String>,
+
+    /// Type of the key of the identity created for this node, when there
+    /// is no default identity to use.
+    /// Defaults to P-256 for AWS KMS vaults, and to Ed25519 otherwise
+    #[arg(long, value_enum, conflicts_with = "identity")]
+    key_type: Option<KeyType>,
+
+    /// Where to store the attributes of the identities known to the node:
+    /// `memory`, or `lmdb` for a database in the node's directory.
+    /// Defaults to the identities storage shared by all nodes
+    #[arg(long, value_name = "STORAGE")]
+    pub attributes_storage: Option<AttributesStorageKind>,
+
+    /// Print what would be created and started, without creating any
+    /// state or process.
+    #[arg(display_order = 900, long, conflicts_with = "child_process")]
+    pub dry_run: bool,
+
+    /// Don't fail if a node with the same name already exists: check that
+    /// its listener address, vault, identity, attributes storage and launch
+    /// configuration match the arguments, start it if it is stopped, and
+    /// print its status.
+    #[arg(
+        display_order = 900,
+        long,
+        conflicts_with_all = ["foreground", "child_process", "dry_run"]
+    )]
+    pub if_not_exists: bool,
 }
 
 impl Default for CreateCommand {
@@ -111,24 +179,37 @@ impl Default for CreateCommand {
         Self {
             node_name: hex::encode(random::<[u8; 4]>()),
             exit_on_eof: false,
+            json_logs: false,
+            log_level: None,
             tcp_listener_address: "127.0.0.1:0".to_string(),
+            no_api_transport: false,
             foreground: false,
             child_process: false,
             launch_config: None,
             project: None,
             token: None,
+            force_credential_refresh: false,
             vault: None,
             identity: None,
+            key_type: None,
             trusted_identities: None,
             trusted_identities_file: None,
             reload_from_trusted_identities_file: None,
+            attributes_storage: None,
+            dry_run: false,
+            if_not_exists: false,
         }
     }
 }
 
 impl CreateCommand {
     pub fn run(self, options: CommandGlobalOpts) {
-        if self.foreground {
+        if self.dry_run {
+            if let Err(e) = dry_run(&options, &self) {
+                eprintln!("{e:?}");
+                std::process::exit(e.code());
+            }
+        } else if self.foreground {
             // Create a new node in the foreground (i.e. in this OS process)
             if let Err(e) = create_foreground_node(&options, &self) {
                 error!(%e);
@@ -140,14 +221,18 @@ impl CreateCommand {
         }
     }
 
+    /// Replace a `0` port in the listener address with an available port,
+    /// probed on the same IP address (IPv4 or IPv6) the node will bind to.
     fn overwrite_addr(&self) -> anyhow::Result<Self> {
         let cmd = self.clone();
-        let addr: SocketAddr = if &cmd.tcp_listener_address == "127.0.0.1:0" {
-            let port = find_available_port().context("failed to acquire available port")?;
-            SocketAddr::new(IpAddr::from_str("127.0.0.1")?, port)
-        } else {
-            cmd.tcp_listener_address.parse()?
-        };
+        let mut addr: SocketAddr = cmd.tcp_listener_address.parse().with_context(|| {
+            format!("invalid TCP listener address {}", cmd.tcp_listener_address)
+        })?;
+        if addr.port() == 0 {
+            let port =
+                find_available_port(addr.ip()).context("failed to acquire available port")?;
+            addr.set_port(port);
+        }
         Ok(Self {
             tcp_listener_address: addr.to_string(),
             ..cmd
@@ -169,13 +254,22 @@ impl BackgroundNode for CreateCommand {
             ));
         }
 
-        // Spawn node in another, new process
-        let self = self.overwrite_addr()?;
+        if self.if_not_exists {
+            if let Ok(node_state) = opts.state.nodes.get(node_name) {
+                return use_existing_node(&ctx, &opts, &self, node_state).await;
+            }
+        }
+
+        // Spawn node in another, new process, which picks the port to listen on if it's `0`
         let addr = SocketAddr::from_str(&self.tcp_listener_address)?;
 
         spawn_background_node(&ctx, &opts, &self, addr).await?;
 
         // Print node status
+        if self.no_api_transport {
+            println!("Created node '{node_name}' without an API transport");
+            return Ok(());
+        }
         let tcp = TcpTransport::create(&ctx).await?;
         let mut rpc = RpcBuilder::new(&ctx, &opts, node_name).tcp(&tcp)?.build();
         let mut is_default = false;
@@ -187,6 +281,9 @@ impl BackgroundNode for CreateCommand {
         // Do we need to eagerly fetch a project membership credential?
         let get_credential = self.project.is_some() && self.token.is_some();
         if get_credential {
+            if self.force_credential_refresh {
+                opts.state.nodes.get(node_name)?.clear_credential()?;
+            }
             rpc.request(api::credentials::get_credential(false)).await?;
             if rpc.parse_and_print_response::<Credential>().is_err() {
                 eprintln!("failed to fetch membership credential");
@@ -220,6 +317,7 @@ impl ForegroundNode for CreateCommand {
                 &node_name,
                 self.vault.as_ref(),
                 self.identity.as_ref(),
+                self.key_type,
             )
             .await?;
         }
@@ -240,20 +338,45 @@ impl ForegroundNode for CreateCommand {
         let get_credential = !self.child_process && self.project.is_some() && self.token.is_some();
 
         let tcp = TcpTransport::create(&ctx).await?;
-        let bind = self.tcp_listener_address;
-        tcp.listen(&bind).await?;
+        let addr = match self.no_api_transport {
+            true => addr,
+            false => listen_with_retries(&tcp, addr).await?,
+        };
+        let bind = addr.to_string();
 
         let node_state = opts.state.nodes.get(&node_name)?;
-        let setup_config = node_state.setup()?;
-        node_state.set_setup(
-            &setup_config
-                .set_verbose(opts.global_args.verbose)
-                .add_transport(CreateTransportJson::new(
-                    TransportType::Tcp,
-                    TransportMode::Listen,
-                    &bind,
-                )?),
-        )?;
+        let transport = match self.no_api_transport {
+            true => None,
+            false => Some(CreateTransportJson::new(
+                TransportType::Tcp,
+                TransportMode::Listen,
+                &bind,
+            )?),
+        };
+        let launch_config = match &self.launch_config {
+            Some(config) => Some(serde_json::to_value(config)?),
+            None => None,
+        };
+        let setup_config = node_state.update_setup(|mut setup_config| {
+            // A child process logs with the filter of its parent, which recorded its verbosity
+            if !self.child_process {
+                setup_config = setup_config
+                    .set_verbose(opts.global_args.verbose)
+                    .set_json_logs(self.json_logs)
+                    .set_log_level(self.log_level.map(|l| LevelFilter::from(l).to_string()));
+            }
+            setup_config = match transport {
+                Some(transport) => setup_config.add_transport(transport),
+                None => setup_config.set_no_api_transport(),
+            };
+            if let Some(kind) = self.attributes_storage {
+                setup_config = setup_config.set_attributes_storage(kind);
+            }
+            if let Some(config) = launch_config {
+                setup_config = setup_config.set_launch_config(config);
+            }
+            setup_config
+        })?;
 
         let pre_trusted_identities = match (
             self.trusted_identities,
@@ -266,23 +389,29 @@ impl ForegroundNode for CreateCommand {
             _ => None,
         };
         let projects = cfg.inner().lookup().projects().collect();
+        let transport_options = if self.no_api_transport {
+            NodeManagerTransportOptions::without_api_transport(tcp.async_try_clone().await?)
+        } else {
+            NodeManagerTransportOptions::new(
+                (TransportType::Tcp, TransportMode::Listen, bind),
+                tcp.async_try_clone().await?,
+            )
+        };
         let node_man = NodeManager::create(
             &ctx,
             NodeManagerGeneralOptions::new(
                 self.node_name.clone(),
                 self.launch_config.is_some(),
                 pre_trusted_identities,
-            ),
+            )
+            .with_attributes_storage(setup_config.attributes_storage()),
             NodeManagerProjectsOptions::new(
                 Some(&cfg.authorities(&node_name)?.snapshot()),
                 project_id,
                 projects,
                 self.token,
             ),
-            NodeManagerTransportOptions::new(
-                (TransportType::Tcp, TransportMode::Listen, bind),
-                tcp.async_try_clone().await?,
-            ),
+            transport_options,
         )
         .await?;
         let node_manager_worker = NodeManagerWorker::new(node_man);
@@ -295,6 +424,20 @@ impl ForegroundNode for CreateCommand {
         )
         .await?;
 
+        // Re-create the secure channel listeners recorded by previous runs of this node
+        for listener in node_state.setup()?.secure_channel_listeners() {
+            let req = api::create_secure_channel_listener(
+                &Address::from_string(&listener.address),
+                listener.authorized_identifiers.clone(),
+                listener.identity.clone(),
+            )?;
+            let res: Vec<u8> = ctx.send_and_receive(NODEMANAGER_ADDR, req).await?;
+            let res = api::parse_create_secure_channel_listener_response(&res)?;
+            if res.status() != Some(Status::Ok) {
+                error!(address = %listener.address, "failed to re-create secure channel listener");
+            }
+        }
+
         if let Some(path) = &self.launch_config {
             let node_opts = super::NodeOpts {
                 api_node: node_name.clone(),
@@ -303,6 +446,9 @@ impl ForegroundNode for CreateCommand {
         }
 
         if get_credential {
+            if self.force_credential_refresh {
+                node_state.clear_credential()?;
+            }
             let req = api::credentials::get_credential(false).to_vec()?;
             let res: Vec<u8> = ctx.send_and_receive(NODEMANAGER_ADDR, req).await?;
             let mut d = Decoder::new(&res);
@@ -318,18 +464,15 @@ impl ForegroundNode for CreateCommand {
             }
         }
 
-        if self.exit_on_eof {
-            stop_node_on_eof(&mut ctx, &opts, &node_name).await?;
-        }
-
-        Ok(())
+        wait_for_shutdown(self.exit_on_eof).await?;
+        shutdown_node(&mut ctx, &opts, &node_name).await
     }
 }
 
 fn parse_launch_config(config_or_path: &str) -> anyhow::Result<Config> {
-    match serde_json::from_str::<Config>(config_or_path) {
-        Ok(c) => Ok(c),
-        Err(_) => {
+    match serde_json::from_str::<serde_json::Value>(config_or_path) {
+        Ok(value) if value.is_object() => Config::from_value(value),
+        _ => {
             let path = PathBuf::from_str(config_or_path).context(anyhow!("Not a valid path"))?;
             Config::read(path)
         }
@@ -337,32 +480,193 @@ fn parse_launch_config(config_or_path: &str) -> anyhow::Result<Config> {
 }
 
 fn create_foreground_node(opts: &CommandGlobalOpts, cmd: &CreateCommand) -> crate::Result<()> {
+    let addr = SocketAddr::from_str(&cmd.tcp_listener_address)?;
+
+    ForegroundNode::run(cmd.clone(), (opts.clone(), addr))
+}
+
+/// What `node create` would do, as reported by `--dry-run`
+#[derive(Serialize)]
+struct NodeCreatePlan {
+    node_name: String,
+    foreground: bool,
+    tcp_listener_address: Option<String>,
+    vault: PlannedState,
+    identity: PlannedState,
+    services: Vec<PlannedService>,
+}
+
+/// An existing vault or identity to reuse, or a new one to create
+#[derive(Serialize)]
+struct PlannedState {
+    name: Option<String>,
+    create: bool,
+}
+
+impl PlannedState {
+    fn existing(name: String) -> Self {
+        Self {
+            name: Some(name),
+            create: false,
+        }
+    }
+
+    fn new_default() -> Self {
+        Self {
+            name: None,
+            create: true,
+        }
+    }
+}
+
+impl std::fmt::Display for PlannedState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{name}"),
+            None => write!(f, "a new default one would be created"),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct PlannedService {
+    name: &'static str,
+    address: String,
+}
+
+impl Output for NodeCreatePlan {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut w = String::new();
+        writeln!(w, "Dry run, nothing was created")?;
+        writeln!(w, "Node: {}", self.node_name)?;
+        let mode = if self.foreground {
+            "foreground"
+        } else {
+            "background"
+        };
+        writeln!(w, "  Mode: {mode}")?;
+        match &self.tcp_listener_address {
+            Some(addr) => writeln!(w, "  TCP Listener Address: {addr}")?,
+            None => writeln!(w, "  TCP Listener Address: none")?,
+        }
+        writeln!(w, "  Vault: {}", self.vault)?;
+        write!(w, "  Identity: {}", self.identity)?;
+        if !self.services.is_empty() {
+            write!(w, "\n  Services:")?;
+            for s in &self.services {
+                write!(w, "\n    {} at {}", s.name, s.address)?;
+            }
+        }
+        Ok(w)
+    }
+}
+
+/// Run the same checks as a real `node create`, then print the plan
+/// instead of creating the node state and spawning the node.
+fn dry_run(opts: &CommandGlobalOpts, cmd: &CreateCommand) -> crate::Result<()> {
+    let node_name = parse_node_name(&cmd.node_name)?;
+    if opts.state.nodes.get(&node_name).is_ok() {
+        return Err(crate::Error::new(
+            exitcode::CANTCREAT,
+            anyhow!("Node {node_name} already exists"),
+        ));
+    }
+
     let cmd = cmd.overwrite_addr()?;
     let addr = SocketAddr::from_str(&cmd.tcp_listener_address)?;
+    if !cmd.no_api_transport && !bind_to_port_check(&addr) {
+        return Err(crate::Error::new(
+            exitcode::IOERR,
+            anyhow!("Another process is listening on the provided port!"),
+        ));
+    }
+
+    // Mirror `init_node_state`: a named vault or identity must exist,
+    // otherwise the default one is used or a new one created
+    let vault = match &cmd.vault {
+        Some(name) => PlannedState::existing(opts.state.vaults.get(name)?.name),
+        None if opts.state.vaults.default_path()?.exists() => {
+            PlannedState::existing(opts.state.vaults.default()?.name)
+        }
+        None => PlannedState::new_default(),
+    };
+    let identity = match &cmd.identity {
+        Some(name) => PlannedState::existing(opts.state.identities.get(name)?.name),
+        None => match opts.state.identities.default() {
+            Ok(i) => PlannedState::existing(i.name),
+            Err(_) => PlannedState::new_default(),
+        },
+    };
 
-    ForegroundNode::run(cmd, (opts.clone(), addr))
+    let services = cmd
+        .launch_config
+        .as_ref()
+        .map(|c| {
+            c.enabled_services()
+                .into_iter()
+                .map(|(name, address)| PlannedService {
+                    name,
+                    address: address.to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let plan = NodeCreatePlan {
+        node_name,
+        foreground: cmd.foreground,
+        tcp_listener_address: (!cmd.no_api_transport).then_some(cmd.tcp_listener_address),
+        vault,
+        identity,
+        services,
+    };
+    print_output(plan, &opts.global_args.output_format)?;
+    Ok(())
 }
 
-// Read STDIN until EOF is encountered and then stop the node
-async fn stop_node_on_eof(
+/// Wait for SIGTERM or, with `--exit-on-eof`, until STDIN is closed
+async fn wait_for_shutdown(exit_on_eof: bool) -> crate::Result<()> {
+    let mut sigterm = signal(SignalKind::terminate())?;
+    let eof = async {
+        if !exit_on_eof {
+            return std::future::pending().await;
+        }
+        let reader = tokio::io::BufReader::new(tokio::io::stdin());
+        let mut lines = reader.lines();
+        while let Ok(Some(_)) = lines.next_line().await {}
+    };
+    tokio::select! {
+        _ = sigterm.recv() => info!("Received SIGTERM, stopping the node"),
+        _ = eof => info!("STDIN was closed, stopping the node"),
+    }
+    Ok(())
+}
+
+/// Stop the workers of the node and mark it as stopped, then exit the process.
+///
+/// The process is exited in any case, even when the workers can't be stopped
+/// within `SHUTDOWN_TIMEOUT`, as a pending read of STDIN would keep it alive.
+async fn shutdown_node(
     ctx: &mut Context,
     opts: &CommandGlobalOpts,
     node_name: &str,
 ) -> crate::Result<()> {
-    let reader = tokio::io::BufReader::new(tokio::io::stdin());
-    let mut lines = reader.lines();
-
-    loop {
-        match lines.next_line().await {
-            Ok(Some(_)) => (),
-            Ok(None) => break,
-            Err(_) => unreachable!(),
+    let stopped = tokio::time::timeout(SHUTDOWN_TIMEOUT, ctx.stop()).await;
+    opts.state.nodes.get(node_name)?.clear_pid()?;
+    match stopped {
+        Ok(Ok(())) => std::process::exit(exitcode::OK),
+        Ok(Err(e)) => {
+            error!(%e, "failed to stop the node");
+            std::process::exit(exitcode::SOFTWARE)
+        }
+        Err(_) => {
+            error!(
+                "the node did not stop within {:?}, exiting",
+                SHUTDOWN_TIMEOUT
+            );
+            std::process::exit(exitcode::TEMPFAIL)
         }
     }
-
-    ctx.stop().await?;
-    opts.state.nodes.get(node_name)?.kill_process(false)?;
-    Ok(())
 }
 
 async fn start_services(
@@ -426,6 +730,9 @@ async fn start_services(
                 &cfg.enrollers,
                 cfg.reload_enrollers,
                 &cfg.project,
+                cfg.token_duration,
+                cfg.credential_ttl,
+                cfg.inherited_attributes,
                 Some(tcp),
             )
             .await?
@@ -448,8 +755,9 @@ async fn spawn_background_node(
     cmd: &CreateCommand,
     addr: SocketAddr,
 ) -> crate::Result<()> {
-    // Check if the port is used by some other services or process
-    if !bind_to_port_check(&addr) {
+    // Check if the port is used by some other services or process. A port
+    // still to be picked is checked by the node when it binds it.
+    if !cmd.no_api_transport && addr.port() != 0 && !bind_to_port_check(&addr) {
         return Err(crate::Error::new(
             exitcode::IOERR,
             anyhow!("Another process is listening on the provided port!"),
@@ -465,16 +773,36 @@ async fn spawn_background_node(
         &node_name,
         cmd.vault.as_ref(),
         cmd.identity.as_ref(),
+        cmd.key_type,
     )
     .await?;
 
+    // Record the attributes storage, so that it's also used when the node is restarted
+    if let Some(kind) = cmd.attributes_storage {
+        let node_state = opts.state.nodes.get(&node_name)?;
+        node_state.update_setup(|s| s.set_attributes_storage(kind))?;
+    }
+
+    // Same for the log format
+    if cmd.json_logs {
+        let node_state = opts.state.nodes.get(&node_name)?;
+        node_state.update_setup(|s| s.set_json_logs(true))?;
+    }
+
+    // And for the log level
+    if let Some(level) = cmd.log_level {
+        let node_state = opts.state.nodes.get(&node_name)?;
+        let level = LevelFilter::from(level).to_string();
+        node_state.update_setup(|s| s.set_log_level(Some(level)))?;
+    }
+
     // Construct the arguments list and re-execute the ockam
     // CLI in foreground mode to start the newly created node
     spawn_node(
         opts,
         opts.global_args.verbose,
         &node_name,
-        &cmd.tcp_listener_address,
+        (!cmd.no_api_transport).then_some(cmd.tcp_listener_address.as_str()),
         cmd.project.as_deref(),
         cmd.token.as_ref(),
         cmd.trusted_identities.as_ref(),
@@ -488,6 +816,90 @@ async fn spawn_background_node(
     Ok(())
 }
 
+/// Reuse an existing node for `--if-not-exists`: check that its configuration
+/// doesn't conflict with the arguments, start it if needed, and print its status.
+async fn use_existing_node(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    cmd: &CreateCommand,
+    node_state: NodeState,
+) -> crate::Result<()> {
+    let node_name = &node_state.config.name;
+    let setup = node_state.setup()?;
+    let addr = setup.default_tcp_listener()?.addr.to_string();
+
+    let mut conflicts = vec![];
+    let requested_addr = SocketAddr::from_str(&cmd.tcp_listener_address)
+        .with_context(|| format!("invalid TCP listener address {}", cmd.tcp_listener_address))?;
+    if requested_addr.port() != 0 && SocketAddr::from_str(&addr).ok() != Some(requested_addr) {
+        conflicts.push(format!("it listens on {addr}"));
+    }
+    if let Some(vault) = &cmd.vault {
+        let path = opts.state.vaults.get(vault)?.path;
+        if !same_file(&path, node_state.config.vault_path()) {
+            conflicts.push(format!("it doesn't use the vault '{vault}'"));
+        }
+    }
+    if let Some(identity) = &cmd.identity {
+        let path = opts.state.identities.get(identity)?.path;
+        if !same_file(&path, node_state.config.identity_path()) {
+            conflicts.push(format!("it doesn't use the identity '{identity}'"));
+        }
+    }
+    if let Some(kind) = cmd.attributes_storage {
+        if setup.attributes_storage() != Some(kind) {
+            conflicts.push("it uses another attributes storage".to_string());
+        }
+    }
+    if let Some(config) = &cmd.launch_config {
+        if setup.launch_config() != Some(&serde_json::to_value(config)?) {
+            conflicts.push("it was launched with another configuration".to_string());
+        }
+    }
+    if !conflicts.is_empty() {
+        return Err(crate::Error::new(
+            exitcode::CONFIG,
+            anyhow!(
+                "Node '{node_name}' already exists with a different configuration: {}",
+                conflicts.join(", ")
+            ),
+        ));
+    }
+
+    if !node_state.is_running() {
+        spawn_node(
+            opts,
+            setup.verbose, // Previously user-chosen verbosity level
+            node_name,     // The existing node name
+            Some(&addr),   // The same api address as before
+            None,          // No project information available
+            None,          // No invitation code available
+            None,          // No trusted identities
+            None,          // "
+            None,          // "
+            setup.launch_config().map(|c| c.to_string()),
+        )?;
+    }
+
+    let tcp = TcpTransport::create(ctx).await?;
+    let mut rpc = RpcBuilder::new(ctx, opts, node_name).tcp(&tcp)?.build();
+    let mut is_default = false;
+    if let Ok(state) = opts.state.nodes.default() {
+        is_default = &state.config.name == node_name;
+    }
+    let details = query_node_details(&mut rpc, node_name, true, is_default).await?;
+    print_output(details, &opts.global_args.output_format)?;
+    Ok(())
+}
+
+/// Whether two paths point to the same file, once symbolic links are resolved
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
 fn otc_parser(val: &str) -> anyhow::Result<OneTimeCode> {
     let bytes = hex::decode(val)?;
     let code = <[u8; 32]>::try_from(bytes.as_slice())?;
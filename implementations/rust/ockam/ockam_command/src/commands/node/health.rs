@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use anyhow::anyhow;
+use clap::Args;
+use ockam::{Context, TcpTransport};
+use ockam_api::nodes::models::base::NodeHealth;
+
+use super::default_node_name;
+use crate::util::{api, node_rpc, Rpc, RpcBuilder};
+use crate::{exitcode, CommandGlobalOpts};
+
+/// How long to wait for the node to answer, unless `--timeout` is given
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Check that a node is alive and responsive
+#[derive(Clone, Debug, Args)]
+pub struct HealthCommand {
+    /// Name of the node.
+    #[arg(default_value_t = default_node_name())]
+    node_name: String,
+}
+
+impl HealthCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, HealthCommand),
+) -> crate::Result<()> {
+    let node_state = opts.state.nodes.get(&cmd.node_name)?;
+    let timeout = opts
+        .global_args
+        .timeout
+        .map(Duration::from_secs)
+        .unwrap_or(HEALTH_CHECK_TIMEOUT);
+
+    let tcp = TcpTransport::create(&ctx).await?;
+    let mut rpc = RpcBuilder::new(&ctx, &opts, &node_state.config.name)
+        .tcp(&tcp)?
+        .build();
+    if let Err(e) = query_health(&mut rpc, timeout).await {
+        return Err(crate::Error::new(
+            exitcode::UNAVAILABLE,
+            anyhow!("Node {} is unreachable: {e}", cmd.node_name),
+        ));
+    }
+    rpc.print_response(rpc.parse_response::<NodeHealth>()?)?;
+    Ok(())
+}
+
+async fn query_health(rpc: &mut Rpc<'_>, timeout: Duration) -> anyhow::Result<()> {
+    rpc.request_with_timeout(api::query_health(), timeout)
+        .await?;
+    rpc.is_ok()
+}
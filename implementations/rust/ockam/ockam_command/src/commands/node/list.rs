@@ -3,12 +3,12 @@ use std::time::Duration;
 use anyhow::{anyhow, Context as _};
 use clap::Args;
 use ockam::{Context, TcpTransport};
-use ockam_api::nodes::models::base::NodeStatus;
+use ockam_api::nodes::models::base::{NodeListItem, NodeListStatus, NodeStatus};
 
 use crate::commands::node::show::print_query_status;
 use crate::commands::node::HELP_DETAIL;
-use crate::util::{api, exitcode, node_rpc, RpcBuilder};
-use crate::{help, CommandGlobalOpts};
+use crate::util::{api, exitcode, node_rpc, print_output, RpcBuilder};
+use crate::{help, CommandGlobalOpts, OutputFormat};
 
 /// List nodes
 #[derive(Clone, Debug, Args)]
@@ -46,6 +46,15 @@ async fn run_impl(
         }
         nodes_states.iter().map(|s| s.config.name.clone()).collect()
     };
+
+    // For machine-readable output we rely only on the recorded node state,
+    // so that nodes that are not responding don't block the listing.
+    if opts.global_args.output_format != OutputFormat::Plain {
+        let items = list_items(&opts, &node_names, &default)?;
+        print_output(items, &opts.global_args.output_format)?;
+        return Ok(());
+    }
+
     let tcp = TcpTransport::create(&ctx).await?;
     verify_pids(&ctx, &opts, &tcp, &node_names).await?;
 
@@ -59,6 +68,35 @@ async fn run_impl(
     Ok(())
 }
 
+/// Build a summary of every node from the data stored in `CliState`.
+pub(crate) fn list_items(
+    opts: &CommandGlobalOpts,
+    nodes: &[String],
+    default: &str,
+) -> crate::Result<Vec<NodeListItem>> {
+    let mut items = Vec::with_capacity(nodes.len());
+    for node_name in nodes {
+        let node_state = opts.state.nodes.get(node_name)?;
+        let tcp_listener_address = node_state
+            .setup()
+            .ok()
+            .and_then(|s| s.default_tcp_listener().ok().map(|t| t.addr.to_string()));
+        let status = if node_state.is_running() {
+            NodeListStatus::Running
+        } else {
+            NodeListStatus::Stopped
+        };
+        items.push(NodeListItem::new(
+            node_name,
+            node_state.pid()?,
+            tcp_listener_address,
+            status,
+            node_name == default,
+        ));
+    }
+    Ok(items)
+}
+
 /// Update the persisted configuration data with the pids
 /// responded by nodes.
 async fn verify_pids(
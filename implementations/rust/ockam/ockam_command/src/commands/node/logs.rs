@@ -1,11 +1,21 @@
-use std::path::PathBuf;
+use std::fs::File;
+use std::io::{Read, Seek, Write};
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use anyhow::{anyhow, Context};
 use clap::Args;
 
 use crate::commands::node::{default_node_name, HELP_DETAIL};
+use crate::util::civil::days_from_civil;
+use crate::util::parse_duration;
 use crate::{help, CommandGlobalOpts};
 
-/// Get the stdout/stderr log file of a node
+/// How often a followed log file is checked for new lines
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Print the stdout/stderr log of a node
 #[derive(Clone, Debug, Args)]
 #[command(
     after_long_help = help::template(HELP_DETAIL)
@@ -18,6 +28,22 @@ pub struct LogCommand {
     /// Show the standard error log file.
     #[arg(long = "err")]
     show_err: bool,
+
+    /// Only print the path of the log file.
+    #[arg(long, conflicts_with_all = ["follow", "since", "lines"])]
+    path: bool,
+
+    /// Keep printing new lines as they are written to the log file.
+    #[arg(long)]
+    follow: bool,
+
+    /// Only print the lines logged within the given duration, e.g. `30s`, `5m` or `2h`.
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    since: Option<Duration>,
+
+    /// Only print the last N lines.
+    #[arg(long, value_name = "N")]
+    lines: Option<usize>,
 }
 
 impl LogCommand {
@@ -29,7 +55,7 @@ impl LogCommand {
     }
 }
 
-fn run_impl(opts: CommandGlobalOpts, cmd: LogCommand) -> crate::Result<PathBuf> {
+fn run_impl(opts: CommandGlobalOpts, cmd: LogCommand) -> crate::Result<()> {
     let node_state = opts.state.nodes.get(&cmd.node_name)?;
 
     let log_file_path = if cmd.show_err {
@@ -37,6 +63,147 @@ fn run_impl(opts: CommandGlobalOpts, cmd: LogCommand) -> crate::Result<PathBuf>
     } else {
         node_state.stdout_log()
     };
-    println!("{}", log_file_path.display());
-    Ok(log_file_path)
+    if cmd.path {
+        println!("{}", log_file_path.display());
+        return Ok(());
+    }
+
+    let mut file = File::open(&log_file_path).with_context(|| {
+        anyhow!(
+            "failed to open the log file {}",
+            log_file_path.to_string_lossy()
+        )
+    })?;
+    let mut contents = Vec::new();
+    file.read_to_end(&mut contents)?;
+    let contents = String::from_utf8_lossy(&contents);
+    // A duration reaching before the epoch selects every line
+    let since = cmd
+        .since
+        .map(|d| SystemTime::now().checked_sub(d).unwrap_or(UNIX_EPOCH));
+    let stdout = std::io::stdout();
+    let mut stdout = stdout.lock();
+    for line in select_lines(&contents, since, cmd.lines) {
+        writeln!(stdout, "{line}")?;
+    }
+    stdout.flush()?;
+    drop(stdout);
+
+    if cmd.follow {
+        follow(&log_file_path, file)?;
+    }
+    Ok(())
+}
+
+/// Lines of `contents` logged after `since`, limited to the last `lines` ones.
+fn select_lines(contents: &str, since: Option<SystemTime>, lines: Option<usize>) -> Vec<&str> {
+    let mut selected: Vec<&str> = contents.lines().collect();
+    if let Some(since) = since {
+        // Log lines are written in order, so everything after the first recent
+        // enough line is kept, including multi-line entries without a timestamp.
+        let start = selected
+            .iter()
+            .position(|l| line_time(l).map(|t| t >= since).unwrap_or(false))
+            .unwrap_or(selected.len());
+        selected.drain(..start);
+    }
+    if let Some(n) = lines {
+        let start = selected.len().saturating_sub(n);
+        selected.drain(..start);
+    }
+    selected
+}
+
+/// Keep printing what gets appended to the log file until Ctrl-C is pressed.
+fn follow(path: &Path, file: File) -> crate::Result<()> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async {
+        tokio::select! {
+            res = tail(path, file) => res,
+            _ = tokio::signal::ctrl_c() => Ok(()),
+        }
+    })
+}
+
+async fn tail(path: &Path, mut file: File) -> crate::Result<()> {
+    let mut inode = file.metadata()?.ino();
+    let stdout = std::io::stdout();
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        file.read_to_end(&mut buf)?;
+        if !buf.is_empty() {
+            let mut stdout = stdout.lock();
+            stdout.write_all(&buf)?;
+            stdout.flush()?;
+        }
+        tokio::time::sleep(FOLLOW_INTERVAL).await;
+
+        // The log file may have been rotated (replaced by a new file) or truncated,
+        // in which case it is reopened and read from the start.
+        if let Ok(metadata) = std::fs::metadata(path) {
+            if metadata.ino() != inode || metadata.len() < file.stream_position()? {
+                file = File::open(path)?;
+                inode = metadata.ino();
+            }
+        }
+    }
+}
+
+/// Time of a log line, from the RFC 3339 UTC timestamp it starts with,
+/// e.g. `2023-01-10T12:34:56.789012Z`.
+fn line_time(line: &str) -> Option<SystemTime> {
+    let timestamp = line.split_whitespace().next()?.strip_suffix('Z')?;
+    let (date, time) = timestamp.split_once('T')?;
+
+    let mut date = date.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let time = time.split('.').next()?;
+    let mut time = time.splitn(3, ':').map(|p| p.parse::<i64>().ok());
+    let (hours, minutes, seconds) = (time.next()??, time.next()??, time.next()??);
+
+    let secs = days_from_civil(year, month, day) * 86400 + hours * 3600 + minutes * 60 + seconds;
+    Some(UNIX_EPOCH + Duration::from_secs(u64::try_from(secs).ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_line_time() {
+        let t = line_time("2023-01-10T12:34:56.789012Z  INFO ockam_api: started").unwrap();
+        assert_eq!(t, UNIX_EPOCH + Duration::from_secs(1673354096));
+        let t = line_time("1970-01-01T00:00:00Z DEBUG").unwrap();
+        assert_eq!(t, UNIX_EPOCH);
+        assert!(line_time("  at src/main.rs:10").is_none());
+        assert!(line_time("2023-13-10T12:34:56Z INFO").is_none());
+    }
+
+    #[test]
+    fn select_recent_and_last_lines() {
+        let contents = "2023-01-10T12:00:00.000000Z  INFO first\n\
+                        2023-01-10T12:10:00.000000Z  INFO second\n  \
+                        continued\n\
+                        2023-01-10T12:20:00.000000Z  INFO third";
+        let since = UNIX_EPOCH + Duration::from_secs(1673352300); // 12:05
+        assert_eq!(
+            select_lines(contents, Some(since), None),
+            vec![
+                "2023-01-10T12:10:00.000000Z  INFO second",
+                "  continued",
+                "2023-01-10T12:20:00.000000Z  INFO third"
+            ]
+        );
+        assert_eq!(
+            select_lines(contents, Some(since), Some(1)),
+            vec!["2023-01-10T12:20:00.000000Z  INFO third"]
+        );
+        assert_eq!(select_lines(contents, None, Some(0)), Vec::<&str>::new());
+        assert_eq!(select_lines(contents, None, None).len(), 4);
+    }
 }
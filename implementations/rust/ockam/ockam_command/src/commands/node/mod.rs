@@ -1,20 +1,26 @@
 use clap::{Args, Subcommand};
 pub(crate) use create::CreateCommand;
 use delete::DeleteCommand;
+use health::HealthCommand;
+pub(crate) use list::list_items;
 use list::ListCommand;
 use logs::LogCommand;
 use ockam_api::cli_state::CliState;
+use restart::RestartCommand;
 use show::ShowCommand;
 use start::StartCommand;
 use stop::StopCommand;
+use tracing_subscriber::filter::LevelFilter;
 
 use crate::util::BackgroundNode;
 use crate::{help, CommandGlobalOpts};
 
 mod create;
 mod delete;
+mod health;
 mod list;
 mod logs;
+mod restart;
 mod show;
 mod start;
 mod stop;
@@ -41,9 +47,13 @@ pub enum NodeSubcommand {
     #[command(display_order = 800)]
     Delete(DeleteCommand),
     #[command(display_order = 800)]
+    Health(HealthCommand),
+    #[command(display_order = 800)]
     List(ListCommand),
     #[command(display_order = 800)]
     Logs(LogCommand),
+    #[command(display_order = 800)]
+    Restart(RestartCommand),
     Show(ShowCommand),
     #[command(display_order = 800)]
     Start(StartCommand),
@@ -52,11 +62,26 @@ pub enum NodeSubcommand {
 }
 
 impl NodeCommand {
+    /// Whether `node create --json-logs` was requested
+    pub fn json_logs(&self) -> bool {
+        matches!(&self.subcommand, NodeSubcommand::Create(c) if c.json_logs)
+    }
+
+    /// The `--log-level` of `node create --foreground`, whose node runs in this process
+    pub fn foreground_log_level(&self) -> Option<LevelFilter> {
+        match &self.subcommand {
+            NodeSubcommand::Create(c) if c.foreground => c.log_level.map(LevelFilter::from),
+            _ => None,
+        }
+    }
+
     pub fn run(self, options: CommandGlobalOpts) {
         match self.subcommand {
             NodeSubcommand::Create(c) => c.run(options),
             NodeSubcommand::Delete(c) => c.run(options),
+            NodeSubcommand::Health(c) => c.run(options),
             NodeSubcommand::List(c) => c.run(options),
+            NodeSubcommand::Restart(c) => c.run(options),
             NodeSubcommand::Show(c) => c.run(options),
             NodeSubcommand::Start(c) => c.run(options),
             NodeSubcommand::Stop(c) => c.run(options),
@@ -81,9 +106,8 @@ pub struct NodeOpts {
 
 pub fn default_node_name() -> String {
     CliState::new()
-        .unwrap()
-        .nodes
-        .default()
+        .ok()
+        .and_then(|s| s.nodes.default().ok())
         .map(|n| n.config.name)
-        .unwrap_or_else(|_| "default".to_string())
+        .unwrap_or_else(|| "default".to_string())
 }
@@ -0,0 +1,102 @@
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use clap::Args;
+
+use crate::commands::node::start::start_node;
+use crate::commands::node::{default_node_name, HELP_DETAIL};
+use crate::util::{bind_to_port_check, exitcode, node_rpc};
+use crate::{help, CommandGlobalOpts};
+
+/// Maximum time given to the stopped node to release its listener port
+const RELEASE_PORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Restart a node with the configuration it was created with
+#[derive(Clone, Debug, Args)]
+#[command(
+    after_long_help = help::template(HELP_DETAIL)
+)]
+pub struct RestartCommand {
+    /// Name of the node.
+    #[arg(default_value_t = default_node_name())]
+    node_name: String,
+
+    /// Whether to use the SIGTERM or SIGKILL signal to stop the node
+    #[arg(long)]
+    force: bool,
+}
+
+impl RestartCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    ctx: ockam::Context,
+    (opts, cmd): (CommandGlobalOpts, RestartCommand),
+) -> crate::Result<()> {
+    let node_name = &cmd.node_name;
+
+    // Read the setup before stopping the node, so that a node which
+    // can't be restarted is left untouched
+    let no_setup = || {
+        crate::Error::new(
+            exitcode::CONFIG,
+            anyhow!(
+                "Node '{node_name}' has no persisted setup configuration, it can't be restarted"
+            ),
+        )
+    };
+    let node_path = opts.state.nodes.dir.join(node_name);
+    if node_path.is_dir() && !node_path.join("setup.json").exists() {
+        return Err(no_setup());
+    }
+    let node_state = opts.state.nodes.get(node_name)?;
+    let node_setup = node_state.setup().map_err(|_| no_setup())?;
+    let addr = match node_setup.has_api_transport() {
+        true => Some(
+            node_setup
+                .default_tcp_listener()
+                .map_err(|_| no_setup())?
+                .addr
+                .to_string(),
+        ),
+        false => None,
+    };
+    let launch_config = node_setup.launch_config().map(|c| c.to_string());
+
+    node_state.kill_process(cmd.force)?;
+    if let Some(addr) = &addr {
+        wait_for_port_release(&SocketAddr::from_str(addr)?).await?;
+    }
+
+    start_node(
+        &ctx,
+        &opts,
+        node_name,
+        node_setup.verbose,
+        addr.as_deref(),
+        launch_config,
+    )
+    .await
+}
+
+/// Wait until the stopped node doesn't listen on its address anymore
+async fn wait_for_port_release(addr: &SocketAddr) -> crate::Result<()> {
+    let step = Duration::from_millis(100);
+    let mut waited = Duration::ZERO;
+    while !bind_to_port_check(addr) {
+        if waited >= RELEASE_PORT_TIMEOUT {
+            return Err(crate::Error::new(
+                exitcode::TEMPFAIL,
+                anyhow!("The node is still listening on {addr}, it can't be restarted"),
+            ));
+        }
+        tokio::time::sleep(step).await;
+        waited += step;
+    }
+    Ok(())
+}
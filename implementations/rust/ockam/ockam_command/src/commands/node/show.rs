@@ -1,7 +1,10 @@
+use core::fmt::{Display, Write};
 use core::time::Duration;
 
+use anyhow::anyhow;
 use clap::Args;
 use colorful::Colorful;
+use futures::StreamExt;
 use ockam::TcpTransport;
 use ockam_api::nodes::models::portal::{InletList, OutletList};
 use ockam_api::nodes::models::services::ServiceList;
@@ -10,23 +13,32 @@ use ockam_api::{addr_to_multiaddr, cli_state, route_to_multiaddr};
 use ockam_core::Route;
 use ockam_multiaddr::proto::{DnsAddr, Node, Tcp};
 use ockam_multiaddr::MultiAddr;
+use serde::Serialize;
 use tokio_retry::strategy::FibonacciBackoff;
 use tracing::debug;
 
 use super::{default_node_name, HELP_DETAIL};
-use crate::util::{api, BackgroundNode, Rpc, RpcBuilder};
-use crate::{help, CommandGlobalOpts};
+use crate::util::output::Output;
+use crate::util::{api, exitcode, print_output, BackgroundNode, Rpc, RpcBuilder};
+use crate::{help, CommandGlobalOpts, OutputFormat};
 
 const IS_NODE_UP_MAX_ATTEMPTS: usize = 50;
 const IS_NODE_UP_MAX_TIMEOUT: Duration = Duration::from_secs(1);
 
+/// Maximum number of nodes queried at the same time with `--all` or a pattern
+const MAX_CONCURRENT_QUERIES: usize = 8;
+
 /// Show node details
 #[derive(Clone, Debug, Args)]
 #[command(arg_required_else_help = true, after_long_help = help::template(HELP_DETAIL))]
 pub struct ShowCommand {
-    /// Name of the node.
+    /// Name of the node, or a pattern like `prefix*` matching several nodes.
     #[arg(default_value_t = default_node_name())]
     node_name: String,
+
+    /// Show all the nodes
+    #[arg(long, conflicts_with = "node_name")]
+    all: bool,
 }
 
 #[ockam_core::async_trait]
@@ -34,115 +46,270 @@ impl BackgroundNode for ShowCommand {
     type Args = CommandGlobalOpts;
 
     async fn run_in_background(self, ctx: ockam::Context, opts: Self::Args) -> crate::Result<()> {
-        let node_name = &self.node_name;
-
         let tcp = TcpTransport::create(&ctx).await?;
-        let mut rpc = RpcBuilder::new(&ctx, &opts, node_name).tcp(&tcp)?.build();
-        let mut is_default = false;
-        if let Ok(state) = opts.state.nodes.default() {
-            is_default = &state.config.name == node_name;
+        let default = opts.state.nodes.default().ok().map(|s| s.config.name);
+
+        if !self.all && !is_pattern(&self.node_name) {
+            let node_name = &self.node_name;
+            let mut rpc = RpcBuilder::new(&ctx, &opts, node_name).tcp(&tcp)?.build();
+            let is_default = default.as_ref() == Some(node_name);
+            if opts.global_args.output_format == OutputFormat::Plain {
+                print_query_status(&mut rpc, node_name, false, is_default).await?;
+            } else {
+                let details = query_node_details(&mut rpc, node_name, false, is_default).await?;
+                print_output(details, &opts.global_args.output_format)?;
+            }
+            return Ok(());
         }
-        print_query_status(&mut rpc, node_name, false, is_default).await?;
+
+        let node_names: Vec<String> = opts
+            .state
+            .nodes
+            .list()?
+            .into_iter()
+            .map(|s| s.config.name)
+            .filter(|n| self.all || glob_match(&self.node_name, n))
+            .collect();
+        if node_names.is_empty() {
+            return Err(crate::Error::new(
+                exitcode::UNAVAILABLE,
+                anyhow!("No nodes matching '{}' were found", self.node_name),
+            ));
+        }
+
+        // A node failing to answer is reported with its error, instead of
+        // aborting the whole listing
+        let details: Vec<NodeDetails> = futures::stream::iter(node_names)
+            .map(|node_name| {
+                let (ctx, opts, tcp) = (&ctx, &opts, &tcp);
+                let is_default = default.as_ref() == Some(&node_name);
+                async move {
+                    let result = async {
+                        let mut rpc = RpcBuilder::new(ctx, opts, &node_name).tcp(tcp)?.build();
+                        query_node_details(&mut rpc, &node_name, false, is_default).await
+                    }
+                    .await;
+                    result.unwrap_or_else(|e| NodeDetails::failed(&node_name, is_default, e))
+                }
+            })
+            .buffered(MAX_CONCURRENT_QUERIES)
+            .collect()
+            .await;
+        print_output(details, &opts.global_args.output_format)?;
         Ok(())
     }
 }
 
-// TODO: This function should be replaced with a better system of
-// printing the node state in the future but for now we can just tell
-// clippy to stop complaining about it.
-#[allow(clippy::too_many_arguments)]
-fn print_node_info(
-    node_port: u16,
-    node_name: &str,
-    is_default: bool,
-    status_is_up: bool,
-    default_id: Option<&str>,
-    services: Option<&ServiceList>,
-    tcp_listeners: Option<&TransportList>,
-    secure_channel_listeners: Option<&Vec<String>>,
-    inlets_outlets: Option<(&InletList, &OutletList)>,
-) {
-    println!();
-    println!("Node:");
-    if is_default {
-        println!("  Name: {node_name} (Default)");
-    } else {
-        println!("  Name: {node_name}");
-    }
-    println!(
-        "  Status: {}",
-        match status_is_up {
-            true => "UP".light_green(),
-            false => "DOWN".light_red(),
-        }
-    );
+/// Whether a node name given to `node show` is a glob pattern
+fn is_pattern(name: &str) -> bool {
+    name.contains(['*', '?'])
+}
 
-    println!("  Route To Node:");
-    let mut m = MultiAddr::default();
-    if m.push_back(Node::new(node_name)).is_ok() {
-        println!("    Short: {m}");
+/// Match a name against a glob pattern, where `*` matches any sequence of
+/// characters and `?` matches a single character
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let (p, n): (Vec<char>, Vec<char>) = (pattern.chars().collect(), name.chars().collect());
+    let (mut pi, mut ni) = (0, 0);
+    // Position of the last `*` in the pattern, and of the name when it was met
+    let mut star: Option<(usize, usize)> = None;
+    while ni < n.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == n[ni]) {
+            pi += 1;
+            ni += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ni));
+            pi += 1;
+        } else if let Some((spi, sni)) = star {
+            // Let the last `*` match one more character
+            pi = spi + 1;
+            ni = sni + 1;
+            star = Some((spi, sni + 1));
+        } else {
+            return false;
+        }
     }
+    p[pi..].iter().all(|c| *c == '*')
+}
 
-    let mut m = MultiAddr::default();
-    if m.push_back(DnsAddr::new("localhost")).is_ok() && m.push_back(Tcp::new(node_port)).is_ok() {
-        println!("    Verbose: {m}");
-    }
+/// The status of a node, and the resources it's running when it's up
+#[derive(Debug, Serialize)]
+pub struct NodeDetails {
+    name: String,
+    is_default: bool,
+    status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    identity: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transports: Option<Vec<TransportDetails>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secure_channel_listeners: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inlets: Option<Vec<InletDetails>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    outlets: Option<Vec<OutletDetails>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    services: Option<Vec<ServiceDetails>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct TransportDetails {
+    transport_type: String,
+    mode: String,
+    address: String,
+}
+
+#[derive(Debug, Serialize)]
+struct InletDetails {
+    listen_address: String,
+    route_to_outlet: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct OutletDetails {
+    forward_address: String,
+    address: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ServiceDetails {
+    service_type: String,
+    address: Option<String>,
+}
 
-    if let Some(id) = default_id {
-        println!("  Identity: {id}");
+impl NodeDetails {
+    fn down(node_name: &str, is_default: bool, port: u16) -> Self {
+        NodeDetails {
+            name: node_name.to_string(),
+            is_default,
+            status: "DOWN".to_string(),
+            port: Some(port),
+            identity: None,
+            transports: None,
+            secure_channel_listeners: None,
+            inlets: None,
+            outlets: None,
+            services: None,
+            error: None,
+        }
     }
 
-    if let Some(list) = tcp_listeners {
-        println!("  Transports:");
-        for e in &list.list {
-            println!("    Transport:");
-            println!("      Type: {}", e.tt);
-            println!("      Mode: {}", e.tm);
-            println!("      Address: {}", e.payload);
+    /// A node whose status couldn't be queried
+    fn failed(node_name: &str, is_default: bool, error: impl Display) -> Self {
+        NodeDetails {
+            port: None,
+            error: Some(error.to_string()),
+            ..NodeDetails::down(node_name, is_default, 0)
         }
     }
+}
+
+impl Output for NodeDetails {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut w = String::new();
+        writeln!(w)?;
+        writeln!(w, "Node:")?;
+        if self.is_default {
+            writeln!(w, "  Name: {} (Default)", self.name)?;
+        } else {
+            writeln!(w, "  Name: {}", self.name)?;
+        }
+        let status = match self.status.as_str() {
+            "UP" => self.status.as_str().light_green(),
+            _ => self.status.as_str().light_red(),
+        };
+        write!(w, "  Status: {status}")?;
+
+        if let Some(error) = &self.error {
+            write!(w, "\n  Error: {error}")?;
+            return Ok(w);
+        }
 
-    if let Some(list) = secure_channel_listeners {
-        println!("  Secure Channel Listeners:");
-        for e in list {
-            println!("    Listener:");
-            if let Some(ma) = addr_to_multiaddr(e) {
-                println!("      Address: {ma}");
+        write!(w, "\n  Route To Node:")?;
+        let mut m = MultiAddr::default();
+        if m.push_back(Node::new(&self.name)).is_ok() {
+            write!(w, "\n    Short: {m}")?;
+        }
+
+        if let Some(port) = self.port {
+            let mut m = MultiAddr::default();
+            if m.push_back(DnsAddr::new("localhost")).is_ok() && m.push_back(Tcp::new(port)).is_ok()
+            {
+                write!(w, "\n    Verbose: {m}")?;
+            }
+        }
+
+        if let Some(id) = &self.identity {
+            write!(w, "\n  Identity: {id}")?;
+        }
+
+        if let Some(list) = &self.transports {
+            write!(w, "\n  Transports:")?;
+            for e in list {
+                write!(w, "\n    Transport:")?;
+                write!(w, "\n      Type: {}", e.transport_type)?;
+                write!(w, "\n      Mode: {}", e.mode)?;
+                write!(w, "\n      Address: {}", e.address)?;
+            }
+        }
+
+        if let Some(list) = &self.secure_channel_listeners {
+            write!(w, "\n  Secure Channel Listeners:")?;
+            for e in list {
+                write!(w, "\n    Listener:")?;
+                write!(w, "\n      Address: {e}")?;
             }
         }
-    }
 
-    if let Some((inlets, outlets)) = inlets_outlets {
-        println!("  Inlets:");
-        for e in &inlets.list {
-            println!("    Inlet:");
-            println!("      Listen Address: {}", e.bind_addr);
-            if let Some(r) = Route::parse(e.outlet_route.as_ref()) {
-                if let Some(ma) = route_to_multiaddr(&r) {
-                    println!("      Route To Outlet: {ma}");
+        if let Some(list) = &self.inlets {
+            write!(w, "\n  Inlets:")?;
+            for e in list {
+                write!(w, "\n    Inlet:")?;
+                write!(w, "\n      Listen Address: {}", e.listen_address)?;
+                if let Some(ma) = &e.route_to_outlet {
+                    write!(w, "\n      Route To Outlet: {ma}")?;
                 }
             }
         }
-        println!("  Outlets:");
-        for e in &outlets.list {
-            println!("    Outlet:");
-            println!("      Forward Address: {}", e.tcp_addr);
 
-            if let Some(ma) = addr_to_multiaddr(e.worker_addr.as_ref()) {
-                println!("      Address: {ma}");
+        if let Some(list) = &self.outlets {
+            write!(w, "\n  Outlets:")?;
+            for e in list {
+                write!(w, "\n    Outlet:")?;
+                write!(w, "\n      Forward Address: {}", e.forward_address)?;
+                if let Some(ma) = &e.address {
+                    write!(w, "\n      Address: {ma}")?;
+                }
             }
         }
+
+        if let Some(list) = &self.services {
+            write!(w, "\n  Services:")?;
+            for e in list {
+                write!(w, "\n    Service:")?;
+                write!(w, "\n      Type: {}", e.service_type)?;
+                if let Some(ma) = &e.address {
+                    write!(w, "\n      Address: {ma}")?;
+                }
+            }
+        }
+        Ok(w)
     }
+}
 
-    if let Some(list) = services {
-        println!("  Services:");
-        for e in &list.list {
-            println!("    Service:");
-            println!("      Type: {}", e.service_type);
-            if let Some(ma) = addr_to_multiaddr(e.addr.as_ref()) {
-                println!("      Address: {ma}");
+impl Output for Vec<NodeDetails> {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut w = String::new();
+        for (i, details) in self.iter().enumerate() {
+            if i > 0 {
+                writeln!(w)?;
             }
+            write!(w, "{}", details.output()?)?;
         }
+        Ok(w)
     }
 }
 
@@ -152,62 +319,113 @@ pub async fn print_query_status(
     wait_until_ready: bool,
     is_default: bool,
 ) -> anyhow::Result<()> {
+    let details = query_node_details(rpc, node_name, wait_until_ready, is_default).await?;
+    println!("{}", details.output()?);
+    Ok(())
+}
+
+/// Query the status of a node, and the resources it's running if it's up
+pub async fn query_node_details(
+    rpc: &mut Rpc<'_>,
+    node_name: &str,
+    wait_until_ready: bool,
+    is_default: bool,
+) -> anyhow::Result<NodeDetails> {
     let cli_state = cli_state::CliState::new()?;
     let node_state = cli_state.nodes.get(node_name)?;
     if !is_node_up(rpc, wait_until_ready).await? {
         let node_port = node_state.setup()?.default_tcp_listener()?.addr.port();
-        print_node_info(
-            node_port, node_name, is_default, false, None, None, None, None, None,
-        );
-    } else {
-        // Get short id for the node
-        let default_id = match node_state.config.identity_config() {
-            Ok(resp) => resp.identifier.to_string(),
-            Err(_) => String::from("None"),
-        };
+        return Ok(NodeDetails::down(node_name, is_default, node_port));
+    }
 
-        // Get list of services for the node
-        let mut rpc = rpc.clone();
-        rpc.request(api::list_services()).await?;
-        let services = rpc.parse_response::<ServiceList>()?;
+    // Get short id for the node
+    let default_id = match node_state.config.identity_config() {
+        Ok(resp) => resp.identifier.to_string(),
+        Err(_) => String::from("None"),
+    };
 
-        // Get list of TCP listeners for node
-        let mut rpc = rpc.clone();
-        rpc.request(api::list_tcp_listeners()).await?;
-        let tcp_listeners = rpc.parse_response::<TransportList>()?;
+    // Get list of services for the node
+    let mut rpc = rpc.clone();
+    rpc.request(api::list_services()).await?;
+    let services = rpc.parse_response::<ServiceList>()?;
 
-        // Get list of Secure Channel Listeners
-        let mut rpc = rpc.clone();
-        rpc.request(api::list_secure_channel_listener()).await?;
-        let secure_channel_listeners = rpc.parse_response::<Vec<String>>()?;
+    // Get list of TCP listeners for node
+    let mut rpc = rpc.clone();
+    rpc.request(api::list_tcp_listeners()).await?;
+    let tcp_listeners = rpc.parse_response::<TransportList>()?;
 
-        // Get list of inlets
-        let mut rpc = rpc.clone();
-        rpc.request(api::list_inlets()).await?;
-        let inlets = rpc.parse_response::<InletList>()?;
+    // Get list of Secure Channel Listeners
+    let mut rpc = rpc.clone();
+    rpc.request(api::list_secure_channel_listener()).await?;
+    let secure_channel_listeners = rpc.parse_response::<Vec<String>>()?;
 
-        // Get list of outlets
-        let mut rpc = rpc.clone();
-        rpc.request(api::list_outlets()).await?;
-        let outlets = rpc.parse_response::<OutletList>()?;
+    // Get list of inlets
+    let mut rpc = rpc.clone();
+    rpc.request(api::list_inlets()).await?;
+    let inlets = rpc.parse_response::<InletList>()?;
 
-        let node_state = cli_state.nodes.get(node_name)?;
-        let node_port = node_state.setup()?.default_tcp_listener()?.addr.port();
+    // Get list of outlets
+    let mut rpc = rpc.clone();
+    rpc.request(api::list_outlets()).await?;
+    let outlets = rpc.parse_response::<OutletList>()?;
 
-        print_node_info(
-            node_port,
-            node_name,
-            is_default,
-            true,
-            Some(&default_id),
-            Some(&services),
-            Some(&tcp_listeners),
-            Some(&secure_channel_listeners),
-            Some((&inlets, &outlets)),
-        );
-    }
+    let node_state = cli_state.nodes.get(node_name)?;
+    let node_port = node_state.setup()?.default_tcp_listener()?.addr.port();
 
-    Ok(())
+    Ok(NodeDetails {
+        status: "UP".to_string(),
+        identity: Some(default_id),
+        transports: Some(
+            tcp_listeners
+                .list
+                .iter()
+                .map(|e| TransportDetails {
+                    transport_type: e.tt.to_string(),
+                    mode: e.tm.to_string(),
+                    address: e.payload.to_string(),
+                })
+                .collect(),
+        ),
+        secure_channel_listeners: Some(
+            secure_channel_listeners
+                .iter()
+                .filter_map(|e| addr_to_multiaddr(e).map(|ma| ma.to_string()))
+                .collect(),
+        ),
+        inlets: Some(
+            inlets
+                .list
+                .iter()
+                .map(|e| InletDetails {
+                    listen_address: e.bind_addr.to_string(),
+                    route_to_outlet: Route::parse(e.outlet_route.as_ref())
+                        .and_then(|r| route_to_multiaddr(&r))
+                        .map(|ma| ma.to_string()),
+                })
+                .collect(),
+        ),
+        outlets: Some(
+            outlets
+                .list
+                .iter()
+                .map(|e| OutletDetails {
+                    forward_address: e.tcp_addr.to_string(),
+                    address: addr_to_multiaddr(e.worker_addr.as_ref()).map(|ma| ma.to_string()),
+                })
+                .collect(),
+        ),
+        services: Some(
+            services
+                .list
+                .iter()
+                .map(|e| ServiceDetails {
+                    service_type: e.service_type.to_string(),
+                    address: addr_to_multiaddr(e.addr.as_ref()).map(|ma| ma.to_string()),
+                })
+                .collect(),
+        ),
+        ..NodeDetails::down(node_name, is_default, node_port)
+    })
 }
 
 /// Send message(s) to a node to determine if it is 'up' and
@@ -257,3 +475,22 @@ async fn is_node_up(rpc: &mut Rpc<'_>, wait_until_ready: bool) -> anyhow::Result
     }
     Ok(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_matching() {
+        assert!(glob_match("n*", "n1"));
+        assert!(glob_match("n*", "n"));
+        assert!(glob_match("*", "anything"));
+        assert!(glob_match("a*c", "abbbc"));
+        assert!(glob_match("a?c", "abc"));
+        assert!(glob_match("*b*", "abc"));
+        assert!(!glob_match("n*", "m1"));
+        assert!(!glob_match("a?c", "ac"));
+        assert!(!glob_match("a*c", "abd"));
+        assert!(!glob_match("abc", "abcd"));
+    }
+}
@@ -47,27 +47,54 @@ async fn run_impl(
     }
     node_state.kill_process(false)?;
     let node_setup = node_state.setup()?;
+    let addr = match node_setup.has_api_transport() {
+        true => Some(node_setup.default_tcp_listener()?.addr.to_string()),
+        false => None,
+    };
 
-    // Restart node
-    spawn_node(
+    start_node(
+        &ctx,
         &opts,
-        node_setup.verbose, // Previously user-chosen verbosity level
-        node_name,          // The selected node name
-        &node_setup.default_tcp_listener()?.addr.to_string(), // The selected node api address
-        None,               // No project information available
-        None,               // No invitation code available
-        None,               // No trusted identities
-        None,               // "
-        None,               // "
-        None,               // No launch config available
+        node_name,
+        node_setup.verbose,
+        addr.as_deref(),
+        None,
+    )
+    .await
+}
+
+/// Spawn a stopped node with its previous verbosity and api address, and print its status
+pub(super) async fn start_node(
+    ctx: &ockam::Context,
+    opts: &CommandGlobalOpts,
+    node_name: &str,
+    verbose: u8,
+    addr: Option<&str>,
+    launch_config: Option<String>,
+) -> crate::Result<()> {
+    spawn_node(
+        opts,
+        verbose,       // Previously user-chosen verbosity level
+        node_name,     // The selected node name
+        addr,          // The selected node api address
+        None,          // No project information available
+        None,          // No invitation code available
+        None,          // No trusted identities
+        None,          // "
+        None,          // "
+        launch_config, // The services the node was launched with, if known
     )?;
 
     // Print node status
-    let tcp = TcpTransport::create(&ctx).await?;
-    let mut rpc = RpcBuilder::new(&ctx, &opts, node_name).tcp(&tcp)?.build();
+    if addr.is_none() {
+        println!("Started node '{node_name}' without an API transport");
+        return Ok(());
+    }
+    let tcp = TcpTransport::create(ctx).await?;
+    let mut rpc = RpcBuilder::new(ctx, opts, node_name).tcp(&tcp)?.build();
     let mut is_default = false;
     if let Ok(state) = opts.state.nodes.default() {
-        is_default = &state.config.name == node_name;
+        is_default = state.config.name == node_name;
     }
     print_query_status(&mut rpc, node_name, true, is_default).await?;
 
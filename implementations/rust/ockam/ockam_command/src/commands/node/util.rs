@@ -2,10 +2,12 @@ use std::env::current_exe;
 use std::fs::OpenOptions;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
 
 use anyhow::{anyhow, Context as _};
 use ockam::identity::credential::OneTimeCode;
-use ockam::identity::{Identity, PublicIdentity};
+use ockam::identity::PublicIdentity;
 use ockam::{Context, TcpTransport};
 use ockam_api::cli_state;
 use ockam_api::config::cli;
@@ -21,13 +23,91 @@ use ockam_core::AllowAll;
 use ockam_multiaddr::MultiAddr;
 use ockam_vault::Vault;
 use rand::random;
+use tracing_subscriber::filter::LevelFilter;
 
+use crate::commands::identity::{create_identity, KeyType};
 use crate::commands::node::CreateCommand;
 use crate::commands::project;
 use crate::config::project::ProjectInfo;
 use crate::util::api::ProjectOpts;
+use crate::util::{level_log_filter, verbose_level};
 use crate::{CommandGlobalOpts, OckamConfig};
 
+/// The embedded node shared by all the RPCs of a command.
+///
+/// Starting an embedded node with the vault, identity and project of the node
+/// already started by the command returns it instead of creating a new one, and
+/// the node is only deleted when the command ends, once the last copy of the
+/// handle is dropped. This way, a command calling several functions that each
+/// start and delete an embedded node around their requests only creates a single one.
+#[derive(Clone, Debug)]
+pub struct EmbeddedNodeHandle {
+    inner: Arc<Mutex<EmbeddedNodes>>,
+}
+
+#[derive(Debug)]
+struct EmbeddedNodes {
+    state: cli_state::CliState,
+    node: Option<SharedEmbeddedNode>,
+}
+
+#[derive(Debug)]
+struct SharedEmbeddedNode {
+    name: String,
+    key: EmbeddedNodeKey,
+}
+
+/// The vault, identity and project an embedded node is started with
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct EmbeddedNodeKey {
+    vault: Option<String>,
+    identity: Option<String>,
+    /// The project file, if the node is started with project options
+    project: Option<Option<PathBuf>>,
+}
+
+impl EmbeddedNodeHandle {
+    pub(crate) fn new(state: cli_state::CliState) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(EmbeddedNodes { state, node: None })),
+        }
+    }
+
+    /// The name of the running embedded node, if it was started with the same
+    /// vault, identity and project
+    fn get(&self, key: &EmbeddedNodeKey) -> anyhow::Result<Option<String>> {
+        match &self.inner.lock().unwrap().node {
+            Some(node) if &node.key == key => Ok(Some(node.name.clone())),
+            Some(node) => Err(anyhow!(
+                "The embedded node {} was started with another vault, identity or project",
+                node.name
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Record an embedded node that was just started
+    fn started(&self, name: &str, key: EmbeddedNodeKey) {
+        self.inner.lock().unwrap().node = Some(SharedEmbeddedNode {
+            name: name.to_string(),
+            key,
+        });
+    }
+
+    /// Whether the node was started through this handle, which deletes it
+    pub(crate) fn is_shared(&self, name: &str) -> bool {
+        matches!(&self.inner.lock().unwrap().node, Some(node) if node.name == name)
+    }
+}
+
+impl Drop for EmbeddedNodes {
+    fn drop(&mut self) {
+        if let Some(node) = &self.node {
+            let _ = self.state.nodes.delete(&node.name, false);
+        }
+    }
+}
+
 pub async fn start_embedded_node(
     ctx: &Context,
     opts: &CommandGlobalOpts,
@@ -43,12 +123,22 @@ pub async fn start_embedded_node_with_vault_and_identity(
     identity: Option<&String>,
     project_opts: Option<&ProjectOpts>,
 ) -> anyhow::Result<String> {
+    // Reuse the embedded node already started by this command
+    let key = EmbeddedNodeKey {
+        vault: vault.cloned(),
+        identity: identity.cloned(),
+        project: project_opts.map(|p| p.project_path.clone()),
+    };
+    if let Some(node_name) = opts.embedded_node.get(&key)? {
+        return Ok(node_name);
+    }
+
     let cfg = &opts.config;
     let cmd = CreateCommand::default();
 
     // This node was initially created as a foreground node
     if !cmd.child_process {
-        init_node_state(ctx, opts, &cmd.node_name, vault, identity).await?;
+        init_node_state(ctx, opts, &cmd.node_name, vault, identity, None).await?;
     }
 
     let project_id = if let Some(p) = project_opts {
@@ -96,6 +186,7 @@ pub async fn start_embedded_node_with_vault_and_identity(
     )
     .await?;
 
+    opts.embedded_node.started(&cmd.node_name, key);
     Ok(cmd.node_name.clone())
 }
 
@@ -139,14 +230,16 @@ pub(super) async fn init_node_state(
     node_name: &str,
     vault: Option<&String>,
     identity: Option<&String>,
+    key_type: Option<KeyType>,
 ) -> anyhow::Result<()> {
     // Get vault specified in the argument, or get the default
     let vault_state = if let Some(v) = vault {
         opts.state.vaults.get(v)?
     }
-    // Or get the default
-    else if let Ok(v) = opts.state.vaults.default() {
-        v
+    // Or get the default, if one was set. A default that can't be read
+    // is reported rather than silently replaced by a new vault
+    else if opts.state.vaults.default_path()?.exists() {
+        opts.state.vaults.default()?
     } else {
         let n = hex::encode(random::<[u8; 4]>());
         let c = cli_state::VaultConfig::from_name(&n)?;
@@ -161,14 +254,8 @@ pub(super) async fn init_node_state(
     else if let Ok(idt) = opts.state.identities.default() {
         idt
     } else {
-        let vault = vault_state.config.get().await?;
         let identity_name = hex::encode(random::<[u8; 4]>());
-        let identity = Identity::create_ext(
-            ctx,
-            &opts.state.identities.authenticated_storage().await?,
-            &vault,
-        )
-        .await?;
+        let identity = create_identity(ctx, opts, &vault_state.config, key_type).await?;
         let identity_config = cli_state::IdentityConfig::new(&identity).await;
         opts.state
             .identities
@@ -218,8 +305,12 @@ pub(super) async fn add_project_authority_from_project_info(
     }
 }
 
+/// Delete an embedded node, unless it's shared by the RPCs of the command: it's
+/// then deleted when the command ends.
 pub async fn delete_embedded_node(opts: &CommandGlobalOpts, name: &str) {
-    let _ = delete_node(opts, name, false);
+    if !opts.embedded_node.is_shared(name) {
+        let _ = delete_node(opts, name, false);
+    }
 }
 
 pub fn delete_node(opts: &CommandGlobalOpts, name: &str, force: bool) -> anyhow::Result<()> {
@@ -244,13 +335,26 @@ pub fn delete_all_nodes(opts: CommandGlobalOpts, force: bool) -> anyhow::Result<
     Ok(())
 }
 
-/// A utility function to spawn a new node into foreground mode
+/// Tracing filter directives of a node spawned by a process with the given verbosity,
+/// and the given value of the OCKAM_LOG env variable
+fn child_log_filter(verbose: u8, ockam_log: Option<String>) -> String {
+    match verbose_level(verbose) {
+        Some(level) => level_log_filter(level),
+        None => ockam_log
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| level_log_filter(LevelFilter::DEBUG)),
+    }
+}
+
+/// A utility function to spawn a new node into foreground mode.
+/// The node listens for API requests on `address`, or doesn't create
+/// any TCP listener without it.
 #[allow(clippy::too_many_arguments)]
 pub fn spawn_node(
     opts: &CommandGlobalOpts,
     verbose: u8,
     name: &str,
-    address: &str,
+    address: Option<&str>,
     project: Option<&Path>,
     invite: Option<&OneTimeCode>,
     trusted_identities: Option<&String>,
@@ -278,19 +382,36 @@ pub fn spawn_node(
         .open(elog)
         .context("failed to open stderr log path")?;
 
+    // The child logs at its recorded log level if any, otherwise with the exact filter
+    // of this process, or at the debug level by default. The verbosity is recorded for
+    // the node to be restarted with the same one.
+    let setup = node_state.update_setup(|s| s.set_verbose(verbose))?;
+    let log_level = setup
+        .log_level()
+        .and_then(|l| LevelFilter::from_str(l).ok());
+    let log_filter = match log_level {
+        Some(level) => level_log_filter(level),
+        None => child_log_filter(verbose, std::env::var("OCKAM_LOG").ok()),
+    };
+
     let mut args = vec![
-        match verbose {
-            0 => "-vv".to_string(),
-            v => format!("-{}", "v".repeat(v as usize)),
-        },
         "--no-color".to_string(),
         "node".to_string(),
         "create".to_string(),
-        "--tcp-listener-address".to_string(),
-        address.to_string(),
         "--foreground".to_string(),
         "--child-process".to_string(),
     ];
+    if setup.json_logs() {
+        args.push("--json-logs".to_string());
+    }
+
+    match address {
+        Some(address) => {
+            args.push("--tcp-listener-address".to_string());
+            args.push(address.to_string());
+        }
+        None => args.push("--no-api-transport".to_string()),
+    }
 
     if let Some(path) = project {
         args.push("--project".to_string());
@@ -333,6 +454,7 @@ pub fn spawn_node(
 
     let child = Command::new(ockam_exe)
         .args(args)
+        .env("OCKAM_LOG", log_filter)
         .stdout(main_log_file)
         .stderr(stderr_log_file)
         .spawn()?;
@@ -340,3 +462,77 @@ pub fn spawn_node(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::util::log_filter;
+    use crate::OckamCommand;
+
+    #[ockam_macros::test(crate = "ockam")]
+    async fn embedded_node_is_shared_by_a_command(ctx: &mut Context) -> ockam::Result<()> {
+        let state = cli_state::CliState::test()?;
+        let cmd = OckamCommand::parse_from(["ockam", "node", "list"]);
+        let opts = CommandGlobalOpts::new(cmd.global_args, OckamConfig::load().unwrap()).unwrap();
+
+        // Several calls in a row, each starting and deleting an embedded node,
+        // share a single node which is kept between the calls
+        let name = start_embedded_node(ctx, &opts, None).await.unwrap();
+        delete_embedded_node(&opts, &name).await;
+        for _ in 0..3 {
+            assert_eq!(name, start_embedded_node(ctx, &opts, None).await.unwrap());
+            delete_embedded_node(&opts, &name).await;
+            assert_eq!(1, state.nodes.list()?.len());
+        }
+
+        // The node isn't returned to a call asking for another identity
+        let identity = "other".to_string();
+        let res =
+            start_embedded_node_with_vault_and_identity(ctx, &opts, None, Some(&identity), None)
+                .await;
+        assert!(res.is_err());
+
+        // It's deleted when the command ends
+        drop(opts);
+        assert!(state.nodes.list()?.is_empty());
+        ctx.stop().await
+    }
+
+    #[test]
+    fn child_logs_at_the_requested_level() {
+        let filter = |verbose, log_level, ockam_log| {
+            log_filter(verbose, log_level, ockam_log).map(|f| f.to_string())
+        };
+        for verbose in 1..=4 {
+            // The child is spawned without `-v` and reads the filter from OCKAM_LOG,
+            // which is ignored when the parent is verbose
+            let ockam_log = Some("ockam=error".to_string());
+            let child = child_log_filter(verbose, ockam_log.clone());
+            assert_eq!(
+                filter(0, None, Some(child)),
+                filter(verbose, None, ockam_log)
+            );
+        }
+
+        // A node with a log level logs like a process with this level
+        for level in [LevelFilter::ERROR, LevelFilter::INFO, LevelFilter::TRACE] {
+            let child = level_log_filter(level);
+            assert_eq!(filter(0, None, Some(child)), filter(0, Some(level), None));
+        }
+
+        // Otherwise the filter of OCKAM_LOG is passed on, or the child logs at the debug level
+        let ockam_log = Some("ockam_api=trace".to_string());
+        assert_eq!(child_log_filter(0, ockam_log), "ockam_api=trace");
+        assert_eq!(
+            child_log_filter(0, None),
+            level_log_filter(LevelFilter::DEBUG)
+        );
+        assert_eq!(filter(0, None, None), None);
+
+        // The crates outside OCKAM_CRATES log at the level of the verbosity too
+        let filter = filter(1, None, None).unwrap();
+        assert!(filter.split(',').any(|d| d == "info"));
+    }
+}
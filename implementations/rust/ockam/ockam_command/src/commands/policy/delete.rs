@@ -0,0 +1,63 @@
+use clap::Args;
+use ockam::Context;
+use ockam_abac::{Action, Resource};
+use ockam_api::nodes::models::policy::PolicyList;
+use ockam_core::api::Request;
+
+use super::{policy_path, PolicyListItem};
+use crate::util::{extract_address_value, node_rpc, Rpc};
+use crate::{CommandGlobalOpts, OutputFormat, Result};
+
+/// Delete the policies of a resource, or only the policy of one of its actions
+#[derive(Clone, Debug, Args)]
+pub struct DeleteCommand {
+    /// Node on which the policies are set
+    #[arg(long, display_order = 900, id = "NODE")]
+    at: String,
+
+    #[arg(short, long, value_parser = Resource::try_new)]
+    resource: Resource,
+
+    /// Only delete the policy of this action
+    #[arg(short, long, value_parser = Action::try_new)]
+    action: Option<Action>,
+}
+
+impl DeleteCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(rpc, (opts, self))
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, DeleteCommand)) -> Result<()> {
+    let node = extract_address_value(&cmd.at)?;
+    let path = match &cmd.action {
+        Some(action) => policy_path(&cmd.resource, action),
+        None => format!("/policy/{}", cmd.resource),
+    };
+    let mut rpc = Rpc::background(&ctx, &opts, &node)?;
+    rpc.request(Request::delete(path)).await?;
+    let deleted: PolicyList = rpc.parse_response()?;
+
+    // Deleting a policy which isn't set is not an error
+    if deleted.expressions().is_empty() && opts.global_args.output_format == OutputFormat::Plain {
+        match &cmd.action {
+            Some(action) => println!(
+                "No policy is set on action '{action}' of resource '{}', nothing to delete",
+                cmd.resource
+            ),
+            None => println!(
+                "No policy is set on resource '{}', nothing to delete",
+                cmd.resource
+            ),
+        }
+        return Ok(());
+    }
+    let items: Vec<PolicyListItem> = deleted
+        .expressions()
+        .iter()
+        .map(|(a, e)| PolicyListItem::new(&cmd.resource, a, e))
+        .collect();
+    rpc.print_response(items)?;
+    Ok(())
+}
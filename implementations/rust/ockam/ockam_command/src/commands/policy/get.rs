@@ -0,0 +1,39 @@
+use clap::Args;
+use ockam::Context;
+use ockam_abac::{Action, Resource};
+use ockam_api::nodes::models::policy::Policy;
+use ockam_core::api::Request;
+
+use super::policy_path;
+use crate::util::{extract_address_value, node_rpc, Rpc};
+use crate::{CommandGlobalOpts, Result};
+
+/// Show the policy of an action on a resource
+#[derive(Clone, Debug, Args)]
+pub struct GetCommand {
+    /// Node on which the policy is set
+    #[arg(long, display_order = 900, id = "NODE")]
+    at: String,
+
+    #[arg(short, long, value_parser = Resource::try_new)]
+    resource: Resource,
+
+    #[arg(short, long, value_parser = Action::try_new)]
+    action: Action,
+}
+
+impl GetCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(rpc, (opts, self))
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, GetCommand)) -> Result<()> {
+    let node = extract_address_value(&cmd.at)?;
+    let req = Request::get(policy_path(&cmd.resource, &cmd.action));
+    let mut rpc = Rpc::background(&ctx, &opts, &node)?;
+    rpc.request(req).await?;
+    let pol: Policy = rpc.parse_response()?;
+    println!("{}", pol.expression());
+    Ok(())
+}
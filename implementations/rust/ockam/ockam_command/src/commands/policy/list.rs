@@ -0,0 +1,53 @@
+use clap::Args;
+use ockam::Context;
+use ockam_abac::Resource;
+use ockam_api::nodes::models::policy::{PolicyList, ResourcePolicyList};
+use ockam_core::api::Request;
+
+use super::PolicyListItem;
+use crate::util::{extract_address_value, node_rpc, Rpc};
+use crate::{CommandGlobalOpts, Result};
+
+/// List the policies of a node, or only those of a resource
+#[derive(Clone, Debug, Args)]
+pub struct ListCommand {
+    /// Node on which the policies are set
+    #[arg(long, display_order = 900, id = "NODE")]
+    at: String,
+
+    /// Only list the policies of this resource
+    #[arg(short, long, value_parser = Resource::try_new)]
+    resource: Option<Resource>,
+}
+
+impl ListCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(rpc, (opts, self))
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, ListCommand)) -> Result<()> {
+    let node = extract_address_value(&cmd.at)?;
+    let mut rpc = Rpc::background(&ctx, &opts, &node)?;
+    let items: Vec<PolicyListItem> = match &cmd.resource {
+        Some(resource) => {
+            rpc.request(Request::get(format!("/policy/{resource}")))
+                .await?;
+            let pol: PolicyList = rpc.parse_response()?;
+            pol.expressions()
+                .iter()
+                .map(|(a, e)| PolicyListItem::new(resource, a, e))
+                .collect()
+        }
+        None => {
+            rpc.request(Request::get("/policy")).await?;
+            let pol: ResourcePolicyList = rpc.parse_response()?;
+            pol.policies()
+                .iter()
+                .map(|(r, a, e)| PolicyListItem::new(r, a, e))
+                .collect()
+        }
+    };
+    rpc.print_response(items)?;
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use clap::{Args, Subcommand};
+use ockam_abac::{Action, Expr, Resource};
+use serde::Serialize;
+
+use delete::DeleteCommand;
+use get::GetCommand;
+use list::ListCommand;
+use set::SetCommand;
+
+use crate::{help, CommandGlobalOpts};
+
+mod delete;
+mod get;
+mod list;
+mod set;
+
+const HELP_DETAIL: &str = "";
+
+#[derive(Clone, Debug, Args)]
+#[command(hide = help::hide(), after_long_help = help::template(HELP_DETAIL))]
+pub struct PolicyCommand {
+    #[command(subcommand)]
+    subcommand: PolicySubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum PolicySubcommand {
+    Set(SetCommand),
+    Get(GetCommand),
+    Delete(DeleteCommand),
+    List(ListCommand),
+}
+
+impl PolicyCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        match self.subcommand {
+            PolicySubcommand::Set(c) => c.run(opts),
+            PolicySubcommand::Get(c) => c.run(opts),
+            PolicySubcommand::Delete(c) => c.run(opts),
+            PolicySubcommand::List(c) => c.run(opts),
+        }
+    }
+}
+
+/// A policy attached to an action on a resource
+#[derive(Debug, Serialize)]
+pub struct PolicyListItem {
+    pub resource: String,
+    pub action: String,
+    pub expression: String,
+}
+
+impl PolicyListItem {
+    fn new(resource: &Resource, action: &Action, expression: &Expr) -> Self {
+        PolicyListItem {
+            resource: resource.to_string(),
+            action: action.to_string(),
+            expression: expression.to_string(),
+        }
+    }
+}
+
+fn policy_path(r: &Resource, a: &Action) -> String {
+    format!("/policy/{r}/{a}")
+}
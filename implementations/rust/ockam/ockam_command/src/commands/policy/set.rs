@@ -0,0 +1,41 @@
+use clap::Args;
+use ockam::Context;
+use ockam_abac::{Action, Expr, Resource};
+use ockam_api::nodes::models::policy::Policy;
+use ockam_core::api::Request;
+
+use super::policy_path;
+use crate::util::{extract_address_value, node_rpc, Rpc};
+use crate::{CommandGlobalOpts, Result};
+
+/// Set the policy of an action on a resource
+#[derive(Clone, Debug, Args)]
+pub struct SetCommand {
+    /// Node on which to set the policy
+    #[arg(long, display_order = 900, id = "NODE")]
+    at: String,
+
+    #[arg(short, long, value_parser = Resource::try_new)]
+    resource: Resource,
+
+    #[arg(short, long, default_value = "handle_message", value_parser = Action::try_new)]
+    action: Action,
+
+    #[arg(short, long)]
+    expression: Expr,
+}
+
+impl SetCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(rpc, (opts, self))
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, SetCommand)) -> Result<()> {
+    let node = extract_address_value(&cmd.at)?;
+    let bdy = Policy::new(cmd.expression);
+    let req = Request::post(policy_path(&cmd.resource, &cmd.action)).body(bdy);
+    let mut rpc = Rpc::background(&ctx, &opts, &node)?;
+    rpc.request(req).await?;
+    rpc.is_ok()
+}
@@ -1,6 +1,12 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context as _};
 use clap::Args;
+use colorful::Colorful;
 use ockam::Context;
 use ockam_api::cloud::project::Enroller;
+use ockam_multiaddr::MultiAddr;
+use serde::Deserialize;
 
 use crate::commands::node::util::delete_embedded_node;
 use crate::util::api::{self, CloudOpts};
@@ -16,17 +22,34 @@ pub struct AddEnrollerCommand {
     pub project_id: String,
 
     /// Identity id to add as an authorized enroller.
-    #[arg(display_order = 1002)]
-    pub enroller_identity_id: String,
+    #[arg(display_order = 1002, required_unless_present = "from_file")]
+    pub enroller_identity_id: Option<String>,
 
     /// Description of this enroller, optional.
-    #[arg(display_order = 1003)]
+    #[arg(display_order = 1003, requires = "enroller_identity_id")]
     pub description: Option<String>,
 
+    /// Path to a JSON file with an array of `{"identity_id": ..., "description": ...}`
+    /// objects, to add several enrollers at once.
+    #[arg(
+        long,
+        display_order = 1004,
+        value_name = "PATH",
+        conflicts_with = "enroller_identity_id"
+    )]
+    pub from_file: Option<PathBuf>,
+
     #[command(flatten)]
     pub cloud_opts: CloudOpts,
 }
 
+/// An entry of the `--from-file` enrollers list
+#[derive(Debug, Deserialize)]
+struct EnrollerEntry {
+    identity_id: String,
+    description: Option<String>,
+}
+
 impl AddEnrollerCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         node_rpc(rpc, (options, self));
@@ -46,8 +69,68 @@ async fn run_impl(
     cmd: AddEnrollerCommand,
 ) -> crate::Result<()> {
     let mut rpc = Rpc::embedded(ctx, &opts).await?;
-    rpc.request(api::project::add_enroller(&cmd)).await?;
-    rpc.parse_and_print_response::<Enroller>()?;
+    let route = cmd.cloud_opts.route();
+    let res = match (&cmd.from_file, &cmd.enroller_identity_id) {
+        (Some(path), _) => add_from_file(&mut rpc, &cmd.project_id, &route, path).await,
+        (None, Some(identity_id)) => {
+            let req = api::project::add_enroller(
+                &cmd.project_id,
+                identity_id,
+                cmd.description.as_deref(),
+                &route,
+            );
+            rpc.request(req).await?;
+            rpc.parse_and_print_response::<Enroller>()?;
+            Ok(())
+        }
+        // Prevented by clap's `required_unless_present`
+        (None, None) => unreachable!(),
+    };
     delete_embedded_node(&opts, rpc.node_name()).await;
+    res
+}
+
+/// Add every enroller listed in `path`, reporting failures at the end
+/// instead of stopping at the first one.
+async fn add_from_file(
+    rpc: &mut Rpc<'_>,
+    project_id: &str,
+    route: &MultiAddr,
+    path: &Path,
+) -> crate::Result<()> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read enrollers file {}", path.display()))?;
+    let entries: Vec<EnrollerEntry> = serde_json::from_str(&contents)
+        .with_context(|| format!("Invalid enrollers file {}", path.display()))?;
+
+    let mut failed = 0;
+    for entry in &entries {
+        let req = api::project::add_enroller(
+            project_id,
+            &entry.identity_id,
+            entry.description.as_deref(),
+            route,
+        );
+        let res = match rpc.request(req).await {
+            Ok(()) => rpc.is_ok(),
+            Err(e) => Err(e),
+        };
+        match res {
+            Ok(()) => println!("{} {}", "✔".light_green(), entry.identity_id),
+            Err(e) => {
+                failed += 1;
+                println!("{} {}: {e}", "✘".light_red(), entry.identity_id);
+            }
+        }
+    }
+
+    println!(
+        "\nAdded {} of {} enrollers",
+        entries.len() - failed,
+        entries.len()
+    );
+    if failed > 0 {
+        return Err(anyhow!("Failed to add {failed} enrollers").into());
+    }
     Ok(())
 }
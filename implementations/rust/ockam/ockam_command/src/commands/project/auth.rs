@@ -15,10 +15,11 @@ use tracing::debug;
 
 use crate::commands::enroll::{Auth0Provider, Auth0Service};
 use crate::commands::node::util::{delete_embedded_node, start_embedded_node};
+use crate::commands::project::enroll::parse_attributes;
 use crate::commands::project::util::create_secure_channel_to_authority;
 use crate::config::project::ProjectInfo;
 use crate::util::api::{CloudOpts, ProjectOpts};
-use crate::util::{node_rpc, RpcBuilder};
+use crate::util::{node_rpc, print_output, RpcBuilder};
 use crate::CommandGlobalOpts;
 
 /// Authenticate with a project node
@@ -30,6 +31,16 @@ pub struct AuthCommand {
     #[arg(long = "token", group = "authentication_method", value_name = "ENROLLMENT TOKEN", value_parser = OneTimeCode::from_str)]
     token: Option<OneTimeCode>,
 
+    /// Attributes in `key=value` format to request when redeeming the token.
+    /// The enroller must have allowed them when creating the token
+    #[arg(
+        short,
+        long = "attribute",
+        value_name = "ATTRIBUTE",
+        requires = "token"
+    )]
+    attributes: Vec<String>,
+
     #[command(flatten)]
     cloud_opts: CloudOpts,
 
@@ -105,9 +116,18 @@ async fn run_impl(
 
     let credential = match cmd.token {
         None => client.credential().await?,
-        Some(token) => client.credential_with(&token).await?,
+        Some(token) => {
+            let attributes = parse_attributes(&cmd.attributes)?;
+            let attributes = attributes
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str().into()))
+                .collect();
+            client
+                .credential_with_attributes(&token, attributes)
+                .await?
+        }
     };
-    println!("{credential}");
+    print_output(credential, &opts.global_args.output_format)?;
     delete_embedded_node(&opts, &node_name).await;
     Ok(())
 }
@@ -122,7 +142,7 @@ async fn authenticate_through_okta(
     // Get auth0 token
     let okta_config: OktaAuth0 = p.okta_config.context("Okta addon not configured")?.into();
     let auth0 = Auth0Service::new(Auth0Provider::Okta(okta_config));
-    let token = auth0.token().await?;
+    let token = auth0.token(opts).await?;
 
     // Return address to the "okta_authenticator" worker on the authority node through the secure channel
     let okta_authenticator_addr = {
@@ -13,7 +13,7 @@ use tracing::debug;
 
 use crate::commands::node::util::{delete_embedded_node, start_embedded_node};
 use crate::commands::node::NodeOpts;
-use crate::commands::project::util::create_secure_channel_to_authority;
+use crate::commands::project::util::secure_authority_address;
 use crate::util::api::{CloudOpts, ProjectOpts};
 use crate::util::{node_rpc, RpcBuilder};
 use crate::{CommandGlobalOpts, Result};
@@ -40,6 +40,15 @@ pub struct EnrollCommand {
     /// Attributes in `key=value` format to be attached to the member
     #[arg(short, long = "attribute", value_name = "ATTRIBUTE")]
     attributes: Vec<String>,
+
+    /// Attribute that the member redeeming the token can request with
+    /// `ockam project authenticate --attribute`
+    #[arg(
+        long = "allow-attribute",
+        value_name = "KEY",
+        conflicts_with = "member"
+    )]
+    allowed_attributes: Vec<String>,
 }
 
 impl EnrollCommand {
@@ -51,17 +60,22 @@ impl EnrollCommand {
     }
 
     fn attributes(&self) -> Result<HashMap<String, String>> {
-        let mut attributes = HashMap::new();
-        for attr in &self.attributes {
-            let mut parts = attr.splitn(2, '=');
-            let key = parts.next().context("key expected")?;
-            let value = parts.next().context("value expected)")?;
-            attributes.insert(key.to_string(), value.to_string());
-        }
-        Ok(attributes)
+        parse_attributes(&self.attributes)
     }
 }
 
+/// Parse attributes given in `key=value` format
+pub(crate) fn parse_attributes(attributes: &[String]) -> Result<HashMap<String, String>> {
+    let mut parsed = HashMap::new();
+    for attr in attributes {
+        let mut parts = attr.splitn(2, '=');
+        let key = parts.next().context("key expected")?;
+        let value = parts.next().context("value expected)")?;
+        parsed.insert(key.to_string(), value.to_string());
+    }
+    Ok(parsed)
+}
+
 struct Runner {
     ctx: Context,
     opts: CommandGlobalOpts,
@@ -77,24 +91,14 @@ impl Runner {
         let node_name =
             start_embedded_node(&self.ctx, &self.opts, Some(&self.cmd.project_opts)).await?;
 
-        let map = self.opts.config.lookup();
-        let to = if let Some(a) = project_authority(&self.cmd.to, &map)? {
-            let mut addr = create_secure_channel_to_authority(
-                &self.ctx,
-                &self.opts,
-                &node_name,
-                a,
-                &replace_project(&self.cmd.to, a.address())?,
-                self.cmd.cloud_opts.identity.clone(),
-            )
-            .await?;
-            for proto in self.cmd.to.iter().skip(1) {
-                addr.push_back_value(&proto).map_err(anyhow::Error::from)?
-            }
-            addr
-        } else {
-            self.cmd.to.clone()
-        };
+        let to = secure_authority_address(
+            &self.ctx,
+            &self.opts,
+            &node_name,
+            &self.cmd.to,
+            self.cmd.cloud_opts.identity.clone(),
+        )
+        .await?;
         let mut rpc = RpcBuilder::new(&self.ctx, &self.opts, &node_name)
             .to(&to)?
             .build();
@@ -110,8 +114,11 @@ impl Runner {
             rpc.is_ok()?;
         } else {
             debug!(addr = %to, attrs = ?self.cmd.attributes, "requesting token");
-            let req = Request::post("/tokens")
-                .body(CreateToken::new().with_attributes(self.cmd.attributes()?));
+            let req = Request::post("/tokens").body(
+                CreateToken::new()
+                    .with_attributes(self.cmd.attributes()?)
+                    .with_requestable_attributes(self.cmd.allowed_attributes.clone()),
+            );
             rpc.request(req).await?;
             let res: OneTimeCode = rpc.parse_response()?;
             println!("{}", res.to_string())
@@ -125,7 +132,7 @@ impl Runner {
 /// Get the project authority from the first address protocol.
 ///
 /// If the first protocol is a `/project`, look up the project's config.
-fn project_authority<'a>(
+pub(super) fn project_authority<'a>(
     input: &MultiAddr,
     map: &'a ConfigLookup,
 ) -> anyhow::Result<Option<&'a ProjectAuthority>> {
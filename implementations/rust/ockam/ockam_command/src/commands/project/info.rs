@@ -53,6 +53,7 @@ async fn run_impl(
     rpc.request(api::project::show(&id, controller_route))
         .await?;
     let info: ProjectInfo = rpc.parse_response::<Project>()?.into();
+    let info = info.with_authority_identifier().await;
     rpc.print_response(&info)?;
     delete_embedded_node(&opts, rpc.node_name()).await;
     Ok(())
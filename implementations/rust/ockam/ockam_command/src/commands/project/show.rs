@@ -5,6 +5,7 @@ use ockam_api::cloud::project::Project;
 
 use crate::commands::node::util::{delete_embedded_node, start_embedded_node};
 use crate::commands::project::util::config;
+use crate::config::project::ProjectInfo;
 use crate::util::api::{self, CloudOpts};
 use crate::util::{node_rpc, RpcBuilder};
 use crate::CommandGlobalOpts;
@@ -52,8 +53,10 @@ async fn run_impl(
     let mut rpc = RpcBuilder::new(ctx, &opts, &node_name).build();
     rpc.request(api::project::show(&id, controller_route))
         .await?;
-    let project = rpc.parse_and_print_response::<Project>()?;
+    let project = rpc.parse_response::<Project>()?;
     config::set_project(&opts.config, &project).await?;
+    let info = ProjectInfo::from(project).with_authority_identifier().await;
+    rpc.print_response(&info)?;
     delete_embedded_node(&opts, rpc.node_name()).await;
     Ok(())
 }
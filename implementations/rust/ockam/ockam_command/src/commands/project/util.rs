@@ -14,7 +14,7 @@ use ockam_core::api::Request;
 use ockam_multiaddr::{MultiAddr, Protocol};
 use tracing::debug;
 
-use crate::commands::project::enroll::replace_project;
+use crate::commands::project::enroll::{project_authority, replace_project};
 use crate::util::api::CloudOpts;
 use crate::util::{api, RpcBuilder};
 use crate::{CommandGlobalOpts, OckamConfig};
@@ -152,7 +152,36 @@ pub async fn create_secure_channel_to_authority(
     Ok(addr)
 }
 
-async fn delete_secure_channel<'a>(
+/// The address of `to`, going through a secure channel to the project
+/// authority if `to` starts with a `/project`.
+pub async fn secure_authority_address(
+    ctx: &ockam::Context,
+    opts: &CommandGlobalOpts,
+    node_name: &str,
+    to: &MultiAddr,
+    identity: Option<String>,
+) -> crate::Result<MultiAddr> {
+    let map = opts.config.lookup();
+    if let Some(a) = project_authority(to, &map)? {
+        let mut addr = create_secure_channel_to_authority(
+            ctx,
+            opts,
+            node_name,
+            a,
+            &replace_project(to, a.address())?,
+            identity,
+        )
+        .await?;
+        for proto in to.iter().skip(1) {
+            addr.push_back_value(&proto).map_err(anyhow::Error::from)?
+        }
+        Ok(addr)
+    } else {
+        Ok(to.clone())
+    }
+}
+
+pub(crate) async fn delete_secure_channel<'a>(
     ctx: &ockam::Context,
     opts: &CommandGlobalOpts,
     api_node: &str,
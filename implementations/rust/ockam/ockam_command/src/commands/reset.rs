@@ -9,12 +9,24 @@ use crate::CommandGlobalOpts;
 pub struct ResetCommand {
     #[arg(display_order = 901, long, short)]
     yes: bool,
+
+    /// Keep the identities and their vaults, so that new nodes can use them
+    #[arg(display_order = 902, long)]
+    keep_identities: bool,
+
+    /// Keep the vaults, deleting everything else
+    #[arg(display_order = 903, long)]
+    keep_vaults: bool,
+
+    /// Only remove the vaults, identities and nodes whose files are corrupt
+    #[arg(display_order = 904, long, conflicts_with_all = ["keep_identities", "keep_vaults"])]
+    repair: bool,
 }
 
 impl ResetCommand {
     pub fn run(self, opts: CommandGlobalOpts) {
-        if self.yes || get_user_confirmation() {
-            if let Err(e) = run_impl(opts) {
+        if self.yes || get_user_confirmation(&self) {
+            if let Err(e) = run_impl(opts, self) {
                 eprintln!("{e}");
                 std::process::exit(e.code());
             }
@@ -22,13 +34,39 @@ impl ResetCommand {
     }
 }
 
-fn run_impl(opts: CommandGlobalOpts) -> crate::Result<()> {
-    opts.state.delete(true)?;
+fn run_impl(opts: CommandGlobalOpts, cmd: ResetCommand) -> crate::Result<()> {
+    if cmd.repair {
+        let removed = opts.state.repair()?;
+        if removed.is_empty() {
+            println!("Nothing to repair");
+        }
+        for path in removed {
+            println!("Removed {}", path.display());
+        }
+    } else if cmd.keep_identities || cmd.keep_vaults {
+        // Identities can't be used without the vaults holding their keys,
+        // so keeping the identities keeps the vaults as well.
+        opts.state
+            .delete_keeping_vaults(cmd.keep_identities, true)?;
+        opts.config.remove_spaces_alias();
+        opts.config.remove_projects_alias();
+        opts.config.persist_config_updates()?;
+    } else {
+        opts.state.delete(true)?;
+    }
     Ok(())
 }
 
-fn get_user_confirmation() -> bool {
-    let prompt = "Please confirm the you really want a full reset (y/N) ";
+fn get_user_confirmation(cmd: &ResetCommand) -> bool {
+    let prompt = if cmd.repair {
+        "Please confirm that you really want to delete the corrupt vaults, identities and nodes (y/N) "
+    } else if cmd.keep_identities {
+        "Please confirm that you really want to delete all nodes and projects, keeping identities and vaults (y/N) "
+    } else if cmd.keep_vaults {
+        "Please confirm that you really want to delete all nodes, projects and identities, keeping vaults (y/N) "
+    } else {
+        "Please confirm that you really want a full reset (y/N) "
+    };
     print!("{prompt}");
     if io::stdout().flush().is_err() {
         // If stdout wasn't flushed properly, fallback to println
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use anyhow::Context as _;
 use clap::Args;
 use colorful::Colorful;
@@ -15,6 +17,7 @@ use ockam_multiaddr::MultiAddr;
 use serde_json::json;
 
 use super::HELP_DETAIL;
+use crate::commands::credential::read_credential;
 use crate::util::api::CloudOpts;
 use crate::util::{exitcode, extract_address_value, is_tty, node_rpc, RpcBuilder};
 use crate::{help, CommandGlobalOpts, OutputFormat, Result};
@@ -35,6 +38,11 @@ pub struct CreateCommand {
     #[arg(value_name = "IDENTIFIER", long, short, display_order = 801)]
     pub authorized: Option<Vec<IdentityIdentifier>>,
 
+    /// File containing the credential to present to the listener once the channel is
+    /// created, instead of the credential of the node
+    #[arg(value_name = "PATH", long, display_order = 802)]
+    pub credential: Option<PathBuf>,
+
     /// Orchestrator address to resolve projects present in the `at` argument
     #[command(flatten)]
     cloud_opts: CloudOpts,
@@ -83,7 +91,7 @@ impl CreateCommand {
         parsed_to: &MultiAddr,
         options: &CommandGlobalOpts,
         response: CreateSecureChannelResponse,
-    ) {
+    ) -> Result<()> {
         let route = &route![response.addr.to_string()];
         match route_to_multiaddr(route) {
             Some(multiaddr) => {
@@ -93,10 +101,13 @@ impl CreateCommand {
                     println!("{multiaddr}")
                 }
 
-                // if output format is json, write json to stdout.
+                // if output format is json or yaml, write it to stdout.
                 if options.global_args.output_format == OutputFormat::Json {
                     let json = json!([{ "address": multiaddr.to_string() }]);
                     println!("{json}");
+                } else if options.global_args.output_format == OutputFormat::Yaml {
+                    let json = json!([{ "address": multiaddr.to_string() }]);
+                    print!("{}", serde_yaml::to_string(&json)?);
                 }
 
                 // if stderr is interactive/tty and we haven't been asked to be quiet
@@ -145,6 +156,7 @@ impl CreateCommand {
                 std::process::exit(exitcode::PROTOCOL);
             }
         };
+        Ok(())
     }
 }
 
@@ -158,22 +170,26 @@ async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, CreateCommand)) -> R
         .await?;
 
     let authorized_identifiers = cmd.authorized.clone();
+    let credential = cmd.credential.as_deref().map(read_credential).transpose()?;
 
     // Delegate the request to create a secure channel to the from node.
     let mut rpc = RpcBuilder::new(&ctx, &opts, from).tcp(&tcp)?.build();
 
-    let payload = models::secure_channel::CreateSecureChannelRequest::new(
+    let mut payload = models::secure_channel::CreateSecureChannelRequest::new(
         to,
         authorized_identifiers,
         CredentialExchangeMode::Mutual,
         cmd.cloud_opts.identity.clone(),
     );
+    if let Some(credential) = credential {
+        payload = payload.with_credential(credential);
+    }
     let request = Request::post("/node/secure_channel").body(payload);
 
     rpc.request(request).await?;
     let response = rpc.parse_response::<CreateSecureChannelResponse>()?;
 
-    cmd.print_output(from, to, &opts, response);
+    cmd.print_output(from, to, &opts, response)?;
 
     Ok(())
 }
@@ -41,7 +41,7 @@ impl DeleteCommand {
         address: &Address,
         options: &CommandGlobalOpts,
         response: DeleteSecureChannelResponse,
-    ) {
+    ) -> Result<()> {
         match response.channel {
             Some(address) => {
                 let route = &route![address.to_string()];
@@ -53,10 +53,13 @@ impl DeleteCommand {
                             println!("{multiaddr}")
                         }
 
-                        // if output format is json, write json to stdout.
+                        // if output format is json or yaml, write it to stdout.
                         if options.global_args.output_format == OutputFormat::Json {
                             let json = json!([{ "address": multiaddr.to_string() }]);
                             println!("{json}");
+                        } else if options.global_args.output_format == OutputFormat::Yaml {
+                            let json = json!([{ "address": multiaddr.to_string() }]);
+                            print!("{}", serde_yaml::to_string(&json)?);
                         }
 
                         // if stderr is interactive/tty and we haven't been asked to be quiet
@@ -101,21 +104,14 @@ impl DeleteCommand {
                 }
             }
             None => {
-                // if stderr is interactive/tty and we haven't been asked to be quiet
-                // and output format is plain then write a plain info to stderr.
-                if is_tty(std::io::stderr())
-                    && !options.global_args.quiet
-                    && options.global_args.output_format == OutputFormat::Plain
-                {
-                    eprintln!(
-                        "Could not find secure channel with address {} at node {}",
-                        address, &self.at
-                    );
-                }
+                eprintln!("No such secure channel: {} at node {}", address, &self.at);
 
-                println!("channel with address {address} not found")
+                // return the exitcode::UNAVAILABLE since the node has no
+                // secure channel with the given address.
+                std::process::exit(exitcode::UNAVAILABLE);
             }
         }
+        Ok(())
     }
 }
 
@@ -145,7 +141,7 @@ async fn rpc(ctx: Context, (options, command): (CommandGlobalOpts, DeleteCommand
     rpc.request(request).await?;
     let response = rpc.parse_response::<DeleteSecureChannelResponse>()?;
 
-    command.print_output(at, address, &options, response);
+    command.print_output(at, address, &options, response)?;
 
     Ok(())
 }
@@ -73,10 +73,15 @@ impl ListCommand {
                 println!("{at}")
             }
 
-            // if output format is json, write json to stdout.
+            // if output format is json or yaml, write it to stdout.
             if options.global_args.output_format == OutputFormat::Json {
                 let json = json!([{ "address": at }]);
                 println!("{json}");
+            } else if options.global_args.output_format == OutputFormat::Yaml {
+                let json = json!([{ "address": at }]);
+                let yaml = serde_yaml::to_string(&json)
+                    .map_err(|e| format!("Failed to serialize output -- {e}"))?;
+                print!("{yaml}");
             }
 
             // if stderr is interactive/tty and we haven't been asked to be quiet
@@ -2,6 +2,7 @@ use anyhow::anyhow;
 use clap::Args;
 use ockam::identity::IdentityIdentifier;
 use ockam::Context;
+use ockam_api::cli_state::SecureChannelListenerConfig;
 use ockam_api::nodes::models::secure_channel::CreateSecureChannelListenerRequest;
 use ockam_api::nodes::NODEMANAGER_ADDR;
 use ockam_core::api::{Request, Status};
@@ -58,13 +59,22 @@ async fn run_impl(
     let req = Request::post("/node/secure_channel_listener").body(
         CreateSecureChannelListenerRequest::new(
             &cmd.address,
-            cmd.authorized_identifiers,
-            cmd.identity,
+            cmd.authorized_identifiers.clone(),
+            cmd.identity.clone(),
         ),
     );
     rpc.request(req).await?;
     match rpc.is_ok() {
         Ok(_) => {
+            // Record the listener so that it's re-created, with the same
+            // authorized identifiers, when the node is restarted
+            let node_state = opts.state.nodes.get(&node)?;
+            let listener = SecureChannelListenerConfig::new(
+                cmd.address.address(),
+                cmd.authorized_identifiers,
+                cmd.identity,
+            );
+            node_state.update_setup(|s| s.add_secure_channel_listener(listener))?;
             println!("/service/{}", cmd.address.address());
             Ok(())
         }
@@ -0,0 +1,73 @@
+use anyhow::anyhow;
+use clap::Args;
+use ockam::Context;
+use ockam_core::api::Status;
+use ockam_core::Address;
+use serde::Serialize;
+
+use crate::commands::node::default_node_name;
+use crate::commands::secure_channel::HELP_DETAIL;
+use crate::util::output::Output;
+use crate::util::{api, exitcode, extract_address_value, node_rpc, print_output, Rpc};
+use crate::{help, CommandGlobalOpts};
+
+/// Delete Secure Channel Listeners
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, after_long_help = help::template(HELP_DETAIL))]
+pub struct DeleteCommand {
+    /// Node at which the listener was created
+    #[arg(long, value_name = "NODE", default_value_t = default_node_name())]
+    at: String,
+
+    /// Address of the listener to delete
+    address: Address,
+}
+
+impl DeleteCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(rpc, (opts, self));
+    }
+}
+
+/// A deleted secure channel listener
+#[derive(Serialize)]
+struct DeletedListener {
+    node: String,
+    address: String,
+}
+
+impl Output for DeletedListener {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!(
+            "Secure channel listener `{}` successfully deleted from node {}",
+            self.address, self.node
+        ))
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, DeleteCommand)) -> crate::Result<()> {
+    let node = extract_address_value(&cmd.at)?;
+    let address = cmd.address.address().to_string();
+
+    let mut rpc = Rpc::background(&ctx, &opts, &node)?;
+    rpc.request(api::delete_secure_channel_listener(&cmd.address))
+        .await?;
+    let (res, _) = rpc.check_response()?;
+    if res.status() == Some(Status::NotFound) {
+        return Err(crate::Error::new(
+            exitcode::UNAVAILABLE,
+            anyhow!("No secure channel listener at address `{address}` on node {node}"),
+        ));
+    }
+    rpc.is_ok()?;
+
+    // The listener must not be re-created when the node is restarted
+    let node_state = opts.state.nodes.get(&node)?;
+    node_state.update_setup(|s| s.remove_secure_channel_listener(&address))?;
+
+    print_output(
+        DeletedListener { node, address },
+        &opts.global_args.output_format,
+    )?;
+    Ok(())
+}
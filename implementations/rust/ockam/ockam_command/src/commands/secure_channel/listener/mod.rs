@@ -1,8 +1,10 @@
 pub mod create;
+pub mod delete;
 pub mod list;
 
 use clap::{Args, Subcommand};
 pub(crate) use create::CreateCommand;
+pub(crate) use delete::DeleteCommand;
 pub(crate) use list::ListCommand;
 
 use crate::commands::secure_channel::HELP_DETAIL;
@@ -25,6 +27,8 @@ pub enum SecureChannelListenerSubcommand {
     #[command(display_order = 800)]
     Create(CreateCommand),
     #[command(display_order = 800)]
+    Delete(DeleteCommand),
+    #[command(display_order = 800)]
     List(ListCommand),
 }
 
@@ -32,6 +36,7 @@ impl SecureChannelListenerCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         match self.subcommand {
             SecureChannelListenerSubcommand::Create(c) => c.run(options),
+            SecureChannelListenerSubcommand::Delete(c) => c.run(options),
             SecureChannelListenerSubcommand::List(c) => c.run(options),
         }
     }
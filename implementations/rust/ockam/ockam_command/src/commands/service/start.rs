@@ -1,6 +1,9 @@
+use std::time::Duration;
+
 use anyhow::{anyhow, Result};
 use clap::{Args, Subcommand};
 use minicbor::Encode;
+use ockam::identity::credential::MAX_CREDENTIAL_VALIDITY;
 use ockam::{Context, TcpTransport};
 use ockam_api::nodes::models::services::{
     StartKafkaConsumerRequest,
@@ -16,7 +19,7 @@ use ockam_multiaddr::MultiAddr;
 
 use crate::commands::node::NodeOpts;
 use crate::config::service::OktaIdentityProviderConfig;
-use crate::util::{api, node_rpc, RpcBuilder};
+use crate::util::{api, node_rpc, parse_duration, RpcBuilder};
 use crate::{help, CommandGlobalOpts};
 
 /// Start a specified service
@@ -66,6 +69,19 @@ pub enum StartSubCommand {
 
         #[arg(long)]
         reload_enrollers: bool,
+
+        /// How long enrollment tokens stay valid, in seconds
+        #[arg(long, value_name = "SECONDS")]
+        token_duration: Option<u64>,
+
+        /// How long issued credentials stay valid, e.g. `12h` or `7d`. Defaults to one day
+        #[arg(long, value_name = "DURATION", value_parser = parse_credential_ttl)]
+        credential_ttl: Option<Duration>,
+
+        /// Attribute of the enrollers copied to the members they enroll,
+        /// replacing any value given for it (can be repeated)
+        #[arg(long = "inherit-attribute", value_name = "NAME")]
+        inherited_attributes: Vec<String>,
     },
     #[command(hide = help::hide())]
     KafkaConsumer {
@@ -179,7 +195,9 @@ async fn run_impl(
             enrollers,
             reload_enrollers,
             project,
-            ..
+            token_duration,
+            credential_ttl,
+            inherited_attributes,
         } => {
             start_authenticator_service(
                 ctx,
@@ -189,6 +207,9 @@ async fn run_impl(
                 &enrollers,
                 reload_enrollers,
                 &project,
+                token_duration,
+                credential_ttl.map(|d| d.as_secs()),
+                inherited_attributes,
                 Some(&tcp),
             )
             .await?
@@ -307,6 +328,17 @@ pub async fn start_verifier_service(
     start_service_impl(ctx, opts, node_name, serv_addr, "Verifier", req, tcp).await
 }
 
+fn parse_credential_ttl(input: &str) -> Result<Duration> {
+    let ttl = parse_duration(input)?;
+    if ttl > MAX_CREDENTIAL_VALIDITY {
+        return Err(anyhow!(
+            "credential ttl can't exceed {} days",
+            MAX_CREDENTIAL_VALIDITY.as_secs() / (24 * 3600)
+        ));
+    }
+    Ok(ttl)
+}
+
 /// Public so `ockam_command::node::create` can use it.
 #[allow(clippy::too_many_arguments)]
 pub async fn start_authenticator_service(
@@ -317,9 +349,20 @@ pub async fn start_authenticator_service(
     enrollers: &str,
     reload_enrollers: bool,
     project: &str,
+    token_duration: Option<u64>,
+    credential_ttl: Option<u64>,
+    inherited_attributes: Vec<String>,
     tcp: Option<&'_ TcpTransport>,
 ) -> Result<()> {
-    let req = api::start_authenticator_service(serv_addr, enrollers, reload_enrollers, project);
+    let req = api::start_authenticator_service(
+        serv_addr,
+        enrollers,
+        reload_enrollers,
+        project,
+        token_duration,
+        credential_ttl,
+        inherited_attributes,
+    );
     start_service_impl(ctx, opts, node_name, serv_addr, "Authenticator", req, tcp).await
 }
 
@@ -1,12 +1,13 @@
 use clap::Args;
 use ockam::Context;
 use ockam_api::cloud::space::Space;
+use serde::Serialize;
 
 use crate::commands::node::util::delete_embedded_node;
 use crate::commands::space::util::config;
 use crate::util::api::{self, CloudOpts};
-use crate::util::{node_rpc, Rpc};
-use crate::CommandGlobalOpts;
+use crate::util::{enrollment_error, node_rpc, print_output, Rpc};
+use crate::{CommandGlobalOpts, OutputFormat};
 
 #[derive(Clone, Debug, Args)]
 pub struct ListCommand {
@@ -20,6 +21,22 @@ impl ListCommand {
     }
 }
 
+/// A space, as printed by `space list` with a non-plain output format
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct SpaceListItem {
+    pub id: String,
+    pub name: String,
+}
+
+impl From<&Space<'_>> for SpaceListItem {
+    fn from(space: &Space<'_>) -> Self {
+        SpaceListItem {
+            id: space.id.to_string(),
+            name: space.name.to_string(),
+        }
+    }
+}
+
 async fn rpc(mut ctx: Context, (opts, cmd): (CommandGlobalOpts, ListCommand)) -> crate::Result<()> {
     run_impl(&mut ctx, opts, cmd).await
 }
@@ -30,10 +47,55 @@ async fn run_impl(
     cmd: ListCommand,
 ) -> crate::Result<()> {
     let mut rpc = Rpc::embedded(ctx, &opts).await?;
-    rpc.request(api::space::list(&cmd.cloud_opts.route()))
-        .await?;
-    let spaces = rpc.parse_and_print_response::<Vec<Space>>()?;
+    let res = rpc
+        .request(api::space::list(&cmd.cloud_opts.route()))
+        .await
+        .and_then(|_| rpc.parse_response::<Vec<Space>>());
+    let spaces = match res {
+        Ok(spaces) => spaces,
+        Err(e) => {
+            delete_embedded_node(&opts, rpc.node_name()).await;
+            return Err(enrollment_error(&opts, e));
+        }
+    };
+    match opts.global_args.output_format {
+        OutputFormat::Plain => {
+            print_output(spaces.clone(), &opts.global_args.output_format)?;
+        }
+        _ => {
+            let items: Vec<SpaceListItem> = spaces.iter().map(SpaceListItem::from).collect();
+            print_output(items, &opts.global_args.output_format)?;
+        }
+    }
     config::set_spaces(&opts.config, &spaces)?;
     delete_embedded_node(&opts, rpc.node_name()).await;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use ockam_core::CowStr;
+
+    use super::*;
+
+    fn space(id: &'static str, name: &'static str) -> Space<'static> {
+        Space {
+            id: CowStr::from(id),
+            name: CowStr::from(name),
+            users: vec![CowStr::from("alice@example.com")],
+        }
+    }
+
+    #[test]
+    fn list_items_json_schema() {
+        let spaces = vec![space("1234", "space-a"), space("5678", "space-b")];
+        let items: Vec<SpaceListItem> = spaces.iter().map(SpaceListItem::from).collect();
+        assert_eq!(
+            serde_json::to_value(&items).unwrap(),
+            serde_json::json!([
+                {"id": "1234", "name": "space-a"},
+                {"id": "5678", "name": "space-b"},
+            ])
+        );
+    }
+}
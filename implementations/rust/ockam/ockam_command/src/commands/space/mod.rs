@@ -1,7 +1,7 @@
 use clap::{Args, Subcommand};
 pub use create::CreateCommand;
 pub use delete::DeleteCommand;
-pub use list::ListCommand;
+pub use list::{ListCommand, SpaceListItem};
 pub use show::ShowCommand;
 pub use util::config;
 
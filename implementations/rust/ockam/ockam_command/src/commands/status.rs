@@ -4,13 +4,18 @@ use anyhow::anyhow;
 use clap::Args;
 use ockam::{Context, TcpTransport};
 use ockam_api::cli_state::{IdentityState, NodeState};
+use ockam_api::cloud::project::Project;
 use ockam_api::lmdb::LmdbStorage;
-use ockam_api::nodes::models::base::NodeStatus;
-use ockam_identity::Identity;
+use ockam_api::nodes::models::base::{NodeListItem, NodeStatus};
+use ockam_api::nodes::NodeManager;
+use ockam_identity::{Identity, IdentityIdentifier};
 use ockam_vault::Vault;
+use serde::Serialize;
 
-use crate::util::{api, node_rpc, RpcBuilder};
-use crate::{CommandGlobalOpts, Result};
+use crate::commands::node::list_items;
+use crate::util::output::Output;
+use crate::util::{api, node_rpc, print_output, RpcBuilder};
+use crate::{CommandGlobalOpts, OutputFormat, Result};
 
 /// Display Ockam Status
 #[derive(Clone, Debug, Args)]
@@ -20,6 +25,44 @@ pub struct StatusCommand {
     all: bool,
 }
 
+/// Machine-readable status, built from the local state only
+#[derive(Serialize)]
+struct StatusInfo {
+    enrolled: bool,
+    controller_identity: Option<IdentityIdentifier>,
+    default_space: Option<String>,
+    default_project: Option<String>,
+    nodes: Vec<NodeListItem>,
+}
+
+impl Output for StatusInfo {
+    fn output(&self) -> anyhow::Result<String> {
+        let yes_no = |b: bool| if b { "yes" } else { "no" };
+        let none = || "-".to_string();
+        let mut lines = vec![
+            format!("Enrolled: {}", yes_no(self.enrolled)),
+            format!(
+                "Controller Identity: {}",
+                self.controller_identity
+                    .as_ref()
+                    .map_or_else(none, |i| i.to_string())
+            ),
+            format!(
+                "Default Space: {}",
+                self.default_space.clone().unwrap_or_else(none)
+            ),
+            format!(
+                "Default Project: {}",
+                self.default_project.clone().unwrap_or_else(none)
+            ),
+        ];
+        if !self.nodes.is_empty() {
+            lines.push(self.nodes.output()?);
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
 struct NodeDetails {
     identity: Identity<Vault, LmdbStorage>,
     state: NodeState,
@@ -37,6 +80,11 @@ async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, StatusCommand)) -> R
 }
 
 async fn run_impl(ctx: &Context, opts: CommandGlobalOpts, cmd: StatusCommand) -> Result<()> {
+    if opts.global_args.output_format != OutputFormat::Plain {
+        print_output(status_info(&opts)?, &opts.global_args.output_format)?;
+        return Ok(());
+    }
+
     let node_states = opts.state.nodes.list()?;
     if node_states.is_empty() {
         return Err(anyhow!("No nodes registered on this system!").into());
@@ -70,6 +118,50 @@ async fn run_impl(ctx: &Context, opts: CommandGlobalOpts, cmd: StatusCommand) ->
     Ok(())
 }
 
+fn status_info(opts: &CommandGlobalOpts) -> Result<StatusInfo> {
+    let enrolled = opts.state.identities.list()?.iter().any(|i| {
+        i.config
+            .enrollment_status
+            .as_ref()
+            .map_or(false, |s| s.is_enrolled)
+    });
+
+    // The default space is the one holding the default project
+    let (default_space, default_project) = match opts.state.projects.default() {
+        Ok(state) => {
+            let contents = std::fs::read_to_string(&state.path)?;
+            let project: Project = serde_json::from_str(&contents)?;
+            (
+                Some(project.space_name.to_string()),
+                Some(project.name.to_string()),
+            )
+        }
+        Err(_) => (None, None),
+    };
+
+    let node_names: Vec<String> = opts
+        .state
+        .nodes
+        .list()?
+        .into_iter()
+        .map(|s| s.config.name)
+        .collect();
+    let default_node = opts
+        .state
+        .nodes
+        .default()
+        .map(|s| s.config.name)
+        .unwrap_or_default();
+
+    Ok(StatusInfo {
+        enrolled,
+        controller_identity: NodeManager::load_controller_identity_id().ok(),
+        default_space,
+        default_project,
+        nodes: list_items(opts, &node_names, &default_node)?,
+    })
+}
+
 async fn get_node_status(
     ctx: &Context,
     opts: &CommandGlobalOpts,
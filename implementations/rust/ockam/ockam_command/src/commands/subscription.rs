@@ -1,21 +1,26 @@
 use core::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use clap::builder::NonEmptyStringValueParser;
 use clap::{Args, Subcommand};
+use colorful::Colorful;
 use ockam::Context;
 use ockam_api::cloud::subscription::Subscription;
 use ockam_api::cloud::CloudRequestWrapper;
 use ockam_core::api::Request;
 use ockam_core::CowStr;
+use serde::Serialize;
 
 use crate::commands::node::util::delete_embedded_node;
 use crate::util::api::CloudOpts;
 use crate::util::output::Output;
-use crate::util::{node_rpc, Rpc};
-use crate::{help, CommandGlobalOpts};
+use crate::util::{node_rpc, print_output, Rpc};
+use crate::{help, CommandGlobalOpts, OutputFormat};
 
 const HELP_DETAIL: &str = "";
 
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
 #[derive(Clone, Debug, Args)]
 #[command(hide = help::hide(), after_long_help = help::template(HELP_DETAIL))]
 pub struct SubscriptionCommand {
@@ -28,6 +33,13 @@ pub struct SubscriptionCommand {
 
 #[derive(Clone, Debug, Subcommand)]
 pub enum SubscriptionSubcommand {
+    /// List the subscriptions of the current user
+    List {
+        /// Warn about the subscriptions which expire within this number of days
+        #[arg(long, value_name = "DAYS", default_value_t = 7)]
+        expiry_warning_days: u64,
+    },
+
     /// Show the details of a single subscription.
     /// You can use either the subscription ID or the space ID.
     #[command(arg_required_else_help = true)]
@@ -61,6 +73,27 @@ async fn run_impl(
     let controller_route = &cmd.cloud_opts.route();
     let mut rpc = Rpc::embedded(&ctx, &opts).await?;
     match cmd.subcommand {
+        SubscriptionSubcommand::List {
+            expiry_warning_days,
+        } => {
+            let req =
+                Request::get("subscription").body(CloudRequestWrapper::bare(controller_route));
+            rpc.request(req).await?;
+            let subscriptions = rpc.parse_response::<Vec<Subscription>>()?;
+            match opts.global_args.output_format {
+                OutputFormat::Plain => {
+                    print_output(&subscriptions, &opts.global_args.output_format)?;
+                    print_expiry_warnings(&opts, &subscriptions, expiry_warning_days);
+                }
+                _ => {
+                    let items: Vec<SubscriptionListItem> = subscriptions
+                        .iter()
+                        .map(SubscriptionListItem::from)
+                        .collect();
+                    print_output(items, &opts.global_args.output_format)?;
+                }
+            }
+        }
         SubscriptionSubcommand::Show {
             subscription_id,
             space_id,
@@ -84,6 +117,95 @@ async fn run_impl(
     Ok(())
 }
 
+/// A subscription, as printed by `subscription list` with a non-plain output format
+#[derive(Debug, Serialize)]
+pub struct SubscriptionListItem {
+    pub id: String,
+    pub status: String,
+    /// Unix timestamp, in seconds
+    pub expires_at: Option<u64>,
+}
+
+impl From<&Subscription<'_>> for SubscriptionListItem {
+    fn from(s: &Subscription<'_>) -> Self {
+        SubscriptionListItem {
+            id: s.id.to_string(),
+            status: s.status.to_string(),
+            expires_at: expires_at(s),
+        }
+    }
+}
+
+impl Output for Vec<SubscriptionListItem> {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.is_empty() {
+            return Ok("No subscriptions found".to_string());
+        }
+        let mut w = String::new();
+        for (idx, s) in self.iter().enumerate() {
+            write!(w, "\n{idx}:")?;
+            write!(w, "\n  Id: {}", s.id)?;
+            write!(w, "\n  Status: {}", s.status)?;
+            match s.expires_at {
+                Some(t) => write!(w, "\n  Expires at: {t}")?,
+                None => write!(w, "\n  Expires at: N/A")?,
+            }
+            writeln!(w)?;
+        }
+        Ok(w)
+    }
+}
+
+/// Print a warning for each subscription which expires within `threshold_days`
+fn print_expiry_warnings(
+    opts: &CommandGlobalOpts,
+    subscriptions: &[Subscription],
+    threshold_days: u64,
+) {
+    let now = match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(d) => d.as_secs(),
+        Err(_) => return,
+    };
+    for s in subscriptions {
+        let warning =
+            expires_at(s).and_then(|expires_at| expiry_warning(expires_at, now, threshold_days));
+        if let Some(warning) = warning {
+            let line = format!("Warning: subscription {} {warning}", s.id);
+            if opts.global_args.no_color {
+                eprintln!("{line}");
+            } else {
+                eprintln!("{}", line.light_yellow());
+            }
+        }
+    }
+}
+
+/// Unix timestamp, in seconds, at which a subscription expires
+///
+/// TODO: the Orchestrator doesn't send the expiry of subscriptions yet
+fn expires_at(_subscription: &Subscription) -> Option<u64> {
+    None
+}
+
+/// Describe how soon a subscription expires, when it's at most `threshold_days` away
+///
+/// Both `expires_at`, as provided by the Orchestrator, and `now`, taken from the
+/// local clock, are Unix timestamps in seconds.
+fn expiry_warning(expires_at: u64, now: u64, threshold_days: u64) -> Option<String> {
+    if expires_at <= now {
+        return Some("has expired".to_string());
+    }
+    let remaining = expires_at - now;
+    if remaining > threshold_days.saturating_mul(SECONDS_PER_DAY) {
+        return None;
+    }
+    match remaining / SECONDS_PER_DAY {
+        0 => Some("expires in less than a day".to_string()),
+        1 => Some("expires in 1 day".to_string()),
+        days => Some(format!("expires in {days} days")),
+    }
+}
+
 pub mod utils {
     use anyhow::anyhow;
     use ockam_multiaddr::MultiAddr;
@@ -169,3 +291,31 @@ impl Output for Vec<Subscription<'_>> {
         Ok(w)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expiry_warning_threshold() {
+        let now = 1_700_000_000;
+        let warning = |days_left: f64| {
+            let expires_at = (now as f64 + days_left * SECONDS_PER_DAY as f64) as u64;
+            expiry_warning(expires_at, now, 7)
+        };
+        assert_eq!(warning(30.0), None);
+        assert_eq!(warning(7.5), None);
+        assert_eq!(warning(7.0).as_deref(), Some("expires in 7 days"));
+        assert_eq!(warning(1.2).as_deref(), Some("expires in 1 day"));
+        assert_eq!(warning(0.5).as_deref(), Some("expires in less than a day"));
+        assert_eq!(warning(0.0).as_deref(), Some("has expired"));
+        assert_eq!(warning(-3.0).as_deref(), Some("has expired"));
+
+        // No warning is printed with a threshold of 0 days, unless it has expired
+        assert_eq!(expiry_warning(now + 60, now, 0), None);
+        assert_eq!(
+            expiry_warning(now - 60, now, 0).as_deref(),
+            Some("has expired")
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use std::net::SocketAddrV4;
+use std::time::Duration;
 
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use clap::Args;
 use colorful::Colorful;
 use ockam::{route, Route, TCP};
@@ -9,9 +10,12 @@ use ockam_api::route_to_multiaddr;
 use serde_json::json;
 
 use crate::commands::node::default_node_name;
-use crate::util::{api, extract_address_value, node_rpc, Rpc};
+use crate::util::{api, exitcode, extract_address_value, node_rpc, parse_duration, Rpc};
 use crate::{CommandGlobalOpts, OutputFormat};
 
+/// Time given to the node to report a connection timeout, on top of the timeout
+const RESPONSE_MARGIN: Duration = Duration::from_secs(2);
+
 #[derive(Clone, Debug, Args)]
 pub struct TcpConnectionNodeOpts {
     /// Node that will initiate the connection
@@ -28,6 +32,16 @@ pub struct CreateCommand {
     /// The address to connect to (required)
     #[arg(id = "to", short, long, value_name = "ADDRESS")]
     pub address: String,
+
+    /// Send heartbeats to the peer at this interval, such as `30s`, and close
+    /// the connection when the peer stops answering them. `0` disables heartbeats
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub keepalive: Option<Duration>,
+
+    /// Give up connecting when the connection isn't established after this
+    /// duration, such as `30s`
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, default_value = "10s")]
+    pub connect_timeout: Duration,
 }
 
 impl CreateCommand {
@@ -41,7 +55,7 @@ impl CreateCommand {
         opts: &CommandGlobalOpts,
         response: &models::transport::TransportStatus,
     ) -> crate::Result<()> {
-        // if output format is json, write json to stdout.
+        // if output format is json or yaml, write it to stdout.
         match opts.global_args.output_format {
             OutputFormat::Plain => {
                 let from = &self.node_opts.from;
@@ -66,7 +80,7 @@ impl CreateCommand {
                     );
                 }
             }
-            OutputFormat::Json => {
+            OutputFormat::Json | OutputFormat::Yaml => {
                 let port = opts
                     .state
                     .nodes
@@ -82,7 +96,11 @@ impl CreateCommand {
                 let multiaddr = route_to_multiaddr(&route)
                     .context("Couldn't convert given address into `MultiAddr`")?;
                 let json = json!([{"route": multiaddr.to_string() }]);
-                println!("{json}");
+                if opts.global_args.output_format == OutputFormat::Yaml {
+                    print!("{}", serde_yaml::to_string(&json)?);
+                } else {
+                    println!("{json}");
+                }
             }
         }
         Ok(())
@@ -97,7 +115,23 @@ async fn run_impl(
     let node_name = extract_address_value(from.as_str())?;
     let mut rpc = Rpc::background(&ctx, &options, &node_name)?;
     let request = api::create_tcp_connection(&command);
-    rpc.request(request).await?;
+
+    // The node gives up connecting after the timeout and reports it, so the
+    // response is only waited for a bit longer in case the node doesn't answer
+    let timeout = command.connect_timeout;
+    if tokio::time::timeout(timeout + RESPONSE_MARGIN, rpc.request(request))
+        .await
+        .is_err()
+    {
+        return Err(crate::Error::new(
+            exitcode::TEMPFAIL,
+            anyhow!(
+                "Connection timed out, {} couldn't be reached within {}s",
+                command.address,
+                timeout.as_secs()
+            ),
+        ));
+    }
     let response = rpc.parse_response::<models::transport::TransportStatus>()?;
 
     command.print_output(&node_name, &options, &response)
@@ -1,10 +1,8 @@
-use anyhow::Context;
 use clap::Args;
-use cli_table::{print_stdout, Cell, Style, Table};
 use ockam_api::nodes::models;
-use ockam_api::nodes::models::transport::TransportStatus;
 use ockam_core::api::Request;
 
+use super::TcpConnectionListItem;
 use crate::commands::node::NodeOpts;
 use crate::util::{extract_address_value, node_rpc, Rpc};
 use crate::CommandGlobalOpts;
@@ -30,32 +28,53 @@ async fn run_impl(
     rpc.request(Request::get("/node/tcp/connection")).await?;
     let response = rpc.parse_response::<models::transport::TransportList>()?;
 
-    let table = response
+    let items: Vec<TcpConnectionListItem> = response
         .list
         .iter()
-        .fold(
-            vec![],
-            |mut acc,
-             TransportStatus {
-                 tt,
-                 tm,
-                 payload,
-                 tid,
-                 ..
-             }| {
-                let row = vec![tid.cell(), tt.cell(), tm.cell(), payload.cell()];
-                acc.push(row);
-                acc
-            },
-        )
-        .table()
-        .title(vec![
-            "Transport ID".cell().bold(true),
-            "Transport Type".cell().bold(true),
-            "Mode".cell().bold(true),
-            "Address bind".cell().bold(true),
-        ]);
-
-    print_stdout(table).context("failed to print node status")?;
+        .map(TcpConnectionListItem::from)
+        .collect();
+    rpc.print_response(items)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use ockam_api::nodes::models::transport::{TransportMode, TransportStatus, TransportType};
+
+    use super::*;
+    use crate::util::output::Output;
+
+    #[test]
+    fn json_fields_match_table_columns() {
+        let status = TransportStatus::new(
+            TransportType::Tcp,
+            TransportMode::Connect,
+            "127.0.0.1:4000",
+            "abcdef",
+        );
+        let items = vec![TcpConnectionListItem::from(&status)];
+        let json = serde_json::to_value(&items).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([{
+                "tid": "abcdef",
+                "transport_type": "TCP",
+                "mode": "Remote connection",
+                "address": "127.0.0.1:4000",
+            }])
+        );
+
+        // The table shows the same values, in the same order
+        let table = items.output().unwrap();
+        let row = table.lines().find(|l| l.contains("abcdef")).unwrap();
+        let cells: Vec<&str> = row
+            .split('|')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .collect();
+        assert_eq!(
+            cells,
+            ["abcdef", "TCP", "Remote connection", "127.0.0.1:4000"]
+        );
+    }
+}
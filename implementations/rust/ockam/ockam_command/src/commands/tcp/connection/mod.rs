@@ -6,6 +6,8 @@ use clap::{Args, Subcommand};
 pub(crate) use create::CreateCommand;
 pub(crate) use delete::DeleteCommand;
 pub(crate) use list::ListCommand;
+use ockam_api::nodes::models::transport::TransportStatus;
+use serde::Serialize;
 
 use crate::CommandGlobalOpts;
 
@@ -33,3 +35,23 @@ impl TcpConnectionCommand {
         }
     }
 }
+
+/// A TCP connection, as printed by `tcp-connection list`
+#[derive(Debug, Serialize)]
+pub struct TcpConnectionListItem {
+    pub tid: String,
+    pub transport_type: String,
+    pub mode: String,
+    pub address: String,
+}
+
+impl From<&TransportStatus<'_>> for TcpConnectionListItem {
+    fn from(status: &TransportStatus<'_>) -> Self {
+        TcpConnectionListItem {
+            tid: status.tid.to_string(),
+            transport_type: status.tt.to_string(),
+            mode: status.tm.to_string(),
+            address: status.payload.to_string(),
+        }
+    }
+}
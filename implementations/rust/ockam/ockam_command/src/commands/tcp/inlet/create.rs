@@ -1,4 +1,5 @@
 use std::net::SocketAddr;
+use std::path::PathBuf;
 
 use anyhow::{anyhow, ensure};
 use clap::Args;
@@ -10,6 +11,7 @@ use ockam_multiaddr::proto::Project;
 use ockam_multiaddr::{MultiAddr, Protocol as _};
 
 use crate::util::{
+    absolute_path,
     bind_to_port_check,
     exitcode,
     extract_address_value,
@@ -54,6 +56,19 @@ pub struct CreateCommand {
     /// Assign a name to this inlet.
     #[arg(long, display_order = 900, id = "ALIAS", value_parser = alias_parser)]
     alias: Option<String>,
+
+    /// Terminate TLS at the inlet: clients connect with TLS and the outlet
+    /// receives the decrypted traffic. Requires `--tls-cert` and `--tls-key`.
+    #[arg(long, display_order = 901, requires_all = ["tls_cert", "tls_key"])]
+    tls: bool,
+
+    /// PEM file of the certificate chain presented to TLS clients.
+    #[arg(long, display_order = 901, value_name = "PATH", requires = "tls")]
+    tls_cert: Option<PathBuf>,
+
+    /// PEM file of the private key of the TLS certificate.
+    #[arg(long, display_order = 901, value_name = "PATH", requires = "tls")]
+    tls_key: Option<PathBuf>,
 }
 
 impl CreateCommand {
@@ -99,6 +114,9 @@ async fn rpc(ctx: Context, (opts, mut cmd): (CommandGlobalOpts, CreateCommand))
         if let Some(a) = cmd.alias {
             payload.set_alias(a)
         }
+        if let (true, Some(cert), Some(key)) = (cmd.tls, &cmd.tls_cert, &cmd.tls_key) {
+            payload.set_tls(absolute_path(cert)?, absolute_path(key)?)
+        }
         Request::post("/node/inlet").body(payload)
     };
 
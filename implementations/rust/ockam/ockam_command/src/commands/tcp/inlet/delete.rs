@@ -0,0 +1,49 @@
+use anyhow::anyhow;
+use clap::Args;
+use ockam_core::api::Status;
+
+use crate::commands::node::NodeOpts;
+use crate::util::{api, extract_address_value, node_rpc, Rpc};
+use crate::{exitcode, CommandGlobalOpts};
+
+/// Delete a TCP Inlet
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true)]
+pub struct DeleteCommand {
+    #[command(flatten)]
+    node_opts: NodeOpts,
+
+    /// Alias of the tcp inlet
+    pub alias: String,
+}
+
+impl DeleteCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    ctx: ockam::Context,
+    (opts, cmd): (CommandGlobalOpts, DeleteCommand),
+) -> crate::Result<()> {
+    let node_name = extract_address_value(&cmd.node_opts.api_node)?;
+
+    let mut rpc = Rpc::background(&ctx, &opts, &node_name)?;
+    rpc.request(api::delete_inlet(&cmd.alias)).await?;
+    let (res, _) = rpc.check_response()?;
+    if res.status() == Some(Status::NotFound) {
+        return Err(crate::Error::new(
+            exitcode::UNAVAILABLE,
+            anyhow!(
+                "No tcp inlet with alias `{}` at node {}",
+                cmd.alias,
+                node_name
+            ),
+        ));
+    }
+    rpc.is_ok()?;
+
+    println!("Tcp inlet `{}` successfully deleted", cmd.alias);
+    Ok(())
+}
@@ -1,7 +1,11 @@
 mod create;
+mod delete;
+mod list;
 
 use clap::{Args, Subcommand};
 use create::CreateCommand;
+use delete::DeleteCommand;
+use list::ListCommand;
 
 use crate::CommandGlobalOpts;
 
@@ -15,12 +19,16 @@ pub struct TcpInletCommand {
 #[derive(Clone, Debug, Subcommand)]
 pub enum TcpInletSubCommand {
     Create(CreateCommand),
+    Delete(DeleteCommand),
+    List(ListCommand),
 }
 
 impl TcpInletCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         match self.subcommand {
             TcpInletSubCommand::Create(c) => c.run(options),
+            TcpInletSubCommand::Delete(c) => c.run(options),
+            TcpInletSubCommand::List(c) => c.run(options),
         }
     }
 }
@@ -1,7 +1,9 @@
 use std::net::SocketAddr;
+use std::path::PathBuf;
 
 use anyhow::ensure;
 use clap::Args;
+use ockam::identity::IdentityIdentifier;
 use ockam::Context;
 use ockam_api::error::ApiError;
 use ockam_api::nodes::models::portal::{CreateOutlet, OutletStatus};
@@ -9,7 +11,7 @@ use ockam_api::route_to_multiaddr;
 use ockam_core::api::{Request, RequestBuilder};
 use ockam_core::route;
 
-use crate::util::{extract_address_value, node_rpc, Rpc};
+use crate::util::{absolute_path, extract_address_value, node_rpc, Rpc};
 use crate::{help, CommandGlobalOpts};
 
 const HELP_DETAIL: &str = include_str!("../../../constants/tcp/outlet/help_detail.txt");
@@ -32,7 +34,11 @@ pub struct CreateCommand {
 
     /// Enable credential authorization.
     /// Defaults to the Node's `enable-credential-checks` value passed upon creation.
-    #[arg(long, display_order = 900, conflicts_with = "disable_check_credential")]
+    #[arg(
+        long,
+        display_order = 900,
+        conflicts_with_all = ["disable_check_credential", "allow"]
+    )]
     check_credential: bool,
 
     /// Disable credential authorization.
@@ -43,6 +49,25 @@ pub struct CreateCommand {
     /// Assign a name to this outlet.
     #[arg(long, display_order = 900, id = "ALIAS", value_parser = alias_parser)]
     alias: Option<String>,
+
+    /// Originate TLS at the outlet: the traffic received from inlets is
+    /// sent to the tcp address over TLS, after validating its certificate.
+    #[arg(long, display_order = 903, requires = "tls_server_name")]
+    tls: bool,
+
+    /// PEM file of the CA certificates validating the certificate of the tcp address.
+    /// Defaults to the platform's trusted root certificates.
+    #[arg(long, display_order = 903, value_name = "PATH", requires = "tls")]
+    tls_ca_cert: Option<PathBuf>,
+
+    /// Name the certificate of the tcp address must be valid for.
+    #[arg(long, display_order = 903, value_name = "NAME", requires = "tls")]
+    tls_server_name: Option<String>,
+
+    /// Only allow this identity to reach the outlet through a secure channel.
+    /// Can be repeated. Defaults to the node's access control.
+    #[arg(long, display_order = 904, value_name = "IDENTITY_IDENTIFIER")]
+    allow: Vec<IdentityIdentifier>,
 }
 
 impl CreateCommand {
@@ -89,7 +114,17 @@ fn make_api_request<'a>(cmd: CreateCommand) -> crate::Result<RequestBuilder<'a,
     let check_credential = cmd.check_credential();
     let worker_addr = cmd.from;
     let alias = cmd.alias.map(|a| a.into());
-    let payload = CreateOutlet::new(tcp_addr, worker_addr, alias, check_credential);
+    let mut payload = CreateOutlet::new(tcp_addr, worker_addr, alias, check_credential);
+    if let (true, Some(server_name)) = (cmd.tls, cmd.tls_server_name) {
+        let ca_cert = match &cmd.tls_ca_cert {
+            Some(path) => Some(absolute_path(path)?),
+            None => None,
+        };
+        payload = payload.with_tls(ca_cert, server_name);
+    }
+    if !cmd.allow.is_empty() {
+        payload = payload.with_allowed_identities(cmd.allow);
+    }
     let request = Request::post("/node/outlet").body(payload);
     Ok(request)
 }
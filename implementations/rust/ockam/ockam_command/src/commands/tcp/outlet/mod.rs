@@ -1,7 +1,11 @@
 mod create;
+mod delete;
+mod list;
 
 use clap::{Args, Subcommand};
 use create::CreateCommand;
+use delete::DeleteCommand;
+use list::ListCommand;
 
 use crate::CommandGlobalOpts;
 
@@ -15,12 +19,16 @@ pub struct TcpOutletCommand {
 #[derive(Clone, Debug, Subcommand)]
 pub enum TcpOutletSubCommand {
     Create(CreateCommand),
+    Delete(DeleteCommand),
+    List(ListCommand),
 }
 
 impl TcpOutletCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         match self.subcommand {
             TcpOutletSubCommand::Create(c) => c.run(options),
+            TcpOutletSubCommand::Delete(c) => c.run(options),
+            TcpOutletSubCommand::List(c) => c.run(options),
         }
     }
 }
@@ -0,0 +1,23 @@
+use anyhow::anyhow;
+use clap::Args;
+
+use crate::commands::vault::VaultInfo;
+use crate::util::print_output;
+use crate::CommandGlobalOpts;
+
+/// List vaults
+#[derive(Clone, Debug, Args)]
+pub struct ListCommand {}
+
+pub(super) fn run_impl(opts: &CommandGlobalOpts, _cmd: ListCommand) -> crate::Result<()> {
+    let states = opts.state.vaults.list()?;
+    if states.is_empty() {
+        return Err(anyhow!("No vaults registered on this system!").into());
+    }
+    let vaults = states
+        .iter()
+        .map(|state| VaultInfo::new(opts, state))
+        .collect::<crate::Result<Vec<_>>>()?;
+    print_output(vaults, &opts.global_args.output_format)?;
+    Ok(())
+}
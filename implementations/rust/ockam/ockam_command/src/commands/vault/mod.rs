@@ -1,17 +1,23 @@
 mod default;
+mod list;
+mod show;
 
+use core::fmt::Write;
 use std::path::PathBuf;
 
 use anyhow::anyhow;
 use clap::{Args, Subcommand};
 use ockam::Context;
-use ockam_api::cli_state;
-use ockam_core::vault::{Secret, SecretAttributes, SecretPersistence, SecretType, SecretVault};
-use ockam_identity::{Identity, IdentityStateConst, KeyAttributes};
+use ockam_api::cli_state::{self, CliStateError, VaultState};
 use rand::prelude::random;
+use serde::Serialize;
 
+use crate::commands::identity::create_identity_with_kms_key;
 use crate::commands::vault::default::DefaultCommand;
-use crate::util::node_rpc;
+use crate::commands::vault::list::ListCommand;
+use crate::commands::vault::show::ShowCommand;
+use crate::util::output::Output;
+use crate::util::{exitcode, node_rpc};
 use crate::{help, CommandGlobalOpts, Result};
 
 const HELP_DETAIL: &str = "";
@@ -39,8 +45,18 @@ pub enum VaultSubcommand {
         #[arg(short, long)]
         path: Option<String>,
 
+        /// Delegate the signing keys of the vault to AWS KMS
         #[arg(long, default_value = "false")]
         aws_kms: bool,
+
+        /// AWS region of the KMS keys, taken from the environment by default
+        #[arg(long, requires = "aws_kms")]
+        aws_region: Option<String>,
+
+        /// Existing AWS KMS key to use as the root key of the vault's identities,
+        /// instead of creating a new key for each of them
+        #[arg(long, requires = "aws_kms")]
+        aws_key_id: Option<String>,
     },
     /// Attach a key to a vault
     #[command(arg_required_else_help = true)]
@@ -53,17 +69,14 @@ pub enum VaultSubcommand {
         key_id: String,
     },
     /// Show vault details
-    Show {
-        /// Name of the vault
-        name: Option<String>,
-    },
+    Show(ShowCommand),
     /// Delete a vault
     Delete {
         /// Name of the vault
         name: String,
     },
     /// List vaults
-    List {},
+    List(ListCommand),
     /// Set the default identity
     Default(DefaultCommand),
 }
@@ -80,11 +93,23 @@ async fn run_impl(ctx: Context, (opts, cmd): (CommandGlobalOpts, VaultCommand))
             name,
             path,
             aws_kms,
+            aws_region,
+            aws_key_id,
         } => {
+            if aws_kms && !cfg!(feature = "aws-kms") {
+                return Err(crate::Error::new(
+                    exitcode::UNAVAILABLE,
+                    anyhow!(
+                        "AWS KMS vaults are not supported, \
+                         ockam was built without the `aws-kms` feature"
+                    ),
+                ));
+            }
             let path = path.map(PathBuf::from).unwrap_or_else(|| {
                 cli_state::VaultConfig::path(&name).expect("Failed to build Vault's path")
             });
-            let config = cli_state::VaultConfig::new(path, aws_kms)?;
+            let config = cli_state::VaultConfig::new(path, aws_kms)?
+                .with_aws_kms_options(aws_region, aws_key_id);
             opts.state.vaults.create(&name, config.clone()).await?;
             println!("Vault created: {}", &name);
         }
@@ -97,45 +122,14 @@ async fn run_impl(ctx: Context, (opts, cmd): (CommandGlobalOpts, VaultCommand))
                 return Err(anyhow!("Vault {} is not an AWS KMS vault", v_name).into());
             }
             let v = v_config.get().await?;
-            let idt = {
-                let attrs =
-                    SecretAttributes::new(SecretType::NistP256, SecretPersistence::Persistent, 32);
-                let kid = v.secret_import(Secret::Aws(key_id), attrs).await?;
-                let attrs = KeyAttributes::new(IdentityStateConst::ROOT_LABEL.to_string(), attrs);
-                Identity::create_with_external_key_ext(
-                    &ctx,
-                    &opts.state.identities.authenticated_storage().await?,
-                    &v,
-                    &kid,
-                    attrs,
-                )
-                .await?
-            };
+            let idt = create_identity_with_kms_key(&ctx, &opts, &v, &key_id).await?;
             let idt_name = cli_state::random_name();
             let idt_config = cli_state::IdentityConfig::new(&idt).await;
             opts.state.identities.create(&idt_name, idt_config)?;
             println!("Identity attached to vault: {idt_name}");
         }
-        VaultSubcommand::Show { name } => {
-            let name = name.unwrap_or(opts.state.vaults.default()?.name()?);
-            let state = opts.state.vaults.get(&name)?;
-            println!("Vault:");
-            for line in state.to_string().lines() {
-                println!("{:2}{}", "", line)
-            }
-        }
-        VaultSubcommand::List {} => {
-            let states = opts.state.vaults.list()?;
-            if states.is_empty() {
-                return Err(anyhow!("No vaults registered on this system!").into());
-            }
-            for (idx, vault) in states.iter().enumerate() {
-                println!("Vault[{idx}]:");
-                for line in vault.to_string().lines() {
-                    println!("{:2}{}", "", line)
-                }
-            }
-        }
+        VaultSubcommand::Show(cmd) => show::run_impl(&opts, cmd).await?,
+        VaultSubcommand::List(cmd) => list::run_impl(&opts, cmd)?,
         VaultSubcommand::Delete { name } => {
             opts.state.vaults.delete(&name).await?;
             println!("Vault '{name}' deleted");
@@ -144,3 +138,112 @@ async fn run_impl(ctx: Context, (opts, cmd): (CommandGlobalOpts, VaultCommand))
     }
     Ok(())
 }
+
+/// Get a vault by name, with a friendlier error if it doesn't exist
+fn get_vault(opts: &CommandGlobalOpts, name: &str) -> Result<VaultState> {
+    match opts.state.vaults.get(name) {
+        Ok(state) => Ok(state),
+        Err(CliStateError::NotFound(_)) => Err(anyhow!("Vault '{name}' not found").into()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Vault description printed by `vault list` and `vault show`.
+/// It never includes any secret material.
+#[derive(Serialize)]
+struct VaultInfo {
+    name: String,
+    #[serde(rename = "type")]
+    vault_type: VaultType,
+    is_default: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secrets: Option<usize>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum VaultType {
+    File,
+    AwsKms,
+}
+
+impl std::fmt::Display for VaultType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            VaultType::File => "OCKAM",
+            VaultType::AwsKms => "AWS KMS",
+        })
+    }
+}
+
+impl VaultInfo {
+    fn new(opts: &CommandGlobalOpts, state: &VaultState) -> Result<Self> {
+        let name = state.name()?;
+        let is_default = opts
+            .state
+            .vaults
+            .default()
+            .map(|default| default.path == state.path)
+            .unwrap_or(false);
+        Ok(Self {
+            name,
+            vault_type: if state.config.is_aws() {
+                VaultType::AwsKms
+            } else {
+                VaultType::File
+            },
+            is_default,
+            path: None,
+            secrets: None,
+        })
+    }
+
+    /// Add the vault's storage path and number of stored secrets
+    async fn with_details(mut self, state: &VaultState) -> Result<Self> {
+        self.path = Some(state.config.storage_path().to_path_buf());
+        self.secrets = Some(state.config.secrets_count().await?);
+        Ok(self)
+    }
+}
+
+impl Output for VaultInfo {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut w = String::new();
+        write!(w, "Vault:")?;
+        write!(w, "\n  Name: {}", self.name)?;
+        write!(w, "\n  Type: {}", self.vault_type)?;
+        write!(
+            w,
+            "\n  Default: {}",
+            if self.is_default { "yes" } else { "no" }
+        )?;
+        if let Some(path) = &self.path {
+            write!(w, "\n  Path: {}", path.display())?;
+        }
+        if let Some(secrets) = self.secrets {
+            write!(w, "\n  Secrets: {secrets}")?;
+        }
+        Ok(w)
+    }
+}
+
+impl Output for Vec<VaultInfo> {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut w = String::new();
+        for (idx, vault) in self.iter().enumerate() {
+            if idx > 0 {
+                writeln!(w)?;
+            }
+            write!(
+                w,
+                "{}",
+                vault
+                    .output()?
+                    .replacen("Vault:", &format!("Vault[{idx}]:"), 1)
+            )?;
+        }
+        Ok(w)
+    }
+}
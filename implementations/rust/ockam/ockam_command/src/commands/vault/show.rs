@@ -0,0 +1,23 @@
+use clap::Args;
+
+use crate::commands::vault::VaultInfo;
+use crate::util::print_output;
+use crate::CommandGlobalOpts;
+
+/// Show vault details
+#[derive(Clone, Debug, Args)]
+pub struct ShowCommand {
+    /// Name of the vault, defaults to the default vault
+    name: Option<String>,
+}
+
+pub(super) async fn run_impl(opts: &CommandGlobalOpts, cmd: ShowCommand) -> crate::Result<()> {
+    let name = match cmd.name {
+        Some(name) => name,
+        None => opts.state.vaults.default()?.name()?,
+    };
+    let state = super::get_vault(opts, &name)?;
+    let info = VaultInfo::new(opts, &state)?.with_details(&state).await?;
+    print_output(info, &opts.global_args.output_format)?;
+    Ok(())
+}
@@ -1,9 +1,11 @@
 use clap::{Args, Subcommand};
 use list::ListCommand;
+use show::ShowCommand;
 
 use crate::{help, CommandGlobalOpts};
 
 mod list;
+mod show;
 
 const HELP_DETAIL: &str = "";
 
@@ -23,12 +25,15 @@ pub struct WorkerCommand {
 pub enum WorkerSubcommand {
     #[command(display_order = 800)]
     List(ListCommand),
+    #[command(display_order = 800)]
+    Show(ShowCommand),
 }
 
 impl WorkerCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         match self.subcommand {
             WorkerSubcommand::List(c) => c.run(options),
+            WorkerSubcommand::Show(c) => c.run(options),
         }
     }
 }
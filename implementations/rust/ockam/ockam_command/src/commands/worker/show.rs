@@ -0,0 +1,39 @@
+use clap::Args;
+use ockam::Context;
+use ockam_api::nodes::models::workers::WorkerDetails;
+
+use crate::commands::node::default_node_name;
+use crate::util::{api, node_rpc, Rpc};
+use crate::{help, CommandGlobalOpts};
+
+const HELP_DETAIL: &str = "";
+
+/// Show the details of a worker
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, after_long_help = help::template(HELP_DETAIL))]
+pub struct ShowCommand {
+    /// Address of the worker
+    #[arg(display_order = 800)]
+    address: String,
+
+    /// Node at which to lookup the worker
+    #[arg(value_name = "NODE", long, default_value_t = default_node_name(), display_order = 800)]
+    at: String,
+}
+
+impl ShowCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, ShowCommand),
+) -> crate::Result<()> {
+    let mut rpc = Rpc::background(&ctx, &opts, &cmd.at)?;
+    rpc.request(api::show_worker(&cmd.address)).await?;
+    let worker = rpc.parse_response::<WorkerDetails>()?;
+    rpc.print_response(worker)?;
+    Ok(())
+}
@@ -1,7 +1,9 @@
 use ockam::identity::IdentityIdentifier;
 use ockam_api::cloud::project::{OktaConfig, Project};
+use ockam_api::config::lookup::ProjectAuthority;
 use ockam_core::CowStr;
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
@@ -17,11 +19,34 @@ pub struct ProjectInfo<'a> {
     pub authority_access_route: Option<CowStr<'a>>,
     #[serde(borrow)]
     pub authority_identity: Option<CowStr<'a>>,
+    /// Identifier of the `authority_identity`, see [`ProjectInfo::with_authority_identifier`]
+    #[serde(default)]
+    pub authority_identifier: Option<IdentityIdentifier>,
     #[serde(borrow)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub okta_config: Option<OktaConfig<'a>>,
 }
 
+impl ProjectInfo<'_> {
+    /// Decode the authority identity to set its identifier.
+    ///
+    /// The identifier is left unset if the authority can't be decoded, so that
+    /// the rest of the project information can still be shown.
+    pub async fn with_authority_identifier(mut self) -> Self {
+        let authority =
+            ProjectAuthority::from_raw(&self.authority_access_route, &self.authority_identity)
+                .await;
+        self.authority_identifier = match authority {
+            Ok(authority) => authority.map(|a| a.identity_id().clone()),
+            Err(e) => {
+                warn!(project = %self.name, "failed to decode the project authority: {e}");
+                None
+            }
+        };
+        self
+    }
+}
+
 impl<'a> From<Project<'a>> for ProjectInfo<'a> {
     fn from(p: Project<'a>) -> Self {
         Self {
@@ -31,6 +56,7 @@ impl<'a> From<Project<'a>> for ProjectInfo<'a> {
             access_route: p.access_route,
             authority_access_route: p.authority_access_route,
             authority_identity: p.authority_identity,
+            authority_identifier: None,
             okta_config: p.okta_config,
         }
     }
@@ -50,3 +76,31 @@ impl<'a> From<&ProjectInfo<'a>> for Project<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn authority_identifier() {
+        // Without authority, the identifier is unset
+        let info = ProjectInfo::from(Project::default())
+            .with_authority_identifier()
+            .await;
+        assert!(info.authority_identifier.is_none());
+
+        // An authority which can't be decoded is not an error
+        let project = Project {
+            name: "p1".into(),
+            authority_access_route: Some("/dnsaddr/localhost/tcp/4000".into()),
+            authority_identity: Some("not hex".into()),
+            ..Default::default()
+        };
+        let info = ProjectInfo::from(project).with_authority_identifier().await;
+        assert!(info.authority_identifier.is_none());
+        assert_eq!(
+            Some("/dnsaddr/localhost/tcp/4000"),
+            info.authority_access_route.as_deref()
+        );
+    }
+}
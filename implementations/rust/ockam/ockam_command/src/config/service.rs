@@ -4,6 +4,7 @@ use anyhow::{anyhow, Context, Result};
 use ockam::identity::IdentityIdentifier;
 use ockam_api::DefaultAddress;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -14,10 +15,100 @@ impl Config {
     pub(crate) fn read<P: AsRef<Path>>(path: P) -> Result<Self> {
         let s = std::fs::read_to_string(path.as_ref())
             .with_context(|| anyhow!("failed to read {:?}", path.as_ref()))?;
-        serde_json::from_str(&s).with_context(|| anyhow!("invalid config {:?}", path.as_ref()))
+        let value = serde_json::from_str(&s)
+            .with_context(|| anyhow!("invalid config {:?}", path.as_ref()))?;
+        Self::from_value(value).with_context(|| anyhow!("invalid config {:?}", path.as_ref()))
+    }
+
+    /// Deserialize a config after expanding the `${VAR}` and `${VAR:-default}`
+    /// references to environment variables found in its strings.
+    pub(crate) fn from_value(mut value: Value) -> Result<Self> {
+        expand_env_vars(&mut value)?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Names and addresses of the startup services that are not disabled,
+    /// in the order they are started.
+    pub(crate) fn enabled_services(&self) -> Vec<(&'static str, &str)> {
+        let sc = match &self.startup_services {
+            Some(sc) => sc,
+            None => return Vec::new(),
+        };
+        let mut services = Vec::new();
+        if let Some(c) = sc.vault.as_ref().filter(|c| !c.disabled) {
+            services.push(("vault", c.address.as_str()));
+        }
+        if let Some(c) = sc.identity.as_ref().filter(|c| !c.disabled) {
+            services.push(("identity", c.address.as_str()));
+        }
+        if let Some(c) = sc.secure_channel_listener.as_ref().filter(|c| !c.disabled) {
+            services.push(("secure-channel-listener", c.address.as_str()));
+        }
+        if let Some(c) = sc.verifier.as_ref().filter(|c| !c.disabled) {
+            services.push(("verifier", c.address.as_str()));
+        }
+        if let Some(c) = sc.authenticator.as_ref().filter(|c| !c.disabled) {
+            services.push(("authenticator", c.address.as_str()));
+        }
+        if let Some(c) = sc.okta_identity_provider.as_ref().filter(|c| !c.disabled) {
+            services.push(("okta-identity-provider", c.address.as_str()));
+        }
+        services
     }
 }
 
+fn expand_env_vars(value: &mut Value) -> Result<()> {
+    match value {
+        Value::String(s) => *s = expand_env(s)?,
+        Value::Array(values) => {
+            for v in values {
+                expand_env_vars(v)?;
+            }
+        }
+        Value::Object(map) => {
+            for v in map.values_mut() {
+                expand_env_vars(v)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Replace the `${VAR}` and `${VAR:-default}` references in `input` by the value
+/// of the environment variable `VAR`. The default is used when `VAR` is unset or empty.
+fn expand_env(input: &str) -> Result<String> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find("${") {
+        output.push_str(&rest[..start]);
+        let reference = &rest[start + 2..];
+        let end = reference
+            .find('}')
+            .ok_or_else(|| anyhow!("unterminated variable reference in {input:?}"))?;
+        let (name, default) = match reference[..end].split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (&reference[..end], None),
+        };
+        if name.is_empty() {
+            return Err(anyhow!("empty variable reference in {input:?}"));
+        }
+        match (std::env::var(name), default) {
+            (Ok(v), Some(default)) if v.is_empty() => output.push_str(default),
+            (Ok(v), _) => output.push_str(&v),
+            (Err(_), Some(default)) => output.push_str(default),
+            (Err(_), None) => {
+                return Err(anyhow!(
+                    "environment variable `{name}` referenced in the config is not set"
+                ))
+            }
+        }
+        rest = &reference[end + 1..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VaultConfig {
     #[serde(default = "vault_default_addr")]
@@ -72,6 +163,17 @@ pub struct AuthenticatorConfig {
 
     pub(crate) project: String,
 
+    #[serde(default)]
+    pub(crate) token_duration: Option<u64>,
+
+    /// Validity of issued credentials, in seconds
+    #[serde(default)]
+    pub(crate) credential_ttl: Option<u64>,
+
+    /// Attributes of the enrollers copied to the members they enroll
+    #[serde(default)]
+    pub(crate) inherited_attributes: Vec<String>,
+
     #[serde(default)]
     pub(crate) disabled: bool,
 }
@@ -129,3 +231,73 @@ fn reload_enrollers_default() -> bool {
 fn okta_identity_provider_default_addr() -> String {
     DefaultAddress::OKTA_IDENTITY_PROVIDER.to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(config: &str) -> Result<Config> {
+        Config::from_value(serde_json::from_str(config)?)
+    }
+
+    fn addresses(config: &Config) -> (String, String) {
+        let sc = config.startup_services.as_ref().unwrap();
+        (
+            sc.vault.as_ref().unwrap().address.clone(),
+            sc.secure_channel_listener.as_ref().unwrap().address.clone(),
+        )
+    }
+
+    #[test]
+    fn substitute_env_vars_in_addresses() {
+        std::env::set_var("OCKAM_TEST_VAULT_ADDR", "vault_a");
+        std::env::set_var("OCKAM_TEST_LISTENER_SUFFIX", "1");
+        let config = parse(
+            r#"{"startup_services": {
+                "vault": {"address": "${OCKAM_TEST_VAULT_ADDR}"},
+                "secure_channel_listener": {"address": "listener_${OCKAM_TEST_LISTENER_SUFFIX}"}
+            }}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            addresses(&config),
+            ("vault_a".to_string(), "listener_1".to_string())
+        );
+    }
+
+    #[test]
+    fn substitute_defaults_for_unset_env_vars() {
+        std::env::remove_var("OCKAM_TEST_UNSET_VAULT_ADDR");
+        std::env::set_var("OCKAM_TEST_EMPTY_LISTENER_ADDR", "");
+        let config = parse(
+            r#"{"startup_services": {
+                "vault": {"address": "${OCKAM_TEST_UNSET_VAULT_ADDR:-vault_b}"},
+                "secure_channel_listener": {"address": "${OCKAM_TEST_EMPTY_LISTENER_ADDR:-listener_b}"}
+            }}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            addresses(&config),
+            ("vault_b".to_string(), "listener_b".to_string())
+        );
+    }
+
+    #[test]
+    fn fail_on_unset_env_vars() {
+        std::env::remove_var("OCKAM_TEST_UNSET_LISTENER_ADDR");
+        let err = parse(
+            r#"{"startup_services": {
+                "secure_channel_listener": {"address": "${OCKAM_TEST_UNSET_LISTENER_ADDR}"}
+            }}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("OCKAM_TEST_UNSET_LISTENER_ADDR"));
+
+        assert!(expand_env("${UNTERMINATED").is_err());
+        assert!(expand_env("${}").is_err());
+        assert_eq!(
+            expand_env("no $references {here}").unwrap(),
+            "no $references {here}"
+        );
+    }
+}
@@ -1,6 +1,9 @@
 use std::convert::Infallible;
 use std::fmt::{Debug, Display, Formatter};
 
+use ockam_api::cli_state::CliStateError;
+use ockam_core::errcode::Kind;
+
 use crate::version::Version;
 use crate::{exitcode, ExitCode};
 
@@ -50,19 +53,61 @@ impl std::error::Error for Error {
 
 impl From<ockam::Error> for Error {
     fn from(e: ockam::Error) -> Self {
-        Error::new(exitcode::SOFTWARE, e.into())
+        Error::new(ockam_exit_code(&e), e.into())
     }
 }
 
-impl From<ockam_api::cli_state::CliStateError> for Error {
-    fn from(e: ockam_api::cli_state::CliStateError) -> Self {
-        Error::new(exitcode::SOFTWARE, e.into())
+impl From<CliStateError> for Error {
+    fn from(e: CliStateError) -> Self {
+        Error::new(cli_state_exit_code(&e), e.into())
     }
 }
 
 impl From<anyhow::Error> for Error {
     fn from(e: anyhow::Error) -> Self {
-        Error::new(exitcode::SOFTWARE, e)
+        // Preserve the exit code of an `Error` that was wrapped into an `anyhow::Error`
+        let e = match e.downcast::<Error>() {
+            Ok(e) => return e,
+            Err(e) => e,
+        };
+        let code = if let Some(inner) = e.downcast_ref::<CliStateError>() {
+            cli_state_exit_code(inner)
+        } else if let Some(inner) = e.downcast_ref::<ockam::Error>() {
+            ockam_exit_code(inner)
+        } else {
+            exitcode::SOFTWARE
+        };
+        Error::new(code, e)
+    }
+}
+
+fn ockam_exit_code(e: &ockam::Error) -> ExitCode {
+    match e.code().kind {
+        Kind::NotFound | Kind::Unsupported => exitcode::UNAVAILABLE,
+        Kind::AlreadyExists => exitcode::CANTCREAT,
+        Kind::Invalid | Kind::Serialization => exitcode::DATAERR,
+        Kind::Misuse => exitcode::USAGE,
+        Kind::Timeout | Kind::Cancelled | Kind::Shutdown | Kind::ResourceExhausted => {
+            exitcode::TEMPFAIL
+        }
+        Kind::Io => exitcode::IOERR,
+        Kind::Protocol => exitcode::PROTOCOL,
+        _ => exitcode::SOFTWARE,
+    }
+}
+
+fn cli_state_exit_code(e: &CliStateError) -> ExitCode {
+    match e {
+        CliStateError::NotFound(_) | CliStateError::NoApiTransport => exitcode::UNAVAILABLE,
+        CliStateError::AlreadyExists(_) => exitcode::CANTCREAT,
+        CliStateError::Io(_) => exitcode::IOERR,
+        CliStateError::Serde(_)
+        | CliStateError::InvalidVersion(_)
+        | CliStateError::Corrupt { .. } => exitcode::CONFIG,
+        CliStateError::Invalid(_) => exitcode::DATAERR,
+        CliStateError::LockTimeout(_) => exitcode::TEMPFAIL,
+        CliStateError::Ockam(e) => ockam_exit_code(e),
+        CliStateError::Unknown => exitcode::SOFTWARE,
     }
 }
 
@@ -74,7 +119,7 @@ impl From<std::io::Error> for Error {
 
 impl From<ockam_multiaddr::Error> for Error {
     fn from(e: ockam_multiaddr::Error) -> Self {
-        Error::new(exitcode::SOFTWARE, e.into())
+        Error::new(exitcode::USAGE, e.into())
     }
 }
 
@@ -96,8 +141,14 @@ impl From<serde_json::Error> for Error {
     }
 }
 
+impl From<serde_yaml::Error> for Error {
+    fn from(e: serde_yaml::Error) -> Self {
+        Error::new(exitcode::DATAERR, e.into())
+    }
+}
+
 impl From<std::net::AddrParseError> for Error {
     fn from(e: std::net::AddrParseError) -> Self {
-        Error::new(exitcode::SOFTWARE, e.into())
+        Error::new(exitcode::USAGE, e.into())
     }
 }
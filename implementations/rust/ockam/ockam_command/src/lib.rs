@@ -10,7 +10,9 @@ mod upgrade;
 mod util;
 mod version;
 
-use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+use anyhow::anyhow;
+use clap::error::ErrorKind;
+use clap::{ArgAction, Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
 use commands::admin::AdminCommand;
 use commands::authenticated::AuthenticatedCommand;
 use commands::completion::CompletionCommand;
@@ -19,9 +21,11 @@ use commands::credential::CredentialCommand;
 use commands::enroll::EnrollCommand;
 use commands::forwarder::ForwarderCommand;
 use commands::identity::IdentityCommand;
+use commands::kafka::{KafkaCommand, KafkaServiceKind};
 use commands::lease::LeaseCommand;
 use commands::manpages::ManpagesCommand;
 use commands::message::MessageCommand;
+use commands::node::util::EmbeddedNodeHandle;
 use commands::node::NodeCommand;
 use commands::policy::PolicyCommand;
 use commands::project::ProjectCommand;
@@ -41,9 +45,13 @@ use commands::worker::WorkerCommand;
 use config::ockam_config::OckamConfig;
 use error::{Error, Result};
 use ockam_api::cli_state::CliState;
+use ockam_api::cloud::OCKAM_CONTROLLER_IDENTITY_ID;
+use std::io::Read;
+use tracing_subscriber::filter::LevelFilter;
 use upgrade::check_if_an_upgrade_is_available;
+use util::api::controller_identity_arg;
 use util::exitcode::ExitCode;
-use util::{exitcode, setup_logging};
+use util::{exitcode, json_logs_from_env, print_output, setup_logging};
 use version::Version;
 
 const ABOUT: &str = include_str!("constants/lib/about.txt");
@@ -110,6 +118,10 @@ pub struct GlobalArgs {
     )]
     output_format: OutputFormat,
 
+    /// Maximum number of seconds to wait for a response from a node
+    #[arg(hide = help::hide(), global = true, long, value_name = "SECONDS")]
+    timeout: Option<u64>,
+
     // if test_argument_parser is true, command arguments are checked
     // but the command is not executed.
     #[arg(global = true, long, hide = true)]
@@ -120,6 +132,7 @@ pub struct GlobalArgs {
 pub enum OutputFormat {
     Plain,
     Json,
+    Yaml,
 }
 
 #[derive(Clone)]
@@ -127,15 +140,18 @@ pub struct CommandGlobalOpts {
     pub global_args: GlobalArgs,
     pub config: OckamConfig,
     pub state: CliState,
+    pub embedded_node: EmbeddedNodeHandle,
 }
 
 impl CommandGlobalOpts {
-    fn new(global_args: GlobalArgs, config: OckamConfig) -> Self {
-        Self {
+    fn new(global_args: GlobalArgs, config: OckamConfig) -> Result<Self> {
+        let state = CliState::new()?;
+        Ok(Self {
             global_args,
             config,
-            state: CliState::new().expect("Failed to load CLI state"),
-        }
+            embedded_node: EmbeddedNodeHandle::new(state.clone()),
+            state,
+        })
     }
 }
 
@@ -189,20 +205,63 @@ pub enum OckamSubcommand {
     Admin(AdminCommand),
     Manpages(ManpagesCommand),
     Lease(LeaseCommand),
+    /// Manage Kafka consumers
+    KafkaConsumer(KafkaCommand),
+    /// Manage Kafka producers
+    KafkaProducer(KafkaCommand),
 }
 
 pub fn run() {
-    let input = std::env::args()
-        .map(replace_hyphen_with_stdin)
-        .collect::<Vec<_>>();
-    let command: OckamCommand = OckamCommand::parse_from(input);
+    let input = match replace_hyphen_with_stdin(std::env::args().collect(), std::io::stdin()) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("{e:?}");
+            std::process::exit(e.code());
+        }
+    };
+    let parsed = OckamCommand::command()
+        .try_get_matches_from(&input)
+        .and_then(|matches| Ok((OckamCommand::from_arg_matches(&matches)?, matches)));
+    let (command, matches) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) if e.kind() == ErrorKind::DisplayVersion => {
+            match version::output_format_arg(&input) {
+                Some(format) if format != OutputFormat::Plain => {
+                    if let Err(e) = print_output(Version::info(), &format) {
+                        eprintln!("{e:?}");
+                        std::process::exit(e.code());
+                    }
+                    std::process::exit(exitcode::OK);
+                }
+                _ => e.exit(),
+            }
+        }
+        // Help and version requests are not errors and keep clap's exit code
+        Err(e) if !e.use_stderr() => e.exit(),
+        Err(e) => {
+            let _ = e.print();
+            std::process::exit(exitcode::USAGE);
+        }
+    };
 
     if !command.global_args.test_argument_parser {
         check_if_an_upgrade_is_available();
     }
 
+    // Nodes, embedded or spawned in the background, enforce the controller
+    // identity set in their environment. This only reaches the nodes started by
+    // this process: a running node keeps the identity it was started with
+    if let Some(id) = controller_identity_arg(&matches) {
+        std::env::set_var(OCKAM_CONTROLLER_IDENTITY_ID, id.to_string());
+    }
+
     if !command.global_args.quiet {
-        setup_logging(command.global_args.verbose, command.global_args.no_color);
+        setup_logging(
+            command.global_args.verbose,
+            command.log_level(),
+            command.global_args.no_color,
+            command.json_logs(),
+        );
         tracing::debug!("{}", Version::short());
         tracing::debug!("Parsed {:?}", &command);
     }
@@ -211,9 +270,29 @@ pub fn run() {
 }
 
 impl OckamCommand {
+    /// Whether to log json lines instead of human-readable text
+    fn json_logs(&self) -> bool {
+        let flag = matches!(&self.subcommand, OckamSubcommand::Node(c) if c.json_logs());
+        flag || json_logs_from_env()
+    }
+
+    /// Level of the logs of a foreground node, regardless of the verbosity
+    fn log_level(&self) -> Option<LevelFilter> {
+        match &self.subcommand {
+            OckamSubcommand::Node(c) => c.foreground_log_level(),
+            _ => None,
+        }
+    }
+
     pub fn run(self) {
         let config = OckamConfig::load().expect("Failed to load config");
-        let options = CommandGlobalOpts::new(self.global_args, config);
+        let options = match CommandGlobalOpts::new(self.global_args, config) {
+            Ok(options) => options,
+            Err(e) => {
+                eprintln!("{e:?}");
+                std::process::exit(e.code());
+            }
+        };
 
         // If test_argument_parser is true, command arguments are checked
         // but the command is not executed. This is useful to test arguments
@@ -244,7 +323,7 @@ impl OckamCommand {
 
             OckamSubcommand::Completion(c) => c.run(),
 
-            OckamSubcommand::Authenticated(c) => c.run(),
+            OckamSubcommand::Authenticated(c) => c.run(options),
             OckamSubcommand::Configuration(c) => c.run(options),
             OckamSubcommand::Credential(c) => c.run(options),
             OckamSubcommand::Service(c) => c.run(options),
@@ -253,38 +332,148 @@ impl OckamCommand {
             OckamSubcommand::Admin(c) => c.run(options),
             OckamSubcommand::Manpages(c) => c.run(),
             OckamSubcommand::Lease(c) => c.run(options),
+            OckamSubcommand::KafkaConsumer(c) => c.run(options, KafkaServiceKind::Consumer),
+            OckamSubcommand::KafkaProducer(c) => c.run(options, KafkaServiceKind::Producer),
         }
     }
 }
 
-fn replace_hyphen_with_stdin(s: String) -> String {
-    let input_stream = std::io::stdin();
-    if s.contains("/-") {
-        let mut buffer = String::new();
-        input_stream
-            .read_line(&mut buffer)
-            .expect("could not read from standard input");
-        let args_from_stdin = buffer
-            .trim()
-            .split('/')
-            .filter(|&s| !s.is_empty())
-            .fold("".to_owned(), |acc, s| format!("{acc}/{s}"));
-
-        s.replace("/-", &args_from_stdin)
-    } else if s.contains("-/") {
-        let mut buffer = String::new();
-        input_stream
-            .read_line(&mut buffer)
-            .expect("could not read from standard input");
-
-        let args_from_stdin = buffer
-            .trim()
-            .split('/')
-            .filter(|&s| !s.is_empty())
-            .fold("/".to_owned(), |acc, s| format!("{acc}{s}/"));
-
-        s.replace("-/", &args_from_stdin)
+/// Replace the `/-` and `-/` placeholders of the arguments with the routes
+/// read from `input`.
+///
+/// The input is only read if an argument contains a placeholder. Each argument
+/// with a placeholder takes its own line of the input, in order. When there is a
+/// single placeholder, the whole input is read until EOF instead so that a route
+/// can be piped as several lines, e.g. the output of several commands.
+/// A route is split into its segments: `/-` is replaced by `/a/b` and `-/` by `/a/b/`.
+fn replace_hyphen_with_stdin(args: Vec<String>, mut input: impl Read) -> Result<Vec<String>> {
+    let placeholders = args.iter().filter(|s| has_placeholder(s)).count();
+    if placeholders == 0 {
+        return Ok(args);
+    }
+    let mut buffer = String::new();
+    input.read_to_string(&mut buffer).map_err(|e| {
+        Error::new(
+            exitcode::NOINPUT,
+            anyhow!("Could not read from standard input: {e}"),
+        )
+    })?;
+    let routes: Vec<Vec<&str>> = if placeholders == 1 {
+        vec![route_segments(buffer.lines())]
     } else {
-        s
+        buffer
+            .lines()
+            .take(placeholders)
+            .map(|line| route_segments(std::iter::once(line)))
+            .collect()
+    };
+    if routes.len() < placeholders || routes.iter().any(Vec::is_empty) {
+        let msg = if placeholders == 1 {
+            anyhow!("Expected a route on standard input, but it was empty")
+        } else {
+            anyhow!("Expected {placeholders} routes on standard input, one per line")
+        };
+        return Err(Error::new(exitcode::NOINPUT, msg));
+    }
+    let mut routes = routes.into_iter();
+    let args = args
+        .into_iter()
+        .map(|s| {
+            if !has_placeholder(&s) {
+                return s;
+            }
+            let segments = routes.next().expect("a route for each placeholder");
+            if s.contains("/-") {
+                let route = segments
+                    .iter()
+                    .fold(String::new(), |acc, s| format!("{acc}/{s}"));
+                s.replace("/-", &route)
+            } else {
+                let route = segments
+                    .iter()
+                    .fold("/".to_owned(), |acc, s| format!("{acc}{s}/"));
+                s.replace("-/", &route)
+            }
+        })
+        .collect();
+    Ok(args)
+}
+
+fn has_placeholder(arg: &str) -> bool {
+    arg.contains("/-") || arg.contains("-/")
+}
+
+/// The non-empty segments of the route given by `lines`.
+fn route_segments<'a>(lines: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    lines
+        .flat_map(|line| line.trim().split('/'))
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_replace_hyphen_with_stdin() {
+        // Without a placeholder the input is not read
+        let res = replace_hyphen_with_stdin(args(&["ockam", "--to", "/node/n1"]), &b""[..]);
+        assert_eq!(res.unwrap(), args(&["ockam", "--to", "/node/n1"]));
+
+        let res = replace_hyphen_with_stdin(args(&["--to", "/-"]), &b"/node/n1\n"[..]);
+        assert_eq!(res.unwrap(), args(&["--to", "/node/n1"]));
+
+        let res = replace_hyphen_with_stdin(args(&["--to", "-/service/echo"]), &b"/node/n1"[..]);
+        assert_eq!(res.unwrap(), args(&["--to", "/node/n1/service/echo"]));
+
+        // A route piped as several lines
+        let input = &b"/ip4/127.0.0.1/tcp/4000\n\n/service/hop/\n"[..];
+        let res = replace_hyphen_with_stdin(args(&["--to", "-/service/echo"]), input);
+        assert_eq!(
+            res.unwrap(),
+            args(&["--to", "/ip4/127.0.0.1/tcp/4000/service/hop/service/echo"])
+        );
+
+        // Several placeholders take a line each
+        let input = &b"/node/n1\n/service/hop\n"[..];
+        let res = replace_hyphen_with_stdin(args(&["--to", "/node/n2/-", "--from", "-/x"]), input);
+        assert_eq!(
+            res.unwrap(),
+            args(&["--to", "/node/n2/node/n1", "--from", "/service/hop/x"])
+        );
+    }
+
+    #[test]
+    fn test_replace_hyphen_with_stdin_errors() {
+        struct FailingReader;
+
+        impl Read for FailingReader {
+            fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::BrokenPipe,
+                    "closed",
+                ))
+            }
+        }
+
+        let err = replace_hyphen_with_stdin(args(&["--to", "-/service/echo"]), FailingReader);
+        assert_eq!(err.unwrap_err().code(), exitcode::NOINPUT);
+
+        let err = replace_hyphen_with_stdin(args(&["--to", "-/service/echo"]), &b" \n\n"[..]);
+        assert_eq!(err.unwrap_err().code(), exitcode::NOINPUT);
+
+        // A line is missing for the second placeholder
+        let input = &b"/node/n1\n"[..];
+        let err = replace_hyphen_with_stdin(args(&["--to", "/-", "--from", "-/x"]), input);
+        assert_eq!(err.unwrap_err().code(), exitcode::NOINPUT);
+
+        // Invalid UTF-8
+        let err = replace_hyphen_with_stdin(args(&["--to", "/-"]), &b"\xff\xfe"[..]);
+        assert_eq!(err.unwrap_err().code(), exitcode::NOINPUT);
     }
 }
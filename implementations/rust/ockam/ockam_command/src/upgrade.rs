@@ -1,10 +1,18 @@
 use std::env;
+use std::time::{Duration, SystemTime};
 
 use clap::crate_version;
 use colorful::Colorful;
+use ockam_api::cli_state::CliState;
 use serde::Deserialize;
 use tokio::runtime::Builder;
 
+/// Give up on the upgrade check if the release server is slower than this
+const UPGRADE_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Minimum time between two upgrade checks
+const UPGRADE_CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
 #[derive(Deserialize)]
 struct UpgradeFile {
     upgrade_message: Option<String>,
@@ -12,22 +20,56 @@ struct UpgradeFile {
 }
 
 pub fn check_if_an_upgrade_is_available() {
-    if !upgrade_check_is_disabled() {
-        // check if a new version has been released
-        Builder::new_current_thread()
-            .enable_all()
-            .build()
-            .unwrap()
-            .block_on(check());
+    if upgrade_check_is_disabled() {
+        return;
+    }
+
+    // only check once per interval, and don't fail the command
+    // if the CLI state can't be read or written
+    let state = CliState::new().ok();
+    if let Some(last) = state.as_ref().and_then(|s| s.last_upgrade_check()) {
+        if let Ok(elapsed) = last.elapsed() {
+            if elapsed < UPGRADE_CHECK_INTERVAL {
+                return;
+            }
+        }
+    }
+
+    // check if a new version has been released
+    let checked = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(async {
+            tokio::time::timeout(UPGRADE_CHECK_TIMEOUT, check())
+                .await
+                .unwrap_or(false)
+        });
+
+    // a failed check is retried by the next command
+    if let (true, Some(state)) = (checked, state) {
+        let _ = state.set_last_upgrade_check(SystemTime::now());
     }
 }
 
-async fn check() {
-    let url = format!(
-        "https://github.com/build-trust/ockam/releases/download/ockam_v{}/upgrade.json",
-        crate_version!()
-    );
-    let resp = reqwest::get(url).await;
+/// Print the upgrade message of the release server, if any.
+/// Return whether the upgrade file could be retrieved.
+async fn check() -> bool {
+    let url = match env::var("OCKAM_UPGRADE_CHECK_URL") {
+        Ok(url) => url,
+        Err(_) => format!(
+            "https://github.com/build-trust/ockam/releases/download/ockam_v{}/upgrade.json",
+            crate_version!()
+        ),
+    };
+    let client = match reqwest::Client::builder()
+        .timeout(UPGRADE_CHECK_TIMEOUT)
+        .build()
+    {
+        Ok(client) => client,
+        Err(_) => return false,
+    };
+    let resp = client.get(url).send().await;
 
     if let Ok(r) = resp {
         if let Ok(upgrade) = r.json::<UpgradeFile>().await {
@@ -42,8 +84,10 @@ async fn check() {
 
                 eprintln!();
             }
+            return true;
         }
     }
+    false
 }
 
 fn upgrade_check_is_disabled() -> bool {
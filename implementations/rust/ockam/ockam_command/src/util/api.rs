@@ -4,7 +4,7 @@ use std::path::PathBuf;
 use std::str::FromStr;
 
 use anyhow::{anyhow, Context};
-use clap::Args;
+use clap::{ArgMatches, Args};
 // TODO: maybe we can remove this cross-dependency inside the CLI?
 use minicbor::Decoder;
 use ockam::identity::IdentityIdentifier;
@@ -26,6 +26,7 @@ use ockam_multiaddr::MultiAddr;
 use regex::Regex;
 use tracing::trace;
 
+use crate::config::ockam_config::OckamConfig;
 use crate::util::DEFAULT_CONTROLLER_ADDRESS;
 
 ////////////// !== generators
@@ -35,6 +36,11 @@ pub(crate) fn query_status() -> RequestBuilder<'static, ()> {
     Request::get("/node")
 }
 
+/// Construct a request to query node health
+pub(crate) fn query_health() -> RequestBuilder<'static, ()> {
+    Request::get("/node/health")
+}
+
 /// Construct a request to query node tcp listeners
 pub(crate) fn list_tcp_listeners() -> RequestBuilder<'static, ()> {
     Request::get("/node/tcp/listener")
@@ -49,8 +55,12 @@ pub(crate) fn create_tcp_connection(
         cmd.address.clone(),
     );
 
-    let payload =
-        models::transport::CreateTransport::new(models::transport::TransportType::Tcp, tt, addr);
+    let mut payload =
+        models::transport::CreateTransport::new(models::transport::TransportType::Tcp, tt, addr)
+            .with_connect_timeout(cmd.connect_timeout);
+    if let Some(keepalive) = cmd.keepalive {
+        payload = payload.with_keepalive(keepalive);
+    }
     Request::post("/node/tcp/connection").body(payload)
 }
 
@@ -69,6 +79,26 @@ pub(crate) fn list_outlets() -> RequestBuilder<'static, ()> {
     Request::get("/node/outlet")
 }
 
+/// Construct a request to delete the inlet with the given alias
+pub(crate) fn delete_inlet(alias: &str) -> RequestBuilder<'static, ()> {
+    Request::delete(format!("/node/inlet/{alias}"))
+}
+
+/// Construct a request to delete the outlet with the given alias
+pub(crate) fn delete_outlet(alias: &str) -> RequestBuilder<'static, ()> {
+    Request::delete(format!("/node/outlet/{alias}"))
+}
+
+/// Construct a request to list the forwarders of the given node
+pub(crate) fn list_forwarders() -> RequestBuilder<'static, ()> {
+    Request::get("/node/forwarder")
+}
+
+/// Construct a request to delete the forwarder with the given alias
+pub(crate) fn delete_forwarder(alias: &str) -> RequestBuilder<'static, ()> {
+    Request::delete(format!("/node/forwarder/{alias}"))
+}
+
 /// Construct a request builder to list all secure channels on the given node
 pub(crate) fn list_secure_channels() -> RequestBuilder<'static, ()> {
     Request::get("/node/secure_channel")
@@ -79,6 +109,11 @@ pub(crate) fn list_workers() -> RequestBuilder<'static, ()> {
     Request::get("/node/workers")
 }
 
+/// Construct a request builder to show a single worker on the given node
+pub(crate) fn show_worker(addr: &str) -> RequestBuilder<'static, ()> {
+    Request::get(format!("/node/workers/{addr}"))
+}
+
 pub(crate) fn delete_secure_channel(
     addr: &Address,
 ) -> RequestBuilder<'static, models::secure_channel::DeleteSecureChannelRequest<'static>> {
@@ -117,6 +152,11 @@ pub(crate) fn list_secure_channel_listener() -> RequestBuilder<'static, ()> {
     Request::get("/node/secure_channel_listener")
 }
 
+/// Construct a request to delete the secure channel listener at the given address
+pub(crate) fn delete_secure_channel_listener(addr: &Address) -> RequestBuilder<'static, ()> {
+    Request::delete(format!("/node/secure_channel_listener/{}", addr.address()))
+}
+
 /// Construct a request to start a Vault Service
 pub(crate) fn start_vault_service(addr: &str) -> RequestBuilder<'static, StartVaultServiceRequest> {
     let payload = StartVaultServiceRequest::new(addr);
@@ -160,12 +200,26 @@ pub(crate) fn start_authenticator_service<'a>(
     enrollers: &'a str,
     reload_enrollers: bool,
     project: &'a str,
+    token_duration: Option<u64>,
+    credential_ttl: Option<u64>,
+    inherited_attributes: Vec<String>,
 ) -> RequestBuilder<'static, StartAuthenticatorRequest<'a>> {
     let payload =
-        StartAuthenticatorRequest::new(addr, enrollers, reload_enrollers, project.as_bytes());
+        StartAuthenticatorRequest::new(addr, enrollers, reload_enrollers, project.as_bytes())
+            .with_token_duration(token_duration)
+            .with_credential_ttl(credential_ttl)
+            .with_inherited_attributes(inherited_attributes);
     Request::post(node_service(DefaultAddress::AUTHENTICATOR)).body(payload)
 }
 
+/// Construct a request to rotate the key signing the credentials of an Authenticator Service
+pub(crate) fn rotate_authenticator_key() -> RequestBuilder<'static, ()> {
+    Request::post(format!(
+        "{}/actions/rotate_key",
+        node_service(DefaultAddress::AUTHENTICATOR)
+    ))
+}
+
 pub(crate) mod credentials {
     use ockam_api::nodes::models::credentials::{GetCredentialRequest, PresentCredentialRequest};
 
@@ -193,6 +247,8 @@ fn node_service(service_name: &str) -> String {
 /// Helpers to create enroll API requests
 pub(crate) mod enroll {
     use ockam_api::cloud::enroll::auth0::{Auth0Token, AuthenticateAuth0Token};
+    use ockam_api::cloud::enroll::enrollment_token::EnrollmentToken;
+    use ockam_api::cloud::enroll::Token;
 
     use super::*;
     use crate::commands::enroll::*;
@@ -208,6 +264,18 @@ pub(crate) mod enroll {
             None::<CowStr>,
         ))
     }
+
+    pub(crate) fn token<'a>(
+        cmd: &EnrollCommand,
+        token: String,
+    ) -> RequestBuilder<'a, CloudRequestWrapper<'a, EnrollmentToken>> {
+        let token = EnrollmentToken::new(Token::new(token));
+        Request::put("v0/enroll/token").body(CloudRequestWrapper::new(
+            token,
+            &cmd.cloud_opts.route(),
+            None::<CowStr>,
+        ))
+    }
 }
 
 /// Helpers to create spaces API requests
@@ -285,13 +353,18 @@ pub(crate) mod project {
             .body(CloudRequestWrapper::bare(cloud_route))
     }
 
-    pub(crate) fn add_enroller(
-        cmd: &AddEnrollerCommand,
-    ) -> RequestBuilder<CloudRequestWrapper<AddEnroller>> {
-        let b = AddEnroller::new(&cmd.enroller_identity_id, cmd.description.as_deref());
-        Request::post(format!("v0/project-enrollers/{}", cmd.project_id)).body(
-            CloudRequestWrapper::new(b, &cmd.cloud_opts.route(), None::<CowStr>),
-        )
+    pub(crate) fn add_enroller<'a>(
+        project_id: &str,
+        identity_id: &'a str,
+        description: Option<&'a str>,
+        cloud_route: &MultiAddr,
+    ) -> RequestBuilder<'a, CloudRequestWrapper<'a, AddEnroller<'a>>> {
+        let b = AddEnroller::new(identity_id, description);
+        Request::post(format!("v0/project-enrollers/{project_id}")).body(CloudRequestWrapper::new(
+            b,
+            cloud_route,
+            None::<CowStr>,
+        ))
     }
 
     pub(crate) fn list_enrollers(
@@ -328,6 +401,13 @@ pub(crate) const OCKAM_CONTROLLER_ADDR: &str = "OCKAM_CONTROLLER_ADDR";
 pub struct CloudOpts {
     #[arg(global = true, value_name = "IDENTITY", long)]
     pub identity: Option<String>,
+
+    /// Identifier the controller must present, the secure channel to a controller
+    /// presenting another identity is rejected. Defaults to the Ockam Orchestrator's.
+    /// Only applies to the nodes started by this command, a node which is already
+    /// running keeps the controller identity it was started with
+    #[arg(global = true, value_name = "IDENTIFIER", long)]
+    pub controller_identity: Option<IdentityIdentifier>,
 }
 
 #[derive(Clone, Debug, Args)]
@@ -338,9 +418,13 @@ pub struct ProjectOpts {
 }
 
 impl CloudOpts {
+    /// The controller route is taken from `$OCKAM_CONTROLLER_ADDR`, then from
+    /// the `controller.route` configuration key, and defaults to the Ockam Orchestrator
     pub fn route(&self) -> MultiAddr {
         let route = if let Ok(s) = std::env::var(OCKAM_CONTROLLER_ADDR) {
             s
+        } else if let Some(route) = configured_controller_route() {
+            route.to_string()
         } else {
             DEFAULT_CONTROLLER_ADDRESS.to_string()
         };
@@ -351,6 +435,29 @@ impl CloudOpts {
     }
 }
 
+/// The `--controller-identity` given to the command, if any.
+///
+/// Nodes read the controller identity from `$OCKAM_CONTROLLER_IDENTITY_ID` when
+/// they start, so it must be exported before any embedded or background node is created.
+/// Requests sent to a node which is already running are not affected.
+pub(crate) fn controller_identity_arg(matches: &ArgMatches) -> Option<IdentityIdentifier> {
+    let mut matches = matches;
+    while let Some((_, m)) = matches.subcommand() {
+        matches = m;
+    }
+    matches
+        .try_get_one::<IdentityIdentifier>("controller_identity")
+        .ok()
+        .flatten()
+        .cloned()
+}
+
+fn configured_controller_route() -> Option<MultiAddr> {
+    let config = OckamConfig::load().ok()?;
+    let route = config.inner().controller.route.clone();
+    route
+}
+
 ////////////// !== validators
 
 pub(crate) fn validate_cloud_resource_name(s: &str) -> anyhow::Result<()> {
@@ -365,7 +472,37 @@ pub(crate) fn validate_cloud_resource_name(s: &str) -> anyhow::Result<()> {
 
 #[cfg(test)]
 mod test {
-    use crate::util::api::validate_cloud_resource_name;
+    use clap::CommandFactory;
+
+    use crate::util::api::{controller_identity_arg, validate_cloud_resource_name};
+    use crate::OckamCommand;
+
+    #[test]
+    fn test_controller_identity_arg() {
+        let id = "P624ed0b2e5a2be82e267ead6b3279f683616b66de9537a23e45343c95cbb357a";
+        let matches = OckamCommand::command()
+            .try_get_matches_from(["ockam", "space", "list", "--controller-identity", id])
+            .unwrap();
+        assert_eq!(
+            Some(id.to_string()),
+            controller_identity_arg(&matches).map(String::from)
+        );
+
+        let matches = OckamCommand::command()
+            .try_get_matches_from(["ockam", "space", "list"])
+            .unwrap();
+        assert_eq!(None, controller_identity_arg(&matches));
+
+        // Commands not talking to the controller don't have the argument
+        let matches = OckamCommand::command()
+            .try_get_matches_from(["ockam", "node", "list"])
+            .unwrap();
+        assert_eq!(None, controller_identity_arg(&matches));
+
+        assert!(OckamCommand::command()
+            .try_get_matches_from(["ockam", "space", "list", "--controller-identity", "bad"])
+            .is_err());
+    }
 
     #[test]
     fn test_validate_cloud_resource_name() {
@@ -1,14 +1,19 @@
 use core::time::Duration;
 use std::env;
-use std::net::{SocketAddr, TcpListener};
+use std::future::Future;
+use std::io::Write;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr, TcpListener};
 use std::path::Path;
 use std::str::FromStr;
 
 use anyhow::{anyhow, Context as _, Result};
+use clap::ValueEnum;
+use colorful::Colorful;
 use minicbor::data::Type;
 use minicbor::{Decode, Decoder, Encode};
 use ockam::{Address, Context, NodeBuilder, Route, TcpTransport, TCP};
 use ockam_api::cli_state::{CliState, NodeState};
+use ockam_api::config::lookup::InternetAddress;
 use ockam_api::nodes::NODEMANAGER_ADDR;
 use ockam_core::api::{RequestBuilder, Response, Status};
 use ockam_core::DenyAll;
@@ -24,6 +29,7 @@ use crate::util::output::Output;
 use crate::{CommandGlobalOpts, OutputFormat};
 
 pub mod api;
+pub mod civil;
 pub mod exitcode;
 pub mod orchestrator_api;
 
@@ -138,16 +144,39 @@ impl<'a> Rpc<'a> {
         &self.node_name
     }
 
+    /// Send a request and wait for its response.
+    ///
+    /// If the global `--timeout` argument is set, the round-trip fails
+    /// with a `TEMPFAIL` exit code once it expires.
     pub async fn request<T>(&mut self, req: RequestBuilder<'_, T>) -> Result<()>
     where
         T: Encode<()>,
     {
-        let route = self.route_impl(self.ctx).await?;
-        self.buf = self
-            .ctx
-            .send_and_receive(route.clone(), req.to_vec()?)
-            .await
-            .context("Failed to receive response from node")?;
+        let req = req.to_vec()?;
+        let round_trip = async {
+            let route = self.route_impl(self.ctx).await?;
+            self.ctx.send_and_receive(route, req).await.map_err(|e| {
+                anyhow::Error::from(crate::Error::new(
+                    exitcode::TEMPFAIL,
+                    anyhow!(e).context("Failed to receive response from node"),
+                ))
+            })
+        };
+        self.buf = match self.opts.global_args.timeout {
+            None => round_trip.await?,
+            Some(secs) => tokio::time::timeout(Duration::from_secs(secs), round_trip)
+                .await
+                .map_err(|_| {
+                    crate::Error::new(
+                        exitcode::TEMPFAIL,
+                        anyhow!(
+                            "Timed out after {secs}s waiting for a response from node '{}' at {}",
+                            self.node_name,
+                            self.to
+                        ),
+                    )
+                })??,
+        };
         Ok(())
     }
 
@@ -177,13 +206,22 @@ impl<'a> Rpc<'a> {
                 ref node_state,
                 ref tcp,
             } => {
-                let port = node_state.setup()?.default_tcp_listener()?.addr.port();
-                let addr = Address::from((TCP, format!("localhost:{port}")));
+                let setup = node_state.setup()?;
+                let listener = &setup.default_tcp_listener()?.addr;
+                let addr = Address::from((TCP, local_node_address(listener)));
                 let addr_str = addr.address();
                 match tcp {
                     None => {
                         let tcp = TcpTransport::create(ctx).await?;
-                        tcp.connect(addr_str).await?;
+                        tcp.connect(addr_str).await.map_err(|e| {
+                            crate::Error::new(
+                                exitcode::TEMPFAIL,
+                                anyhow!(e).context(format!(
+                                    "Failed to connect to node '{}'",
+                                    self.node_name
+                                )),
+                            )
+                        })?;
                     }
                     Some(tcp) => {
                         // Ignore "already connected" error.
@@ -236,8 +274,9 @@ impl<'a> Rpc<'a> {
         if hdr.status() == Some(Status::Ok) {
             Ok(dec)
         } else {
+            let code = status_exit_code(hdr.status());
             let msg = self.parse_err_msg(hdr, dec);
-            Err(anyhow!(msg))
+            Err(crate::Error::new(code, anyhow!(msg)).into())
         }
     }
 
@@ -299,11 +338,48 @@ where
         OutputFormat::Json => {
             serde_json::to_string_pretty(&b).context("Failed to serialize output")?
         }
+        OutputFormat::Yaml => serde_yaml::to_string(&b).context("Failed to serialize output")?,
     };
     println!("{o}");
     Ok(b)
 }
 
+/// Exit code matching the status of a failed response.
+fn status_exit_code(status: Option<Status>) -> exitcode::ExitCode {
+    match status {
+        Some(Status::BadRequest) => exitcode::DATAERR,
+        Some(Status::Unauthorized) | Some(Status::Forbidden) => exitcode::NOPERM,
+        Some(Status::NotFound) => exitcode::UNAVAILABLE,
+        Some(Status::Conflict) => exitcode::CANTCREAT,
+        Some(Status::MethodNotAllowed) | Some(Status::NotImplemented) => exitcode::UNAVAILABLE,
+        _ => exitcode::SOFTWARE,
+    }
+}
+
+/// Whether the default identity was enrolled with the Orchestrator.
+pub fn is_enrolled(opts: &CommandGlobalOpts) -> bool {
+    opts.state.identities.default().map_or(false, |i| {
+        i.config
+            .enrollment_status
+            .as_ref()
+            .map_or(false, |s| s.is_enrolled)
+    })
+}
+
+/// Turn the failure of an Orchestrator request into a "not enrolled" error
+/// if the default identity was never enrolled, since the controller can't
+/// tell us that by itself.
+pub fn enrollment_error(opts: &CommandGlobalOpts, err: anyhow::Error) -> crate::Error {
+    if is_enrolled(opts) {
+        err.into()
+    } else {
+        crate::Error::new(
+            exitcode::NOPERM,
+            anyhow!("not enrolled; run `ockam enroll`"),
+        )
+    }
+}
+
 /// A simple wrapper for shutting down the local embedded node (for
 /// the client side of the CLI).  Swallows errors and turns them into
 /// eprintln logs.
@@ -351,21 +427,20 @@ where
     F: FnOnce(Context, A) -> Fut + Send + Sync + 'static,
     Fut: core::future::Future<Output = crate::Result<()>> + Send + 'static,
 {
-    let res = embedded_node(
-        |ctx, a| async {
-            let res = f(ctx, a).await;
-            if let Err(e) = res {
-                error!(%e);
-                eprintln!("{e:?}");
-                std::process::exit(e.code());
-            }
-            Ok(())
-        },
-        a,
-    );
-    if let Err(e) = res {
-        eprintln!("Ockam node failed: {e}");
-        std::process::exit(exitcode::SOFTWARE);
+    // The command's error is only reported once its arguments are dropped,
+    // so that the embedded node it shares between its RPCs is deleted first
+    let res = embedded_node(|ctx, a| async { Ok(f(ctx, a).await) }, a);
+    match res {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => {
+            error!(%e);
+            eprintln!("{e:?}");
+            std::process::exit(e.code());
+        }
+        Err(e) => {
+            eprintln!("Ockam node failed: {e}");
+            std::process::exit(exitcode::SOFTWARE);
+        }
     }
 }
 
@@ -413,50 +488,173 @@ where
     })?
 }
 
-pub fn find_available_port() -> Result<u16> {
-    let listener = TcpListener::bind("127.0.0.1:0").context("Unable to bind to an open port")?;
+/// Find a free port on the given local IP address, which can be IPv4 or IPv6
+pub fn find_available_port(ip: IpAddr) -> Result<u16> {
+    let listener = TcpListener::bind((ip, 0)).context("Unable to bind to an open port")?;
     let address = listener
         .local_addr()
         .context("Unable to get local address")?;
     Ok(address.port())
 }
 
-pub fn setup_logging(verbose: u8, no_color: bool) {
-    let ockam_crates = [
-        "ockam",
-        "ockam_node",
-        "ockam_core",
-        "ockam_command",
-        "ockam_identity",
-        "ockam_transport_tcp",
-        "ockam_vault",
-        "ockam_vault_sync_core",
-    ];
-    let builder = EnvFilter::builder();
-    // If `verbose` is not set, try to read the log level from the OCKAM_LOG env variable.
-    // If both `verbose` and OCKAM_LOG are not set, logging will not be enabled.
-    // Otherwise, use `verbose` to define the log level.
-    let filter = match verbose {
-        0 => match env::var("OCKAM_LOG") {
-            Ok(s) if !s.is_empty() => builder.with_env_var("OCKAM_LOG").from_env_lossy(),
-            _ => return,
+/// Environment variable setting how many ports `listen_with_retries` tries
+pub(crate) const OCKAM_PORT_BIND_RETRIES: &str = "OCKAM_PORT_BIND_RETRIES";
+const DEFAULT_PORT_BIND_RETRIES: usize = 5;
+
+/// Listen on the given address, picking an available port if its port is `0`.
+///
+/// Another process may take the picked port before it gets bound, so a new port
+/// is picked each time the bind fails, up to `$OCKAM_PORT_BIND_RETRIES` times.
+pub async fn listen_with_retries(tcp: &TcpTransport, addr: SocketAddr) -> Result<SocketAddr> {
+    bind_with_retries(addr, port_bind_retries(), |addr| async move {
+        Ok(tcp.listen(addr.to_string()).await?)
+    })
+    .await
+}
+
+fn port_bind_retries() -> usize {
+    match env::var(OCKAM_PORT_BIND_RETRIES) {
+        Ok(v) => match v.trim().parse::<usize>() {
+            Ok(n) if n > 0 => n,
+            _ => {
+                debug!(value = %v, "invalid {OCKAM_PORT_BIND_RETRIES}, using the default");
+                DEFAULT_PORT_BIND_RETRIES
+            }
         },
-        1 => builder
-            .with_default_directive(LevelFilter::INFO.into())
-            .parse_lossy(ockam_crates.map(|c| format!("{c}=info")).join(",")),
-        2 => builder
-            .with_default_directive(LevelFilter::DEBUG.into())
-            .parse_lossy(ockam_crates.map(|c| format!("{c}=debug")).join(",")),
-        _ => builder
-            .with_default_directive(LevelFilter::TRACE.into())
-            .parse_lossy(ockam_crates.map(|c| format!("{c}=trace")).join(",")),
+        Err(_) => DEFAULT_PORT_BIND_RETRIES,
+    }
+}
+
+async fn bind_with_retries<F, Fut>(
+    addr: SocketAddr,
+    attempts: usize,
+    mut bind: F,
+) -> Result<SocketAddr>
+where
+    F: FnMut(SocketAddr) -> Fut,
+    Fut: Future<Output = Result<SocketAddr>>,
+{
+    // A port chosen by the user is never replaced
+    if addr.port() != 0 {
+        return bind(addr).await;
+    }
+    for attempt in 1..=attempts {
+        let port = find_available_port(addr.ip())?;
+        match bind(SocketAddr::new(addr.ip(), port)).await {
+            Ok(bound) => return Ok(bound),
+            Err(e) => debug!(%addr, port, attempt, %e, "port was taken before it could be bound"),
+        }
+    }
+    Err(anyhow!(
+        "Unable to bind to an open port after {attempts} attempts"
+    ))
+}
+
+/// Crates whose logs are enabled by `--verbose`
+const OCKAM_CRATES: [&str; 8] = [
+    "ockam",
+    "ockam_node",
+    "ockam_core",
+    "ockam_command",
+    "ockam_identity",
+    "ockam_transport_tcp",
+    "ockam_vault",
+    "ockam_vault_sync_core",
+];
+
+/// Tracing filter directives enabling the logs of the ockam crates up to `level`
+pub fn crates_log_filter(level: LevelFilter) -> String {
+    OCKAM_CRATES.map(|c| format!("{c}={level}")).join(",")
+}
+
+/// Level of the logs enabled by a verbosity, if any
+pub fn verbose_level(verbose: u8) -> Option<LevelFilter> {
+    match verbose {
+        0 => None,
+        1 => Some(LevelFilter::INFO),
+        2 => Some(LevelFilter::DEBUG),
+        _ => Some(LevelFilter::TRACE),
+    }
+}
+
+/// Tracing filter directives enabling the logs of all the crates up to `level`.
+/// The bare level applies to the crates which are not listed.
+pub fn level_log_filter(level: LevelFilter) -> String {
+    format!("{level},{}", crates_log_filter(level))
+}
+
+/// Tracing filter of a process logging at the given level if any, or else according
+/// to the verbosity.
+///
+/// If neither is set, the directives are read from `ockam_log`, the value of the
+/// OCKAM_LOG env variable. If OCKAM_LOG is not set either, logging is not enabled.
+pub fn log_filter(
+    verbose: u8,
+    log_level: Option<LevelFilter>,
+    ockam_log: Option<String>,
+) -> Option<EnvFilter> {
+    match log_level.or_else(|| verbose_level(verbose)) {
+        // The default directive is only used when no directive is valid, so the
+        // level of the crates outside OCKAM_CRATES is also given as a directive
+        Some(level) => Some(
+            EnvFilter::builder()
+                .with_default_directive(level.into())
+                .parse_lossy(level_log_filter(level)),
+        ),
+        None => match ockam_log {
+            Some(s) if !s.is_empty() => Some(EnvFilter::builder().parse_lossy(s)),
+            _ => None,
+        },
+    }
+}
+
+/// Whether logs are requested as json lines with OCKAM_LOG_FORMAT=json
+pub fn json_logs_from_env() -> bool {
+    env::var("OCKAM_LOG_FORMAT").map_or(false, |f| f.eq_ignore_ascii_case("json"))
+}
+
+/// Level of the logs of a node, set independently of the verbosity
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Trace => LevelFilter::TRACE,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Error => LevelFilter::ERROR,
+        }
+    }
+}
+
+/// Set up the logging of this process, at the given level if any,
+/// or else according to the verbosity.
+pub fn setup_logging(verbose: u8, log_level: Option<LevelFilter>, no_color: bool, json: bool) {
+    let filter = match log_filter(verbose, log_level, env::var("OCKAM_LOG").ok()) {
+        Some(filter) => filter,
+        None => return,
     };
-    let fmt = fmt::Layer::default().with_ansi(!no_color);
-    let result = tracing_subscriber::registry()
+    let registry = tracing_subscriber::registry()
         .with(filter)
-        .with(tracing_error::ErrorLayer::default())
-        .with(fmt)
-        .try_init();
+        .with(tracing_error::ErrorLayer::default());
+    let result = if json {
+        let fmt = fmt::Layer::default()
+            .json()
+            .with_current_span(true)
+            .with_span_list(true);
+        registry.with(fmt).try_init()
+    } else {
+        let fmt = fmt::Layer::default().with_ansi(!no_color);
+        registry.with(fmt).try_init()
+    };
     if result.is_err() {
         eprintln!("Failed to initialise tracing logging.");
     }
@@ -467,6 +665,18 @@ pub fn print_path(p: &Path) -> String {
     p.to_str().unwrap_or("<unprintable>").to_string()
 }
 
+/// Absolute path of an existing file, to be used by a node which may
+/// run in another working directory
+pub fn absolute_path(p: &Path) -> crate::Result<String> {
+    let path = p.canonicalize().map_err(|e| {
+        crate::Error::new(
+            exitcode::IOERR,
+            anyhow!("Failed to read {}: {e}", p.display()),
+        )
+    })?;
+    Ok(print_path(&path))
+}
+
 /// Get address value from a string.
 ///
 /// The input string can be either a plain address of a MultiAddr formatted string.
@@ -574,6 +784,58 @@ pub fn comma_separated<T: AsRef<str>>(data: &[T]) -> String {
     data.iter().map(AsRef::as_ref).intersperse(", ").collect()
 }
 
+/// Address used by the CLI to connect to a local node listening on `listener`.
+///
+/// IPv6 listeners are reached through the IPv6 loopback, since `localhost`
+/// may resolve to an IPv4 address only.
+fn local_node_address(listener: &InternetAddress) -> String {
+    match listener {
+        InternetAddress::V6(v6) if v6.ip().is_unspecified() => {
+            SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), v6.port()).to_string()
+        }
+        InternetAddress::V6(v6) => v6.to_string(),
+        _ => format!("localhost:{}", listener.port()),
+    }
+}
+
+/// Parse a duration such as `90s`, `5m`, `2h` or `1d`. A bare number is in seconds.
+pub fn parse_duration(input: &str) -> anyhow::Result<Duration> {
+    let (value, unit) = match input.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => input.split_at(i),
+        None => (input, "s"),
+    };
+    let value: u64 = value
+        .parse()
+        .with_context(|| format!("invalid duration `{input}`"))?;
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        _ => {
+            return Err(anyhow!(
+                "invalid duration unit `{unit}`, expected one of `s`, `m`, `h` or `d`"
+            ))
+        }
+    };
+    let secs = value
+        .checked_mul(multiplier)
+        .ok_or_else(|| anyhow!("duration `{input}` is too large"))?;
+    Ok(Duration::from_secs(secs))
+}
+
+/// Format a POSIX timestamp as an RFC 3339 UTC date, e.g. `2023-01-10T12:34:56Z`.
+pub fn fmt_timestamp(secs: u64) -> String {
+    let (year, month, day) = civil::civil_from_days((secs / 86400) as i64);
+    let secs_of_day = secs % 86400;
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60
+    )
+}
+
 pub fn bind_to_port_check(address: &SocketAddr) -> bool {
     let port = address.port();
     let ip = address.ip();
@@ -585,8 +847,61 @@ pub fn is_tty<S: io_lifetimes::AsFilelike>(s: S) -> bool {
     s.is_terminal()
 }
 
+/// A spinner drawn on stderr while waiting for a long operation.
+///
+/// Nothing is drawn with `--quiet`, or when stderr is not a terminal.
+pub struct Spinner {
+    message: String,
+    enabled: bool,
+    color: bool,
+    frame: usize,
+}
+
+impl Spinner {
+    const FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+
+    pub fn new(opts: &CommandGlobalOpts, message: impl Into<String>) -> Self {
+        Spinner {
+            message: message.into(),
+            enabled: !opts.global_args.quiet && is_tty(std::io::stderr()),
+            color: !opts.global_args.no_color,
+            frame: 0,
+        }
+    }
+
+    /// Draw the next frame of the spinner
+    pub fn tick(&mut self) {
+        if !self.enabled {
+            return;
+        }
+        let frame = Self::FRAMES[self.frame % Self::FRAMES.len()];
+        self.frame += 1;
+        if self.color {
+            eprint!("\r{} {}", frame.to_string().light_yellow(), self.message);
+        } else {
+            eprint!("\r{frame} {}", self.message);
+        }
+        let _ = std::io::stderr().flush();
+    }
+
+    /// Erase the spinner
+    pub fn finish(&mut self) {
+        if self.enabled && self.frame > 0 {
+            eprint!("\r{}\r", " ".repeat(self.message.chars().count() + 2));
+            self.frame = 0;
+        }
+    }
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        self.finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::net::Ipv4Addr;
     use std::sync::Arc;
 
     use ockam_api::cli_state;
@@ -598,6 +913,69 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_find_available_port_ipv6() {
+        let ip = IpAddr::V6(Ipv6Addr::LOCALHOST);
+        let port = find_available_port(ip).unwrap();
+        assert_ne!(port, 0);
+        assert!(bind_to_port_check(&SocketAddr::new(ip, port)));
+    }
+
+    #[tokio::test]
+    async fn test_bind_with_retries() {
+        let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0);
+        let bind = |addr: SocketAddr| async move {
+            Ok::<_, anyhow::Error>(TcpListener::bind(addr)?.local_addr()?)
+        };
+
+        // Another process takes the first picked port before it's bound
+        let mut taken = None;
+        let bound = bind_with_retries(addr, 2, |addr| {
+            taken.get_or_insert_with(|| TcpListener::bind(addr).unwrap());
+            bind(addr)
+        })
+        .await
+        .unwrap();
+        let taken = taken.unwrap();
+        let taken_addr = taken.local_addr().unwrap();
+        assert_ne!(taken_addr.port(), bound.port());
+
+        // A port chosen by the user isn't replaced
+        assert!(bind_with_retries(taken_addr, 2, bind).await.is_err());
+    }
+
+    #[test]
+    fn test_local_node_address() {
+        let v4 = InternetAddress::new("127.0.0.1:4000").unwrap();
+        assert_eq!(local_node_address(&v4), "localhost:4000");
+        let v6 = InternetAddress::new("[::1]:4000").unwrap();
+        assert_eq!(local_node_address(&v6), "[::1]:4000");
+        let v6 = InternetAddress::new("[::]:4000").unwrap();
+        assert_eq!(local_node_address(&v6), "[::1]:4000");
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_duration("30m").unwrap(), Duration::from_secs(1800));
+        assert_eq!(parse_duration("2h").unwrap(), Duration::from_secs(7200));
+        assert_eq!(parse_duration("1d").unwrap(), Duration::from_secs(86400));
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("5w").is_err());
+        assert!(parse_duration("-5s").is_err());
+    }
+
+    #[test]
+    fn test_fmt_timestamp() {
+        assert_eq!(fmt_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(fmt_timestamp(1673354096), "2023-01-10T12:34:56Z");
+        assert_eq!(fmt_timestamp(951782400), "2000-02-29T00:00:00Z");
+        assert_eq!(fmt_timestamp(4102444799), "2099-12-31T23:59:59Z");
+    }
+
     #[test]
     fn test_extract_address_value() {
         let test_cases = vec![
@@ -43,7 +43,9 @@ pub struct OrchestratorApiBuilder<'a> {
 impl<'a> Drop for OrchestratorApiBuilder<'a> {
     fn drop(&mut self) {
         if let Some(node_name) = &self.node_name {
-            let _ = delete_node(self.opts, node_name, false);
+            if !self.opts.embedded_node.is_shared(node_name) {
+                let _ = delete_node(self.opts, node_name, false);
+            }
         }
     }
 }
@@ -6,13 +6,19 @@ use colorful::Colorful;
 use ockam::identity::credential::Credential;
 use ockam_api::cloud::project::{Enroller, Project};
 use ockam_api::cloud::space::Space;
+use ockam_api::nodes::models::base::{NodeHealth, NodeListItem};
+use ockam_api::nodes::models::portal::{InletList, InletStatus, OutletList, OutletStatus};
 use ockam_api::nodes::models::secure_channel::{
     CreateSecureChannelResponse,
     ShowSecureChannelResponse,
 };
+use ockam_api::nodes::models::workers::WorkerDetails;
 use ockam_api::route_to_multiaddr;
 use ockam_core::route;
 
+use crate::commands::policy::PolicyListItem;
+use crate::commands::space::SpaceListItem;
+use crate::commands::tcp::connection::TcpConnectionListItem;
 use crate::config::project::ProjectInfo;
 use crate::util::comma_separated;
 
@@ -86,6 +92,24 @@ impl Output for Vec<Space<'_>> {
     }
 }
 
+impl Output for Vec<SpaceListItem> {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.is_empty() {
+            return Ok("No spaces found".to_string());
+        }
+        let mut rows = vec![];
+        for SpaceListItem { id, name } in self {
+            rows.push([id.cell(), name.cell()]);
+        }
+        let table = rows
+            .table()
+            .title(["Id".cell().bold(true), "Name".cell().bold(true)])
+            .display()?
+            .to_string();
+        Ok(table)
+    }
+}
+
 impl Output for Project<'_> {
     fn output(&self) -> anyhow::Result<String> {
         let mut w = String::new();
@@ -114,10 +138,16 @@ impl Output for ProjectInfo<'_> {
             .unwrap_or_else(|| "N/A".to_string());
         let ar = self.authority_access_route.as_deref().unwrap_or("N/A");
         let ai = self.authority_identity.as_deref().unwrap_or("N/A");
+        let aid = self
+            .authority_identifier
+            .as_ref()
+            .map(|i| i.to_string())
+            .unwrap_or_else(|| "N/A".to_string());
         let mut w = String::new();
         writeln!(w, "{}: {}", "Project ID".bold(), self.id)?;
         writeln!(w, "{}: {}", "Project identity".bold(), pi)?;
         writeln!(w, "{}: {}", "Authority address".bold(), ar)?;
+        writeln!(w, "{}: {}", "Authority identifier".bold(), aid)?;
         write!(w, "{}: {}", "Authority identity".bold(), ai)?;
         Ok(w)
     }
@@ -169,29 +199,53 @@ impl Output for CreateSecureChannelResponse<'_> {
 
 impl Output for ShowSecureChannelResponse<'_> {
     fn output(&self) -> anyhow::Result<String> {
-        let s = match &self.channel {
-            Some(addr) => {
-                format!(
-                    "\n  Secure Channel:\n{} {}\n{} {}\n{} {}",
-                    "  •         At: ".light_magenta(),
-                    route_to_multiaddr(&route![addr.to_string()])
-                        .context("Invalid Secure Channel Address")?
-                        .to_string()
-                        .light_yellow(),
-                    "  •         To: ".light_magenta(),
-                    self.route.as_ref().unwrap().light_yellow(),
-                    "  • Authorized: ".light_magenta(),
-                    self.authorized_identifiers
-                        .as_ref()
-                        .unwrap_or(&Vec::<ockam_core::CowStr>::from(["none".into()]))
-                        .iter()
-                        .map(|id| id.light_yellow().to_string())
-                        .collect::<Vec<String>>()
-                        .join("\n\t")
-                )
-            }
-            None => format!("{}", "Channel not found".red()),
+        let addr = match &self.channel {
+            Some(addr) => addr,
+            None => return Ok(format!("{}", "Channel not found".red())),
+        };
+        // Fields missing from responses of older nodes are shown as unknown
+        let unknown = || "unknown".to_string();
+        let to_multiaddr = |addr: &str| -> anyhow::Result<String> {
+            Ok(route_to_multiaddr(&route![addr.to_string()])
+                .context("Invalid Secure Channel Address")?
+                .to_string())
+        };
+        let decryptor = match &self.decryptor {
+            Some(addr) => to_multiaddr(addr)?,
+            None => unknown(),
         };
+        let s = format!(
+            "\n  Secure Channel:\n{} {}\n{} {}\n{} {}\n{} {}\n{} {}\n{} {}",
+            "  •         At: ".light_magenta(),
+            to_multiaddr(addr)?.light_yellow(),
+            "  •         To: ".light_magenta(),
+            self.route
+                .as_ref()
+                .map(|r| r.to_string())
+                .unwrap_or_else(unknown)
+                .light_yellow(),
+            "  •  Decryptor: ".light_magenta(),
+            decryptor.light_yellow(),
+            "  •     Remote: ".light_magenta(),
+            self.remote_identity
+                .as_ref()
+                .map(|id| id.to_string())
+                .unwrap_or_else(unknown)
+                .light_yellow(),
+            "  •    Created: ".light_magenta(),
+            self.created_at
+                .map(|t| t.to_string())
+                .unwrap_or_else(unknown)
+                .light_yellow(),
+            "  • Authorized: ".light_magenta(),
+            self.authorized_identifiers
+                .as_ref()
+                .unwrap_or(&Vec::<ockam_core::CowStr>::from(["none".into()]))
+                .iter()
+                .map(|id| id.light_yellow().to_string())
+                .collect::<Vec<String>>()
+                .join("\n\t")
+        );
 
         Ok(s)
     }
@@ -244,3 +298,197 @@ impl Output for Vec<u8> {
         Ok(hex::encode(self))
     }
 }
+
+impl Output for Vec<NodeListItem> {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.is_empty() {
+            return Ok("No nodes found".to_string());
+        }
+        let mut rows = vec![];
+        for NodeListItem {
+            name,
+            pid,
+            tcp_listener_address,
+            status,
+            is_default,
+        } in self
+        {
+            rows.push([
+                name.cell(),
+                pid.map(|p| p.to_string()).unwrap_or_default().cell(),
+                tcp_listener_address.as_deref().unwrap_or_default().cell(),
+                status.cell(),
+                is_default.cell(),
+            ]);
+        }
+        let table = rows
+            .table()
+            .title([
+                "Name".cell().bold(true),
+                "PID".cell().bold(true),
+                "TCP Listener".cell().bold(true),
+                "Status".cell().bold(true),
+                "Default".cell().bold(true),
+            ])
+            .display()?
+            .to_string();
+        Ok(table)
+    }
+}
+
+impl Output for Vec<PolicyListItem> {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.is_empty() {
+            return Ok("No policies found".to_string());
+        }
+        let mut rows = vec![];
+        for PolicyListItem {
+            resource,
+            action,
+            expression,
+        } in self
+        {
+            rows.push([resource.cell(), action.cell(), expression.cell()]);
+        }
+        let table = rows
+            .table()
+            .title([
+                "Resource".cell().bold(true),
+                "Action".cell().bold(true),
+                "Expression".cell().bold(true),
+            ])
+            .display()?
+            .to_string();
+        Ok(table)
+    }
+}
+
+impl Output for Vec<TcpConnectionListItem> {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut rows = vec![];
+        for TcpConnectionListItem {
+            tid,
+            transport_type,
+            mode,
+            address,
+        } in self
+        {
+            rows.push([
+                tid.cell(),
+                transport_type.cell(),
+                mode.cell(),
+                address.cell(),
+            ]);
+        }
+        let table = rows
+            .table()
+            .title([
+                "Transport ID".cell().bold(true),
+                "Transport Type".cell().bold(true),
+                "Mode".cell().bold(true),
+                "Address bind".cell().bold(true),
+            ])
+            .display()?
+            .to_string();
+        Ok(table)
+    }
+}
+
+impl Output for NodeHealth<'_> {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut w = String::new();
+        write!(w, "Node: {}", self.node_name)?;
+        write!(w, "\n  Status: {}", "UP".light_green())?;
+        write!(w, "\n  Uptime: {}s", self.uptime)?;
+        write!(
+            w,
+            "\n  Transport Listening: {}",
+            if self.transport_listening {
+                "yes"
+            } else {
+                "no"
+            }
+        )?;
+        write!(w, "\n  Services: {}", self.services)?;
+        if let Some(e) = &self.last_error {
+            write!(w, "\n  Last Error: {e}")?;
+        }
+        Ok(w)
+    }
+}
+
+impl Output for InletList<'_> {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.list.is_empty() {
+            return Ok("No TCP inlets found".to_string());
+        }
+        let mut rows = vec![];
+        for InletStatus {
+            alias,
+            bind_addr,
+            outlet_route,
+            ..
+        } in &self.list
+        {
+            rows.push([alias.cell(), bind_addr.cell(), outlet_route.cell()]);
+        }
+        let table = rows
+            .table()
+            .title([
+                "Alias".cell().bold(true),
+                "Listen Address".cell().bold(true),
+                "Outlet Route".cell().bold(true),
+            ])
+            .display()?
+            .to_string();
+        Ok(table)
+    }
+}
+
+impl Output for OutletList<'_> {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.list.is_empty() {
+            return Ok("No TCP outlets found".to_string());
+        }
+        let mut rows = vec![];
+        for OutletStatus {
+            alias,
+            tcp_addr,
+            worker_addr,
+            ..
+        } in &self.list
+        {
+            rows.push([alias.cell(), tcp_addr.cell(), worker_addr.cell()]);
+        }
+        let table = rows
+            .table()
+            .title([
+                "Alias".cell().bold(true),
+                "Socket Address".cell().bold(true),
+                "Worker Address".cell().bold(true),
+            ])
+            .display()?
+            .to_string();
+        Ok(table)
+    }
+}
+
+impl Output for WorkerDetails<'_> {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut w = String::new();
+        writeln!(w, "Worker:")?;
+        writeln!(w, "  Address: {}", self.addr)?;
+        writeln!(w, "  Mailboxes: {}", self.mailboxes)?;
+        writeln!(
+            w,
+            "  Incoming access control: {}",
+            self.incoming_access_control
+        )?;
+        write!(
+            w,
+            "  Outgoing access control: {}",
+            self.outgoing_access_control
+        )?;
+        Ok(w)
+    }
+}
@@ -1,6 +1,11 @@
 //! Helpers to display version information
 
 use clap::crate_version;
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::util::output::Output;
+use crate::OutputFormat;
 
 pub(crate) struct Version;
 
@@ -18,4 +23,80 @@ impl Version {
         let message = format!("Version {crate_version}, hash: {git_hash}");
         Box::leak(message.into_boxed_str())
     }
+
+    /// Structured version information, set at build time
+    pub(crate) fn info() -> VersionInfo {
+        VersionInfo {
+            version: crate_version!(),
+            commit: env!("GIT_HASH").trim(),
+            build_date: env!("BUILD_DATE"),
+            target: env!("BUILD_TARGET"),
+        }
+    }
+}
+
+/// Version information printed by `ockam --version --output json`
+#[derive(Debug, Serialize)]
+pub(crate) struct VersionInfo {
+    version: &'static str,
+    commit: &'static str,
+    build_date: &'static str,
+    target: &'static str,
+}
+
+impl Output for VersionInfo {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(Version::long().to_string())
+    }
+}
+
+/// Output format requested with `--output`.
+///
+/// Clap prints the version as soon as it meets `--version`, without parsing the
+/// other arguments, so they are looked up in the raw arguments instead.
+pub(crate) fn output_format_arg(args: &[String]) -> Option<OutputFormat> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let value = match arg.strip_prefix("--output") {
+            Some("") => args.next().map(String::as_str),
+            Some(value) => value.strip_prefix('='),
+            None => None,
+        };
+        if let Some(value) = value {
+            return OutputFormat::from_str(value, true).ok();
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn version_info_is_semver() {
+        let json = serde_json::to_value(Version::info()).unwrap();
+        let version = json["version"].as_str().unwrap();
+        // Pre-release and build metadata suffixes are allowed by semver
+        let core = version.split(['-', '+']).next().unwrap();
+        let parts: Vec<&str> = core.split('.').collect();
+        assert_eq!(parts.len(), 3);
+        assert!(parts.iter().all(|p| p.parse::<u64>().is_ok()));
+        for key in ["commit", "build_date", "target"] {
+            assert!(json[key].is_string());
+        }
+    }
+
+    #[test]
+    fn output_format_is_found_in_raw_args() {
+        let format = output_format_arg(&args(&["ockam", "--version", "--output", "json"]));
+        assert_eq!(format, Some(OutputFormat::Json));
+        let format = output_format_arg(&args(&["ockam", "--output=yaml", "--version"]));
+        assert_eq!(format, Some(OutputFormat::Yaml));
+        assert_eq!(output_format_arg(&args(&["ockam", "--version"])), None);
+    }
 }
@@ -20,5 +20,42 @@ fn valid_arguments() -> Result<(), Box<dyn std::error::Error>> {
         .arg("node-name");
     cmd.assert().success();
 
+    // node health success
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("node")
+        .arg("health")
+        .arg("node-name");
+    cmd.assert().success();
+
+    // node create with a log level
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("node")
+        .arg("create")
+        .arg("node-name")
+        .arg("--log-level")
+        .arg("info");
+    cmd.assert().success();
+
+    // node create with an unknown log level
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("node")
+        .arg("create")
+        .arg("node-name")
+        .arg("--log-level")
+        .arg("verbose");
+    cmd.assert().failure();
+
+    // a credential refresh needs an enrollment token
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("node")
+        .arg("create")
+        .arg("node-name")
+        .arg("--force-credential-refresh");
+    cmd.assert().failure();
+
     Ok(())
 }
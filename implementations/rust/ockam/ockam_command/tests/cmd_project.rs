@@ -35,7 +35,34 @@ fn valid_arguments() -> Result<(), Box<dyn std::error::Error>> {
     cmd.args(prefix_args)
         .arg("authenticate")
         .arg("--token")
-        .arg("02043d7bc316467b25b8df7118f4d1ba4b1911284236a3f94d8017ac7faff625");
+        .arg("02043d7bc316467b25b8df7118f4d1ba4b1911284236a3f94d8017ac7faff625")
+        .arg("--attribute")
+        .arg("team=blue");
+    cmd.assert().success();
+
+    // Attributes can only be requested when redeeming a token
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.args(prefix_args)
+        .arg("authenticate")
+        .arg("--attribute")
+        .arg("team=blue");
+    cmd.assert().failure();
+
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.args(prefix_args)
+        .arg("enroll")
+        .arg("--attribute")
+        .arg("role=member")
+        .arg("--allow-attribute")
+        .arg("team");
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.args(prefix_args)
+        .arg("add-enroller")
+        .arg("project-id")
+        .arg("--from-file")
+        .arg("enrollers.json");
     cmd.assert().success();
 
     Ok(())
@@ -0,0 +1,41 @@
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+
+#[test]
+fn valid_arguments() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("secure-channel-listener")
+        .arg("delete")
+        .arg("my_listener")
+        .arg("--at")
+        .arg("n1");
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("secure-channel-listener")
+        .arg("delete")
+        .arg("my_listener")
+        .arg("--at")
+        .arg("/node/n1")
+        .arg("--output")
+        .arg("json");
+    cmd.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn missing_address() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("secure-channel-listener")
+        .arg("delete")
+        .arg("--at")
+        .arg("n1");
+    cmd.assert().failure();
+
+    Ok(())
+}
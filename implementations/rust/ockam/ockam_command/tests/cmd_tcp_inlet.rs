@@ -0,0 +1,27 @@
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+
+#[test]
+fn valid_arguments() -> Result<(), Box<dyn std::error::Error>> {
+    // list inlets success
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("tcp-inlet")
+        .arg("list")
+        .arg("--node")
+        .arg("node-name");
+    cmd.assert().success();
+
+    // delete inlet success
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("tcp-inlet")
+        .arg("delete")
+        .arg("inlet-alias")
+        .arg("--node")
+        .arg("node-name");
+    cmd.assert().success();
+
+    Ok(())
+}
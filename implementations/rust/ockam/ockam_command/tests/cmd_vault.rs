@@ -0,0 +1,69 @@
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+
+#[test]
+fn valid_arguments() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("vault")
+        .arg("create")
+        .arg("v1")
+        .arg("--aws-kms")
+        .arg("--aws-region")
+        .arg("eu-west-1");
+    cmd.assert().success();
+
+    // The AWS options only make sense for a KMS vault
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("vault")
+        .arg("create")
+        .arg("v1")
+        .arg("--aws-region")
+        .arg("eu-west-1");
+    cmd.assert().failure();
+
+    Ok(())
+}
+
+/// Create a KMS vault and an identity in it, against the AWS account
+/// configured in the environment. Skipped when there are no credentials.
+#[cfg(feature = "aws-kms")]
+#[test]
+fn aws_kms_identity() -> Result<(), Box<dyn std::error::Error>> {
+    if std::env::var_os("AWS_ACCESS_KEY_ID").is_none() {
+        eprintln!("skipping aws_kms_identity: no AWS credentials in the environment");
+        return Ok(());
+    }
+    let home = tempfile::tempdir()?;
+
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.env("OCKAM_HOME", home.path())
+        .arg("vault")
+        .arg("create")
+        .arg("kms")
+        .arg("--aws-kms");
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.env("OCKAM_HOME", home.path())
+        .arg("identity")
+        .arg("create")
+        .arg("kms-identity")
+        .arg("--vault")
+        .arg("kms");
+    cmd.assert().success();
+
+    // The root key of the identity is a P-256 key held in KMS
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.env("OCKAM_HOME", home.path())
+        .arg("identity")
+        .arg("show")
+        .arg("kms-identity")
+        .arg("--full");
+    let output = cmd.assert().success().get_output().stdout.clone();
+    assert!(String::from_utf8(output)?.contains("NistP256"));
+
+    Ok(())
+}
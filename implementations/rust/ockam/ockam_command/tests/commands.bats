@@ -77,11 +77,59 @@ teardown() {
 }
 
 
+@test "print the version as json" {
+  run $OCKAM --version --output json
+  assert_success
+  assert_output --regexp '"version": "[0-9]+\.[0-9]+\.[0-9]+'
+  assert_output --partial '"commit": "'
+  assert_output --partial '"target": "'
+
+  # The plain version is still printed by clap
+  run $OCKAM --version
+  assert_success
+  assert_output --partial "Compiled from (git hash)"
+}
+
+@test "enroll rejects an expired or malformed ticket" {
+  echo '{"token": "abcd1234", "expires_at": 1}' >"$BATS_TEST_TMPDIR/expired.ticket"
+  run $OCKAM enroll --ticket "$BATS_TEST_TMPDIR/expired.ticket"
+  assert_failure 77
+  assert_output --partial "The enrollment ticket has expired"
+
+  echo '{"expires_at": 1}' >"$BATS_TEST_TMPDIR/malformed.ticket"
+  run $OCKAM enroll --ticket "$BATS_TEST_TMPDIR/malformed.ticket"
+  assert_failure 64
+  assert_output --partial "Malformed enrollment ticket"
+}
+
 @test "create a node without a name" {
   run $OCKAM node create
   assert_success
 }
 
+@test "create a node with --if-not-exists" {
+  n=$(openssl rand -hex 4)
+  run $OCKAM node create $n --if-not-exists
+  assert_success
+
+  # An existing node is reused
+  run $OCKAM node create $n --if-not-exists --output json
+  assert_success
+  assert_output --partial "\"name\": \"$n\""
+
+  # A stopped node is started again
+  $OCKAM node stop $n
+  run $OCKAM node create $n --if-not-exists
+  assert_success
+  run $OCKAM node show $n
+  assert_output --partial "UP"
+
+  # A conflicting configuration is an error
+  run $OCKAM node create $n --if-not-exists --tcp-listener-address 127.0.0.1:1
+  assert_failure 78
+  assert_output --partial "already exists with a different configuration"
+}
+
 @test "node is restarted with default services" {
   # Create node, check that it has one of the default services running
   run $OCKAM node create n1
@@ -95,6 +143,448 @@ teardown() {
   assert_output --partial "/service/vault_service"
 }
 
+@test "node is restarted with the same listener and launch services" {
+  n=$(openssl rand -hex 4)
+  echo '{"startup_services": {"verifier": {"address": "restart_verifier"}}}' >/tmp/restart_launch_config.json
+  run $OCKAM node create $n --tcp-listener-address 127.0.0.1:6231 --launch-config /tmp/restart_launch_config.json
+  assert_success
+  sleep 1
+
+  run $OCKAM node restart $n
+  assert_success
+  assert_output --partial "127.0.0.1:6231"
+
+  # Launch services are started asynchronously
+  sleep 1
+  run $OCKAM node show $n
+  assert_success
+  assert_output --partial "/service/restart_verifier"
+}
+
+@test "node restart fails without a persisted setup" {
+  export OCKAM_HOME=/tmp/ockam
+  n=$(openssl rand -hex 4)
+  run $OCKAM node create $n
+  assert_success
+  $OCKAM node stop $n
+  rm "$OCKAM_HOME/nodes/$n/setup.json"
+
+  run $OCKAM node restart $n
+  assert_failure
+  assert_output --partial "no persisted setup configuration"
+}
+
+@test "node logs json lines with --json-logs" {
+  export OCKAM_HOME=/tmp/ockam
+  n=$(openssl rand -hex 4)
+  run $OCKAM node create $n --json-logs -vv
+  assert_success
+
+  run bash -c "head -n 1 '$OCKAM_HOME/nodes/$n/stdout.log' | python3 -m json.tool"
+  assert_success
+  assert_output --partial '"level": "'
+
+  # The format is kept when the node is restarted
+  : >"$OCKAM_HOME/nodes/$n/stdout.log"
+  run $OCKAM node restart $n
+  assert_success
+  run bash -c "head -n 1 '$OCKAM_HOME/nodes/$n/stdout.log' | python3 -m json.tool"
+  assert_success
+}
+
+@test "background node logs at the requested verbosity" {
+  export OCKAM_HOME=/tmp/ockam
+  n=$(openssl rand -hex 4)
+  run $OCKAM node create $n -vvv
+  assert_success
+
+  run cat "$OCKAM_HOME/nodes/$n/stdout.log"
+  assert_output --partial "TRACE"
+
+  # The verbosity is kept when the node is restarted
+  : >"$OCKAM_HOME/nodes/$n/stdout.log"
+  run $OCKAM node restart $n
+  assert_success
+  run cat "$OCKAM_HOME/nodes/$n/stdout.log"
+  assert_output --partial "TRACE"
+}
+
+@test "background node logs at the requested log level" {
+  export OCKAM_HOME=/tmp/ockam
+  n=$(openssl rand -hex 4)
+  # The command itself stays quiet
+  run $OCKAM node create $n --log-level trace
+  assert_success
+  refute_output --partial "TRACE"
+
+  run cat "$OCKAM_HOME/nodes/$n/stdout.log"
+  assert_output --partial "TRACE"
+  run cat "$OCKAM_HOME/nodes/$n/setup.json"
+  assert_output --partial '"log_level":"trace"'
+
+  # The log level is kept when the node is restarted, and takes precedence over the verbosity
+  : >"$OCKAM_HOME/nodes/$n/stdout.log"
+  run $OCKAM node restart $n -v
+  assert_success
+  run cat "$OCKAM_HOME/nodes/$n/stdout.log"
+  assert_output --partial "TRACE"
+
+  # A node can log less than the command
+  m=$(openssl rand -hex 4)
+  run $OCKAM node create $m --log-level error -vvv
+  assert_success
+  run cat "$OCKAM_HOME/nodes/$m/stdout.log"
+  refute_output --partial "INFO"
+  refute_output --partial "DEBUG"
+}
+
+@test "show several nodes with a pattern or --all" {
+  p=$(openssl rand -hex 4)
+  run $OCKAM node create "$p-1"
+  assert_success
+  run $OCKAM node create "$p-2"
+  assert_success
+  run $OCKAM node create other
+  assert_success
+  $OCKAM node stop "$p-2"
+
+  run $OCKAM node show "$p-*" --output json
+  assert_success
+  assert_output --regexp '^\['
+  assert_output --partial "\"name\": \"$p-1\""
+  assert_output --partial "\"name\": \"$p-2\""
+  assert_output --partial '"status": "DOWN"'
+  refute_output --partial '"name": "other"'
+
+  run $OCKAM node show --all
+  assert_success
+  assert_output --partial "Name: $p-1"
+  assert_output --partial "Name: $p-2"
+  assert_output --partial "Name: other"
+
+  run $OCKAM node show "nomatch-*"
+  assert_failure
+}
+
+@test "list nodes with json output" {
+  run $OCKAM node create n1
+  assert_success
+  run $OCKAM node create n2
+  assert_success
+  $OCKAM node stop n2
+
+  run $OCKAM node list --output json
+  assert_success
+  assert_output --regexp '"name": "n1",'
+  assert_output --regexp '"status": "running",'
+  assert_output --regexp '"name": "n2",'
+  assert_output --regexp '"status": "stopped",'
+}
+
+@test "check the health of a node" {
+  run $OCKAM node create n1
+  assert_success
+
+  run $OCKAM node health n1 --output json
+  assert_success
+  assert_output --partial '"node_name": "n1"'
+  assert_output --partial '"transport_listening": true'
+
+  $OCKAM node stop n1
+  run $OCKAM node health n1
+  assert_failure
+}
+
+@test "show a worker" {
+  n=$(openssl rand -hex 4)
+  run $OCKAM node create $n
+  assert_success
+
+  run $OCKAM worker show echo --at $n --output json
+  assert_success
+  assert_output --partial '"addr": "echo"'
+  assert_output --partial '"mailboxes": 1'
+
+  run $OCKAM worker show missing --at $n
+  assert_failure
+  assert_output --partial "no such worker"
+}
+
+@test "create a node listening on IPv6" {
+  n=$(openssl rand -hex 4)
+  run $OCKAM node create $n --tcp-listener-address "[::1]:0"
+  assert_success
+
+  run $OCKAM node show $n
+  assert_success
+  assert_output --partial "UP"
+
+  # The port picked for the listener is recorded in the node setup
+  run $OCKAM node list --output json
+  assert_success
+  assert_output --partial "\"tcp_listener_address\": \"[::1]:"
+  refute_output --partial "\"tcp_listener_address\": \"[::1]:0\""
+}
+
+@test "create a node with an lmdb attributes storage" {
+  n=$(openssl rand -hex 4)
+  run $OCKAM node create $n --attributes-storage lmdb
+  assert_success
+
+  run $OCKAM node show $n
+  assert_success
+  assert_output --partial "UP"
+  assert [ -f "${OCKAM_HOME:-$HOME/.ockam}/nodes/$n/attributes_storage.lmdb" ]
+
+  run $OCKAM node create --attributes-storage sql
+  assert_failure
+}
+
+@test "status with json output" {
+  n=$(openssl rand -hex 4)
+  run $OCKAM node create $n
+  assert_success
+
+  run $OCKAM status --output json
+  assert_success
+  assert_output --partial "\"enrolled\": false"
+  assert_output --partial "\"controller_identity\":"
+  assert_output --partial "\"default_space\": null"
+  assert_output --partial "\"default_project\": null"
+  assert_output --partial "\"nodes\": ["
+  assert_output --partial "\"name\": \"$n\""
+}
+
+@test "create a node in dry-run mode" {
+  n=$(openssl rand -hex 4)
+  run $OCKAM node create $n --dry-run
+  assert_success
+  assert_output --partial "Dry run"
+
+  run $OCKAM node create $n --dry-run --output json
+  assert_success
+  assert_output --partial "\"node_name\": \"$n\""
+  assert_output --partial "\"tcp_listener_address\": \"127.0.0.1:"
+
+  # Nothing was created
+  run $OCKAM node show $n
+  assert_failure
+}
+
+@test "print the logs of a node" {
+  n=$(openssl rand -hex 4)
+  run $OCKAM node create $n
+  assert_success
+
+  run --separate-stderr $OCKAM node logs $n --path
+  assert_success
+  assert_output --regexp "stdout.log$"
+
+  run --separate-stderr $OCKAM node logs $n --lines 1
+  assert_success
+  assert [ "${#lines[@]}" -le 1 ]
+
+  run $OCKAM node logs $n --path --follow
+  assert_failure
+}
+
+@test "stop a foreground node with SIGTERM" {
+  n=$(openssl rand -hex 4)
+  $OCKAM node create $n --foreground &
+  pid=$!
+  sleep 1
+
+  kill -TERM $pid
+  # `run` would wait from a subshell, which isn't the parent of the node
+  status=0
+  wait $pid || status=$?
+  assert_equal "$status" 0
+
+  # The node is marked as stopped
+  run $OCKAM node show $n
+  assert_success
+  assert_output --partial "DOWN"
+}
+
+@test "create and stop a node without an api transport" {
+  n=$(openssl rand -hex 4)
+  run $OCKAM node create $n --no-api-transport
+  assert_success
+  assert_output --partial "without an API transport"
+
+  # The node can't be reached by other commands
+  run $OCKAM node show $n
+  assert_failure 69
+  assert_output --partial "--no-api-transport"
+
+  run $OCKAM node stop $n
+  assert_success
+
+  run $OCKAM node create --no-api-transport --tcp-listener-address 127.0.0.1:6001
+  assert_failure
+}
+
+@test "reset keeping identities" {
+  export OCKAM_HOME=/tmp/ockam
+  i=$(openssl rand -hex 4)
+  run $OCKAM identity create $i
+  assert_success
+  run $OCKAM node create n1 --identity $i
+  assert_success
+
+  run $OCKAM reset --keep-identities --yes
+  assert_success
+
+  # Nodes are gone, the identity is still there and can be used by a new node
+  run $OCKAM node show n1
+  assert_failure
+  run $OCKAM identity show $i
+  assert_success
+  run $OCKAM node create n2 --identity $i
+  assert_success
+  unset OCKAM_HOME
+}
+
+@test "a corrupt node is reported and removed by reset --repair" {
+  export OCKAM_HOME=/tmp/ockam
+  run $OCKAM node create n1
+  assert_success
+  $OCKAM node stop n1
+  $OCKAM node create n2
+  echo "{" >"$OCKAM_HOME/nodes/n1/setup.json"
+  echo "{" >"$OCKAM_HOME/projects/p1.json"
+
+  # Commands which don't need the node keep working, and skip it
+  run $OCKAM identity list
+  assert_success
+  run $OCKAM node list
+  assert_success
+  assert_output --partial "n2"
+  refute_output --partial "n1"
+
+  run $OCKAM node show n1
+  assert_failure 78
+  assert_output --partial "nodes/n1/setup.json"
+  assert_output --partial "ockam reset --repair"
+
+  run $OCKAM reset --repair --yes
+  assert_success
+  assert_output --partial "nodes/n1"
+  assert_output --partial "projects/p1.json"
+
+  run $OCKAM node show n1
+  assert_failure
+  run $OCKAM identity list
+  assert_success
+  unset OCKAM_HOME
+}
+
+@test "exit codes follow sysexits" {
+  # Missing node: EX_UNAVAILABLE
+  run $OCKAM node show missing-$(openssl rand -hex 4)
+  assert_failure 69
+
+  # Bad argument: EX_USAGE
+  run $OCKAM node create --no-such-argument
+  assert_failure 64
+
+  # Unreachable node: EX_TEMPFAIL
+  n=$(openssl rand -hex 4)
+  run $OCKAM node create $n
+  assert_success
+  $OCKAM node stop $n
+  run $OCKAM credential get --node $n
+  assert_failure 75
+}
+
+@test "lease show --raw conflicts with --output" {
+  run $OCKAM lease show --token-id 1 --raw --output json
+  assert_failure 64
+  assert_output --partial "--raw can't be used together with --output"
+}
+
+@test "install shell completions" {
+  home=$(mktemp -d)
+
+  run env HOME=$home SHELL=/bin/zsh $OCKAM completion --install
+  assert_success
+  assert_output --partial "$home/.zfunc/_ockam"
+
+  run env HOME=$home $OCKAM completion --install --shell fish
+  assert_success
+  assert [ -f "$home/.config/fish/completions/ockam.fish" ]
+
+  # An existing file is only overwritten with --force
+  run env HOME=$home $OCKAM completion --install --shell fish
+  assert_failure 73
+  run env HOME=$home $OCKAM completion --install --shell fish --force
+  assert_success
+
+  rm -rf $home
+}
+
+@test "get and set configuration keys" {
+  export OCKAM_HOME=/tmp/ockam
+  $OCKAM node create n1
+  $OCKAM node create n2
+
+  # `get` still prints the address of a node
+  run $OCKAM configuration get n1
+  assert_success
+  assert_output --partial "Address: "
+
+  run $OCKAM configuration set default_node n2
+  assert_success
+  run $OCKAM configuration get-key default_node
+  assert_success
+  assert_output "n2"
+
+  run $OCKAM configuration get-key controller.route
+  assert_success
+  assert_output "controller.route is not set"
+
+  run $OCKAM configuration set controller.route /dnsaddr/localhost/tcp/4000/service/api
+  assert_success
+  run $OCKAM configuration get-key controller.route --output json
+  assert_success
+  assert_output --partial "\"key\": \"controller.route\""
+  assert_output --partial "\"value\": \"/dnsaddr/localhost/tcp/4000/service/api\""
+
+  # Values are validated before being stored
+  run $OCKAM configuration set controller.route not-a-route
+  assert_failure 64
+  run $OCKAM configuration set default_node n3
+  assert_failure 64
+  run $OCKAM configuration get-key default_node
+  assert_output "n2"
+}
+
+@test "unknown configuration keys are rejected" {
+  export OCKAM_HOME=/tmp/ockam
+  run $OCKAM configuration get-key controller.address
+  assert_failure 64
+  assert_output --partial "Valid keys are: default_node, controller.route"
+
+  run $OCKAM configuration set controller.address /dnsaddr/localhost/tcp/4000
+  assert_failure 64
+}
+
+@test "print the json schema of the node api models" {
+  run bash -c "$OCKAM admin schema | python3 -m json.tool"
+  assert_success
+  assert_output --partial "\"CreateTransport\""
+  assert_output --partial "\"ShortIdentityResponse\""
+
+  run $OCKAM admin schema CreateSecureChannelRequest
+  assert_success
+  assert_output --partial "\"title\": \"credential\""
+  assert_output --partial "\"Credential\""
+
+  run $OCKAM admin schema NotAModel
+  assert_failure 64
+  assert_output --partial "CreateSecureChannelRequest"
+}
+
 @test "create a vault and do show on it" {
   vault_name1=$(openssl rand -hex 4)
   run $OCKAM vault create "${vault_name1}"
@@ -104,6 +594,16 @@ teardown() {
   assert_success
   assert_output --partial "Name: ${vault_name1}"
   assert_output --partial "Type: OCKAM"
+  assert_output --partial "Secrets: 0"
+
+  run $OCKAM vault show "${vault_name1}" --output json
+  assert_success
+  assert_output --partial "\"name\": \"${vault_name1}\""
+  assert_output --partial "\"type\": \"file\""
+
+  run $OCKAM vault show "$(openssl rand -hex 4)"
+  assert_failure
+  assert_output --partial "not found"
 
   vault_name2=$(openssl rand -hex 4)
   run $OCKAM vault create "${vault_name2}" --aws-kms
@@ -120,6 +620,10 @@ teardown() {
   assert_output --partial "Type: OCKAM"
   assert_output --partial "Name: ${vault_name2}"
   assert_output --partial "Type: AWS KMS"
+
+  run $OCKAM vault list --output json
+  assert_success
+  assert_output --partial "\"type\": \"aws-kms\""
 }
 
 @test "create a identity and do show on it" {
@@ -129,7 +633,20 @@ teardown() {
 
   run $OCKAM identity show "${idt_name}"
   assert_success
-  assert_output --regexp '^P'
+  assert_line --regexp '^Identifier: P'
+  assert_line "Key Type: Ed25519"
+  assert_line "Changes: 1"
+  assert_line --regexp '^Created At: [0-9]{4}-[0-9]{2}-[0-9]{2}T[0-9:]{8}Z$'
+}
+
+@test "create a P-256 identity" {
+  idt_name=$(openssl rand -hex 4)
+  run $OCKAM identity create "${idt_name}" --key-type p256
+  assert_success
+
+  run $OCKAM identity show "${idt_name}" --full
+  assert_success
+  assert_output --partial "Key Type: NistP256"
 }
 
 @test "create a identity and do show change history on it" {
@@ -144,6 +661,69 @@ teardown() {
   assert_output --partial "signatures"
 }
 
+@test "show the raw change history of an identity" {
+  idt_name=$(openssl rand -hex 4)
+  run $OCKAM identity create "${idt_name}"
+  assert_success
+  stored=$($OCKAM identity show "${idt_name}" --full --encoding hex)
+
+  run $OCKAM identity show "${idt_name}" --change-history
+  assert_success
+  assert [ "$(echo "$output" | tr -d '\n')" == "$stored" ]
+
+  run $OCKAM identity show "${idt_name}" --change-history --base64
+  assert_success
+  assert [ "$(echo "$output" | tr -d '\n' | base64 -d | od -An -tx1 | tr -d ' \n')" == "$stored" ]
+
+  run --separate-stderr $OCKAM identity show "${idt_name}" --change-history --output json
+  assert_success
+  assert_output --partial "\"change_history\": \"$stored\""
+}
+
+@test "set a policy on a custom resource" {
+  n=$(openssl rand -hex 4)
+  run $OCKAM node create $n
+  assert_success
+
+  run $OCKAM policy set --at $n --resource my-app.db --action read --expression '(= subject.app "app1")'
+  assert_success
+  run --separate-stderr $OCKAM policy get --at $n --resource my-app.db --action read
+  assert_success
+  assert_output --partial "subject.app"
+
+  # Invalid names are rejected
+  run $OCKAM policy set --at $n --resource "my/app" --expression '(= subject.app "app1")'
+  assert_failure 64
+}
+
+@test "list and delete policies" {
+  n=$(openssl rand -hex 4)
+  run $OCKAM node create $n
+  assert_success
+
+  run $OCKAM policy set --at $n --resource my-app --action read --expression '(= subject.app "app1")'
+  assert_success
+  run $OCKAM policy set --at $n --resource my-app --action write --expression '(= subject.app "app2")'
+  assert_success
+
+  run --separate-stderr $OCKAM policy list --at $n --output json
+  assert_success
+  assert_equal "$(echo "$output" | grep -c '"resource": "my-app"')" "2"
+
+  run --separate-stderr $OCKAM policy delete --at $n --resource my-app --output json
+  assert_success
+  assert_equal "$(echo "$output" | grep -c '"action"')" "2"
+
+  run --separate-stderr $OCKAM policy list --at $n --resource my-app --output json
+  assert_success
+  assert_output "[]"
+
+  # Deleting again is a no-op
+  run --separate-stderr $OCKAM policy delete --at $n --resource my-app
+  assert_success
+  assert_output --partial "nothing to delete"
+}
+
 @test "create a node with a name and do show on it" {
   run $OCKAM node create n1
   assert_success
@@ -158,23 +738,123 @@ teardown() {
 @test "create a node with a set of pre-trusted identities" {
   # TODO:  authenticated command doesn't know anything about how to resolve 'node' multiaddr
   run $OCKAM identity create t1
-  test_identity=$($OCKAM identity show t1)
+  test_identity=$(identifier_of t1)
   run $OCKAM node create n1 --tcp-listener-address 127.0.0.1:6001 --trusted-identities "{\"$test_identity\": {\"sample_attr\": \"sample_val\"}}"
   assert_success
-  run $OCKAM authenticated get --id $test_identity  /dnsaddr/127.0.0.1/tcp/6001/service/authenticated
+  run $OCKAM authenticated get --id $test_identity  /dnsaddr/127.0.0.1/tcp/6001/service/authenticated
+  assert_success
+  assert_output --partial "sample_val"
+  run $OCKAM authenticated list /dnsaddr/127.0.0.1/tcp/6001/service/authenticated
+  assert_success
+  assert_output --partial "sample_val"
+}
+
+@test "cloud requests reject a controller presenting another identity" {
+  run $OCKAM identity create i1
+  run $OCKAM identity create i2
+  other_identity=$(identifier_of i1)
+  run $OCKAM node create n1 --identity i2 --tcp-listener-address 127.0.0.1:6001
+  assert_success
+
+  # The node's secure channel listener stands for the controller, presenting i2 instead of i1
+  export OCKAM_CONTROLLER_ADDR=/dnsaddr/127.0.0.1/tcp/6001/service/api
+  run $OCKAM space list -v --controller-identity "$other_identity" --timeout 10
+  unset OCKAM_CONTROLLER_ADDR
+  assert_failure
+  assert_output --partial "SecureChannelTrustCheckFailed"
+
+  run $OCKAM space list --controller-identity not-an-identifier
+  assert_failure 64
+}
+
+@test "create a node with a name and send it a message" {
+  $OCKAM node create n1
+  run --separate-stderr $OCKAM message send "hello" --to /node/n1/service/uppercase
+
+  assert_success
+  assert_output "HELLO"
+}
+
+@test "send a message several times with json lines output" {
+  $OCKAM node create n1
+  run --separate-stderr $OCKAM message send "hello" --to /node/n1/service/uppercase --count 3 --interval 10 --output json
+
+  assert_success
+  assert_equal "$(echo "$output" | wc -l | tr -d ' ')" "3"
+  assert_output --partial '"response_bytes":5'
+
+  run --separate-stderr $OCKAM message send "hello" --to /node/n1/service/uppercase --count 2 --interval 10 --output yaml
+  assert_success
+  assert_equal "$(echo "$output" | grep -c '^---$')" "2"
+  assert_line "response_bytes: 5"
+
+  # A single message prints the reply
+  run --separate-stderr $OCKAM message send "hello" --to /node/n1/service/uppercase --output json
+  assert_success
+  assert_output "HELLO"
+}
+
+@test "check the reply of a message" {
+  $OCKAM node create n1
+  run --separate-stderr $OCKAM message send "hello" --to /node/n1/service/uppercase --expect "^HEL+O$"
+  assert_success
+  assert_output "HELLO"
+  run --separate-stderr $OCKAM message send "hello" --to /node/n1/service/uppercase --expect-exact HELLO
   assert_success
-  assert_output --partial "sample_val"
-  run $OCKAM authenticated list /dnsaddr/127.0.0.1/tcp/6001/service/authenticated
+
+  # A mismatch fails with EX_DATAERR, after printing the reply
+  run --separate-stderr $OCKAM message send "hello" --to /node/n1/service/uppercase --expect "^hello$"
+  assert_failure 65
+  assert_output "HELLO"
+  run --separate-stderr $OCKAM message send "hello" --to /node/n1/service/uppercase --expect-exact hello
+  assert_failure 65
+
+  # An invalid pattern is rejected before sending anything
+  run $OCKAM message send "hello" --to /node/n1/service/uppercase --expect "(HELLO"
+  assert_failure 64
+}
+
+@test "send a message to all the routes of a file" {
+  $OCKAM node create n1
+  $OCKAM node create n2
+  targets="$(mktemp)"
+  printf '# fleet\n/node/n1/service/uppercase\n\n/node/n2/service/uppercase\n' >"$targets"
+
+  run --separate-stderr $OCKAM message send "hello" --to-all "$targets" --output json
   assert_success
-  assert_output --partial "sample_val"
+  assert_equal "$(echo "$output" | wc -l | tr -d ' ')" "2"
+  refute_output --partial '"success":false'
+
+  # One unreachable target fails the command, the others are still reached
+  echo "/node/missing/service/uppercase" >>"$targets"
+  run --separate-stderr $OCKAM message send "hello" --to-all "$targets" --output json
+  assert_failure
+  assert_equal "$(echo "$output" | grep -c '"success":true')" "2"
+  assert_output --partial '"success":false'
 }
 
-@test "create a node with a name and send it a message" {
+@test "send a message read from a file or stdin" {
   $OCKAM node create n1
-  run --separate-stderr $OCKAM message send "hello" --to /node/n1/service/uppercase
+  message="$BATS_TEST_TMPDIR/message.txt"
+  echo -n "hello" >"$message"
+  run --separate-stderr $OCKAM message send --body-file "$message" --to /node/n1/service/uppercase
+  assert_success
+  assert_output "HELLO"
 
+  run --separate-stderr $OCKAM message send --body-file - --to /node/n1/service/uppercase <"$message"
   assert_success
   assert_output "HELLO"
+
+  # Binary payloads are sent as they are
+  payload="$BATS_TEST_TMPDIR/payload.bin"
+  head -c 64 /dev/urandom >"$payload"
+  run --separate-stderr $OCKAM message send --body-file "$payload" --to /node/n1/service/echo --count 2 --interval 10 --output json
+  assert_success
+  assert_output --partial '"response_bytes":64'
+
+  # The message can't come from two sources
+  run $OCKAM message send "hello" --body-file "$message" --to /node/n1/service/uppercase
+  assert_failure
 }
 
 @test "create two nodes and send message from one to the other" {
@@ -228,6 +908,38 @@ teardown() {
   assert_success
 }
 
+@test "a new node uses the default vault" {
+  export OCKAM_HOME=/tmp/ockam
+  v1=$(openssl rand -hex 4)
+  v2=$(openssl rand -hex 4)
+  run $OCKAM vault create "${v1}"
+  assert_success
+  run $OCKAM vault create "${v2}"
+  assert_success
+
+  run $OCKAM vault default "${v2}"
+  assert_success
+  run $OCKAM vault default "${v2}"
+  assert_failure
+
+  # Without --vault, the node is created with the default vault
+  n=$(openssl rand -hex 4)
+  run $OCKAM node create $n --dry-run
+  assert_success
+  assert_output --partial "Vault: ${v2}"
+  run $OCKAM node create $n
+  assert_success
+  run readlink -f "$OCKAM_HOME/nodes/$n/default_vault"
+  assert_output --partial "${v2}.json"
+
+  # --vault still takes precedence
+  n=$(openssl rand -hex 4)
+  run $OCKAM node create $n --vault "${v1}"
+  assert_success
+  run readlink -f "$OCKAM_HOME/nodes/$n/default_vault"
+  assert_output --partial "${v1}.json"
+}
+
 @test "identity CRUD" {
   # Create with random name
   run $OCKAM identity create
@@ -261,6 +973,42 @@ teardown() {
   assert_success
 }
 
+@test "export and import an identity" {
+  idt_name=$(openssl rand -hex 4)
+  run $OCKAM identity create "${idt_name}"
+  assert_success
+  identifier=$(identifier_of "${idt_name}")
+  export_file="${idt_name}.json"
+
+  # Public-only export
+  run $OCKAM identity export "${idt_name}" --file "${export_file}"
+  assert_success
+  refute_output --partial "secret key"
+  run $OCKAM identity delete "${idt_name}"
+  assert_success
+
+  # The default vault still holds the key, so the import succeeds
+  run $OCKAM identity import "${export_file}" --name "${idt_name}"
+  assert_success
+  assert_output --partial "${identifier}"
+  run $OCKAM identity show "${idt_name}"
+  assert_line "Identifier: ${identifier}"
+
+  # Importing into a vault without the key requires the secret
+  vault_name=$(openssl rand -hex 4)
+  run $OCKAM vault create "${vault_name}"
+  assert_success
+  run $OCKAM identity import "${export_file}" --name "${idt_name}-copy" --vault "${vault_name}"
+  assert_failure
+  run $OCKAM identity export "${idt_name}" --file "${export_file}" --include-secret
+  assert_success
+  assert_output --partial "secret key"
+  run $OCKAM identity import "${export_file}" --name "${idt_name}-copy" --vault "${vault_name}"
+  assert_success
+  run $OCKAM identity show "${idt_name}-copy"
+  assert_line "Identifier: ${identifier}"
+}
+
 @test "create a secure channel between two nodes and send message through it" {
   $OCKAM node create n1
   $OCKAM node create n2
@@ -272,6 +1020,50 @@ teardown() {
   assert_output "HELLO"
 }
 
+@test "send a message through a secure channel created for it" {
+  $OCKAM identity create i2
+  $OCKAM identity create other
+  idt=$(identifier_of i2)
+  other=$(identifier_of other)
+  $OCKAM node create n1
+  $OCKAM node create n2 --identity i2
+
+  run --separate-stderr $OCKAM message send hello --from /node/n1 --to /node/n2/service/uppercase --secure-channel --authorized "$idt"
+  assert_success
+  assert_output "HELLO"
+
+  # The channel is deleted once the message is sent
+  run --separate-stderr $OCKAM secure-channel list --at n1
+  assert_success
+  assert_output ""
+
+  # From an embedded node
+  run --separate-stderr $OCKAM message send hello --to /node/n2/service/uppercase --secure-channel
+  assert_success
+  assert_output "HELLO"
+
+  # The listener must present the authorized identity
+  run $OCKAM message send hello --from /node/n1 --to /node/n2/service/uppercase --secure-channel --authorized "$other"
+  assert_failure
+
+  # There must be a service to reach behind the channel
+  run $OCKAM message send hello --from /node/n1 --to /node/n2 --secure-channel
+  assert_failure
+}
+
+@test "show a secure channel with its remote identity" {
+  $OCKAM node create n1
+  $OCKAM node create n2
+
+  output=$($OCKAM secure-channel create --from /node/n1 --to /node/n2/service/api)
+  run --separate-stderr $OCKAM secure-channel show --at n1 "${output##*/}" --output json
+
+  assert_success
+  assert_output --partial '"route": "'
+  assert_output --partial '"remote_identity": "P'
+  assert_output --partial '"created_at": '
+}
+
 @test "create a secure channel between two nodes and send message through it - in a pipeline" {
   $OCKAM node create n1
   $OCKAM node create n2
@@ -302,6 +1094,46 @@ teardown() {
   assert [ "$output" == "HELLO" ]
 }
 
+@test "secure channel listener authorized identifiers survive a node restart" {
+  $OCKAM identity create i1
+  idt=$(identifier_of i1)
+
+  $OCKAM node create n1 --identity i1
+  $OCKAM node create n2
+  $OCKAM node create n3
+
+  $OCKAM secure-channel-listener create "listener" --at /node/n3 --authorized-identifiers "$idt"
+  $OCKAM node stop n3
+  $OCKAM node start n3
+
+  run $OCKAM secure-channel create --from /node/n1 --to /node/n3/service/listener
+  assert_success
+
+  run $OCKAM secure-channel create --from /node/n2 --to /node/n3/service/listener
+  assert_failure
+}
+
+@test "delete a secure channel listener" {
+  $OCKAM node create n1
+  $OCKAM secure-channel-listener create "listener" --at /node/n1
+
+  run --separate-stderr $OCKAM secure-channel-listener delete "listener" --at /node/n1 --output json
+  assert_success
+  assert_output --partial "\"address\": \"listener\""
+
+  run $OCKAM secure-channel-listener list --node n1
+  assert_success
+  refute_line "  listener"
+
+  # It's not re-created on restart, and can't be deleted twice
+  $OCKAM node stop n1
+  $OCKAM node start n1
+  run $OCKAM secure-channel-listener list --node n1
+  refute_line "  listener"
+  run $OCKAM secure-channel-listener delete "listener" --at /node/n1
+  assert_failure 69
+}
+
 @test "create a forwarder and send message through it" {
   $OCKAM node create n1
   $OCKAM node create n2
@@ -313,6 +1145,61 @@ teardown() {
   assert_output "HELLO"
 }
 
+@test "a forwarder is re-created when its node restarts" {
+  skip_if_long_tests_not_enabled
+
+  $OCKAM node create n1
+  $OCKAM node create n2
+
+  $OCKAM forwarder create n2 --at /node/n1/secure/api --to /node/n2
+  run --separate-stderr $OCKAM message send hello --to /node/n1/service/hop/service/forward_to_n2/service/uppercase
+  assert_success
+  assert_output "HELLO"
+
+  # The forwarder registration is lost when n1 restarts. n2 detects that its
+  # secure channel is down and creates a new channel and forwarder
+  $OCKAM node stop n1
+  $OCKAM node start n1
+  sleep 20
+
+  run --separate-stderr $OCKAM message send hello --to /node/n1/service/hop/service/forward_to_n2/service/uppercase
+  assert_success
+  assert_output "HELLO"
+}
+
+@test "create a forwarder at an unreachable route" {
+  $OCKAM node create n2
+
+  run $OCKAM forwarder create n1 --at /dnsaddr/unreachable.invalid/tcp/4000 --to /node/n2
+  assert_failure 69
+  assert_output --partial "cannot reach --at route"
+}
+
+@test "list and delete a forwarder" {
+  $OCKAM node create n1
+  $OCKAM node create n2
+
+  $OCKAM forwarder create n1 --at /node/n1 --to /node/n2
+
+  run $OCKAM forwarder list --node n2
+  assert_success
+  assert_output --partial "forward_to_n1"
+
+  run $OCKAM forwarder list --node n2 --output json
+  assert_success
+  assert_output --partial "\"alias\": \"forward_to_n1\""
+
+  run $OCKAM forwarder delete n1 --node n2
+  assert_success
+
+  run $OCKAM forwarder list --node n2
+  assert_success
+  assert_output "No forwarders found"
+
+  run $OCKAM forwarder delete n1 --node n2
+  assert_failure
+}
+
 @test "create a forwarder with a dynamic name and send message through it" {
   $OCKAM node create n1
   $OCKAM node create n2
@@ -323,6 +1210,18 @@ teardown() {
   assert [ "$output" == "HELLO" ]
 }
 
+@test "send a message to a route piped on several lines" {
+  $OCKAM node create n1
+
+  output=$(printf "/node/n1\n/service/hop\n" | \
+    $OCKAM message send hello --to -/service/uppercase)
+  assert [ "$output" == "HELLO" ]
+
+  # A placeholder without any route on stdin
+  run $OCKAM message send hello --to -/service/uppercase </dev/null
+  assert_failure 66
+}
+
 @test "create an inlet/outlet pair and move tcp traffic through it" {
   $OCKAM node create n1
   $OCKAM node create n2
@@ -334,6 +1233,98 @@ teardown() {
   assert_success
 }
 
+@test "create a tls inlet and move tcp traffic through it" {
+  openssl req -x509 -newkey ec -pkeyopt ec_paramgen_curve:prime256v1 -nodes -days 1 \
+    -subj "/CN=localhost" -addext "subjectAltName=DNS:localhost" \
+    -addext "basicConstraints=critical,CA:FALSE" \
+    -keyout "$BATS_TEST_TMPDIR/inlet.key" -out "$BATS_TEST_TMPDIR/inlet.crt"
+
+  $OCKAM node create n1
+  $OCKAM node create n2
+
+  $OCKAM tcp-outlet create --at /node/n1 --from /service/outlet --to 127.0.0.1:5000
+  $OCKAM tcp-inlet create --at /node/n2 --from 127.0.0.1:6000 --to /node/n1/service/outlet \
+    --tls --tls-cert "$BATS_TEST_TMPDIR/inlet.crt" --tls-key "$BATS_TEST_TMPDIR/inlet.key"
+
+  run curl --fail --head --cacert "$BATS_TEST_TMPDIR/inlet.crt" https://localhost:6000
+  assert_success
+
+  # The inlet doesn't accept plaintext connections
+  run curl --fail --head 127.0.0.1:6000
+  assert_failure
+}
+
+@test "a tls outlet requires a server name" {
+  $OCKAM node create n1
+
+  run $OCKAM tcp-outlet create --at /node/n1 --from /service/outlet --to 127.0.0.1:5000 --tls
+  assert_failure
+}
+
+@test "only the allowed identities can reach an outlet" {
+  $OCKAM identity create i1
+  idt=$(identifier_of i1)
+
+  $OCKAM node create n1
+  $OCKAM node create n2 --identity i1
+  $OCKAM node create n3
+
+  $OCKAM tcp-outlet create --at /node/n1 --from /service/outlet --to 127.0.0.1:5000 --allow "$idt"
+
+  $OCKAM secure-channel create --from /node/n2 --to /node/n1/service/api \
+    | $OCKAM tcp-inlet create --at /node/n2 --from 127.0.0.1:6000 --to -/service/outlet
+  run curl --fail --head --max-time 5 127.0.0.1:6000
+  assert_success
+
+  $OCKAM secure-channel create --from /node/n3 --to /node/n1/service/api \
+    | $OCKAM tcp-inlet create --at /node/n3 --from 127.0.0.1:6001 --to -/service/outlet
+  run curl --fail --head --max-time 5 127.0.0.1:6001
+  assert_failure
+}
+
+@test "list and delete a tcp inlet" {
+  $OCKAM node create n1
+  $OCKAM node create n2
+
+  $OCKAM tcp-outlet create --at /node/n1 --from /service/outlet --to 127.0.0.1:5000
+  $OCKAM tcp-inlet create --at /node/n2 --from 127.0.0.1:6000 --to /node/n1/service/outlet --alias test-inlet
+
+  run $OCKAM tcp-inlet list --node n2 --output json
+  assert_success
+  assert_output --partial "\"alias\": \"test-inlet\""
+  assert_output --partial "\"bind_addr\": \"127.0.0.1:6000\""
+
+  run $OCKAM tcp-inlet delete --node n2 test-inlet
+  assert_success
+
+  # The listening port is free again
+  run curl --fail --head 127.0.0.1:6000
+  assert_failure
+
+  run $OCKAM tcp-inlet delete --node n2 test-inlet
+  assert_failure
+}
+
+@test "list and delete a tcp outlet" {
+  $OCKAM node create n1
+  $OCKAM tcp-outlet create --at /node/n1 --from /service/outlet --to 127.0.0.1:5000 --alias test-outlet
+
+  run $OCKAM tcp-outlet list --node n1 --output json
+  assert_success
+  assert_output --partial "\"alias\": \"test-outlet\""
+  assert_output --partial "\"tcp_addr\": \"127.0.0.1:5000\""
+
+  run $OCKAM tcp-outlet delete --node n1 test-outlet
+  assert_success
+
+  run $OCKAM tcp-outlet list --node n1 --output json
+  assert_success
+  refute_output --partial "test-outlet"
+
+  run $OCKAM tcp-outlet delete --node n1 test-outlet
+  assert_failure
+}
+
 @test "create an inlet/outlet pair with relay through a forwarder and move tcp traffic through it" {
   $OCKAM node create relay
 
@@ -349,6 +1340,19 @@ teardown() {
   assert_success
 }
 
+@test "create kafka consumer and producer services" {
+  $OCKAM node create n1
+
+  run $OCKAM kafka-consumer create --node n1 --bootstrap-server 127.0.0.1:14000 --to /node/n1 --output json
+  assert_success
+  assert_output --partial "\"bootstrap_server\": \"127.0.0.1:14000\""
+  assert_output --partial "\"brokers_port_range\": \"14001-14100\""
+
+  run $OCKAM kafka-producer create --node n1 --bootstrap-server 127.0.0.1:15000 --brokers-port-range 15010-15001 --to /node/n1
+  assert_failure 64
+  assert_output --partial "Invalid --brokers-port-range"
+}
+
 @test "create a node and start services" {
   $OCKAM node create n1
 
@@ -394,6 +1398,44 @@ teardown() {
   run $OCKAM tcp-connection list --node n1
   assert_success
   assert_output --partial "127.0.0.1:5000"
+
+  run $OCKAM tcp-connection list --node n1 --output json
+  assert_success
+  assert_output --partial '"transport_type": "TCP"'
+  assert_output --partial '"mode": "Remote connection"'
+  assert_output --partial '"address": "127.0.0.1:5000"'
+}
+
+@test "create a tcp connection with heartbeats" {
+  run $OCKAM node create n1
+  run $OCKAM node create n2 --tcp-listener-address 127.0.0.1:5001
+  run $OCKAM tcp-connection create --from n1 --to 127.0.0.1:5001 --keepalive 1s
+  assert_success
+
+  run $OCKAM tcp-connection create --from n1 --to 127.0.0.1:5001 --keepalive 1w
+  assert_failure 64
+}
+
+@test "creating a tcp connection to an unreachable address times out" {
+  run $OCKAM node create n1
+
+  # 10.255.255.1 is not routable, connecting to it hangs until the timeout
+  start=$SECONDS
+  run $OCKAM tcp-connection create --from n1 --to 10.255.255.1:4000 --connect-timeout 2s
+  assert_failure
+  assert [ $((SECONDS - start)) -lt 10 ]
+  # The node reports the timeout before the command gives up waiting for it
+  assert_output --partial "timed out after 2000ms"
+
+  run $OCKAM tcp-connection create --from n1 --to 127.0.0.1:5000 --connect-timeout soon
+  assert_failure 64
+}
+
+@test "create a tcp connection with yaml output" {
+  run $OCKAM node create n1
+  run $OCKAM tcp-connection create --from n1 --to 127.0.0.1:5000 --output yaml --quiet
+  assert_success
+  assert_output --regexp '^- route: /dnsaddr/localhost/tcp/[[:digit:]]+/ip4/127.0.0.1/tcp/5000$'
 }
 
 @test "create a tcp connection and then delete it " {
@@ -416,10 +1458,12 @@ teardown() {
   run $OCKAM identity create m1
   run $OCKAM identity create m2
   run $OCKAM identity create m3
-  enroller_identifier=$($OCKAM identity show enroller)
+  run $OCKAM identity create m4
+  run $OCKAM identity create m5
+  enroller_identifier=$(identifier_of enroller)
   authority_identity_full=$($OCKAM identity show --full --encoding hex authority)
-  m1_identifier=$($OCKAM identity show m1)
-  m2_identifier=$($OCKAM identity show m2)
+  m1_identifier=$(identifier_of m1)
+  m2_identifier=$(identifier_of m2)
 
   # To startup an authority, we need some boilerplate setup in place.
   # Create an enrollers file,  with the identity of the enroller we want
@@ -454,6 +1498,88 @@ teardown() {
   run $OCKAM project authenticate --project-path /tmp/project.json --identity m3  --token $token
   assert_success
   assert_output --partial "m3_member"
+
+  # m4 requests an attribute the enroller allowed, but can't request another one
+  token=$($OCKAM project enroll --identity enroller --project-path /tmp/project.json  --attribute sample_attr=m4_member --allow-attribute team)
+  run $OCKAM project authenticate --project-path /tmp/project.json --identity m4  --token $token --attribute role=admin
+  assert_failure
+  assert_output --partial "attributes can't be requested: role"
+  run $OCKAM project authenticate --project-path /tmp/project.json --identity m4  --token $token --attribute team=blue
+  assert_success
+  assert_output --partial "m4_member"
+  assert_output --partial "blue"
+
+  # m5 starts a node with a token. The credential fetched on the first start is
+  # reused by the next one, so the already redeemed token isn't presented again
+  token=$($OCKAM project enroll --identity enroller --project-path /tmp/project.json  --attribute sample_attr=m5_member)
+  run $OCKAM node create m5 --foreground --exit-on-eof --project /tmp/project.json --identity m5 --enrollment-token $token < /dev/null
+  assert_success
+  assert_output --partial "m5_member"
+  run $OCKAM node create m5 --foreground --exit-on-eof --project /tmp/project.json --identity m5 --enrollment-token $token < /dev/null
+  assert_success
+  assert_output --partial "m5_member"
+  run $OCKAM node create m5 --foreground --exit-on-eof --project /tmp/project.json --identity m5 --enrollment-token $token --force-credential-refresh < /dev/null
+  assert_output --partial "failed to fetch membership credential"
+
+  # The credential can be verified offline against the authority identity
+  $OCKAM project authenticate --project-path /tmp/project.json --identity m1 --output json > /tmp/m1_credential.json
+  run --separate-stderr $OCKAM credential verify /tmp/m1_credential.json --authority $authority_identity_full --output json
+  assert_success
+  assert_output --partial "\"valid\": true"
+  assert_output --partial "\"sample_attr\": \"sample_val\""
+
+  enroller_identity_full=$($OCKAM identity show --full --encoding hex enroller)
+  run --separate-stderr $OCKAM credential verify /tmp/m1_credential.json --authority $enroller_identity_full
+  assert_failure
+  assert_output --partial "NOT valid"
+
+  # After a rotation of the authenticator key, the credential issued before
+  # still verifies against the new authority identity
+  run $OCKAM admin authenticator rotate-key --at authority
+  assert_success
+  rotated_identity_full=$(echo "$output" | tail -n 1)
+  run --separate-stderr $OCKAM credential verify /tmp/m1_credential.json --authority $rotated_identity_full --output json
+  assert_success
+  assert_output --partial "\"valid\": true"
+}
+
+@test "create a secure channel presenting a credential" {
+  run $OCKAM identity create authority
+  run $OCKAM identity create enroller
+  run $OCKAM identity create m1
+  run $OCKAM identity create m2
+  enroller_identifier=$(identifier_of enroller)
+  authority_identity_full=$($OCKAM identity show --full --encoding hex authority)
+  m1_identifier=$(identifier_of m1)
+  m2_identifier=$(identifier_of m2)
+
+  echo "{\"$enroller_identifier\": {}}" > /tmp/enrollers.json
+  echo '{"startup_services" : {"authenticator" : {"enrollers" : "/tmp/enrollers.json", "project" : "1"}, "secure_channel_listener": {}}}' >  /tmp/auth_launch_config.json
+  run $OCKAM node create --tcp-listener-address=0.0.0.0:4200 --identity authority --launch-config /tmp/auth_launch_config.json --trusted-identities "{\"$m1_identifier\": {\"sample_attr\" : \"m1_member\"}, \"$m2_identifier\": {\"sample_attr\" : \"m2_member\"}}"  authority
+  assert_success
+
+  echo "{\"id\": \"1\",
+  \"name\" : \"default\",
+  \"identity\" : \"P6c20e814b56579306f55c64e8747e6c1b4a53d9a3f4ca83c252cc2fbfc72fa94\",
+  \"access_route\" : \"/dnsaddr/127.0.0.1/tcp/4000/service/api\",
+  \"authority_access_route\" : \"/dnsaddr/127.0.0.1/tcp/4200/service/api\",
+  \"authority_identity\" : \"$authority_identity_full\"}" > /tmp/project.json
+
+  $OCKAM project authenticate --project-path /tmp/project.json --identity m1 --output json > /tmp/m1_credential.json
+  $OCKAM project authenticate --project-path /tmp/project.json --identity m2 --output json > /tmp/m2_credential.json
+
+  # The listener node validates the presented credentials against the project authority
+  run $OCKAM node create listener --project /tmp/project.json
+  assert_success
+  run $OCKAM node create client --identity m1
+  assert_success
+
+  run $OCKAM secure-channel create --from /node/client --to /node/listener/service/api --credential /tmp/m1_credential.json
+  assert_success
+
+  # A credential issued to another identity is rejected
+  run $OCKAM secure-channel create --from /node/client --to /node/listener/service/api --credential /tmp/m2_credential.json
+  assert_failure
 }
 
 # the below tests will only succeed if already enrolled with `ockam enroll`
@@ -484,7 +1610,7 @@ teardown() {
 
   run $OCKAM identity create m1
   run $OCKAM identity create m2
-  m1_identifier=$($OCKAM identity show m1)
+  m1_identifier=$(identifier_of m1)
 
   $OCKAM project enroll --member $m1_identifier --attribute role=member
 
@@ -512,7 +1638,7 @@ teardown() {
   export OCKAM_HOME=/tmp/ockam
   $OCKAM identity create m1
   $OCKAM identity create m2
-  m1_identifier=$($OCKAM identity show m1)
+  m1_identifier=$(identifier_of m1)
 
   unset OCKAM_HOME
   $OCKAM project enroll --member $m1_identifier --attribute role=member
@@ -556,8 +1682,8 @@ teardown() {
   run $OCKAM identity create green
   run $OCKAM identity create blue
 
-  green_identifier=$($OCKAM identity show green)
-  blue_identifier=$($OCKAM identity show blue)
+  green_identifier=$(identifier_of green)
+  blue_identifier=$(identifier_of blue)
 
   # They haven't been added by enroller yet
   run $OCKAM project authenticate --identity green --project-path /tmp/project.json
@@ -621,8 +1747,8 @@ teardown() {
 
   run $OCKAM identity create green
   run $OCKAM identity create blue
-  green_identifier=$($OCKAM identity show green)
-  blue_identifier=$($OCKAM identity show blue)
+  green_identifier=$(identifier_of green)
+  blue_identifier=$(identifier_of blue)
 
   run $OCKAM node create green --project /tmp/project.json --identity green
   assert_success
@@ -663,8 +1789,8 @@ teardown() {
 
   run $OCKAM identity create green
   run $OCKAM identity create blue
-  green_identifier=$($OCKAM identity show green)
-  blue_identifier=$($OCKAM identity show blue)
+  green_identifier=$(identifier_of green)
+  blue_identifier=$(identifier_of blue)
 
   run $OCKAM node create green --project /tmp/project.json --identity green
   assert_success
@@ -699,8 +1825,8 @@ teardown() {
 
   run $OCKAM identity create green
   run $OCKAM identity create blue
-  green_identifier=$($OCKAM identity show green)
-  blue_identifier=$($OCKAM identity show blue)
+  green_identifier=$(identifier_of green)
+  blue_identifier=$(identifier_of blue)
 
   run $OCKAM node create green --project /tmp/project.json --identity green
   assert_success
@@ -786,8 +1912,8 @@ teardown() {
 
   run $OCKAM identity create green
   run $OCKAM identity create blue
-  green_identifier=$($OCKAM identity show green)
-  blue_identifier=$($OCKAM identity show blue)
+  green_identifier=$(identifier_of green)
+  blue_identifier=$(identifier_of blue)
 
   run $OCKAM node create green --project "/tmp/${project_name}_project.json" --identity green
   assert_success
@@ -875,8 +2001,8 @@ teardown() {
   run $OCKAM identity create m2
   run $OCKAM identity create m3
 
-  m1_identifier=$($OCKAM identity show m1)
-  m2_identifier=$($OCKAM identity show m2)
+  m1_identifier=$(identifier_of m1)
+  m2_identifier=$(identifier_of m2)
 
   unset OCKAM_HOME
   $OCKAM project enroll --member $m1_identifier --attribute service=sensor
@@ -936,6 +2062,11 @@ function skip_if_orchestrator_tests_not_enabled() {
   fi
 }
 
+# Print the identifier of the identity named $1
+function identifier_of() {
+  $OCKAM identity show "$1" | sed -n 's/^Identifier: //p'
+}
+
 function skip_if_long_tests_not_enabled() {
   if [ -z "${LONG_TESTS}" ]; then
     skip "LONG_TESTS are not enabled"
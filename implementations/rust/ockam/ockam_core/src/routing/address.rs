@@ -5,6 +5,7 @@ use crate::compat::{
     sync::Arc,
     vec::Vec,
 };
+use crate::errcode::{Kind, Origin};
 use crate::{debugger, DenyAll, OutgoingAccessControl, RelayMessage, Result};
 use core::cmp::Ordering;
 use core::fmt::{self, Debug, Display};
@@ -126,6 +127,37 @@ impl Mailboxes {
         }
     }
 
+    /// Register an additional [`Mailbox`]
+    ///
+    /// Fails if the address of the given mailbox is already represented by
+    /// these `Mailboxes`, including the main address.
+    pub fn add_mailbox(&mut self, mailbox: Mailbox) -> Result<()> {
+        if self.contains(mailbox.address()) {
+            return Err(crate::Error::new(
+                Origin::Core,
+                Kind::AlreadyExists,
+                "mailbox address already in use",
+            ));
+        }
+        self.additional_mailboxes.push(mailbox);
+        Ok(())
+    }
+
+    /// Remove the additional [`Mailbox`] with the given [`Address`], and return it
+    ///
+    /// The main mailbox can't be removed, `None` is returned for its address
+    /// as well as for an address not represented by these `Mailboxes`.
+    ///
+    /// This only updates the `Mailboxes`, the router still routes messages
+    /// for the removed address until it is informed separately.
+    pub fn remove_mailbox(&mut self, addr: &Address) -> Option<Mailbox> {
+        let i = self
+            .additional_mailboxes
+            .iter()
+            .position(|x| &x.address == addr)?;
+        Some(self.additional_mailboxes.remove(i))
+    }
+
     /// Return an [`AddressSet`] containing all addresses represented by these `Mailboxes`
     pub fn aliases(&self) -> Vec<Address> {
         self.additional_mailboxes
@@ -235,6 +267,7 @@ impl Mailboxes {
 /// For example:
 /// * `"0#alice"` represents a local worker with the address: `alice`.
 /// * `"1#carol"` represents a remote worker with the address `carol`, reachable over TCP transport.
+/// * `"tcp#carol"` is the same address, using the name of the transport type.
 ///
 #[derive(Serialize, Deserialize, Clone, Hash, Ord, PartialOrd, Eq, PartialEq)]
 pub struct Address {
@@ -256,6 +289,10 @@ pub enum AddressParseErrorKind {
     InvalidType(core::num::ParseIntError),
     /// Address string has more than one '#' separator.
     MultipleSep,
+    /// Address string has no address after the '#' separator.
+    EmptyAddress,
+    /// Address string has no address type before the '#' separator.
+    EmptyType,
 }
 
 impl AddressParseError {
@@ -281,6 +318,18 @@ impl Display for AddressParseError {
                     "Invalid address string: more than one '#' separator found"
                 )
             }
+            AddressParseErrorKind::EmptyAddress => {
+                write!(
+                    f,
+                    "Invalid address string: no address found after the '#' separator"
+                )
+            }
+            AddressParseErrorKind::EmptyType => {
+                write!(
+                    f,
+                    "Failed to parse address type: no type found before the '#' separator"
+                )
+            }
         }
     }
 }
@@ -305,6 +354,72 @@ impl Address {
         }
     }
 
+    /// Creates a new address with transport type [`LOCAL`].
+    ///
+    /// Unlike [`Address::from_string`], the data is used as is and never
+    /// parsed for a transport type prefix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ockam_core::{Address, LOCAL};
+    /// let alice = Address::new_local("alice");
+    /// assert!(alice.is_local());
+    /// assert_eq!(alice, Address::new(LOCAL, "alice"));
+    /// ```
+    pub fn new_local<S: Into<String>>(data: S) -> Self {
+        Self::new(LOCAL, data)
+    }
+
+    /// Creates a new address for the given transport type, the same as
+    /// [`Address::new`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ockam_core::{Address, TransportType};
+    /// # pub const TCP: TransportType = TransportType::new(1);
+    /// let carol = Address::with_transport(TCP, "127.0.0.1:4000");
+    /// assert!(!carol.is_local());
+    /// assert_eq!(TCP, carol.transport_type());
+    /// assert_eq!(carol, Address::from((TCP, "127.0.0.1:4000")));
+    /// ```
+    pub fn with_transport<S: Into<String>>(tt: TransportType, data: S) -> Self {
+        Self::new(tt, data)
+    }
+
+    /// Creates a [`LOCAL`] address for `name` in the namespace of a
+    /// service, e.g. `forward_to_alice` for the forwarder named `alice`.
+    ///
+    /// The same prefix and name always give the same address. A `#` in
+    /// the name, which would be parsed as a transport type separator, is
+    /// escaped (as is `%`, the escape character), so the address is always
+    /// local and distinct names get distinct addresses.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ockam_core::Address;
+    /// let alice = Address::prefixed("forward_to", "alice");
+    /// assert!(alice.is_local());
+    /// assert_eq!("forward_to_alice", alice.address());
+    /// assert_eq!("forward_to_a%23b", Address::prefixed("forward_to", "a#b").address());
+    /// ```
+    pub fn prefixed(prefix: &str, name: &str) -> Self {
+        debug_assert!(!prefix.contains('#'), "invalid address prefix {prefix}");
+        let mut data = String::with_capacity(prefix.len() + name.len() + 1);
+        data.push_str(prefix);
+        data.push('_');
+        for c in name.chars() {
+            match c {
+                '#' => data.push_str("%23"),
+                '%' => data.push_str("%25"),
+                c => data.push(c),
+            }
+        }
+        Self::new_local(data)
+    }
+
     /// Parses an address from a string.
     ///
     /// # Panics
@@ -413,25 +528,33 @@ impl core::str::FromStr for Address {
         // If after the split we have 2 elements, we extract the type
         // value from the string, and use the rest as the address
         else if vec.len() == 2 {
-            match str::parse(vec.remove(0)) {
-                Ok(tt) => Ok(Address {
-                    tt: TransportType::new(tt),
-                    inner: vec.remove(0).as_bytes().to_vec(),
-                }),
-                Err(e) => Err(AddressParseError::new(AddressParseErrorKind::InvalidType(
-                    e,
-                ))),
+            if vec[0].is_empty() {
+                return Err(AddressParseError::new(AddressParseErrorKind::EmptyType));
             }
+            if vec[1].is_empty() {
+                return Err(AddressParseError::new(AddressParseErrorKind::EmptyAddress));
+            }
+            let tt = vec.remove(0).parse()?;
+            Ok(Address {
+                tt,
+                inner: vec.remove(0).as_bytes().to_vec(),
+            })
         } else {
             Err(AddressParseError::new(AddressParseErrorKind::MultipleSep))
         }
     }
 }
 
+/// The alternate form (`{:#}`) prints the name of well-known transport
+/// types, e.g. `tcp#carol` instead of `1#carol`.  Both forms can be parsed back.
 impl Display for Address {
     fn fmt<'a>(&'a self, f: &mut fmt::Formatter) -> fmt::Result {
         let inner: &'a str = from_utf8(self.inner.as_slice()).unwrap_or("Invalid UTF-8");
-        write!(f, "{}#{}", self.tt, inner)
+        if f.alternate() {
+            write!(f, "{:#}#{}", self.tt, inner)
+        } else {
+            write!(f, "{}#{}", self.tt, inner)
+        }
     }
 }
 
@@ -541,6 +664,17 @@ pub struct TransportType(u8);
 /// The local transport type.
 pub const LOCAL: TransportType = TransportType::new(0);
 
+/// Names of the transport types defined by the Ockam crates.
+const TRANSPORT_NAMES: &[(u8, &str)] = &[
+    (0, "local"),
+    (1, "tcp"),
+    (2, "udp"),
+    (3, "ws"),
+    (4, "ble"),
+    (5, "uds"),
+    (16, "stream"),
+];
+
 impl TransportType {
     /// Create a new transport type.
     pub const fn new(n: u8) -> Self {
@@ -551,11 +685,45 @@ impl TransportType {
     pub fn is_local(self) -> bool {
         self == LOCAL
     }
+
+    /// The name of this transport type, if it is a well-known one.
+    pub fn name(self) -> Option<&'static str> {
+        TRANSPORT_NAMES
+            .iter()
+            .find(|(n, _)| *n == self.0)
+            .map(|(_, name)| *name)
+    }
+
+    /// The well-known transport type with the given name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        TRANSPORT_NAMES
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(tt, _)| TransportType(*tt))
+    }
 }
 
+/// The alternate form (`{:#}`) prints the name of well-known transport types
+/// and falls back to the number for the other ones.
 impl Display for TransportType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+        match self.name() {
+            Some(name) if f.alternate() => write!(f, "{}", name),
+            _ => write!(f, "{}", self.0),
+        }
+    }
+}
+
+impl core::str::FromStr for TransportType {
+    type Err = AddressParseError;
+
+    /// Parse a transport type from its number or its well-known name.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse() {
+            Ok(n) => Ok(TransportType(n)),
+            Err(e) => Self::from_name(s)
+                .ok_or_else(|| AddressParseError::new(AddressParseErrorKind::InvalidType(e))),
+        }
     }
 }
 
@@ -589,6 +757,30 @@ fn parse_addr_with_type() {
     );
 }
 
+#[test]
+fn prefixed_addr() {
+    use core::str::FromStr;
+
+    let addr = Address::prefixed("forward_to", "alice");
+    assert_eq!(addr, Address::new_local("forward_to_alice"));
+    assert_eq!(addr, Address::prefixed("forward_to", "alice"));
+    assert_ne!(addr, Address::prefixed("kafka_outlet", "alice"));
+
+    // The result always parses back to a local address
+    for name in ["alice", "1#alice", "a#b#c", "100%", ""] {
+        let addr = Address::prefixed("forward_to", name);
+        let parsed = Address::from_str(addr.address()).unwrap();
+        assert_eq!(LOCAL, parsed.transport_type());
+        assert_eq!(addr, parsed);
+    }
+
+    // Escaping doesn't make distinct names collide
+    assert_ne!(
+        Address::prefixed("forward_to", "a#b"),
+        Address::prefixed("forward_to", "a%23b")
+    );
+}
+
 #[test]
 #[should_panic(expected = "Failed to parse address type:")]
 fn parse_addr_invalid() {
@@ -600,3 +792,105 @@ fn parse_addr_invalid() {
 fn parse_addr_invalid_multiple_separators() {
     let _ = Address::from_string("1#invalid#");
 }
+
+#[test]
+fn parse_addr_empty_address() {
+    use core::str::FromStr;
+
+    for s in ["1#", "0#"] {
+        let err = Address::from_str(s).unwrap_err();
+        assert_eq!(err.kind(), &AddressParseErrorKind::EmptyAddress);
+    }
+}
+
+#[test]
+fn parse_addr_empty_type() {
+    use core::str::FromStr;
+
+    let err = Address::from_str("#alice").unwrap_err();
+    assert_eq!(err.kind(), &AddressParseErrorKind::EmptyType);
+}
+
+#[test]
+fn transport_type_names_round_trip() {
+    use core::str::FromStr;
+
+    let tcp = TransportType::new(1);
+    assert_eq!(tcp.name(), Some("tcp"));
+    assert_eq!(format!("{}", tcp), "1");
+    assert_eq!(format!("{:#}", tcp), "tcp");
+    assert_eq!(TransportType::from_str("tcp").unwrap(), tcp);
+    assert_eq!(TransportType::from_str("1").unwrap(), tcp);
+    assert!(TransportType::from_str("carrier-pigeon").is_err());
+
+    // Unknown transport types are still printed and parsed as numbers
+    let unknown = TransportType::new(200);
+    assert_eq!(unknown.name(), None);
+    assert_eq!(format!("{:#}", unknown), "200");
+    assert_eq!(TransportType::from_str("200").unwrap(), unknown);
+}
+
+#[test]
+fn parse_addr_named_and_numeric_round_trip() {
+    use core::str::FromStr;
+
+    let addr = Address::from_str("tcp#carol").unwrap();
+    assert_eq!(addr, Address::from_str("1#carol").unwrap());
+    assert_eq!(addr.to_string(), "1#carol");
+    assert_eq!(format!("{:#}", addr), "tcp#carol");
+    assert_eq!(Address::from_str(&format!("{:#}", addr)).unwrap(), addr);
+    assert_eq!(Address::from_str(&addr.to_string()).unwrap(), addr);
+
+    let local = Address::from_str("local#alice").unwrap();
+    assert!(local.is_local());
+    assert_eq!(format!("{:#}", local), "local#alice");
+
+    let unknown = Address::from_str("42#dave").unwrap();
+    assert_eq!(format!("{:#}", unknown), "42#dave");
+    assert_eq!(
+        Address::from_str(&format!("{:#}", unknown)).unwrap(),
+        unknown
+    );
+}
+
+#[test]
+fn mailboxes_add_mailbox() {
+    let mut mailboxes = Mailboxes::main("main", Arc::new(DenyAll), Arc::new(DenyAll));
+    let extra: Address = "extra".into();
+    assert!(!mailboxes.contains(&extra));
+
+    mailboxes.add_mailbox(Mailbox::deny_all("extra")).unwrap();
+    assert!(mailboxes.contains(&extra));
+    assert_eq!(mailboxes.find_mailbox(&extra).unwrap().address(), &extra);
+    assert_eq!(mailboxes.addresses(), vec!["main".into(), extra]);
+
+    // Duplicates of the main or an additional address are rejected
+    assert!(mailboxes.add_mailbox(Mailbox::deny_all("main")).is_err());
+    assert!(mailboxes.add_mailbox(Mailbox::deny_all("extra")).is_err());
+    assert_eq!(mailboxes.addresses().len(), 2);
+}
+
+#[test]
+fn mailboxes_remove_mailbox() {
+    let mut mailboxes = Mailboxes::main("main", Arc::new(DenyAll), Arc::new(DenyAll));
+    let extra: Address = "extra".into();
+    mailboxes.add_mailbox(Mailbox::deny_all("extra")).unwrap();
+
+    let removed = mailboxes.remove_mailbox(&extra).unwrap();
+    assert_eq!(removed.address(), &extra);
+    assert!(!mailboxes.contains(&extra));
+    assert_eq!(mailboxes.addresses(), vec!["main".into()]);
+
+    // The main mailbox is never removed
+    let main: Address = "main".into();
+    assert!(mailboxes.remove_mailbox(&main).is_none());
+    assert!(mailboxes.contains(&main));
+
+    // Removing an unknown address does nothing
+    assert!(mailboxes.remove_mailbox(&extra).is_none());
+    assert!(mailboxes.remove_mailbox(&"unknown".into()).is_none());
+
+    // A removed address can be registered again
+    mailboxes.add_mailbox(Mailbox::deny_all("extra")).unwrap();
+    assert!(mailboxes.contains(&extra));
+}
@@ -34,6 +34,23 @@ pub struct AttributesEntry {
     #[n(2)] added: Timestamp,
     #[n(3)] expires: Option<Timestamp>,
     #[n(4)] attested_by: Option<IdentityIdentifier>,
+    #[n(5)] types: Option<BTreeMap<String, AttributeType>>,
+}
+
+/// Type of an attribute value.
+///
+/// Attribute values are always stored as the bytes of their string
+/// representation, the type tells how to interpret them.
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum AttributeType {
+    /// UTF-8 string
+    #[n(0)] Str,
+    /// Signed integer
+    #[n(1)] Int,
+    /// `true` or `false`
+    #[n(2)] Bool,
 }
 
 impl AttributesEntry {
@@ -53,14 +70,29 @@ impl AttributesEntry {
             added,
             expires,
             attested_by,
+            types: None,
         }
     }
 
+    /// Set the type of the attributes which are not strings
+    pub fn with_types(mut self, types: BTreeMap<String, AttributeType>) -> Self {
+        self.types = Some(types).filter(|t| !t.is_empty());
+        self
+    }
+
     /// The entry attributes
     pub fn attrs(&self) -> &BTreeMap<String, Vec<u8>> {
         &self.attrs
     }
 
+    /// The type of an attribute, entries written without types only hold strings
+    pub fn attr_type(&self, key: &str) -> AttributeType {
+        self.types
+            .as_ref()
+            .and_then(|t| t.get(key).copied())
+            .unwrap_or(AttributeType::Str)
+    }
+
     /// Expiration time for this entry
     pub fn expires(&self) -> Option<Timestamp> {
         self.expires
@@ -74,6 +106,13 @@ impl AttributesEntry {
     pub fn attested_by(&self) -> Option<IdentityIdentifier> {
         self.attested_by.to_owned()
     }
+
+    /// Check that the entry has all the given attribute values
+    pub fn has_all(&self, attributes: &BTreeMap<String, Vec<u8>>) -> bool {
+        attributes
+            .iter()
+            .all(|(k, v)| self.attrs.get(k).map_or(false, |a| a == v))
+    }
 }
 
 /// Trait implementing read access to an AuthenticatedIdentities table
@@ -87,6 +126,16 @@ pub trait IdentityAttributeStorageReader: AsyncTryClone + Send + Sync + 'static
 
     /// List all identities with their attributes
     async fn list(&self) -> Result<Vec<(IdentityIdentifier, AttributesEntry)>>;
+
+    /// List the identities having all the given attribute values
+    async fn filter(
+        &self,
+        attributes: &BTreeMap<String, Vec<u8>>,
+    ) -> Result<Vec<(IdentityIdentifier, AttributesEntry)>> {
+        let mut entries = self.list().await?;
+        entries.retain(|(_, entry)| entry.has_all(attributes));
+        Ok(entries)
+    }
 }
 
 /// Trait implementing write access to an AuthenticatedIdentities table
@@ -99,6 +148,10 @@ pub trait IdentityAttributeStorageWriter: AsyncTryClone + Send + Sync + 'static
         identity: &IdentityIdentifier,
         entry: AttributesEntry,
     ) -> Result<()>;
+
+    /// Remove the attributes associated with the given identity identifier.
+    /// Removing the attributes of an unknown identity is not an error.
+    async fn delete_attributes(&self, identity: &IdentityIdentifier) -> Result<()>;
 }
 
 /// Trait implementing read/write access to an AuthenticatedIdentities table
@@ -195,6 +248,12 @@ impl<S: AuthenticatedStorage> IdentityAttributeStorageWriter for AuthenticatedAt
 
         Ok(())
     }
+
+    async fn delete_attributes(&self, identity: &IdentityIdentifier) -> Result<()> {
+        self.storage
+            .del(&identity.to_string(), IdentityStateConst::ATTRIBUTES_KEY)
+            .await
+    }
 }
 
 /// In-memory impl
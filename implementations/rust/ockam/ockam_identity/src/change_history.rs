@@ -224,6 +224,19 @@ impl IdentityChangeHistory {
         change.change().public_key()
     }
 
+    /// Public keys that had the given label, the current one first
+    pub(crate) fn get_public_keys_static(
+        changes: &[IdentitySignedChange],
+        label: &str,
+    ) -> Result<Vec<PublicKey>> {
+        changes
+            .iter()
+            .rev()
+            .filter(|e| e.change().has_label(label))
+            .map(|e| e.change().public_key())
+            .collect()
+    }
+
     /// WARNING: This function assumes all existing changes in chain are verified.
     /// WARNING: Correctness of changes sequence is not verified here.
     pub(crate) async fn verify_change(
@@ -29,6 +29,10 @@ use crate::TypeTag;
 
 pub const MAX_CREDENTIAL_VALIDITY: Duration = Duration::from_secs(30 * 24 * 3600);
 
+/// How far ahead of the verifier's clock the not-before time of a credential
+/// can be, as the clocks of the issuer and the verifier may differ slightly.
+pub const MAX_CLOCK_SKEW: Duration = Duration::from_secs(60);
+
 /// Type to represent data of verified credential.
 #[derive(Debug, Encode)]
 pub enum Verified {}
@@ -105,7 +109,9 @@ pub struct CredentialData<T> {
     /// The time this credential expires.
     #[n(7)] expires: Timestamp,
     /// Term to represent the verification status type.
-    #[n(8)] status: Option<PhantomData<T>>
+    #[n(8)] status: Option<PhantomData<T>>,
+    /// The time before which this credential is not valid.
+    #[n(9)] not_before: Option<Timestamp>
 }
 
 impl CredentialData<Unverified> {
@@ -119,6 +125,7 @@ impl CredentialData<Unverified> {
             created: self.created,
             expires: self.expires,
             status: None::<PhantomData<Verified>>,
+            not_before: self.not_before,
         }
     }
 }
@@ -130,6 +137,7 @@ impl Credential {
             subject,
             attrs: Attributes::new(),
             validity: MAX_CREDENTIAL_VALIDITY,
+            not_before: None,
         }
     }
 
@@ -176,6 +184,11 @@ impl CredentialData<Verified> {
         self.expires
     }
 
+    /// The time from which this credential is valid, its creation time unless set explicitly.
+    pub fn not_before(&self) -> Timestamp {
+        self.not_before.unwrap_or(self.created)
+    }
+
     pub fn attributes(&self) -> &Attributes {
         &self.attributes
     }
@@ -192,6 +205,18 @@ impl CredentialData<Unverified> {
     pub fn unverfied_key_label(&self) -> &str {
         &self.issuer_key_label
     }
+    pub fn unverified_subject(&self) -> &IdentityIdentifier {
+        &self.subject
+    }
+    pub fn unverified_expires_at(&self) -> Timestamp {
+        self.expires
+    }
+    pub fn unverified_attributes(&self) -> &Attributes {
+        &self.attributes
+    }
+    pub fn unverified_schema(&self) -> Option<SchemaId> {
+        self.schema
+    }
 }
 
 impl TryFrom<&Credential> for CredentialData<Unverified> {
@@ -282,6 +307,12 @@ impl From<Timestamp> for u64 {
     }
 }
 
+impl From<u64> for Timestamp {
+    fn from(t: u64) -> Self {
+        Timestamp(t)
+    }
+}
+
 /// A schema identifier allows discriminate sets of credential attributes.
 #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cbor(transparent)]
@@ -299,6 +330,7 @@ pub struct CredentialBuilder {
     attrs: Attributes,
     subject: IdentityIdentifier,
     validity: Duration,
+    not_before: Option<Timestamp>,
 }
 
 impl CredentialBuilder {
@@ -308,6 +340,22 @@ impl CredentialBuilder {
         self
     }
 
+    /// Add all the key-value pairs as credential attributes.
+    ///
+    /// Attributes are kept sorted by key, so the credential doesn't depend on
+    /// the iteration order. A key given several times keeps its last value.
+    pub fn with_attributes<I, K, V>(mut self, attrs: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<[u8]>,
+    {
+        for (k, v) in attrs {
+            self.attrs.put(k.as_ref(), v.as_ref());
+        }
+        self
+    }
+
     /// Set the schema identifier of the credential.
     pub fn with_schema(mut self, s: SchemaId) -> Self {
         self.schema = Some(s);
@@ -327,6 +375,12 @@ impl CredentialBuilder {
         self.validity = val;
         self
     }
+
+    /// Set the time before which the credential is not valid.
+    pub fn with_not_before(mut self, t: Timestamp) -> Self {
+        self.not_before = Some(t);
+        self
+    }
 }
 
 impl Serialize for Credential {
@@ -386,6 +440,29 @@ mod test {
         actual == credential
     }
 
+    #[test]
+    fn bulk_attributes_match_folded_attributes() {
+        let subject = IdentityIdentifier::from_key_id("subject");
+        let attrs = vec![("role", b"member".to_vec()), ("cluster", b"c1".to_vec())];
+
+        let folded = attrs
+            .iter()
+            .fold(Credential::builder(subject.clone()), |crd, (k, v)| {
+                crd.with_attribute(k, v)
+            });
+        // The order in which attributes are given doesn't matter
+        let bulk = Credential::builder(subject.clone())
+            .with_attributes(attrs.iter().rev().map(|(k, v)| (k, v)));
+        assert_eq!(
+            minicbor::to_vec(&folded.attrs).unwrap(),
+            minicbor::to_vec(&bulk.attrs).unwrap()
+        );
+
+        let single = Credential::builder(subject)
+            .with_attributes([("role", b"member"), ("role", b"admin_")]);
+        assert_eq!(single.attrs.get("role"), Some(&b"admin_"[..]));
+    }
+
     impl Arbitrary for Credential {
         fn arbitrary(g: &mut Gen) -> Self {
             Credential::new(<Vec<u8>>::arbitrary(g), <Vec<u8>>::arbitrary(g))
@@ -36,7 +36,9 @@ impl<V: IdentityVault, S: AuthenticatedStorage> Identity<V, S> {
         let key_label = IdentityStateConst::ROOT_LABEL;
         let now = Timestamp::now()
             .ok_or_else(|| Error::new(Origin::Core, Kind::Internal, "invalid system time"))?;
-        let exp = Timestamp(u64::from(now).saturating_add(builder.validity.as_secs()));
+        // The validity window starts at the not-before time, if any.
+        let start = builder.not_before.unwrap_or(now);
+        let exp = Timestamp(u64::from(start).saturating_add(builder.validity.as_secs()));
         let dat = CredentialData {
             schema: builder.schema,
             attributes: builder.attrs,
@@ -46,6 +48,7 @@ impl<V: IdentityVault, S: AuthenticatedStorage> Identity<V, S> {
             created: now,
             expires: exp,
             status: None::<PhantomData<Verified>>,
+            not_before: builder.not_before,
         };
         let bytes = minicbor::to_vec(&dat)?;
 
@@ -55,16 +58,25 @@ impl<V: IdentityVault, S: AuthenticatedStorage> Identity<V, S> {
 
     /// Start worker that will be available to receive others attributes and put them into storage,
     /// after successful verification
+    ///
+    /// With `previous_keys`, credentials signed with a key an authority has since rotated are
+    /// accepted too.
     pub async fn start_credential_exchange_worker(
         &self,
         authorities: Vec<PublicIdentity>,
         address: impl Into<Address>,
         present_back: bool,
+        previous_keys: bool,
         attributes_storage: impl IdentityAttributeStorage,
     ) -> Result<()> {
         let s = self.async_try_clone().await?;
-        let worker =
-            CredentialExchangeWorker::new(authorities, present_back, s, attributes_storage);
+        let worker = CredentialExchangeWorker::new(
+            authorities,
+            present_back,
+            previous_keys,
+            s,
+            attributes_storage,
+        );
 
         WorkerBuilder::with_mailboxes(
             Mailboxes::main(
@@ -82,15 +94,17 @@ impl<V: IdentityVault, S: AuthenticatedStorage> Identity<V, S> {
 
     /// Present credential to other party, route shall use secure channel
     pub async fn present_credential(&self, route: impl Into<Route>) -> Result<()> {
-        let credential = self.credential.read().await;
-        let credential = credential.as_ref().ok_or_else(|| {
-            Error::new(
-                Origin::Application,
-                Kind::Invalid,
-                "no credential to present",
-            )
-        })?;
+        let credential = self.credential().await.ok_or_else(no_credential)?;
+        self.present_given_credential(route, &credential).await
+    }
 
+    /// Present the given credential, instead of the one of this identity, to other party,
+    /// route shall use secure channel
+    pub async fn present_given_credential(
+        &self,
+        route: impl Into<Route>,
+        credential: &Credential,
+    ) -> Result<()> {
         let buf = request(
             &self.ctx,
             "credential",
@@ -113,21 +127,37 @@ impl<V: IdentityVault, S: AuthenticatedStorage> Identity<V, S> {
 
     /// Present credential to other party, route shall use secure channel. Other party is expected
     /// to present its credential in response, otherwise this call errors.
+    ///
+    /// With `previous_keys`, their credential may be signed with a key an authority has since
+    /// rotated.
     pub async fn present_credential_mutual(
         &self,
         route: impl Into<Route>,
         authorities: impl IntoIterator<Item = &PublicIdentity>,
+        previous_keys: bool,
         attributes_storage: &impl IdentityAttributeStorage,
     ) -> Result<()> {
-        let credential = self.credential.read().await;
-        let credential = credential.as_ref().ok_or_else(|| {
-            Error::new(
-                Origin::Application,
-                Kind::Invalid,
-                "no credential to present",
-            )
-        })?;
+        let credential = self.credential().await.ok_or_else(no_credential)?;
+        self.present_given_credential_mutual(
+            route,
+            &credential,
+            authorities,
+            previous_keys,
+            attributes_storage,
+        )
+        .await
+    }
 
+    /// Same as [`Identity::present_credential_mutual`], with the given credential instead of
+    /// the one of this identity
+    pub async fn present_given_credential_mutual(
+        &self,
+        route: impl Into<Route>,
+        credential: &Credential,
+        authorities: impl IntoIterator<Item = &PublicIdentity>,
+        previous_keys: bool,
+        attributes_storage: &impl IdentityAttributeStorage,
+    ) -> Result<()> {
         let path = "actions/present_mutual";
         let (buf, local_info) = request_with_local_info(
             &self.ctx,
@@ -157,18 +187,33 @@ impl<V: IdentityVault, S: AuthenticatedStorage> Identity<V, S> {
 
         let credential: Credential = dec.decode()?;
 
-        self.receive_presented_credential(their_id, credential, authorities, attributes_storage)
-            .await?;
+        self.receive_presented_credential(
+            their_id,
+            credential,
+            authorities,
+            previous_keys,
+            attributes_storage,
+        )
+        .await?;
 
         Ok(())
     }
 }
 
+fn no_credential() -> Error {
+    Error::new(
+        Origin::Application,
+        Kind::Invalid,
+        "no credential to present",
+    )
+}
+
 impl<V: IdentityVault, S: AuthenticatedStorage> Identity<V, S> {
     async fn verify_credential(
         sender: &IdentityIdentifier,
         credential: &Credential,
         authorities: impl IntoIterator<Item = &PublicIdentity>,
+        previous_keys: bool,
         vault: &impl IdentityVault,
     ) -> Result<CredentialData<Verified>> {
         let credential_data: CredentialData<Unverified> = match minicbor::decode(&credential.data) {
@@ -184,7 +229,14 @@ impl<V: IdentityVault, S: AuthenticatedStorage> Identity<V, S> {
             None => return Err(IdentityError::UnknownAuthority.into()),
         };
 
-        let credential_data = match issuer.verify_credential(credential, sender, vault).await {
+        let verified = if previous_keys {
+            issuer
+                .verify_credential_with_previous_keys(credential, sender, vault)
+                .await
+        } else {
+            issuer.verify_credential(credential, sender, vault).await
+        };
+        let credential_data = match verified {
             Ok(d) => d,
             Err(_) => return Err(IdentityError::CredentialVerificationFailed.into()),
         };
@@ -196,9 +248,16 @@ impl<V: IdentityVault, S: AuthenticatedStorage> Identity<V, S> {
         &self,
         credential: &Credential,
         authorities: impl IntoIterator<Item = &PublicIdentity>,
+        previous_keys: bool,
     ) -> Result<()> {
-        let _ = Self::verify_credential(self.identifier(), credential, authorities, &self.vault)
-            .await?;
+        let _ = Self::verify_credential(
+            self.identifier(),
+            credential,
+            authorities,
+            previous_keys,
+            &self.vault,
+        )
+        .await?;
         Ok(())
     }
 
@@ -207,10 +266,17 @@ impl<V: IdentityVault, S: AuthenticatedStorage> Identity<V, S> {
         sender: IdentityIdentifier,
         credential: Credential,
         authorities: impl IntoIterator<Item = &PublicIdentity>,
+        previous_keys: bool,
         attributes_storage: &impl IdentityAttributeStorage,
     ) -> Result<()> {
-        let credential_data =
-            Self::verify_credential(&sender, &credential, authorities, &self.vault).await?;
+        let credential_data = Self::verify_credential(
+            &sender,
+            &credential,
+            authorities,
+            previous_keys,
+            &self.vault,
+        )
+        .await?;
 
         //TODO: review the credential' attributes types.   They are references and has lifetimes,
         //etc,  but in reality this is always just deserizalided (either from wire or from
@@ -1,6 +1,6 @@
 use crate::alloc::borrow::ToOwned;
 use crate::authenticated_storage::IdentityAttributeStorage;
-use crate::credential::{Credential, CredentialData, Timestamp, Verified};
+use crate::credential::{Credential, CredentialData, Timestamp, Verified, MAX_CLOCK_SKEW};
 use crate::PublicIdentity;
 use crate::{IdentityIdentifier, IdentityStateConst, IdentityVault};
 use ockam_core::compat::collections::BTreeMap;
@@ -18,6 +18,33 @@ impl PublicIdentity {
         credential: &Credential,
         subject: &IdentityIdentifier,
         vault: &impl IdentityVault,
+    ) -> Result<CredentialData<Verified>> {
+        self.verify_credential_impl(credential, subject, vault, false)
+            .await
+    }
+
+    /// Perform a signature check with the given identity, also accepting a
+    /// credential signed with a root key that the identity has since rotated.
+    ///
+    /// A rotated key which leaked keeps signing credentials passing this check,
+    /// so it's only meant for verifiers which must accept the credentials issued
+    /// before a rotation until they expire.
+    pub async fn verify_credential_with_previous_keys(
+        &self,
+        credential: &Credential,
+        subject: &IdentityIdentifier,
+        vault: &impl IdentityVault,
+    ) -> Result<CredentialData<Verified>> {
+        self.verify_credential_impl(credential, subject, vault, true)
+            .await
+    }
+
+    async fn verify_credential_impl(
+        &self,
+        credential: &Credential,
+        subject: &IdentityIdentifier,
+        vault: &impl IdentityVault,
+        previous_keys: bool,
     ) -> Result<CredentialData<Verified>> {
         let dat = CredentialData::try_from(credential)?;
         if dat.unverfied_key_label() != IdentityStateConst::ROOT_LABEL {
@@ -54,17 +81,27 @@ impl PublicIdentity {
             ));
         }
 
+        let latest_start = Timestamp(u64::from(now).saturating_add(MAX_CLOCK_SKEW.as_secs()));
+        if dat.not_before.map(|t| t > latest_start).unwrap_or(false) {
+            return Err(Error::new(
+                Origin::Application,
+                Kind::Invalid,
+                "credential not yet valid",
+            ));
+        }
+
         let sig = Signature::new(credential.signature().to_vec());
 
-        if !self
-            .verify_signature(
-                &sig,
-                credential.unverified_data(),
-                Some(dat.unverfied_key_label()),
-                vault,
-            )
-            .await?
-        {
+        let data = credential.unverified_data();
+        let label = dat.unverfied_key_label();
+        let verified = if previous_keys {
+            self.verify_signature_with_previous_keys(&sig, data, label, vault)
+                .await?
+        } else {
+            self.verify_signature(&sig, data, Some(label), vault)
+                .await?
+        };
+        if !verified {
             return Err(Error::new(
                 Origin::Application,
                 Kind::Invalid,
@@ -21,6 +21,7 @@ pub struct CredentialExchangeWorker<
 > {
     authorities: Vec<PublicIdentity>,
     present_back: bool,
+    previous_keys: bool,
     identity: Identity<V, S>,
     attributes_storage: AS,
 }
@@ -31,12 +32,14 @@ impl<AS: IdentityAttributeStorage, S: AuthenticatedStorage, V: IdentityVault>
     pub fn new(
         authorities: Vec<PublicIdentity>,
         present_back: bool,
+        previous_keys: bool,
         identity: Identity<V, S>,
         attributes_storage: AS,
     ) -> Self {
         Self {
             authorities,
             present_back,
+            previous_keys,
             identity,
             attributes_storage,
         }
@@ -94,6 +97,7 @@ impl<AS: IdentityAttributeStorage, S: AuthenticatedStorage, V: IdentityVault>
                         sender.clone(),
                         credential,
                         self.authorities.iter(),
+                        self.previous_keys,
                         &self.attributes_storage,
                     )
                     .await;
@@ -125,6 +129,7 @@ impl<AS: IdentityAttributeStorage, S: AuthenticatedStorage, V: IdentityVault>
                         sender.clone(),
                         credential,
                         self.authorities.iter(),
+                        self.previous_keys,
                         &self.attributes_storage,
                     )
                     .await;
@@ -44,6 +44,8 @@ pub enum IdentityError {
     NonceOverflow,
     /// SecureChannel was not found in the Registry
     SecureChannelNotFound,
+    /// Key type can't be used for an `Identity` key
+    InvalidKeyType,
 }
 
 impl ockam_core::compat::error::Error for IdentityError {}
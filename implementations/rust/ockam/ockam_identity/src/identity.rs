@@ -157,10 +157,24 @@ impl<V: IdentityVault, S: AuthenticatedStorage> Identity<V, S> {
 
     /// Create an `Identity`. Extended version
     pub async fn create_ext(ctx: &Context, authenticated_storage: &S, vault: &V) -> Result<Self> {
+        Self::create_with_key_type_ext(ctx, authenticated_storage, vault, SecretType::Ed25519).await
+    }
+
+    /// Create an `Identity` whose root key is of the given type. Only
+    /// `SecretType::Ed25519` and `SecretType::NistP256` can be used. Extended version
+    pub async fn create_with_key_type_ext(
+        ctx: &Context,
+        authenticated_storage: &S,
+        vault: &V,
+        key_type: SecretType,
+    ) -> Result<Self> {
+        if !matches!(key_type, SecretType::Ed25519 | SecretType::NistP256) {
+            return Err(IdentityError::InvalidKeyType.into());
+        }
         let attrs = KeyAttributes::new(
             IdentityStateConst::ROOT_LABEL.to_string(),
             SecretAttributes::new(
-                SecretType::Ed25519,
+                key_type,
                 SecretPersistence::Persistent,
                 CURVE25519_SECRET_LENGTH_U32,
             ),
@@ -327,23 +341,28 @@ impl<V: IdentityVault, S: AuthenticatedStorage> Identity<V, S> {
     }
 
     /// Rotate an existing key with a given label
+    ///
+    /// The new key has the same type as the rotated one.
     pub async fn rotate_key(&self, label: &str) -> Result<()> {
         let change = self
-            .make_rotate_key_change(KeyAttributes::default_with_label(label.to_string()))
+            .make_rotate_key_change(self.rotated_key_attributes(label).await?)
             .await?;
 
         self.add_change(change).await
     }
 
     /// Rotate this `Identity` root key
+    ///
+    /// The new key has the same type as the rotated one.
     pub async fn rotate_root_key(&self) -> Result<()> {
-        let change = self
-            .make_rotate_key_change(KeyAttributes::default_with_label(
-                IdentityStateConst::ROOT_LABEL.to_string(),
-            ))
-            .await?;
+        self.rotate_key(IdentityStateConst::ROOT_LABEL).await
+    }
 
-        self.add_change(change).await
+    /// Attributes of the key replacing the current key with the given label
+    async fn rotated_key_attributes(&self, label: &str) -> Result<KeyAttributes> {
+        let secret = self.get_secret_key(label).await?;
+        let secret_attributes = self.vault.secret_attributes_get(&secret).await?;
+        Ok(KeyAttributes::new(label.to_string(), secret_attributes))
     }
 
     /// Get [`Secret`] key. Key is uniquely identified by label in [`KeyAttributes`]
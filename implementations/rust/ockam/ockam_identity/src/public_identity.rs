@@ -73,4 +73,23 @@ impl PublicIdentity {
 
         vault.verify(signature, &public_key, data).await
     }
+
+    /// Verify signature using the key with the given label, or any key it
+    /// replaced, so that data signed before a key rotation still verifies
+    pub async fn verify_signature_with_previous_keys(
+        &self,
+        signature: &Signature,
+        data: &[u8],
+        key_label: &str,
+        vault: &impl IdentityVault,
+    ) -> Result<bool> {
+        let public_keys =
+            IdentityChangeHistory::get_public_keys_static(self.change_history.as_ref(), key_label)?;
+        for public_key in public_keys {
+            if vault.verify(signature, &public_key, data).await? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
 }
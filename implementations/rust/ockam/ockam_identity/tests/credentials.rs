@@ -5,7 +5,7 @@ use ockam_identity::authenticated_storage::{
     mem::InMemoryStorage, AuthenticatedAttributeStorage, IdentityAttributeStorageReader,
 };
 use ockam_identity::credential::access_control::CredentialAccessControl;
-use ockam_identity::credential::Credential;
+use ockam_identity::credential::{Credential, Timestamp, MAX_CLOCK_SKEW};
 use ockam_identity::{Identity, TrustEveryonePolicy, TrustIdentifierPolicy};
 
 use ockam_node::{Context, WorkerBuilder};
@@ -35,6 +35,7 @@ async fn full_flow_oneway(ctx: &mut Context) -> Result<()> {
             authorities,
             "credential_exchange",
             false,
+            false,
             authenticated_attribute_storage.async_try_clone().await?,
         )
         .await?;
@@ -70,6 +71,74 @@ async fn full_flow_oneway(ctx: &mut Context) -> Result<()> {
     ctx.stop().await
 }
 
+#[ockam_macros::test]
+async fn credentials_verify_after_key_rotation(ctx: &mut Context) -> Result<()> {
+    let vault = Vault::create();
+    let authority = Identity::create(ctx, &vault).await?;
+    let member = Identity::create(ctx, &vault).await?;
+
+    let credential = Credential::builder(member.identifier().clone());
+    let before = authority.issue_credential(credential).await?;
+    let authority_before = authority.to_public().await?;
+
+    authority.rotate_root_key().await?;
+    let credential = Credential::builder(member.identifier().clone());
+    let after = authority.issue_credential(credential).await?;
+
+    // The change history of the authority keeps the key that signed the first
+    // credential, which is only accepted when previous keys are
+    let authority_after = authority.to_public().await?;
+    assert!(authority_after
+        .verify_credential(&before, member.identifier(), &vault)
+        .await
+        .is_err());
+    authority_after
+        .verify_credential_with_previous_keys(&before, member.identifier(), &vault)
+        .await?;
+    authority_after
+        .verify_credential(&after, member.identifier(), &vault)
+        .await?;
+
+    // The new key is unknown to whoever only knows the authority before the rotation
+    assert!(authority_before
+        .verify_credential(&after, member.identifier(), &vault)
+        .await
+        .is_err());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn credentials_not_yet_valid(ctx: &mut Context) -> Result<()> {
+    let vault = Vault::create();
+    let authority = Identity::create(ctx, &vault).await?;
+    let member = Identity::create(ctx, &vault).await?;
+    let now = Timestamp::now().unwrap().unix_time();
+
+    // A not-before time slightly ahead of the verifier's clock is tolerated
+    let skewed = Timestamp::from(now + MAX_CLOCK_SKEW.as_secs() / 2);
+    let credential = Credential::builder(member.identifier().clone()).with_not_before(skewed);
+    let credential = authority.issue_credential(credential).await?;
+    authority
+        .to_public()
+        .await?
+        .verify_credential(&credential, member.identifier(), &vault)
+        .await?;
+
+    // A credential starting later is rejected
+    let later = Timestamp::from(now + 2 * MAX_CLOCK_SKEW.as_secs());
+    let credential = Credential::builder(member.identifier().clone()).with_not_before(later);
+    let credential = authority.issue_credential(credential).await?;
+    assert!(authority
+        .to_public()
+        .await?
+        .verify_credential(&credential, member.identifier(), &vault)
+        .await
+        .is_err());
+
+    ctx.stop().await
+}
+
 #[ockam_macros::test]
 async fn full_flow_twoway(ctx: &mut Context) -> Result<()> {
     let vault = Vault::create();
@@ -98,6 +167,7 @@ async fn full_flow_twoway(ctx: &mut Context) -> Result<()> {
             authorities.clone(),
             "credential_exchange",
             true,
+            false,
             authenticated_attribute_storage_client_2
                 .async_try_clone()
                 .await?,
@@ -120,6 +190,7 @@ async fn full_flow_twoway(ctx: &mut Context) -> Result<()> {
         .present_credential_mutual(
             route![channel, "credential_exchange"],
             &authorities,
+            false,
             &authenticated_attribute_storage_client_1,
         )
         .await?;
@@ -182,6 +253,7 @@ async fn access_control(ctx: &mut Context) -> Result<()> {
             authorities,
             "credential_exchange",
             false,
+            false,
             authenticated_attribute_storage.async_try_clone().await?,
         )
         .await?;
@@ -248,3 +320,115 @@ async fn access_control(ctx: &mut Context) -> Result<()> {
 
     ctx.stop().await
 }
+
+#[ockam_macros::test]
+async fn access_control_with_given_credential(ctx: &mut Context) -> Result<()> {
+    let vault = Vault::create();
+    let authenticated_attribute_storage =
+        AuthenticatedAttributeStorage::new(InMemoryStorage::new());
+
+    let authority = Identity::create(ctx, &vault).await?;
+    let untrusted_authority = Identity::create(ctx, &vault).await?;
+
+    let server = Identity::create(ctx, &vault).await?;
+
+    server
+        .create_secure_channel_listener("listener", TrustEveryonePolicy)
+        .await?;
+
+    let authorities = vec![authority.to_public().await?];
+
+    server
+        .start_credential_exchange_worker(
+            authorities,
+            "credential_exchange",
+            false,
+            false,
+            authenticated_attribute_storage.async_try_clone().await?,
+        )
+        .await?;
+
+    let counter = Arc::new(AtomicI8::new(0));
+
+    let worker = CountingWorker {
+        msgs_count: counter.clone(),
+    };
+
+    let required_attributes = vec![("is_superuser".to_string(), b"true".to_vec())];
+    let access_control =
+        CredentialAccessControl::new(&required_attributes, authenticated_attribute_storage);
+
+    WorkerBuilder::with_access_control(
+        Arc::new(access_control),
+        Arc::new(DenyAll),
+        "counter",
+        worker,
+    )
+    .start(ctx)
+    .await?;
+
+    // The client has no credential of its own
+    let client = Identity::create(ctx, &vault).await?;
+    let channel = client
+        .create_secure_channel(
+            route!["listener"],
+            TrustIdentifierPolicy::new(server.identifier().clone()),
+        )
+        .await?;
+    assert!(client
+        .present_credential(route![channel.clone(), "credential_exchange"])
+        .await
+        .is_err());
+
+    let child_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "child",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+
+    // Messages are rejected without a credential
+    child_ctx
+        .send(route![channel.clone(), "counter"], "Hello".to_string())
+        .await?;
+    ctx.sleep(Duration::from_millis(100)).await;
+    assert_eq!(counter.load(Ordering::Relaxed), 0);
+
+    // A credential issued by an untrusted authority is rejected by the listener
+    let credential =
+        Credential::builder(client.identifier().clone()).with_attribute("is_superuser", b"true");
+    let untrusted_credential = untrusted_authority.issue_credential(credential).await?;
+    assert!(client
+        .present_given_credential(
+            route![channel.clone(), "credential_exchange"],
+            &untrusted_credential
+        )
+        .await
+        .is_err());
+
+    child_ctx
+        .send(route![channel.clone(), "counter"], "Hello".to_string())
+        .await?;
+    ctx.sleep(Duration::from_millis(100)).await;
+    assert_eq!(counter.load(Ordering::Relaxed), 0);
+
+    // Messages are admitted once a trusted credential has been presented
+    let credential =
+        Credential::builder(client.identifier().clone()).with_attribute("is_superuser", b"true");
+    let credential = authority.issue_credential(credential).await?;
+    client
+        .present_given_credential(route![channel.clone(), "credential_exchange"], &credential)
+        .await?;
+
+    child_ctx
+        .send(route![channel, "counter"], "Hello".to_string())
+        .await?;
+    ctx.sleep(Duration::from_millis(100)).await;
+    assert_eq!(counter.load(Ordering::Relaxed), 1);
+
+    // The given credential is not kept by the client
+    assert!(client.credential().await.is_none());
+
+    ctx.stop().await
+}
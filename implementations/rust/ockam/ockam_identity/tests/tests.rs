@@ -1,6 +1,7 @@
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::vault::{SecretAttributes, SecretPersistence, SecretType, SecretVault};
 use ockam_core::{Error, Result};
+use ockam_identity::authenticated_storage::mem::InMemoryStorage;
 use ockam_identity::Identity;
 use ockam_node::Context;
 use ockam_vault::Vault;
@@ -83,6 +84,36 @@ async fn test_key_rotation(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn test_create_with_key_type(ctx: &mut Context) -> Result<()> {
+    let vault = Vault::create();
+    let storage = InMemoryStorage::new();
+
+    let alice =
+        Identity::create_with_key_type_ext(ctx, &storage, &vault, SecretType::NistP256).await?;
+    let root_key = alice.change_history().await.get_root_public_key()?;
+    assert_eq!(root_key.stype(), SecretType::NistP256);
+
+    // The identity must still be verifiable by others
+    let bob = Identity::create(ctx, &Vault::create()).await?;
+    bob.update_known_identity(alice.identifier(), &alice.to_public().await?)
+        .await?;
+
+    // Rotating the root key keeps its type
+    alice.rotate_root_key().await?;
+    let rotated = alice.change_history().await.get_root_public_key()?;
+    assert_eq!(rotated.stype(), SecretType::NistP256);
+    assert_ne!(rotated.data(), root_key.data());
+
+    // Only signing keys can be used as identity keys
+    let res = Identity::create_with_key_type_ext(ctx, &storage, &vault, SecretType::X25519).await;
+    assert!(res.is_err());
+
+    ctx.stop().await?;
+
+    Ok(())
+}
+
 #[ockam_macros::test]
 async fn test_update_contact_and_reprove(ctx: &mut Context) -> Result<()> {
     let alice_vault = Vault::create();
@@ -7,10 +7,26 @@ use ockam_core::{
 /// an Ockam X3DH kex
 #[derive(Clone, Copy, Debug)]
 pub enum X3DHError {
-    InvalidState = 1,
-    MessageLenMismatch,
+    InvalidState,
+    /// A message doesn't have the length needed to read it
+    MessageLenMismatch {
+        expected: usize,
+        actual: usize,
+    },
     SignatureLenMismatch,
     InvalidHash,
+    InvalidKeyType,
+    /// A public key doesn't have the length of its type
+    MalformedKey {
+        expected: usize,
+        actual: usize,
+    },
+    /// A signature is shorter or longer than the signatures of its key type
+    MalformedSignature {
+        min: usize,
+        max: usize,
+        actual: usize,
+    },
 }
 
 impl ockam_core::compat::error::Error for X3DHError {}
@@ -18,9 +34,24 @@ impl core::fmt::Display for X3DHError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::InvalidState => "invalid state".fmt(f),
-            Self::MessageLenMismatch => "message length mismatch".fmt(f),
+            Self::MessageLenMismatch { expected, actual } => write!(
+                f,
+                "message length mismatch: expected {expected} bytes, got {actual}"
+            ),
             Self::SignatureLenMismatch => "signature length mismatch".fmt(f),
             Self::InvalidHash => "invalid hash".fmt(f),
+            Self::InvalidKeyType => "invalid key type".fmt(f),
+            Self::MalformedKey { expected, actual } => write!(
+                f,
+                "malformed public key: expected {expected} bytes, got {actual}"
+            ),
+            Self::MalformedSignature { min, max, actual } if min == max => {
+                write!(f, "malformed signature: expected {max} bytes, got {actual}")
+            }
+            Self::MalformedSignature { min, max, actual } => write!(
+                f,
+                "malformed signature: expected {min} to {max} bytes, got {actual}"
+            ),
         }
     }
 }
@@ -30,8 +61,9 @@ impl From<X3DHError> for Error {
     fn from(err: X3DHError) -> Self {
         use X3DHError::*;
         let kind = match err {
-            InvalidState | InvalidHash => Kind::Invalid,
-            MessageLenMismatch | SignatureLenMismatch => Kind::Misuse,
+            InvalidState | InvalidHash | InvalidKeyType => Kind::Invalid,
+            MalformedKey { .. } | MalformedSignature { .. } => Kind::Invalid,
+            MessageLenMismatch { .. } | SignatureLenMismatch => Kind::Misuse,
         };
 
         Error::new(Origin::KeyExchange, kind, err)
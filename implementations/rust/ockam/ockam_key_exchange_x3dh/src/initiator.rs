@@ -1,4 +1,4 @@
-use crate::{PreKeyBundle, X3DHError, X3dhVault, CSUITE};
+use crate::{csuite, PreKeyBundle, X3DHError, X3dhVault};
 use alloc::vec;
 use ockam_core::vault::Signature as GenericSignature;
 use ockam_core::vault::{
@@ -117,6 +117,7 @@ impl<V: X3dhVault> KeyExchanger for Initiator<V> {
         match self.state {
             InitiatorState::ProcessPreKeyBundle => {
                 let prekey_bundle = PreKeyBundle::try_from(response)?;
+                let csuite = csuite(prekey_bundle.secret_type());
 
                 let identity_key = self.identity_key.as_ref().ok_or(X3DHError::InvalidState)?;
 
@@ -195,12 +196,12 @@ impl<V: X3dhVault> KeyExchanger for Initiator<V> {
 
                 let mut keyrefs = self
                     .vault
-                    .hkdf_sha256(&salt, CSUITE, Some(&ikm), vec![atts, atts])
+                    .hkdf_sha256(&salt, csuite, Some(&ikm), vec![atts, atts])
                     .await?;
                 let encrypt_key = keyrefs.pop().ok_or(X3DHError::InvalidState)?;
                 let decrypt_key = keyrefs.pop().ok_or(X3DHError::InvalidState)?;
 
-                let mut state_hash = self.vault.sha256(CSUITE).await?.to_vec();
+                let mut state_hash = self.vault.sha256(csuite).await?.to_vec();
                 state_hash.append(&mut ikm_bytes);
                 let state_hash = self.vault.sha256(state_hash.as_slice()).await?;
 
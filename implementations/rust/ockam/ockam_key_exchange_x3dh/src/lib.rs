@@ -27,23 +27,29 @@ pub use new_key_exchanger::*;
 /// from Ed25519 or P-256
 #[derive(Clone, Zeroize)]
 #[zeroize(drop)]
-pub struct Signature([u8; 64]);
+pub struct Signature(Vec<u8>);
 
-impl AsRef<[u8; 64]> for Signature {
-    fn as_ref(&self) -> &[u8; 64] {
+impl AsRef<[u8]> for Signature {
+    fn as_ref(&self) -> &[u8] {
         &self.0
     }
 }
 
+impl From<Vec<u8>> for Signature {
+    fn from(data: Vec<u8>) -> Self {
+        Signature(data)
+    }
+}
+
 impl From<[u8; 64]> for Signature {
     fn from(data: [u8; 64]) -> Self {
-        Signature(data)
+        Signature(data.to_vec())
     }
 }
 
 impl From<&[u8; 64]> for Signature {
     fn from(data: &[u8; 64]) -> Self {
-        Signature(*data)
+        Signature(data.to_vec())
     }
 }
 
@@ -64,15 +70,140 @@ pub struct PreKeyBundle {
 }
 
 impl PreKeyBundle {
+    /// Size of a bundle in the original, X25519-only, format
     const SIZE: usize = 32 + 32 + 64 + 32;
+    /// Discriminator byte of a bundle carrying X25519 keys
+    const X25519: u8 = 3;
+    /// Discriminator byte of a bundle carrying P-256 keys
+    const P256: u8 = 5;
+    /// Length of an X25519 public key
+    const X25519_KEY_LEN: usize = 32;
+    /// Length of an XEdDSA signature
+    const X25519_SIGNATURE_LEN: usize = 64;
+    /// Length of a DER-encoded P-256 public key
+    const P256_KEY_LEN: usize = 91;
+    /// Shortest and longest DER-encoded P-256 ECDSA signatures
+    const P256_SIGNATURE_LEN: (usize, usize) = (8, 72);
+
+    /// Return the type of the keys carried by this bundle
+    pub fn secret_type(&self) -> SecretType {
+        self.identity_key.stype()
+    }
+
     /// Convert the prekey bundle to a byte array
-    pub fn to_bytes(&self) -> Vec<u8> {
-        let mut output = Vec::new();
-        output.extend_from_slice(self.identity_key.data());
-        output.extend_from_slice(self.signed_prekey.data());
-        output.extend_from_slice(self.signature_prekey.0.as_ref());
-        output.extend_from_slice(self.one_time_prekey.data());
-        output
+    ///
+    /// The bundle starts with a [`SecretType`] discriminator byte followed by
+    /// length-prefixed fields. Bundles of other key types than X25519 and P-256
+    /// can't be encoded.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ockam_core::Error> {
+        let tag = match self.secret_type() {
+            SecretType::X25519 => Self::X25519,
+            SecretType::NistP256 => Self::P256,
+            _ => return Err(X3DHError::InvalidKeyType.into()),
+        };
+        let mut output = vec![tag];
+        for field in [
+            self.identity_key.data(),
+            self.signed_prekey.data(),
+            self.signature_prekey.as_ref(),
+            self.one_time_prekey.data(),
+        ] {
+            output.extend_from_slice(&(field.len() as u16).to_be_bytes());
+            output.extend_from_slice(field);
+        }
+        Ok(output)
+    }
+
+    /// Parse a bundle in the discriminated format
+    ///
+    /// A length mismatch reports the length needed to read the field that is cut,
+    /// or the length of the fields that were read when there are bytes left.
+    fn try_from_tagged(data: &[u8]) -> Result<Self, ockam_core::Error> {
+        let stype = match data.first() {
+            Some(&Self::X25519) => SecretType::X25519,
+            Some(&Self::P256) => SecretType::NistP256,
+            // Without a known discriminator, this is most likely a bundle in
+            // the original format, which has a fixed size
+            _ => {
+                return Err(X3DHError::MessageLenMismatch {
+                    expected: Self::SIZE,
+                    actual: data.len(),
+                }
+                .into())
+            }
+        };
+        let mut offset = 1;
+        let mut fields: [Vec<u8>; 4] = Default::default();
+        for field in fields.iter_mut() {
+            let len = match data.get(offset..offset + 2) {
+                Some(len) => u16::from_be_bytes([len[0], len[1]]) as usize,
+                None => {
+                    return Err(X3DHError::MessageLenMismatch {
+                        expected: offset + 2,
+                        actual: data.len(),
+                    }
+                    .into())
+                }
+            };
+            offset += 2;
+            *field = data
+                .get(offset..offset + len)
+                .ok_or(X3DHError::MessageLenMismatch {
+                    expected: offset + len,
+                    actual: data.len(),
+                })?
+                .to_vec();
+            offset += len;
+        }
+        if offset != data.len() {
+            return Err(X3DHError::MessageLenMismatch {
+                expected: offset,
+                actual: data.len(),
+            }
+            .into());
+        }
+        let [identity_key, signed_prekey, signature_prekey, one_time_prekey] = fields;
+        Self {
+            identity_key: PublicKey::new(identity_key, stype),
+            signed_prekey: PublicKey::new(signed_prekey, stype),
+            signature_prekey: Signature(signature_prekey),
+            one_time_prekey: PublicKey::new(one_time_prekey, stype),
+        }
+        .validated()
+    }
+
+    /// Check that the keys and the signature have the lengths of the key type
+    fn validated(self) -> Result<Self, ockam_core::Error> {
+        let stype = self.secret_type();
+        let (key_len, (min, max)) = match stype {
+            SecretType::X25519 => (
+                Self::X25519_KEY_LEN,
+                (Self::X25519_SIGNATURE_LEN, Self::X25519_SIGNATURE_LEN),
+            ),
+            SecretType::NistP256 => (Self::P256_KEY_LEN, Self::P256_SIGNATURE_LEN),
+            _ => return Err(X3DHError::InvalidKeyType.into()),
+        };
+        for key in [
+            &self.identity_key,
+            &self.signed_prekey,
+            &self.one_time_prekey,
+        ] {
+            if key.stype() != stype {
+                return Err(X3DHError::InvalidKeyType.into());
+            }
+            if key.data().len() != key_len {
+                return Err(X3DHError::MalformedKey {
+                    expected: key_len,
+                    actual: key.data().len(),
+                }
+                .into());
+            }
+        }
+        let actual = self.signature_prekey.as_ref().len();
+        if actual < min || actual > max {
+            return Err(X3DHError::MalformedSignature { min, max, actual }.into());
+        }
+        Ok(self)
     }
 }
 
@@ -80,12 +211,13 @@ impl TryFrom<&[u8]> for PreKeyBundle {
     type Error = ockam_core::Error;
 
     fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        // A bundle of exactly the original size is an X25519 bundle
         if data.len() != Self::SIZE {
-            return Err(X3DHError::MessageLenMismatch.into());
+            return Self::try_from_tagged(data);
         }
         let identity_key = PublicKey::new(array_ref![data, 0, 32].to_vec(), SecretType::X25519);
         let signed_prekey = PublicKey::new(array_ref![data, 32, 32].to_vec(), SecretType::X25519);
-        let signature_prekey = Signature(*array_ref![data, 64, 64]);
+        let signature_prekey = Signature(array_ref![data, 64, 64].to_vec());
         let one_time_prekey =
             PublicKey::new(array_ref![data, 128, 32].to_vec(), SecretType::X25519);
         Ok(Self {
@@ -98,6 +230,15 @@ impl TryFrom<&[u8]> for PreKeyBundle {
 }
 
 const CSUITE: &[u8] = b"X3DH_25519_AESGCM_SHA256\0\0\0\0\0\0\0\0";
+const CSUITE_P256: &[u8] = b"X3DH_P256_AESGCM_SHA256\0\0\0\0\0\0\0\0\0";
+
+/// Return the cipher suite name matching the given key type
+fn csuite(stype: SecretType) -> &'static [u8] {
+    match stype {
+        SecretType::NistP256 => CSUITE_P256,
+        _ => CSUITE,
+    }
+}
 
 /// Vault with X3DH required functionality
 pub trait X3dhVault:
@@ -136,6 +277,191 @@ mod tests {
     use ockam_node::Context;
     use ockam_vault::Vault;
 
+    fn bundle(stype: SecretType, key_len: usize, sig_len: usize) -> PreKeyBundle {
+        PreKeyBundle {
+            identity_key: PublicKey::new(vec![1; key_len], stype),
+            signed_prekey: PublicKey::new(vec![2; key_len], stype),
+            signature_prekey: Signature(vec![3; sig_len]),
+            one_time_prekey: PublicKey::new(vec![4; key_len], stype),
+        }
+    }
+
+    /// A bundle in the original, X25519-only, format
+    fn legacy(b: &PreKeyBundle) -> Vec<u8> {
+        [
+            b.identity_key.data(),
+            b.signed_prekey.data(),
+            b.signature_prekey.as_ref(),
+            b.one_time_prekey.data(),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn prekey_bundle_x25519_round_trip() {
+        let b = bundle(SecretType::X25519, 32, 64);
+        let bytes = b.to_bytes().unwrap();
+        assert_eq!(bytes[0], PreKeyBundle::X25519);
+
+        let parsed = PreKeyBundle::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(parsed.secret_type(), SecretType::X25519);
+        assert_eq!(parsed.to_bytes().unwrap(), bytes);
+        assert_eq!(csuite(parsed.secret_type()), CSUITE);
+
+        // Bundles in the original format are still read
+        let bytes = legacy(&b);
+        assert_eq!(bytes.len(), PreKeyBundle::SIZE);
+        let parsed = PreKeyBundle::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(parsed.secret_type(), SecretType::X25519);
+        assert_eq!(parsed.to_bytes().unwrap(), b.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn prekey_bundle_other_key_type() {
+        let b = bundle(SecretType::Ed25519, 32, 64);
+        assert_eq!(
+            b.to_bytes().unwrap_err().to_string(),
+            ockam_core::Error::from(X3DHError::InvalidKeyType).to_string()
+        );
+    }
+
+    #[test]
+    fn prekey_bundle_p256_round_trip() {
+        // DER-encoded P-256 public keys and signatures
+        let b = bundle(SecretType::NistP256, 91, 71);
+        let bytes = b.to_bytes().unwrap();
+        assert_eq!(bytes[0], PreKeyBundle::P256);
+
+        let parsed = PreKeyBundle::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(parsed.secret_type(), SecretType::NistP256);
+        assert_eq!(parsed.identity_key.data(), b.identity_key.data());
+        assert_eq!(
+            parsed.signature_prekey.as_ref(),
+            b.signature_prekey.as_ref()
+        );
+        assert_eq!(parsed.to_bytes().unwrap(), bytes);
+        assert_eq!(csuite(parsed.secret_type()), CSUITE_P256);
+    }
+
+    fn parse_error(bytes: &[u8]) -> String {
+        PreKeyBundle::try_from(bytes).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn prekey_bundle_truncated() {
+        let bytes = bundle(SecretType::NistP256, 91, 71).to_bytes().unwrap();
+        let len = bytes.len();
+        // The signed prekey is cut
+        assert_eq!(
+            parse_error(&bytes[..100]),
+            X3DHError::MessageLenMismatch {
+                expected: 1 + 2 + 91 + 2 + 91,
+                actual: 100
+            }
+            .to_string()
+        );
+        // The length of the signed prekey is cut
+        assert_eq!(
+            parse_error(&bytes[..95]),
+            X3DHError::MessageLenMismatch {
+                expected: 1 + 2 + 91 + 2,
+                actual: 95
+            }
+            .to_string()
+        );
+        assert_eq!(
+            parse_error(&bytes[..len - 1]),
+            X3DHError::MessageLenMismatch {
+                expected: len,
+                actual: len - 1
+            }
+            .to_string()
+        );
+
+        let bytes = legacy(&bundle(SecretType::X25519, 32, 64));
+        assert_eq!(
+            parse_error(&bytes[..PreKeyBundle::SIZE - 1]),
+            X3DHError::MessageLenMismatch {
+                expected: PreKeyBundle::SIZE,
+                actual: PreKeyBundle::SIZE - 1
+            }
+            .to_string()
+        );
+        assert_eq!(
+            parse_error(&[]),
+            X3DHError::MessageLenMismatch {
+                expected: PreKeyBundle::SIZE,
+                actual: 0
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn prekey_bundle_too_long() {
+        let mut bytes = bundle(SecretType::NistP256, 91, 71).to_bytes().unwrap();
+        let len = bytes.len();
+        bytes.extend_from_slice(&[0; 3]);
+        assert_eq!(
+            parse_error(&bytes),
+            X3DHError::MessageLenMismatch {
+                expected: len,
+                actual: len + 3
+            }
+            .to_string()
+        );
+
+        let mut bytes = legacy(&bundle(SecretType::X25519, 32, 64));
+        bytes.push(0);
+        assert_eq!(
+            parse_error(&bytes),
+            X3DHError::MessageLenMismatch {
+                expected: PreKeyBundle::SIZE,
+                actual: PreKeyBundle::SIZE + 1
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn prekey_bundle_malformed_key_or_signature() {
+        let bytes = bundle(SecretType::NistP256, 65, 71).to_bytes().unwrap();
+        assert_eq!(
+            parse_error(&bytes),
+            X3DHError::MalformedKey {
+                expected: 91,
+                actual: 65
+            }
+            .to_string()
+        );
+
+        let bytes = bundle(SecretType::NistP256, 91, 64 + 16)
+            .to_bytes()
+            .unwrap();
+        assert_eq!(
+            parse_error(&bytes),
+            X3DHError::MalformedSignature {
+                min: 8,
+                max: 72,
+                actual: 80
+            }
+            .to_string()
+        );
+
+        // X25519 keys in the discriminated format are checked too
+        let mut bytes = bundle(SecretType::NistP256, 32, 63).to_bytes().unwrap();
+        bytes[0] = PreKeyBundle::X25519;
+        assert_eq!(
+            parse_error(&bytes),
+            X3DHError::MalformedSignature {
+                min: 64,
+                max: 64,
+                actual: 63
+            }
+            .to_string()
+        );
+    }
+
     #[allow(non_snake_case)]
     #[ockam_macros::test]
     async fn full_flow__correct_credential__keys_should_match(ctx: &mut Context) -> Result<()> {
@@ -114,18 +114,17 @@ impl<V: X3dhVault> KeyExchanger for Responder<V> {
                     .secret_public_key_get(identity_secret_key)
                     .await?;
                 let one_time_prekey_pub = self.vault.secret_public_key_get(one_time_prekey).await?;
-                if signature.as_ref().len() != 64 {
-                    return Err(X3DHError::SignatureLenMismatch.into());
-                }
-                let signature_array = array_ref![signature.as_ref(), 0, 64]; //check it against panic
+                // The signature length depends on the key type, e.g. P-256
+                // signatures are DER-encoded
                 let bundle = PreKeyBundle {
                     identity_key,
                     signed_prekey: signed_prekey_pub,
-                    signature_prekey: Signature(*signature_array),
+                    signature_prekey: Signature::from(signature.as_ref().to_vec()),
                     one_time_prekey: one_time_prekey_pub,
-                };
+                }
+                .validated()?;
                 self.state = ResponderState::Done;
-                Ok(bundle.to_bytes())
+                bundle.to_bytes()
             }
             ResponderState::HandleInitiatorKeys | ResponderState::Done => {
                 Err(X3DHError::InvalidState.into())
@@ -137,7 +136,11 @@ impl<V: X3dhVault> KeyExchanger for Responder<V> {
         match self.state {
             ResponderState::HandleInitiatorKeys => {
                 if response.len() != 64 {
-                    return Err(X3DHError::MessageLenMismatch.into());
+                    return Err(X3DHError::MessageLenMismatch {
+                        expected: 64,
+                        actual: response.len(),
+                    }
+                    .into());
                 }
                 self.prologue().await?;
 
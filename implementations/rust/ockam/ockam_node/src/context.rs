@@ -229,17 +229,16 @@ impl Context {
         self.rt.spawn(async_drop.run());
 
         // Create a new context and get access to the mailbox senders
-        let addresses = mailboxes.addresses();
         let (ctx, sender, _) = Self::new(
             self.rt.clone(),
             self.sender.clone(),
-            mailboxes,
+            mailboxes.clone(),
             Some(drop_sender),
         );
 
         // Create a "detached relay" and register it with the router
         let (msg, mut rx) =
-            NodeMessage::start_worker(addresses, sender, true, Arc::clone(&self.mailbox_count));
+            NodeMessage::start_worker(mailboxes, sender, true, Arc::clone(&self.mailbox_count));
         self.sender
             .send(msg)
             .await
@@ -845,6 +844,25 @@ impl Context {
             .take_workers()
     }
 
+    /// Return the mailboxes of the worker with the given address
+    ///
+    /// Fails with a "not found" error if no worker is registered at
+    /// this address.
+    pub async fn worker_mailboxes(&self, addr: &Address) -> Result<Mailboxes> {
+        let (msg, mut reply_rx) = NodeMessage::worker_mailboxes(addr.clone());
+
+        self.sender
+            .send(msg)
+            .await
+            .map_err(NodeError::from_send_err)?;
+
+        reply_rx
+            .recv()
+            .await
+            .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??
+            .take_mailboxes()
+    }
+
     /// Register a router for a specific address type
     pub async fn register<A: Into<Address>>(&self, type_: TransportType, addr: A) -> Result<()> {
         self.register_impl(type_, addr.into()).await
@@ -5,15 +5,15 @@ use crate::{
 };
 use core::{fmt, sync::atomic::AtomicUsize};
 use ockam_core::compat::{string::String, sync::Arc, vec::Vec};
-use ockam_core::{Address, Error, RelayMessage, Result, TransportType};
+use ockam_core::{Address, Error, Mailboxes, RelayMessage, Result, TransportType};
 
 /// Messages sent from the Node to the Executor
 #[derive(Debug)]
 pub enum NodeMessage {
     /// Start a new worker and store the send handle
     StartWorker {
-        /// The set of addresses in use by this worker
-        addrs: Vec<Address>,
+        /// The mailboxes (addresses and access control) of this worker
+        mailboxes: Mailboxes,
         /// Pair of senders to the worker relay (msgs and ctrl)
         senders: SenderPair,
         /// A detached context/ "worker" runs no relay state
@@ -25,6 +25,8 @@ pub enum NodeMessage {
     },
     /// Return a list of all worker addresses
     ListWorkers(SmallSender<NodeReplyResult>),
+    /// Return the mailboxes of the worker with the given address
+    WorkerMailboxes(Address, SmallSender<NodeReplyResult>),
     /// Add an existing address to a cluster
     SetCluster(Address, String, SmallSender<NodeReplyResult>),
     /// Stop an existing worker
@@ -54,6 +56,7 @@ impl fmt::Display for NodeMessage {
         match self {
             NodeMessage::StartWorker { .. } => write!(f, "StartWorker"),
             NodeMessage::ListWorkers(_) => write!(f, "ListWorkers"),
+            NodeMessage::WorkerMailboxes(_, _) => write!(f, "WorkerMailboxes"),
             NodeMessage::SetCluster(_, _, _) => write!(f, "SetCluster"),
             NodeMessage::StopWorker(_, _, _) => write!(f, "StopWorker"),
             NodeMessage::StartProcessor(_, _, _) => write!(f, "StartProcessor"),
@@ -79,7 +82,7 @@ impl NodeMessage {
     ///               commands.  Setting this to `true` will disable
     ///               stop ACK support in the router
     pub fn start_worker(
-        addrs: Vec<Address>,
+        mailboxes: Mailboxes,
         senders: SenderPair,
         detached: bool,
         mailbox_count: Arc<AtomicUsize>,
@@ -87,7 +90,7 @@ impl NodeMessage {
         let (reply, rx) = small_channel();
         (
             Self::StartWorker {
-                addrs,
+                mailboxes,
                 senders,
                 detached,
                 mailbox_count,
@@ -118,6 +121,12 @@ impl NodeMessage {
         (Self::ListWorkers(tx), rx)
     }
 
+    /// Create a worker mailboxes message and reply receiver
+    pub fn worker_mailboxes(addr: Address) -> (Self, SmallReceiver<NodeReplyResult>) {
+        let (tx, rx) = small_channel();
+        (Self::WorkerMailboxes(addr, tx), rx)
+    }
+
     /// Create a set cluster message and reply receiver
     pub fn set_cluster(addr: Address, label: String) -> (Self, SmallReceiver<NodeReplyResult>) {
         let (tx, rx) = small_channel();
@@ -164,6 +173,8 @@ pub enum RouterReply {
     Ok,
     /// A list of worker addresses
     Workers(Vec<Address>),
+    /// The mailboxes of a worker
+    Mailboxes(Mailboxes),
     /// Message sender to a specific worker
     Sender {
         /// The address a message is being sent to
@@ -259,6 +270,11 @@ impl RouterReply {
         Ok(Self::Workers(v))
     }
 
+    /// Return [NodeReply::Mailboxes] for the given mailboxes
+    pub fn mailboxes(m: Mailboxes) -> NodeReplyResult {
+        Ok(Self::Mailboxes(m))
+    }
+
     /// Return [NodeReply::Sender] for the given information
     pub fn sender(addr: Address, sender: MessageSender<RelayMessage>) -> NodeReplyResult {
         Ok(RouterReply::Sender { addr, sender })
@@ -280,6 +296,14 @@ impl RouterReply {
         }
     }
 
+    /// Consume the wrapper and return [NodeReply::Mailboxes]
+    pub fn take_mailboxes(self) -> Result<Mailboxes> {
+        match self {
+            Self::Mailboxes(m) => Ok(m),
+            _ => Err(NodeError::NodeState(NodeReason::Unknown).internal()),
+        }
+    }
+
     /// Consume the wrapper and return [NodeReply::State]
     pub fn take_state(self) -> Result<bool> {
         match self {
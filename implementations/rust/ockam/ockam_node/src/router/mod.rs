@@ -173,12 +173,14 @@ impl Router {
 
             //// ==! Basic worker control
             StartWorker {
-                addrs,
+                mailboxes,
                 senders,
                 detached,
                 mailbox_count,
                 ref reply,
-            } => start_worker::exec(self, addrs, senders, detached, mailbox_count, reply).await?,
+            } => {
+                start_worker::exec(self, mailboxes, senders, detached, mailbox_count, reply).await?
+            }
             StopWorker(ref addr, ref detached, ref reply) => {
                 stop_worker::exec(self, addr, *detached, reply).await?
             }
@@ -247,6 +249,11 @@ impl Router {
                 .await
                 .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?,
 
+            WorkerMailboxes(addr, sender) => sender
+                .send(self.map.mailboxes(addr))
+                .await
+                .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?,
+
             SetCluster(addr, label, reply) => {
                 debug!("Setting cluster on address {}", addr);
                 let msg = self.map.set_cluster(label, addr);
@@ -12,7 +12,7 @@ use ockam_core::{
         sync::Arc,
         vec::Vec,
     },
-    Address, RelayMessage, Result,
+    Address, Mailboxes, RelayMessage, Result,
 };
 
 /// Address states and associated logic
@@ -90,6 +90,20 @@ impl InternalMap {
             .map_or(false, |rec| rec.ready(reply))
     }
 
+    /// Get the mailboxes of the worker owning an address
+    pub(super) fn mailboxes(&self, addr: Address) -> NodeReplyResult {
+        let mailboxes = self
+            .addr_map
+            .get(&addr)
+            .and_then(|primary| self.internal.get(primary))
+            .and_then(|rec| rec.mailboxes())
+            .cloned();
+        match mailboxes {
+            Some(m) => RouterReply::mailboxes(m),
+            None => RouterReply::no_such_address(addr),
+        }
+    }
+
     /// Retrieve the next cluster in reverse-initialsation order
     pub(super) fn next_cluster(&mut self) -> Option<Vec<&mut AddressRecord>> {
         let name = self.cluster_order.pop()?;
@@ -169,6 +183,9 @@ pub struct AddressRecord {
     ready: ReadyState,
     meta: AddressMeta,
     msg_count: Arc<AtomicUsize>,
+    /// Mailboxes of a worker or a detached context, processors and the app
+    /// context have none
+    mailboxes: Option<Mailboxes>,
 }
 
 impl AddressRecord {
@@ -196,9 +213,19 @@ impl AddressRecord {
             ready: ReadyState::Initialising(vec![]),
             msg_count,
             meta,
+            mailboxes: None,
         }
     }
 
+    pub fn with_mailboxes(mut self, mailboxes: Mailboxes) -> Self {
+        self.mailboxes = Some(mailboxes);
+        self
+    }
+
+    pub fn mailboxes(&self) -> Option<&Mailboxes> {
+        self.mailboxes.as_ref()
+    }
+
     pub fn increment_msg_count(&self) {
         self.msg_count.fetch_add(1, Ordering::Acquire);
     }
@@ -5,22 +5,19 @@ use crate::{
     NodeReplyResult, RouterReason, RouterReply,
 };
 use core::sync::atomic::AtomicUsize;
-use ockam_core::{
-    compat::{sync::Arc, vec::Vec},
-    Address, Result,
-};
+use ockam_core::{compat::sync::Arc, Mailboxes, Result};
 
 /// Execute a `StartWorker` command
 pub(super) async fn exec(
     router: &mut Router,
-    addrs: Vec<Address>,
+    mailboxes: Mailboxes,
     senders: SenderPair,
     detached: bool,
     metrics: Arc<AtomicUsize>,
     reply: &SmallSender<NodeReplyResult>,
 ) -> Result<()> {
     match router.state.node_state() {
-        NodeState::Running => start(router, addrs, senders, detached, metrics, reply).await,
+        NodeState::Running => start(router, mailboxes, senders, detached, metrics, reply).await,
         NodeState::Stopping(_) => reject(reply).await,
         NodeState::Dead => unreachable!(),
     }?;
@@ -29,12 +26,13 @@ pub(super) async fn exec(
 
 async fn start(
     router: &mut Router,
-    addrs: Vec<Address>,
+    mailboxes: Mailboxes,
     senders: SenderPair,
     detached: bool,
     metrics: Arc<AtomicUsize>,
     reply: &SmallSender<NodeReplyResult>,
 ) -> Result<()> {
+    let addrs = mailboxes.addresses();
     let primary_addr = addrs
         .first()
         .ok_or_else(|| NodeError::RouterState(RouterReason::EmptyAddressSet).internal())?;
@@ -57,7 +55,8 @@ async fn start(
             processor: false,
             detached,
         },
-    );
+    )
+    .with_mailboxes(mailboxes);
 
     router
         .map
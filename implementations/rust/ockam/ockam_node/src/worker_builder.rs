@@ -62,14 +62,13 @@ where
         );
 
         let mailboxes = self.mailboxes;
-        let addresses = mailboxes.addresses();
         let main_address = mailboxes.main_address().clone();
 
         // Pass it to the context
         let (ctx, sender, ctrl_rx) = Context::new(
             context.runtime().clone(),
             context.sender().clone(),
-            mailboxes,
+            mailboxes.clone(),
             None,
         );
 
@@ -80,7 +79,7 @@ where
 
         // Send start request to router
         let (msg, mut rx) =
-            NodeMessage::start_worker(addresses, sender, false, context.mailbox_count());
+            NodeMessage::start_worker(mailboxes, sender, false, context.mailbox_count());
         context
             .sender()
             .send(msg)
@@ -1,4 +1,4 @@
-use crate::TcpPortalWorker;
+use crate::{PortalStream, TcpPortalWorker};
 use ockam_core::compat::net::SocketAddr;
 use ockam_core::{
     async_trait,
@@ -20,6 +20,7 @@ pub(crate) struct TcpInletListenProcessor {
     inner: TcpListener,
     outlet_listener_route: Route,
     access_control: Arc<dyn IncomingAccessControl>,
+    portal_stream: PortalStream,
 }
 
 impl TcpInletListenProcessor {
@@ -29,6 +30,7 @@ impl TcpInletListenProcessor {
         outlet_listener_route: Route,
         addr: SocketAddr,
         access_control: Arc<dyn IncomingAccessControl>,
+        portal_stream: PortalStream,
     ) -> Result<(Address, SocketAddr)> {
         let waddr = Address::random_tagged("TcpInletListenProcessor");
 
@@ -45,6 +47,7 @@ impl TcpInletListenProcessor {
             inner,
             outlet_listener_route,
             access_control: access_control.clone(),
+            portal_stream,
         };
 
         ProcessorBuilder::with_mailboxes(
@@ -71,6 +74,7 @@ impl Processor for TcpInletListenProcessor {
             // self.router_address.clone(),
             self.outlet_listener_route.clone(),
             self.access_control.clone(),
+            self.portal_stream.clone(),
         )
         .await?;
 
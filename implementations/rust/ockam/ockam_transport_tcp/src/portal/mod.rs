@@ -2,10 +2,16 @@ mod inlet_listener;
 mod outlet_listener;
 mod portal_message;
 mod portal_receiver;
+mod portal_stream;
 mod portal_worker;
+#[cfg(feature = "tls")]
+mod tls;
 
 pub(crate) use inlet_listener::*;
 pub(crate) use outlet_listener::*;
 pub use portal_message::*;
 pub(crate) use portal_receiver::*;
+pub(crate) use portal_stream::*;
 pub(crate) use portal_worker::*;
+#[cfg(feature = "tls")]
+pub use tls::*;
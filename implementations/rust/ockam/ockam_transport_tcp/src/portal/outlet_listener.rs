@@ -1,4 +1,4 @@
-use crate::{PortalMessage, TcpPortalWorker, TcpRouterHandle};
+use crate::{PortalMessage, PortalStream, TcpPortalWorker, TcpRouterHandle};
 use ockam_core::{async_trait, IncomingAccessControl, Result, Routed, Worker};
 use ockam_node::Context;
 use ockam_transport_core::TransportError;
@@ -13,14 +13,20 @@ use tracing::debug;
 pub(crate) struct TcpOutletListenWorker {
     peer: String,
     access_control: Arc<dyn IncomingAccessControl>,
+    portal_stream: PortalStream,
 }
 
 impl TcpOutletListenWorker {
     /// Create a new `TcpOutletListenWorker`
-    pub(crate) fn new(peer: String, access_control: Arc<dyn IncomingAccessControl>) -> Self {
+    pub(crate) fn new(
+        peer: String,
+        access_control: Arc<dyn IncomingAccessControl>,
+        portal_stream: PortalStream,
+    ) -> Self {
         Self {
             peer,
             access_control,
+            portal_stream,
         }
     }
 }
@@ -50,6 +56,7 @@ impl Worker for TcpOutletListenWorker {
             // self.router_address.clone(),
             return_route.clone(),
             self.access_control.clone(),
+            self.portal_stream.clone(),
         )
         .await?;
 
@@ -1,10 +1,10 @@
 use crate::portal::portal_message::MAX_PAYLOAD_SIZE;
-use crate::{PortalInternalMessage, PortalMessage};
+use crate::{PortalInternalMessage, PortalMessage, PortalReadHalf};
 use ockam_core::compat::vec::Vec;
 use ockam_core::{async_trait, Encodable, LocalMessage, Route, TransportMessage};
 use ockam_core::{route, Address, Processor, Result};
 use ockam_node::Context;
-use tokio::{io::AsyncReadExt, net::tcp::OwnedReadHalf};
+use tokio::io::AsyncReadExt;
 use tracing::{error, warn};
 
 /// A TCP Portal receiving message processor
@@ -14,14 +14,14 @@ use tracing::{error, warn};
 /// [`TcpPortalWorker::start_receiver`](crate::TcpPortalWorker::start_receiver)
 pub(crate) struct TcpPortalRecvProcessor {
     buf: Vec<u8>,
-    rx: OwnedReadHalf,
+    rx: PortalReadHalf,
     sender_address: Address,
     onward_route: Route,
 }
 
 impl TcpPortalRecvProcessor {
     /// Create a new `TcpPortalRecvProcessor`
-    pub fn new(rx: OwnedReadHalf, sender_address: Address, onward_route: Route) -> Self {
+    pub fn new(rx: PortalReadHalf, sender_address: Address, onward_route: Route) -> Self {
         Self {
             buf: Vec::with_capacity(MAX_PAYLOAD_SIZE),
             rx,
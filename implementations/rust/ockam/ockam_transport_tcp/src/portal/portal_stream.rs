@@ -0,0 +1,51 @@
+use ockam_core::compat::boxed::Box;
+use ockam_core::Result;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+
+#[cfg(feature = "tls")]
+use crate::{TlsInletConfig, TlsOutletConfig};
+
+/// Reading half of the connection of a portal end
+pub(crate) type PortalReadHalf = Box<dyn AsyncRead + Send + Unpin>;
+
+/// Writing half of the connection of a portal end
+pub(crate) type PortalWriteHalf = Box<dyn AsyncWrite + Send + Unpin>;
+
+/// How the TCP connection of a portal end is wrapped before being used
+#[derive(Clone)]
+pub(crate) enum PortalStream {
+    /// The TCP stream is used as is
+    Plain,
+    /// TLS is terminated by an Inlet
+    #[cfg(feature = "tls")]
+    TlsInlet(TlsInletConfig),
+    /// TLS is originated by an Outlet
+    #[cfg(feature = "tls")]
+    TlsOutlet(TlsOutletConfig),
+}
+
+impl PortalStream {
+    /// Wrap a TCP stream, performing the TLS handshake if needed, and split it
+    pub(crate) async fn split(
+        &self,
+        stream: TcpStream,
+    ) -> Result<(PortalReadHalf, PortalWriteHalf)> {
+        match self {
+            PortalStream::Plain => {
+                let (rx, tx) = stream.into_split();
+                Ok((Box::new(rx), Box::new(tx)))
+            }
+            #[cfg(feature = "tls")]
+            PortalStream::TlsInlet(config) => {
+                let (rx, tx) = tokio::io::split(config.accept(stream).await?);
+                Ok((Box::new(rx), Box::new(tx)))
+            }
+            #[cfg(feature = "tls")]
+            PortalStream::TlsOutlet(config) => {
+                let (rx, tx) = tokio::io::split(config.connect(stream).await?);
+                Ok((Box::new(rx), Box::new(tx)))
+            }
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{PortalInternalMessage, PortalMessage, TcpPortalRecvProcessor};
+use crate::{
+    PortalInternalMessage, PortalMessage, PortalReadHalf, PortalStream, PortalWriteHalf,
+    TcpPortalRecvProcessor,
+};
 use core::time::Duration;
 use ockam_core::compat::{boxed::Box, net::SocketAddr, sync::Arc};
 use ockam_core::{
@@ -9,7 +12,6 @@ use ockam_core::{Address, Any, Result, Route, Routed, Worker};
 use ockam_node::{Context, ProcessorBuilder, WorkerBuilder};
 use ockam_transport_core::TransportError;
 use tokio::io::AsyncWriteExt;
-use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::TcpStream;
 use tracing::{debug, info, trace, warn};
 
@@ -42,8 +44,11 @@ enum TypeName {
 /// after a new connection has been accepted.
 pub(crate) struct TcpPortalWorker {
     state: State,
-    tx: Option<OwnedWriteHalf>,
-    rx: Option<OwnedReadHalf>,
+    tx: Option<PortalWriteHalf>,
+    rx: Option<PortalReadHalf>,
+    /// Accepted connection of an Inlet, until it is wrapped by `portal_stream`
+    stream: Option<TcpStream>,
+    portal_stream: PortalStream,
     peer: SocketAddr,
     internal_address: Address,
     remote_address: Address,
@@ -61,6 +66,7 @@ impl TcpPortalWorker {
         peer: SocketAddr,
         ping_route: Route,
         access_control: Arc<dyn IncomingAccessControl>,
+        portal_stream: PortalStream,
     ) -> Result<Address> {
         Self::start(
             ctx,
@@ -69,6 +75,7 @@ impl TcpPortalWorker {
             Some(stream),
             TypeName::Inlet,
             access_control,
+            portal_stream,
         )
         .await
     }
@@ -79,6 +86,7 @@ impl TcpPortalWorker {
         peer: SocketAddr,
         pong_route: Route,
         access_control: Arc<dyn IncomingAccessControl>,
+        portal_stream: PortalStream,
     ) -> Result<Address> {
         Self::start(
             ctx,
@@ -87,6 +95,7 @@ impl TcpPortalWorker {
             None,
             TypeName::Outlet,
             access_control,
+            portal_stream,
         )
         .await
     }
@@ -99,6 +108,7 @@ impl TcpPortalWorker {
         stream: Option<TcpStream>,
         type_name: TypeName,
         access_control: Arc<dyn IncomingAccessControl>,
+        portal_stream: PortalStream,
     ) -> Result<Address> {
         let internal_address = Address::random_tagged("TcpPortalWorker_internal");
         let remote_address = Address::random_tagged("TcpPortalWorker_remote");
@@ -109,18 +119,12 @@ impl TcpPortalWorker {
             type_name, internal_address, remote_address
         );
 
-        let (rx, tx) = match stream {
-            Some(s) => {
-                let (rx, tx) = s.into_split();
-                (Some(rx), Some(tx))
-            }
-            None => (None, None),
-        };
-
         let worker = Self {
             state,
-            tx,
-            rx,
+            tx: None,
+            rx: None,
+            stream,
+            portal_stream,
             peer,
             internal_address,
             remote_address: remote_address.clone(),
@@ -292,7 +296,7 @@ impl TcpPortalWorker {
             let stream = TcpStream::connect(self.peer)
                 .await
                 .map_err(TransportError::from)?;
-            let (rx, tx) = stream.into_split();
+            let (rx, tx) = self.portal_stream.split(stream).await?;
             self.tx = Some(tx);
             self.rx = Some(rx);
 
@@ -321,6 +325,23 @@ impl Worker for TcpPortalWorker {
 
         match state {
             State::SendPing { ping_route } => {
+                if let Some(stream) = self.stream.take() {
+                    match self.portal_stream.split(stream).await {
+                        Ok((rx, tx)) => {
+                            self.rx = Some(rx);
+                            self.tx = Some(tx);
+                        }
+                        Err(err) => {
+                            // E.g. a failed TLS handshake, there is nothing to forward
+                            warn!(
+                                "Inlet at: {} failed to set up the connection from {}: {}",
+                                self.internal_address, self.peer, err
+                            );
+                            ctx.stop_worker(self.internal_address.clone()).await?;
+                            return Ok(());
+                        }
+                    }
+                }
                 self.state = self.handle_send_ping(ctx, ping_route.clone()).await?;
             }
             State::SendPong { pong_route } => {
@@ -0,0 +1,152 @@
+use core::time::Duration;
+use ockam_core::compat::sync::Arc;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+use tokio_rustls::rustls::{
+    Certificate, ClientConfig, PrivateKey, RootCertStore, ServerConfig, ServerName,
+};
+use tokio_rustls::{client, server, TlsAcceptor, TlsConnector};
+
+/// Maximum duration of a TLS handshake at either end of a portal
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// TLS settings of an Inlet terminating the TLS connections of its clients
+///
+/// Used with [`TcpTransport::create_inlet_with_tls`](crate::TcpTransport::create_inlet_with_tls).
+#[derive(Clone)]
+pub struct TlsInletConfig {
+    acceptor: TlsAcceptor,
+}
+
+impl TlsInletConfig {
+    /// Load the certificate chain presented to clients and its private key from PEM files
+    pub fn from_pem_files(cert: impl AsRef<Path>, key: impl AsRef<Path>) -> Result<Self> {
+        let certs = read_certificates(cert.as_ref())?;
+        let key = read_private_key(key.as_ref())?;
+        let config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .map_err(|e| Error::new(Origin::Transport, Kind::Invalid, e))?;
+
+        Ok(Self {
+            acceptor: TlsAcceptor::from(Arc::new(config)),
+        })
+    }
+
+    /// Perform the server side of the TLS handshake on an accepted connection
+    pub(crate) async fn accept(&self, stream: TcpStream) -> Result<server::TlsStream<TcpStream>> {
+        match timeout(TLS_HANDSHAKE_TIMEOUT, self.acceptor.accept(stream)).await {
+            Ok(Ok(stream)) => Ok(stream),
+            Ok(Err(err)) => Err(Error::new(Origin::Transport, Kind::Io, err)),
+            Err(_) => Err(handshake_timeout()),
+        }
+    }
+}
+
+/// TLS settings of an Outlet originating TLS connections to its peer
+///
+/// Used with [`TcpTransport::create_outlet_with_tls`](crate::TcpTransport::create_outlet_with_tls).
+#[derive(Clone)]
+pub struct TlsOutletConfig {
+    connector: TlsConnector,
+    server_name: ServerName,
+}
+
+impl TlsOutletConfig {
+    /// Validate the certificate of the peer against the CA certificates of
+    /// the `ca_cert` PEM file or, if `None`, against the platform's trusted roots.
+    /// The certificate must be valid for `server_name`.
+    pub fn new(ca_cert: Option<&Path>, server_name: &str) -> Result<Self> {
+        let der_certs: Vec<Vec<u8>> = match ca_cert {
+            Some(path) => read_certificates(path)?.into_iter().map(|c| c.0).collect(),
+            None => rustls_native_certs::load_native_certs()
+                .map_err(|e| Error::new(Origin::Transport, Kind::Io, e))?
+                .into_iter()
+                .map(|c| c.0)
+                .collect(),
+        };
+        let mut roots = RootCertStore::empty();
+        let (added, _ignored) = roots.add_parsable_certificates(&der_certs);
+        if added == 0 {
+            return Err(invalid_config("no valid CA certificate was found"));
+        }
+
+        let server_name = ServerName::try_from(server_name)
+            .map_err(|_| invalid_config(format!("invalid TLS server name: {server_name}")))?;
+        let config = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+
+        Ok(Self {
+            connector: TlsConnector::from(Arc::new(config)),
+            server_name,
+        })
+    }
+
+    /// Perform the client side of the TLS handshake on a new connection
+    pub(crate) async fn connect(&self, stream: TcpStream) -> Result<client::TlsStream<TcpStream>> {
+        let handshake = self.connector.connect(self.server_name.clone(), stream);
+        match timeout(TLS_HANDSHAKE_TIMEOUT, handshake).await {
+            Ok(Ok(stream)) => Ok(stream),
+            Ok(Err(err)) => Err(Error::new(Origin::Transport, Kind::Io, err)),
+            Err(_) => Err(handshake_timeout()),
+        }
+    }
+}
+
+fn read_certificates(path: &Path) -> Result<Vec<Certificate>> {
+    let mut reader = open_pem_file(path)?;
+    let certs = rustls_pemfile::certs(&mut reader)
+        .map_err(|e| Error::new(Origin::Transport, Kind::Io, e))?;
+    if certs.is_empty() {
+        return Err(invalid_config(format!(
+            "no certificate found in {}",
+            path.display()
+        )));
+    }
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+fn read_private_key(path: &Path) -> Result<PrivateKey> {
+    let mut reader = open_pem_file(path)?;
+    let items = rustls_pemfile::read_all(&mut reader)
+        .map_err(|e| Error::new(Origin::Transport, Kind::Io, e))?;
+    for item in items {
+        match item {
+            rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::ECKey(key) => return Ok(PrivateKey(key)),
+            _ => {}
+        }
+    }
+    Err(invalid_config(format!(
+        "no private key found in {}",
+        path.display()
+    )))
+}
+
+fn open_pem_file(path: &Path) -> Result<BufReader<File>> {
+    let file = File::open(path).map_err(|e| {
+        Error::new(
+            Origin::Transport,
+            Kind::Io,
+            format!("failed to open {}: {e}", path.display()),
+        )
+    })?;
+    Ok(BufReader::new(file))
+}
+
+fn invalid_config(msg: impl Into<String>) -> Error {
+    Error::new(Origin::Transport, Kind::Invalid, msg.into())
+}
+
+fn handshake_timeout() -> Error {
+    Error::new(Origin::Transport, Kind::Timeout, "TLS handshake timed out")
+}
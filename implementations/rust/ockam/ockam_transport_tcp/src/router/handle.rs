@@ -1,7 +1,8 @@
 use crate::{
-    parse_socket_addr, TcpInletListenProcessor, TcpListenProcessor, TcpRouterRequest,
+    parse_socket_addr, PortalStream, TcpInletListenProcessor, TcpListenProcessor, TcpRouterRequest,
     TcpRouterResponse, WorkerPair, TCP,
 };
+use core::time::Duration;
 use ockam_core::compat::net::{SocketAddr, ToSocketAddrs};
 use ockam_core::{
     async_trait,
@@ -71,12 +72,23 @@ impl TcpRouterHandle {
 
     /// Establish an outgoing TCP connection on an existing transport
     pub async fn connect<S: AsRef<str>>(&self, peer: S) -> Result<Address> {
+        self.connect_with_keepalive(peer, None).await
+    }
+
+    /// Establish an outgoing TCP connection which sends heartbeats to the
+    /// peer at the given interval
+    pub async fn connect_with_keepalive<S: AsRef<str>>(
+        &self,
+        peer: S,
+        keepalive: Option<Duration>,
+    ) -> Result<Address> {
         let response = self
             .ctx
             .send_and_receive(
                 self.api_addr.clone(),
                 TcpRouterRequest::Connect {
                     peer: peer.as_ref().to_string(),
+                    keepalive,
                 },
             )
             .await?;
@@ -188,6 +200,7 @@ impl TcpRouterHandle {
         outlet_listener_route: impl Into<Route>,
         addr: impl Into<SocketAddr>,
         access_control: Arc<dyn IncomingAccessControl>,
+        portal_stream: PortalStream,
     ) -> Result<(Address, SocketAddr)> {
         let socket_addr = addr.into();
         TcpInletListenProcessor::start(
@@ -195,6 +208,7 @@ impl TcpRouterHandle {
             outlet_listener_route.into(),
             socket_addr,
             access_control,
+            portal_stream,
             // self.main_addr.clone(),
         )
         .await
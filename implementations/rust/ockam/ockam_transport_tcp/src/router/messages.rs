@@ -1,3 +1,4 @@
+use core::time::Duration;
 use ockam_core::{Address, Message, Result};
 use serde::{Deserialize, Serialize};
 
@@ -11,7 +12,11 @@ pub enum TcpRouterRequest {
         self_addr: Address,
     },
     /// Connect
-    Connect { peer: String },
+    Connect {
+        peer: String,
+        /// Interval of the heartbeats sent to the peer, if any
+        keepalive: Option<Duration>,
+    },
     /// Connect
     Disconnect { peer: String },
     /// Unregister (usually, after disconnection)
@@ -1,5 +1,6 @@
 use crate::{TcpRouterHandle, TcpRouterRequest, TcpRouterResponse, TcpSendWorker, TCP};
 use core::ops::Deref;
+use core::time::Duration;
 use ockam_core::{async_trait, compat::sync::Arc, LocalOnwardOnly, LocalSourceOnly};
 use ockam_core::{
     Address, Any, Decodable, LocalMessage, Mailbox, Mailboxes, Result, Routed, Worker,
@@ -129,16 +130,26 @@ impl TcpRouter {
     /// This handler starts a `(TcpSendWorker, TcpRecvProcessor)` pair
     /// that open and manage a connection to the given peer and
     /// finally register the given peer with this `TcpRouter`.
-    async fn handle_connect(&mut self, peer: String) -> Result<Address> {
+    async fn handle_connect(
+        &mut self,
+        peer: String,
+        keepalive: Option<Duration>,
+    ) -> Result<Address> {
         // Resolve peer address
         let (peer_addr, hostnames) = TcpRouterHandle::resolve_peer(peer)?;
 
         // Start a new `WorkerPair` for the given peer containing a
         // `TcpSendWorker` and `TcpRecvprocessor`
         let router_handle = self.create_self_handle().await?;
-        let pair =
-            TcpSendWorker::start_pair(&self.ctx, router_handle, None, peer_addr, hostnames.clone())
-                .await?;
+        let pair = TcpSendWorker::start_pair(
+            &self.ctx,
+            router_handle,
+            None,
+            peer_addr,
+            hostnames.clone(),
+            keepalive,
+        )
+        .await?;
 
         // Send this `TcpRouter` a `TcpRouterRequest::Register` message
         // containing the registration request
@@ -275,8 +286,8 @@ impl Worker for TcpRouter {
                     )
                     .await?;
                 }
-                TcpRouterRequest::Connect { peer } => {
-                    let res = self.handle_connect(peer).await;
+                TcpRouterRequest::Connect { peer, keepalive } => {
+                    let res = self.handle_connect(peer, keepalive).await;
 
                     ctx.send_from_address(
                         return_route,
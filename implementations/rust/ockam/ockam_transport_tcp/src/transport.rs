@@ -1,9 +1,12 @@
+use core::time::Duration;
 use ockam_core::access_control::IncomingAccessControl;
 use ockam_core::compat::{boxed::Box, net::SocketAddr, sync::Arc};
 use ockam_core::{Address, AsyncTryClone, DenyAll, Mailboxes, Result, Route};
 use ockam_node::{Context, WorkerBuilder};
 
-use crate::{parse_socket_addr, TcpOutletListenWorker, TcpRouter, TcpRouterHandle};
+use crate::{parse_socket_addr, PortalStream, TcpOutletListenWorker, TcpRouter, TcpRouterHandle};
+#[cfg(feature = "tls")]
+use crate::{TlsInletConfig, TlsOutletConfig};
 
 /// High level management interface for TCP transports
 ///
@@ -86,6 +89,38 @@ impl TcpTransport {
         self.router_handle.connect(peer.as_ref()).await
     }
 
+    /// Establish an outgoing TCP connection which detects a silently dropped peer.
+    ///
+    /// A heartbeat is sent to the peer every `interval`, and the connection is
+    /// closed after several heartbeats in a row were left unanswered. The peer
+    /// must support heartbeats. A zero `interval` disables heartbeats, like
+    /// [`connect`](crate::TcpTransport::connect).
+    ///
+    /// ```rust
+    /// use ockam_transport_tcp::TcpTransport;
+    /// # use core::time::Duration;
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let tcp = TcpTransport::create(&ctx).await?;
+    /// tcp.connect_with_keepalive("127.0.0.1:5000", Duration::from_secs(30)).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn connect_with_keepalive<S: AsRef<str>>(
+        &self,
+        peer: S,
+        interval: Duration,
+    ) -> Result<Address> {
+        let keepalive = if interval.is_zero() {
+            None
+        } else {
+            Some(interval)
+        };
+        self.router_handle
+            .connect_with_keepalive(peer.as_ref(), keepalive)
+            .await
+    }
+
     /// Disconnect from peer
     pub async fn disconnect<S: AsRef<str>>(&self, peer: S) -> Result<()> {
         self.router_handle.disconnect(peer.as_ref()).await
@@ -157,7 +192,29 @@ impl TcpTransport {
     ) -> Result<(Address, SocketAddr)> {
         let bind_addr = parse_socket_addr(bind_addr)?;
         self.router_handle
-            .bind_inlet(outlet_route, bind_addr, access_control)
+            .bind_inlet(outlet_route, bind_addr, access_control, PortalStream::Plain)
+            .await
+    }
+
+    /// Create Tcp Inlet that terminates TLS: the connections accepted on bind_addr must
+    /// start with a TLS handshake, using the certificate of `tls`, and only the decrypted
+    /// stream is forwarded to the Outlet.
+    #[cfg(feature = "tls")]
+    pub async fn create_inlet_with_tls(
+        &self,
+        bind_addr: String,
+        outlet_route: Route,
+        tls: TlsInletConfig,
+        access_control: Arc<dyn IncomingAccessControl>,
+    ) -> Result<(Address, SocketAddr)> {
+        let bind_addr = parse_socket_addr(bind_addr)?;
+        self.router_handle
+            .bind_inlet(
+                outlet_route,
+                bind_addr,
+                access_control,
+                PortalStream::TlsInlet(tls),
+            )
             .await
     }
 
@@ -220,7 +277,32 @@ impl TcpTransport {
         peer: String,
         access_control: Arc<dyn IncomingAccessControl>,
     ) -> Result<()> {
-        let worker = TcpOutletListenWorker::new(peer, access_control.clone());
+        self.start_outlet(address, peer, access_control, PortalStream::Plain)
+            .await
+    }
+
+    /// Create Tcp Outlet Listener that originates TLS: the connections made to peer
+    /// start with a TLS handshake, validating the certificate of the peer with `tls`.
+    #[cfg(feature = "tls")]
+    pub async fn create_outlet_with_tls(
+        &self,
+        address: Address,
+        peer: String,
+        tls: TlsOutletConfig,
+        access_control: Arc<dyn IncomingAccessControl>,
+    ) -> Result<()> {
+        self.start_outlet(address, peer, access_control, PortalStream::TlsOutlet(tls))
+            .await
+    }
+
+    async fn start_outlet(
+        &self,
+        address: Address,
+        peer: String,
+        access_control: Arc<dyn IncomingAccessControl>,
+        portal_stream: PortalStream,
+    ) -> Result<()> {
+        let worker = TcpOutletListenWorker::new(peer, access_control.clone(), portal_stream);
         WorkerBuilder::with_mailboxes(
             Mailboxes::main(address, access_control, Arc::new(DenyAll)),
             worker,
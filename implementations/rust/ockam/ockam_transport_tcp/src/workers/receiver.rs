@@ -1,4 +1,4 @@
-use crate::TcpSendWorkerMsg;
+use crate::{TcpSendWorkerMsg, PING, PONG};
 use ockam_core::async_trait;
 use ockam_core::{Address, Decodable, LocalMessage, Processor, Result, TransportMessage};
 use ockam_node::Context;
@@ -92,6 +92,16 @@ impl Processor for TcpRecvProcessor {
         // Heartbeat message
         if msg.onward_route.next().is_err() {
             trace!("Got heartbeat message from: {}", self.peer_addr);
+            // Heartbeats with an empty payload don't expect an answer
+            let notify = match msg.payload.first() {
+                Some(&PING) => Some(TcpSendWorkerMsg::Ping),
+                Some(&PONG) => Some(TcpSendWorkerMsg::Pong),
+                _ => None,
+            };
+            if let Some(notify) = notify {
+                ctx.send(self.sender_internal_address.clone(), notify)
+                    .await?;
+            }
             return Ok(true);
         }
 
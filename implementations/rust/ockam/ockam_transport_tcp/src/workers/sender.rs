@@ -3,13 +3,13 @@ use core::time::Duration;
 use ockam_core::{
     async_trait,
     compat::{net::SocketAddr, sync::Arc},
-    AllowSourceAddress, DenyAll, LocalOnwardOnly,
+    route, AllowSourceAddress, AllowSourceAddresses, DenyAll, LocalOnwardOnly,
 };
 use ockam_core::{
     Address, Any, Decodable, Encodable, LocalMessage, Mailbox, Mailboxes, Message, Result, Routed,
     TransportMessage, Worker,
 };
-use ockam_node::{Context, ProcessorBuilder, WorkerBuilder};
+use ockam_node::{Context, DelayedEvent, ProcessorBuilder, WorkerBuilder};
 use ockam_transport_core::TransportError;
 use serde::{Deserialize, Serialize};
 use socket2::{SockRef, TcpKeepalive};
@@ -46,6 +46,46 @@ impl WorkerPair {
 #[derive(Serialize, Deserialize, Message, Clone)]
 pub(crate) enum TcpSendWorkerMsg {
     ConnectionClosed,
+    /// Time to send a heartbeat to the peer
+    Heartbeat,
+    /// The peer sent a heartbeat which must be answered
+    Ping,
+    /// The peer answered our heartbeat
+    Pong,
+}
+
+/// Payload of a heartbeat expecting an answer
+pub(crate) const PING: u8 = 1;
+/// Payload of the answer to a heartbeat
+pub(crate) const PONG: u8 = 2;
+
+/// Number of consecutive unanswered heartbeats after which the peer is
+/// considered gone
+const MAX_MISSED_HEARTBEATS: u32 = 3;
+
+/// Periodic application-level heartbeats detecting a silently dropped peer
+pub(crate) struct Heartbeat {
+    event: DelayedEvent<TcpSendWorkerMsg>,
+    interval: Duration,
+    missed: u32,
+}
+
+impl Heartbeat {
+    async fn create(ctx: &Context, destination: Address, interval: Duration) -> Result<Self> {
+        Ok(Self {
+            event: DelayedEvent::create(ctx, destination, TcpSendWorkerMsg::Heartbeat).await?,
+            interval,
+            missed: 0,
+        })
+    }
+
+    fn address(&self) -> Address {
+        self.event.address()
+    }
+
+    async fn schedule(&mut self) -> Result<()> {
+        self.event.schedule(self.interval).await
+    }
 }
 
 /// A TCP sending message worker
@@ -64,6 +104,7 @@ pub(crate) struct TcpSendWorker {
     internal_addr: Address,
     rx_addr: Address,
     rx_should_be_stopped: bool,
+    heartbeat: Option<Heartbeat>,
 }
 
 impl TcpSendWorker {
@@ -91,6 +132,7 @@ impl TcpSendWorker {
             internal_addr,
             rx_addr,
             rx_should_be_stopped: true,
+            heartbeat: None,
         }
     }
 
@@ -132,17 +174,32 @@ impl TcpSendWorker {
 
     /// Start a `(TcpSendWorker, TcpRecvProcessor)` pair that opens and
     /// manages the connection with the given peer
+    ///
+    /// With a `keepalive` interval, a heartbeat is sent to the peer at that
+    /// interval and the connection is closed once several heartbeats in a
+    /// row are left unanswered.
     pub(crate) async fn start_pair(
         ctx: &Context,
         router_handle: TcpRouterHandle,
         stream: Option<TcpStream>,
         peer: SocketAddr,
         hostnames: Vec<String>,
+        keepalive: Option<Duration>,
     ) -> Result<WorkerPair> {
         let tcprouter_main_addr = router_handle.main_addr().clone();
 
         trace!("Creating new TCP worker pair");
-        let (sender, pair) = Self::new_pair(router_handle, stream, peer, hostnames).await?;
+        let (mut sender, pair) = Self::new_pair(router_handle, stream, peer, hostnames).await?;
+
+        // Messages on the internal address come from the receiver, and from
+        // the heartbeat timer if there is one
+        let mut internal_sources = vec![sender.rx_addr().clone()];
+        if let Some(interval) = keepalive {
+            let heartbeat =
+                Heartbeat::create(ctx, sender.internal_addr().clone(), interval).await?;
+            internal_sources.push(heartbeat.address());
+            sender.heartbeat = Some(heartbeat);
+        }
 
         // Allow messages routed from Tcp Router
         let tx_mailbox = Mailbox::new(
@@ -153,7 +210,7 @@ impl TcpSendWorker {
 
         let internal_mailbox = Mailbox::new(
             sender.internal_addr().clone(),
-            Arc::new(AllowSourceAddress(sender.rx_addr().clone())),
+            Arc::new(AllowSourceAddresses(internal_sources)),
             Arc::new(DenyAll),
         );
 
@@ -225,10 +282,18 @@ impl Worker for TcpSendWorker {
             .start(ctx)
             .await?;
 
+        if let Some(heartbeat) = &mut self.heartbeat {
+            heartbeat.schedule().await?;
+        }
+
         Ok(())
     }
 
     async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        if let Some(heartbeat) = &mut self.heartbeat {
+            heartbeat.event.cancel();
+        }
+
         if self.rx_should_be_stopped {
             let _ = ctx.stop_processor(self.rx_addr().clone()).await;
         }
@@ -262,6 +327,44 @@ impl Worker for TcpSendWorker {
 
                     return Ok(());
                 }
+                TcpSendWorkerMsg::Heartbeat => {
+                    let heartbeat = match &mut self.heartbeat {
+                        Some(heartbeat) => heartbeat,
+                        None => return Ok(()),
+                    };
+                    if heartbeat.missed >= MAX_MISSED_HEARTBEATS {
+                        warn!(
+                            "Peer {} missed {} heartbeats, closing the connection",
+                            self.peer, heartbeat.missed
+                        );
+                        self.stop_and_unregister(ctx).await?;
+
+                        return Ok(());
+                    }
+                    heartbeat.missed += 1;
+                    heartbeat.schedule().await?;
+
+                    if tx.write_all(&prepare_heartbeat(PING)?).await.is_err() {
+                        warn!("Failed to send heartbeat to peer {}", self.peer);
+                        self.stop_and_unregister(ctx).await?;
+
+                        return Ok(());
+                    }
+                    trace!("Sent heartbeat to peer {}", self.peer);
+                }
+                TcpSendWorkerMsg::Ping => {
+                    if tx.write_all(&prepare_heartbeat(PONG)?).await.is_err() {
+                        warn!("Failed to answer heartbeat of peer {}", self.peer);
+                        self.stop_and_unregister(ctx).await?;
+
+                        return Ok(());
+                    }
+                }
+                TcpSendWorkerMsg::Pong => {
+                    if let Some(heartbeat) = &mut self.heartbeat {
+                        heartbeat.missed = 0;
+                    }
+                }
             }
         } else {
             let mut msg = LocalMessage::decode(msg.payload())?.into_transport_message();
@@ -283,6 +386,12 @@ impl Worker for TcpSendWorker {
     }
 }
 
+/// Helper that creates a length-prefixed heartbeat, which is a message with
+/// an empty onward route
+fn prepare_heartbeat(kind: u8) -> Result<Vec<u8>> {
+    prepare_message(TransportMessage::v1(route![], route![], vec![kind]))
+}
+
 /// Helper that creates a length-prefixed buffer containing the given
 /// `TransportMessage`'s payload
 ///
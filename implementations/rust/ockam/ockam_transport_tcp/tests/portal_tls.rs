@@ -0,0 +1,163 @@
+#![cfg(feature = "tls")]
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::rustls::{
+    Certificate, ClientConfig, PrivateKey, RootCertStore, ServerConfig, ServerName,
+};
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+
+use ockam_core::compat::rand::random;
+use ockam_core::{route, LocalSourceOnly, Result};
+use ockam_node::Context;
+use ockam_transport_tcp::{TcpTransport, TlsInletConfig, TlsOutletConfig};
+
+const LENGTH: usize = 32;
+
+/// Self-signed certificate for `localhost`, and its key
+fn tls_file(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("tls")
+        .join(name)
+}
+
+fn certificates() -> Vec<Certificate> {
+    let pem = std::fs::read(tls_file("localhost.crt")).unwrap();
+    let certs = rustls_pemfile::certs(&mut pem.as_slice()).unwrap();
+    certs.into_iter().map(Certificate).collect()
+}
+
+fn tls_acceptor() -> TlsAcceptor {
+    let pem = std::fs::read(tls_file("localhost.key")).unwrap();
+    let key = rustls_pemfile::pkcs8_private_keys(&mut pem.as_slice())
+        .unwrap()
+        .remove(0);
+    let config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certificates(), PrivateKey(key))
+        .unwrap();
+    TlsAcceptor::from(Arc::new(config))
+}
+
+fn tls_connector() -> TlsConnector {
+    let mut roots = RootCertStore::empty();
+    for cert in certificates() {
+        roots.add(&cert).unwrap();
+    }
+    let config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    TlsConnector::from(Arc::new(config))
+}
+
+fn generate_binary() -> [u8; LENGTH] {
+    random()
+}
+
+/// Echo the first payload received on the first accepted connection
+async fn echo_once<S: AsyncRead + AsyncWrite + Unpin>(mut stream: S) {
+    let mut payload = [0u8; LENGTH];
+    stream.read_exact(&mut payload).await.unwrap();
+    stream.write_all(&payload).await.unwrap();
+}
+
+async fn assert_echo<S: AsyncRead + AsyncWrite + Unpin>(stream: &mut S) {
+    let payload = generate_binary();
+    stream.write_all(&payload).await.unwrap();
+    let mut echoed = [0u8; LENGTH];
+    stream.read_exact(&mut echoed).await.unwrap();
+    assert_eq!(echoed, payload);
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__tls_inlet__should_succeed(ctx: &mut Context) -> Result<()> {
+    let tcp = TcpTransport::create(ctx).await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let outlet_addr = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet("outlet", outlet_addr, LocalSourceOnly)
+        .await?;
+
+    let cert = tls_file("localhost.crt");
+    let tls = TlsInletConfig::from_pem_files(cert, tls_file("localhost.key"))?;
+    let (_, inlet_saddr) = tcp
+        .create_inlet_with_tls(
+            "127.0.0.1:0".into(),
+            route!["outlet"],
+            tls,
+            Arc::new(LocalSourceOnly),
+        )
+        .await?;
+
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        echo_once(stream).await;
+    });
+
+    // Wait till listener is up
+    tokio::time::sleep(Duration::new(0, 250_000)).await;
+
+    let stream = TcpStream::connect(inlet_saddr).await.unwrap();
+    let server_name = ServerName::try_from("localhost").unwrap();
+    let mut stream = tls_connector().connect(server_name, stream).await.unwrap();
+    assert_echo(&mut stream).await;
+
+    tokio::time::sleep(Duration::new(0, 250_000)).await;
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__tls_outlet__should_succeed(ctx: &mut Context) -> Result<()> {
+    let tcp = TcpTransport::create(ctx).await?;
+
+    // The server behind the outlet terminates TLS
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let acceptor = tls_acceptor();
+
+    let tls = TlsOutletConfig::new(Some(&tls_file("localhost.crt")), "localhost")?;
+    tcp.create_outlet_with_tls(
+        "outlet".into(),
+        format!("localhost:{port}"),
+        tls,
+        Arc::new(LocalSourceOnly),
+    )
+    .await?;
+
+    let (_, inlet_saddr) = tcp
+        .create_inlet("127.0.0.1:0", route!["outlet"], LocalSourceOnly)
+        .await?;
+
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        echo_once(acceptor.accept(stream).await.unwrap()).await;
+    });
+
+    // Wait till listener is up
+    tokio::time::sleep(Duration::new(0, 250_000)).await;
+
+    let mut stream = TcpStream::connect(inlet_saddr).await.unwrap();
+    assert_echo(&mut stream).await;
+
+    tokio::time::sleep(Duration::new(0, 250_000)).await;
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
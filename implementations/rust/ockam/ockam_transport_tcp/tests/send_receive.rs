@@ -5,6 +5,7 @@ use ockam_node::{Context, WorkerBuilder};
 
 use ockam_transport_tcp::{TcpTransport, TCP};
 use std::time::Duration;
+use tokio::io::AsyncReadExt;
 use tracing::info;
 
 #[ockam_macros::test]
@@ -113,6 +114,64 @@ async fn tcp_lifecycle__reconnect__should_not_error(ctx: &mut Context) -> Result
     Ok(())
 }
 
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn tcp_heartbeat__silent_peer__connection_closed(ctx: &mut Context) -> Result<()> {
+    // A peer which accepts the connection but never answers heartbeats
+    let silent_peer = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let peer_address = silent_peer.local_addr().unwrap().to_string();
+
+    let transport = TcpTransport::create(ctx).await?;
+
+    // Without heartbeats the connection is kept open
+    transport
+        .connect_with_keepalive(&peer_address, Duration::ZERO)
+        .await?;
+    let (mut stream, _) = silent_peer.accept().await.unwrap();
+    let mut buf = Vec::new();
+    let read = tokio::time::timeout(Duration::from_millis(500), stream.read_to_end(&mut buf)).await;
+    assert!(read.is_err(), "The connection should still be open");
+    transport.disconnect(&peer_address).await?;
+
+    // With heartbeats the connection is closed and unregistered
+    transport
+        .connect_with_keepalive(&peer_address, Duration::from_millis(100))
+        .await?;
+    let (mut stream, _) = silent_peer.accept().await.unwrap();
+    let read = tokio::time::timeout(Duration::from_secs(5), stream.read_to_end(&mut buf)).await;
+    assert!(read.is_ok(), "The connection should have been closed");
+    assert!(transport.disconnect(&peer_address).await.is_err());
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn tcp_heartbeat__answering_peer__connection_kept(ctx: &mut Context) -> Result<()> {
+    let transport = TcpTransport::create(ctx).await?;
+    let listener_address = transport.listen("127.0.0.1:0").await?.to_string();
+
+    transport
+        .connect_with_keepalive(&listener_address, Duration::from_millis(100))
+        .await?;
+
+    // Long enough for several heartbeats to be sent and answered
+    ctx.sleep(Duration::from_millis(800)).await;
+
+    // The connection is still registered
+    transport.disconnect(&listener_address).await?;
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
 #[ignore]
 #[ockam_macros::test(timeout = 400000)]
 async fn tcp_keepalive_test(ctx: &mut Context) -> Result<()> {
@@ -2,7 +2,7 @@ use aws_sdk_kms::error::{CreateKeyError, GetPublicKeyError, SignError, VerifyErr
 use aws_sdk_kms::error::{ScheduleKeyDeletionError, ScheduleKeyDeletionErrorKind};
 use aws_sdk_kms::model::{KeySpec, KeyUsageType, MessageType, SigningAlgorithmSpec};
 use aws_sdk_kms::types::{Blob, SdkError};
-use aws_sdk_kms::Client;
+use aws_sdk_kms::{Client, Region};
 use ockam_core::vault::SecretType;
 use ockam_core::vault::{KeyId, PublicKey, Signature};
 use ockam_core::Result;
@@ -14,6 +14,7 @@ use tracing as log;
 #[derive(Debug, Default, Clone)]
 pub struct Config {
     multi_region: bool,
+    region: Option<String>,
 }
 
 impl Config {
@@ -22,6 +23,12 @@ impl Config {
         self.multi_region = val;
         self
     }
+
+    /// Use this AWS region instead of the one found in the environment.
+    pub fn region(mut self, val: impl Into<String>) -> Self {
+        self.region = Some(val.into());
+        self
+    }
 }
 
 /// AWS KMS client.
@@ -34,7 +41,15 @@ pub struct Kms {
 impl Kms {
     /// Create a new AWS KMS client.
     pub async fn new(c: Config) -> Result<Self> {
-        let config = aws_config::load_from_env().await;
+        let config = match &c.region {
+            Some(region) => {
+                aws_config::from_env()
+                    .region(Region::new(region.clone()))
+                    .load()
+                    .await
+            }
+            None => aws_config::load_from_env().await,
+        };
         let client = Client::new(&config);
         Ok(Self { client, config: c })
     }
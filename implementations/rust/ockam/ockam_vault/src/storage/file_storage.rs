@@ -111,6 +111,21 @@ impl FileStorage {
         Ok(s)
     }
 
+    /// Number of entries stored in the vault
+    ///
+    /// This doesn't require the storage to be initialized: a missing vault
+    /// file holds no entries, and isn't created.
+    pub async fn count(&self) -> Result<usize> {
+        if !self.path.exists() {
+            return Ok(0);
+        }
+        let t = |v: LegacySerializedVault| -> Result<usize> {
+            let LegacySerializedVault::V1 { entries, .. } = v;
+            Ok(entries.len())
+        };
+        self.read_transaction(t).await
+    }
+
     // Flush vault to target, using temp_path as intermediary file.
     fn flush_to_file(
         target: &PathBuf,
@@ -291,6 +306,9 @@ mod tests {
         let key_id2 = vault.secret_generate(attributes20).await.unwrap();
         let key_id3 = vault.secret_generate(attributes3).await.unwrap();
 
+        // Only persistent secrets are stored
+        assert_eq!(storage.count().await.unwrap(), 2);
+
         let vault = Vault::new(Some(storage.clone()));
 
         let attributes11 = vault.secret_attributes_get(&key_id1).await.unwrap();
@@ -301,6 +319,18 @@ mod tests {
         assert!(attributes31.is_err());
     }
 
+    #[tokio::test]
+    #[allow(non_snake_case)]
+    async fn count__missing_vault__is_not_created() {
+        let mut rand_id = [0u8; 32];
+        thread_rng().fill_bytes(&mut rand_id);
+        let path = std::env::temp_dir().join(hex::encode(rand_id));
+
+        let storage = FileStorage::new(path.clone());
+        assert_eq!(storage.count().await.unwrap(), 0);
+        assert!(!path.exists());
+    }
+
     #[tokio::test]
     #[allow(non_snake_case)]
     async fn vault_syncronization() {
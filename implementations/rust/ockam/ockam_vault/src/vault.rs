@@ -63,7 +63,17 @@ impl Vault {
     /// Enable AWS KMS.
     #[cfg(feature = "aws")]
     pub async fn enable_aws_kms(&mut self) -> Result<(), ockam_core::Error> {
-        let kms = crate::aws::Kms::default().await?;
+        self.enable_aws_kms_with(crate::aws::Config::default())
+            .await
+    }
+
+    /// Enable AWS KMS with the given configuration.
+    #[cfg(feature = "aws")]
+    pub async fn enable_aws_kms_with(
+        &mut self,
+        config: crate::aws::Config,
+    ) -> Result<(), ockam_core::Error> {
+        let kms = crate::aws::Kms::new(config).await?;
         self.aws_kms = Some(kms);
         Ok(())
     }